@@ -0,0 +1,265 @@
+//! # Advanced Encryption Standard (AES) Accelerator
+//!
+//! ### Summary
+//! The AES peripheral encrypts and decrypts single 16 byte blocks in hardware.
+//! The ECB, CBC and CTR block cipher modes are built on top of that by
+//! [Aes::ecb_encrypt], [Aes::cbc_encrypt], [Aes::ctr_apply_keystream] and
+//! friends, processing whole buffers in place.
+//!
+//! The supported key sizes differ between the chips:
+//!
+//! | Chip                | AES-128 | AES-192 | AES-256 |
+//! |---------------------|---------|---------|---------|
+//! | ESP32, ESP32-S2     | ✓       | ✓       | ✓       |
+//! | ESP32-C3, ESP32-S3  | ✓       |         | ✓       |
+//!
+//! The ESP32-C2 doesn't have an AES accelerator.
+//!
+//! Only the typical (register based) mode of the peripheral is supported, the
+//! block modes are implemented in software. The DMA mode of the ESP32-C3,
+//! ESP32-S2 and ESP32-S3, which runs the block modes in hardware, isn't
+//! supported yet.
+//!
+//! ### Example
+//! ```no_run
+//! let mut aes = Aes::new(peripherals.AES, &mut system.peripheral_clock_control);
+//! aes.set_key(Key::Key128(key));
+//!
+//! let mut iv = [0u8; 16];
+//! aes.cbc_encrypt(&mut iv, &mut data).unwrap();
+//! ```
+
+#![deny(missing_docs)]
+
+use crate::{
+    pac::AES,
+    system::{Peripheral, PeripheralClockControl},
+};
+
+/// Length of an AES block in bytes
+pub const BLOCK_SIZE: usize = 16;
+
+/// AES errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The length of the data isn't a multiple of [BLOCK_SIZE]
+    InvalidLength,
+}
+
+/// AES key, the variant selects the key size
+#[derive(Clone, Copy)]
+pub enum Key {
+    /// 128 bit key
+    Key128([u8; 16]),
+    /// 192 bit key
+    #[cfg(any(esp32, esp32s2))]
+    Key192([u8; 24]),
+    /// 256 bit key
+    Key256([u8; 32]),
+}
+
+impl Key {
+    fn as_slice(&self) -> &[u8] {
+        match self {
+            Key::Key128(key) => key,
+            #[cfg(any(esp32, esp32s2))]
+            Key::Key192(key) => key,
+            Key::Key256(key) => key,
+        }
+    }
+
+    /// Value of the `MODE` register selecting encryption with this key size,
+    /// decryption is selected by adding 4
+    fn mode(&self) -> u32 {
+        match self {
+            Key::Key128(_) => 0,
+            #[cfg(any(esp32, esp32s2))]
+            Key::Key192(_) => 1,
+            Key::Key256(_) => 2,
+        }
+    }
+}
+
+/// Offset of the decryption modes in the `MODE` register
+const MODE_DECRYPT: u32 = 4;
+
+/// AES accelerator driver
+pub struct Aes {
+    aes: AES,
+    mode: u32,
+}
+
+impl Aes {
+    /// Create a new instance and enable the peripheral
+    ///
+    /// Until a key is set via [Aes::set_key], an all zero 128 bit key is
+    /// used.
+    pub fn new(aes: AES, peripheral_clock_control: &mut PeripheralClockControl) -> Self {
+        peripheral_clock_control.enable(Peripheral::Aes);
+
+        let mut aes = Self { aes, mode: 0 };
+        aes.init();
+        aes.set_key(Key::Key128([0; 16]));
+
+        aes
+    }
+
+    /// Load `key` into the key registers
+    pub fn set_key(&mut self, key: Key) {
+        write_words(self.aes.key_0.as_ptr(), key.as_slice());
+        self.mode = key.mode();
+    }
+
+    /// Encrypt a single block in place
+    pub fn encrypt_block(&mut self, block: &mut [u8; BLOCK_SIZE]) {
+        self.process_block(self.mode, block);
+    }
+
+    /// Decrypt a single block in place
+    pub fn decrypt_block(&mut self, block: &mut [u8; BLOCK_SIZE]) {
+        self.process_block(self.mode + MODE_DECRYPT, block);
+    }
+
+    /// Encrypt `data` in place using the ECB mode
+    ///
+    /// The length of `data` needs to be a multiple of [BLOCK_SIZE].
+    pub fn ecb_encrypt(&mut self, data: &mut [u8]) -> Result<(), Error> {
+        for block in blocks(data)? {
+            self.encrypt_block(block);
+        }
+
+        Ok(())
+    }
+
+    /// Decrypt `data` in place using the ECB mode
+    ///
+    /// The length of `data` needs to be a multiple of [BLOCK_SIZE].
+    pub fn ecb_decrypt(&mut self, data: &mut [u8]) -> Result<(), Error> {
+        for block in blocks(data)? {
+            self.decrypt_block(block);
+        }
+
+        Ok(())
+    }
+
+    /// Encrypt `data` in place using the CBC mode
+    ///
+    /// The length of `data` needs to be a multiple of [BLOCK_SIZE]. `iv` is
+    /// updated to the last ciphertext block, so a message can be encrypted in
+    /// several calls.
+    pub fn cbc_encrypt(&mut self, iv: &mut [u8; BLOCK_SIZE], data: &mut [u8]) -> Result<(), Error> {
+        for block in blocks(data)? {
+            xor(block, iv);
+            self.encrypt_block(block);
+            *iv = *block;
+        }
+
+        Ok(())
+    }
+
+    /// Decrypt `data` in place using the CBC mode
+    ///
+    /// The length of `data` needs to be a multiple of [BLOCK_SIZE]. `iv` is
+    /// updated to the last ciphertext block, so a message can be decrypted in
+    /// several calls.
+    pub fn cbc_decrypt(&mut self, iv: &mut [u8; BLOCK_SIZE], data: &mut [u8]) -> Result<(), Error> {
+        for block in blocks(data)? {
+            let ciphertext = *block;
+            self.decrypt_block(block);
+            xor(block, iv);
+            *iv = ciphertext;
+        }
+
+        Ok(())
+    }
+
+    /// Encrypt or decrypt `data` in place using the CTR mode
+    ///
+    /// `counter` holds the initial counter block, it's incremented as a 128
+    /// bit big-endian number after each block. `data` can have any length,
+    /// but the unused part of the keystream of a partial last block is
+    /// discarded, so only the last call for a message may pass a partial
+    /// block.
+    pub fn ctr_apply_keystream(&mut self, counter: &mut [u8; BLOCK_SIZE], data: &mut [u8]) {
+        for chunk in data.chunks_mut(BLOCK_SIZE) {
+            let mut keystream = *counter;
+            self.encrypt_block(&mut keystream);
+
+            for (byte, key) in chunk.iter_mut().zip(keystream.iter()) {
+                *byte ^= key;
+            }
+
+            *counter = (u128::from_be_bytes(*counter).wrapping_add(1)).to_be_bytes();
+        }
+    }
+
+    /// Return the raw interface to the underlying AES instance
+    pub fn free(self) -> AES {
+        self.aes
+    }
+
+    fn init(&mut self) {
+        // Have the key and text registers take the bytes in memory order
+        #[cfg(any(esp32, esp32s2))]
+        self.aes.endian.write(|w| unsafe { w.bits(0x3f) });
+
+        // Use the typical mode, i.e. process a single block per trigger
+        #[cfg(not(esp32))]
+        self.aes.dma_enable.write(|w| unsafe { w.bits(0) });
+    }
+
+    fn process_block(&mut self, mode: u32, block: &mut [u8; BLOCK_SIZE]) {
+        self.aes.mode.write(|w| unsafe { w.bits(mode) });
+
+        #[cfg(esp32)]
+        {
+            write_words(self.aes.text_0.as_ptr(), block);
+            self.aes.start.write(|w| unsafe { w.bits(1) });
+            while self.aes.idle.read().bits() & 1 == 0 {}
+            read_words(self.aes.text_0.as_ptr(), block);
+        }
+
+        #[cfg(not(esp32))]
+        {
+            write_words(self.aes.text_in_0.as_ptr(), block);
+            self.aes.trigger.write(|w| unsafe { w.bits(1) });
+            while self.aes.state.read().bits() != 0 {}
+            read_words(self.aes.text_out_0.as_ptr(), block);
+        }
+    }
+}
+
+/// Split `data` into blocks, failing if there's a partial block
+fn blocks(data: &mut [u8]) -> Result<impl Iterator<Item = &mut [u8; BLOCK_SIZE]>, Error> {
+    if data.len() % BLOCK_SIZE != 0 {
+        return Err(Error::InvalidLength);
+    }
+
+    Ok(data
+        .chunks_exact_mut(BLOCK_SIZE)
+        .map(|block| block.try_into().unwrap()))
+}
+
+fn xor(block: &mut [u8], other: &[u8; BLOCK_SIZE]) {
+    for (byte, other) in block.iter_mut().zip(other.iter()) {
+        *byte ^= other;
+    }
+}
+
+/// Write `data` to consecutive 32 bit registers starting at `base`
+fn write_words(base: *mut u32, data: &[u8]) {
+    for (index, word) in data.chunks_exact(4).enumerate() {
+        unsafe {
+            base.add(index)
+                .write_volatile(u32::from_ne_bytes(word.try_into().unwrap()));
+        }
+    }
+}
+
+/// Read consecutive 32 bit registers starting at `base` into `data`
+fn read_words(base: *mut u32, data: &mut [u8]) {
+    for (index, word) in data.chunks_exact_mut(4).enumerate() {
+        let value = unsafe { base.add(index).read_volatile() };
+        word.copy_from_slice(&value.to_ne_bytes());
+    }
+}
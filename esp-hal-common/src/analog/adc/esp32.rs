@@ -4,9 +4,28 @@ use embedded_hal::adc::{Channel, OneShot};
 
 use crate::{
     analog::{ADC1, ADC2},
+    efuse::{Efuse, EfuseField},
     pac::{RTCIO, SENS},
 };
 
+/// Scaling of the slope of the calibration line
+const COEFF_A_SCALE: u32 = 65536;
+
+/// Voltages (mV) of the two point calibration values
+const TP_LOW_VOLTAGE: i32 = 150;
+const TP_HIGH_VOLTAGE: i32 = 850;
+
+/// Slope and intercept of the calibration line per attenuation, for two point
+/// and reference voltage calibration of ADC1 and ADC2
+const ADC1_TP_ATTEN_SCALE: [u32; 4] = [65504, 86975, 120389, 224310];
+const ADC2_TP_ATTEN_SCALE: [u32; 4] = [65467, 86861, 120416, 224708];
+const ADC1_TP_ATTEN_OFFSET: [i32; 4] = [0, 1, 27, 54];
+const ADC2_TP_ATTEN_OFFSET: [i32; 4] = [0, 9, 26, 66];
+const ADC1_VREF_ATTEN_SCALE: [u32; 4] = [57431, 76236, 105481, 196602];
+const ADC2_VREF_ATTEN_SCALE: [u32; 4] = [57236, 76175, 105678, 197170];
+const ADC1_VREF_ATTEN_OFFSET: [i32; 4] = [75, 78, 87, 150];
+const ADC2_VREF_ATTEN_OFFSET: [i32; 4] = [63, 66, 89, 165];
+
 /// The sampling/readout resolution of the ADC
 #[derive(PartialEq, Eq, Clone, Copy)]
 pub enum Resolution {
@@ -97,6 +116,9 @@ pub trait RegisterAccess {
     fn read_done_sar() -> bool;
 
     fn read_data_sar() -> u16;
+
+    /// Index of the ADC unit
+    fn unit() -> u8;
 }
 
 #[doc(hidden)]
@@ -176,6 +198,10 @@ impl RegisterAccess for ADC1 {
         let sensors = unsafe { &*SENS::ptr() };
         sensors.sar_meas_start1.read().meas1_data_sar().bits() as u16
     }
+
+    fn unit() -> u8 {
+        0
+    }
 }
 
 impl RegisterAccess for ADC2 {
@@ -254,10 +280,15 @@ impl RegisterAccess for ADC2 {
         let sensors = unsafe { &*SENS::ptr() };
         sensors.sar_meas_start2.read().meas2_data_sar().bits() as u16
     }
+
+    fn unit() -> u8 {
+        1
+    }
 }
 
 pub struct ADC<ADC> {
     adc: PhantomData<ADC>,
+    resolution: Resolution,
     attenuations: [Option<Attenuation>; 10],
     active_channel: Option<u8>,
 }
@@ -280,7 +311,7 @@ where
 
         for channel in 0..attenuations.len() {
             if let Some(attenuation) = attenuations[channel] {
-                ADC1::set_attenuation(channel, attenuation as u8);
+                ADCI::set_attenuation(channel, attenuation as u8);
             }
         }
 
@@ -325,6 +356,7 @@ where
 
         let adc = ADC {
             adc: PhantomData,
+            resolution: config.resolution,
             attenuations: config.attenuations,
             active_channel: None,
         };
@@ -333,6 +365,95 @@ where
     }
 }
 
+impl<ADCI> ADC<ADCI>
+where
+    ADCI: RegisterAccess,
+{
+    /// Read the voltage at `pin` in mV
+    ///
+    /// The reading is corrected with the two point calibration values burned
+    /// into the eFuse, or with the measured reference voltage on chips
+    /// without them. Chips with neither are assumed to have the nominal
+    /// reference voltage of 1100 mV.
+    ///
+    /// At 11 dB attenuation the ADC is non-linear above roughly 2450 mV, the
+    /// linear correction used here is less accurate in that range.
+    pub fn read_calibrated_mv<PIN: Channel<ADCI, ID = u8>>(
+        &mut self,
+        pin: &mut AdcPin<PIN, ADCI>,
+    ) -> nb::Result<u16, ()> {
+        let raw: u16 = self.read(pin)?;
+
+        // The calibration applies to 12 bit readings
+        let raw = (raw as u32) << (Resolution::Resolution12Bit as u8 - self.resolution as u8);
+
+        let attenuation = self.attenuations[AdcPin::<PIN, ADCI>::channel() as usize].unwrap();
+        let (coeff_a, coeff_b) = calibration_coefficients(ADCI::unit(), attenuation);
+
+        let mv = ((coeff_a * raw + COEFF_A_SCALE / 2) / COEFF_A_SCALE) as i32 + coeff_b;
+        Ok(mv.max(0) as u16)
+    }
+}
+
+/// Slope (scaled by [COEFF_A_SCALE]) and intercept of the line converting raw
+/// readings to mV
+fn calibration_coefficients(unit: u8, attenuation: Attenuation) -> (u32, i32) {
+    let atten = attenuation as usize;
+
+    if Efuse::read_field(EfuseField::Blk3PartReserve) != 0 {
+        let (low, high, scale, offset) = if unit == 0 {
+            (
+                278 + sign_extend(Efuse::read_field(EfuseField::Adc1TpLow), 7) * 4,
+                3265 + sign_extend(Efuse::read_field(EfuseField::Adc1TpHigh), 9) * 4,
+                ADC1_TP_ATTEN_SCALE[atten],
+                ADC1_TP_ATTEN_OFFSET[atten],
+            )
+        } else {
+            (
+                421 + sign_extend(Efuse::read_field(EfuseField::Adc2TpLow), 7) * 4,
+                4075 + sign_extend(Efuse::read_field(EfuseField::Adc2TpHigh), 9) * 4,
+                ADC2_TP_ATTEN_SCALE[atten],
+                ADC2_TP_ATTEN_OFFSET[atten],
+            )
+        };
+
+        let delta_x = high - low;
+        let delta_v = TP_HIGH_VOLTAGE - TP_LOW_VOLTAGE;
+
+        let coeff_a = (delta_v as u32 * scale + delta_x as u32 / 2) / delta_x as u32;
+        let coeff_b = TP_HIGH_VOLTAGE - (delta_v * high + delta_x / 2) / delta_x + offset;
+
+        (coeff_a, coeff_b)
+    } else {
+        // The reference voltage is stored as sign and magnitude in steps of
+        // 7 mV around 1100 mV, unburned chips read as 1100 mV
+        let vref = Efuse::read_field(EfuseField::AdcVref);
+        let magnitude = (vref & 0xf) * 7;
+        let vref = if vref & 0x10 != 0 {
+            1100 - magnitude
+        } else {
+            1100 + magnitude
+        };
+
+        if unit == 0 {
+            (
+                vref * ADC1_VREF_ATTEN_SCALE[atten] / 4096,
+                ADC1_VREF_ATTEN_OFFSET[atten],
+            )
+        } else {
+            (
+                vref * ADC2_VREF_ATTEN_SCALE[atten] / 4096,
+                ADC2_VREF_ATTEN_OFFSET[atten],
+            )
+        }
+    }
+}
+
+/// Interpret the lowest `bits` bits of `value` as a two's complement number
+fn sign_extend(value: u32, bits: u32) -> i32 {
+    ((value << (32 - bits)) as i32) >> (32 - bits)
+}
+
 impl<ADC1> ADC<ADC1> {
     pub fn enable_hall_sensor() {
         // Connect hall sensor
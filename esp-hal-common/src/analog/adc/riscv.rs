@@ -1,14 +1,30 @@
 use core::marker::PhantomData;
 
+#[cfg(esp32c3)]
+use embedded_dma::WriteBuffer;
 use embedded_hal::adc::{Channel, OneShot};
+#[cfg(esp32c3)]
+use fugit::HertzU32;
 
 #[cfg(esp32c3)]
 use crate::analog::ADC2;
+#[cfg(esp32c3)]
+use crate::dma::{
+    private::{AdcPeripheral, Rx, Tx},
+    Channel as DmaChannel,
+    DmaError,
+    DmaPeripheral,
+};
 use crate::{
     analog::ADC1,
     pac::APB_SARADC,
     system::{Peripheral, PeripheralClockControl},
 };
+#[cfg(esp32c3)]
+use crate::{
+    clock::Clocks,
+    efuse::{Efuse, EfuseField},
+};
 
 /// The sampling/readout resolution of the ADC
 #[derive(PartialEq, Eq, Clone, Copy)]
@@ -85,6 +101,9 @@ pub trait RegisterAccess {
     fn read_data() -> u16;
 
     fn reset();
+
+    /// Index of the ADC unit, as used for the monitor channel selection
+    fn unit() -> u8;
 }
 
 impl RegisterAccess for ADC1 {
@@ -126,6 +145,10 @@ impl RegisterAccess for ADC1 {
             .onetime_sample
             .modify(|_, w| w.saradc_onetime_start().clear_bit());
     }
+
+    fn unit() -> u8 {
+        0
+    }
 }
 
 #[cfg(esp32c3)]
@@ -168,6 +191,28 @@ impl RegisterAccess for ADC2 {
             .onetime_sample
             .modify(|_, w| w.saradc_onetime_start().clear_bit());
     }
+
+    fn unit() -> u8 {
+        1
+    }
+}
+
+/// Threshold monitor of the ADC controller, see [AdcContinuous::set_monitor]
+#[cfg(esp32c3)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Monitor {
+    Monitor0,
+    Monitor1,
+}
+
+/// Threshold crossed by a conversion result
+#[cfg(esp32c3)]
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum MonitorEvent {
+    /// The result was above the high threshold
+    High,
+    /// The result was below the low threshold
+    Low,
 }
 
 pub struct ADC<ADC> {
@@ -208,6 +253,552 @@ where
     }
 }
 
+/// Scaling of the slope of the calibration line
+const COEFF_A_SCALE: u32 = 65536;
+
+impl ADC<ADC1> {
+    /// Read the voltage at `pin` in mV
+    ///
+    /// On the ESP32-C3 the reading is corrected with the calibration values
+    /// burned into the eFuse. The ESP32-C2, and ESP32-C3 chips without these
+    /// values, use the nominal input range of the attenuation instead, which
+    /// is off by up to tens of mV.
+    pub fn read_calibrated_mv<PIN: Channel<ADC1, ID = u8>>(
+        &mut self,
+        pin: &mut AdcPin<PIN, ADC1>,
+    ) -> nb::Result<u16, ()> {
+        let raw: u16 = self.read(pin)?;
+
+        let attenuation = self.attenuations[AdcPin::<PIN, ADC1>::channel() as usize].unwrap();
+        let coeff_a = calibration_coefficient(attenuation);
+
+        Ok(((coeff_a * raw as u32 + COEFF_A_SCALE / 2) / COEFF_A_SCALE) as u16)
+    }
+}
+
+/// Slope (scaled by [COEFF_A_SCALE]) of the line converting raw readings to
+/// mV, based on the nominal full scale voltage at the maximum reading
+fn nominal_coefficient(attenuation: Attenuation) -> u32 {
+    let full_scale_mv = match attenuation {
+        Attenuation::Attenuation0dB => 750,
+        Attenuation::Attenuation2p5dB => 1050,
+        Attenuation::Attenuation6dB => 1300,
+        Attenuation::Attenuation11dB => 2500,
+    };
+
+    full_scale_mv * COEFF_A_SCALE / 4095
+}
+
+/// Slope (scaled by [COEFF_A_SCALE]) of the line converting raw readings to
+/// mV
+#[cfg(esp32c2)]
+fn calibration_coefficient(attenuation: Attenuation) -> u32 {
+    nominal_coefficient(attenuation)
+}
+
+/// Slope (scaled by [COEFF_A_SCALE]) of the line converting raw readings to
+/// mV
+#[cfg(esp32c3)]
+fn calibration_coefficient(attenuation: Attenuation) -> u32 {
+    if Efuse::read_field(EfuseField::BlkVersionMinor) != 1 {
+        return nominal_coefficient(attenuation);
+    }
+
+    // The reading of a known voltage, stored as sign and magnitude relative
+    // to 2000
+    let (field, voltage_mv) = match attenuation {
+        Attenuation::Attenuation0dB => (EfuseField::Adc1CalVolAtten0, 400),
+        Attenuation::Attenuation2p5dB => (EfuseField::Adc1CalVolAtten1, 550),
+        Attenuation::Attenuation6dB => (EfuseField::Adc1CalVolAtten2, 750),
+        Attenuation::Attenuation11dB => (EfuseField::Adc1CalVolAtten3, 1370),
+    };
+
+    let cal = Efuse::read_field(field);
+    let magnitude = cal & 0x1ff;
+    let digi = if cal & 0x200 != 0 {
+        2000 - magnitude
+    } else {
+        2000 + magnitude
+    };
+
+    voltage_mv * COEFF_A_SCALE / digi
+}
+
+/// Lowest sample rate of continuous conversions with an 80 MHz APB clock
+#[cfg(esp32c3)]
+pub const CONTINUOUS_MIN_SAMPLE_RATE: u32 = 611;
+
+/// Highest sample rate of continuous conversions with an 80 MHz APB clock
+#[cfg(esp32c3)]
+pub const CONTINUOUS_MAX_SAMPLE_RATE: u32 = 83333;
+
+/// Divider from the APB clock to the digital controller's conversion clock,
+/// `clkm_div_num + 1` (16) times 2
+#[cfg(esp32c3)]
+const CONTINUOUS_CLOCK_DIVIDER: u32 = 16 * 2;
+
+/// Range of the conversion timer's target, the number of conversion clock
+/// cycles between two samples
+#[cfg(esp32c3)]
+const CONTINUOUS_TIMER_TARGET: core::ops::RangeInclusive<u32> = 30..=4095;
+
+/// Size of a conversion result written by the DMA
+#[cfg(esp32c3)]
+const SAMPLE_SIZE: usize = 4;
+
+/// Errors of continuous conversions
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ContinuousError {
+    /// No pin was enabled in the [AdcConfig]
+    NoPins,
+    /// The sample rate can't be derived from the APB clock, see
+    /// [ADC::continuous]
+    InvalidSampleRate,
+    /// The chunk size isn't a multiple of the sample size (4 bytes)
+    InvalidChunkSize,
+    /// The DMA transfer couldn't be set up
+    Dma(DmaError),
+}
+
+#[cfg(esp32c3)]
+impl From<DmaError> for ContinuousError {
+    fn from(err: DmaError) -> Self {
+        ContinuousError::Dma(err)
+    }
+}
+
+/// A conversion result written by the DMA during continuous conversions
+#[cfg(esp32c3)]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sample {
+    /// Channel of the ADC unit the value was sampled from
+    pub channel: u8,
+    /// The converted value
+    pub value: u16,
+}
+
+#[cfg(esp32c3)]
+impl Sample {
+    fn from_raw(raw: u32) -> Self {
+        Sample {
+            channel: ((raw >> 13) & 0b111) as u8,
+            value: (raw & 0xfff) as u16,
+        }
+    }
+}
+
+#[cfg(esp32c3)]
+impl ADC<ADC1> {
+    /// Switch to continuous conversions streamed to memory by DMA
+    ///
+    /// The pins enabled in the [AdcConfig] are sampled one after the other
+    /// (in the order of their channel numbers) at `sample_rate`, which is the
+    /// rate of single conversions, not of complete rounds over all pins. The
+    /// conversions are timed by the APB clock in `clocks`, divided by 32 and
+    /// by a divider of 30 to 4095, i.e. with an 80 MHz APB clock the sample
+    /// rate has to be between [CONTINUOUS_MIN_SAMPLE_RATE] (611 Hz) and
+    /// [CONTINUOUS_MAX_SAMPLE_RATE] (83.3 kHz).
+    ///
+    /// Only ADC1 supports continuous conversions, the DMA mode of ADC2 is
+    /// broken on the ESP32-C3. The ADC of the ESP32-C2 can't be used with DMA
+    /// at all.
+    pub fn continuous<TX, RX, P>(
+        self,
+        mut channel: DmaChannel<TX, RX, P>,
+        sample_rate: HertzU32,
+        clocks: &Clocks,
+    ) -> Result<AdcContinuous<TX, RX, P>, ContinuousError>
+    where
+        TX: Tx,
+        RX: Rx,
+        P: AdcPeripheral,
+    {
+        let conversion_clock = clocks.apb_clock.raw() / CONTINUOUS_CLOCK_DIVIDER;
+        let timer_target = match sample_rate.raw() {
+            0 => return Err(ContinuousError::InvalidSampleRate),
+            sample_rate => conversion_clock / sample_rate,
+        };
+        if !CONTINUOUS_TIMER_TARGET.contains(&timer_target) {
+            return Err(ContinuousError::InvalidSampleRate);
+        }
+
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+
+        // Build the pattern table from the enabled pins, each entry holds the
+        // unit, channel and attenuation in 6 bits, the first entry in the
+        // upper bits of the first register
+        let mut pattern = [0u32; 2];
+        let mut pattern_len = 0;
+        for (ch, attenuation) in self.attenuations.iter().enumerate() {
+            if let Some(attenuation) = attenuation {
+                let entry = (ADC1::unit() as u32) << 5 | (ch as u32) << 2 | *attenuation as u32;
+                pattern[pattern_len / 4] |= entry << (18 - (pattern_len % 4) * 6);
+                pattern_len += 1;
+            }
+        }
+
+        if pattern_len == 0 {
+            return Err(ContinuousError::NoPins);
+        }
+
+        sar_adc
+            .sar_patt_tab1
+            .write(|w| unsafe { w.saradc_sar_patt_tab1().bits(pattern[0]) });
+        sar_adc
+            .sar_patt_tab2
+            .write(|w| unsafe { w.saradc_sar_patt_tab2().bits(pattern[1]) });
+
+        // Clock the digital controller from the APB clock
+        sar_adc.clkm_conf.modify(|_, w| unsafe {
+            w.clkm_div_num()
+                .bits(15)
+                .clkm_div_a()
+                .bits(0)
+                .clkm_div_b()
+                .bits(1)
+                .clk_sel()
+                .bits(2)
+                .clk_en()
+                .set_bit()
+        });
+
+        sar_adc.ctrl.modify(|_, w| unsafe {
+            w.saradc_sar_patt_len()
+                .bits(pattern_len as u8 - 1)
+                .saradc_sar_patt_p_clear()
+                .set_bit()
+                // Conversions are triggered by the timer from now on
+                .saradc_start_force()
+                .clear_bit()
+                .saradc_start()
+                .clear_bit()
+        });
+        sar_adc
+            .ctrl
+            .modify(|_, w| w.saradc_sar_patt_p_clear().clear_bit());
+
+        sar_adc.ctrl2.modify(|_, w| unsafe {
+            w.saradc_timer_target()
+                .bits(timer_target as u16)
+                .saradc_meas_num_limit()
+                .clear_bit()
+                .saradc_timer_en()
+                .clear_bit()
+        });
+
+        channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+        Ok(AdcContinuous { adc: self, channel })
+    }
+}
+
+/// ADC1 doing continuous conversions, see [ADC::continuous]
+#[cfg(esp32c3)]
+pub struct AdcContinuous<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: AdcPeripheral,
+{
+    adc: ADC<ADC1>,
+    channel: DmaChannel<TX, RX, P>,
+}
+
+#[cfg(esp32c3)]
+impl<TX, RX, P> AdcContinuous<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: AdcPeripheral,
+{
+    /// Start converting into `buffer`, which is used as a ring buffer
+    ///
+    /// The buffer is split into chunks of `chunk_size` bytes, each holding
+    /// `chunk_size / 4` samples. Every completed chunk raises the EOF
+    /// interrupt of the DMA channel, see [AdcContinuousTransfer::listen_eof].
+    /// With two chunks this is a notification for every half of the buffer.
+    pub fn start<BUFFER>(
+        mut self,
+        mut buffer: BUFFER,
+        chunk_size: usize,
+    ) -> Result<AdcContinuousTransfer<TX, RX, P, BUFFER>, ContinuousError>
+    where
+        BUFFER: WriteBuffer<Word = u8>,
+    {
+        if chunk_size % SAMPLE_SIZE != 0 {
+            return Err(ContinuousError::InvalidChunkSize);
+        }
+
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+
+        sar_adc
+            .dma_conf
+            .modify(|_, w| w.apb_adc_reset_fsm().set_bit());
+        sar_adc
+            .dma_conf
+            .modify(|_, w| w.apb_adc_reset_fsm().clear_bit());
+
+        self.channel
+            .rx
+            .prepare_circular_transfer(DmaPeripheral::Adc, ptr, len, chunk_size)?;
+
+        sar_adc.dma_conf.modify(|_, w| unsafe {
+            w.apb_adc_eof_num()
+                .bits((chunk_size / SAMPLE_SIZE) as u16)
+                .apb_adc_trans()
+                .set_bit()
+        });
+        sar_adc.ctrl2.modify(|_, w| w.saradc_timer_en().set_bit());
+
+        Ok(AdcContinuousTransfer { adc: self, buffer })
+    }
+
+    /// Configure a threshold monitor for the given pin
+    ///
+    /// The monitor compares the conversion results of the pin against `low`
+    /// and `high` and raises the `APB_ADC` interrupt when a result is below
+    /// `low` or above `high`. Use [AdcContinuousTransfer::take_monitor_event]
+    /// in the handler to find out which threshold was crossed.
+    ///
+    /// The monitors only see the results of the digital controller, i.e. of
+    /// continuous conversions. One-shot readings never trigger them, which is
+    /// why this is only available here.
+    pub fn set_monitor<PIN: Channel<ADC1, ID = u8>>(
+        &mut self,
+        monitor: Monitor,
+        _pin: &AdcPin<PIN, ADC1>,
+        low: u16,
+        high: u16,
+    ) {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+        let channel = (ADC1::unit() << 3) | AdcPin::<PIN, ADC1>::channel();
+
+        match monitor {
+            Monitor::Monitor0 => {
+                sar_adc.thres0_ctrl.write(|w| unsafe {
+                    w.apb_saradc_thres0_channel()
+                        .bits(channel)
+                        .apb_saradc_thres0_low()
+                        .bits(low)
+                        .apb_saradc_thres0_high()
+                        .bits(high)
+                });
+                sar_adc
+                    .thres_ctrl
+                    .modify(|_, w| w.apb_saradc_thres0_en().set_bit());
+                sar_adc.int_ena.modify(|_, w| {
+                    w.apb_saradc_thres0_low_int_ena()
+                        .set_bit()
+                        .apb_saradc_thres0_high_int_ena()
+                        .set_bit()
+                });
+            }
+            Monitor::Monitor1 => {
+                sar_adc.thres1_ctrl.write(|w| unsafe {
+                    w.apb_saradc_thres1_channel()
+                        .bits(channel)
+                        .apb_saradc_thres1_low()
+                        .bits(low)
+                        .apb_saradc_thres1_high()
+                        .bits(high)
+                });
+                sar_adc
+                    .thres_ctrl
+                    .modify(|_, w| w.apb_saradc_thres1_en().set_bit());
+                sar_adc.int_ena.modify(|_, w| {
+                    w.apb_saradc_thres1_low_int_ena()
+                        .set_bit()
+                        .apb_saradc_thres1_high_int_ena()
+                        .set_bit()
+                });
+            }
+        }
+    }
+
+    /// Disable the given threshold monitor and its interrupts
+    pub fn disable_monitor(&mut self, monitor: Monitor) {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+
+        match monitor {
+            Monitor::Monitor0 => {
+                sar_adc
+                    .thres_ctrl
+                    .modify(|_, w| w.apb_saradc_thres0_en().clear_bit());
+                sar_adc.int_ena.modify(|_, w| {
+                    w.apb_saradc_thres0_low_int_ena()
+                        .clear_bit()
+                        .apb_saradc_thres0_high_int_ena()
+                        .clear_bit()
+                });
+            }
+            Monitor::Monitor1 => {
+                sar_adc
+                    .thres_ctrl
+                    .modify(|_, w| w.apb_saradc_thres1_en().clear_bit());
+                sar_adc.int_ena.modify(|_, w| {
+                    w.apb_saradc_thres1_low_int_ena()
+                        .clear_bit()
+                        .apb_saradc_thres1_high_int_ena()
+                        .clear_bit()
+                });
+            }
+        }
+    }
+
+    /// Return to one-shot conversions and give back the ADC and the DMA
+    /// channel
+    pub fn free(self) -> (ADC<ADC1>, DmaChannel<TX, RX, P>) {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+        sar_adc
+            .ctrl
+            .modify(|_, w| w.saradc_start_force().set_bit().saradc_start().set_bit());
+
+        (self.adc, self.channel)
+    }
+}
+
+/// Running continuous conversions, see [AdcContinuous::start]
+#[cfg(esp32c3)]
+pub struct AdcContinuousTransfer<TX, RX, P, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: AdcPeripheral,
+{
+    adc: AdcContinuous<TX, RX, P>,
+    buffer: BUFFER,
+}
+
+#[cfg(esp32c3)]
+impl<TX, RX, P, BUFFER> AdcContinuousTransfer<TX, RX, P, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: AdcPeripheral,
+{
+    /// Number of samples which were converted but not popped yet
+    pub fn available(&mut self) -> usize {
+        self.adc.channel.rx.circular_available() / SAMPLE_SIZE
+    }
+
+    /// Copy converted samples to `samples`, returns the number of samples
+    /// copied
+    pub fn pop(&mut self, samples: &mut [Sample]) -> usize {
+        let count = usize::min(self.available(), samples.len());
+
+        for sample in samples[..count].iter_mut() {
+            let mut raw = [0u8; SAMPLE_SIZE];
+            self.adc.channel.rx.circular_pop(&mut raw);
+            *sample = Sample::from_raw(u32::from_le_bytes(raw));
+        }
+
+        count
+    }
+
+    /// Return and clear a FIFO overflow, i.e. samples were lost because they
+    /// weren't popped in time
+    pub fn take_error(&mut self) -> Option<DmaError> {
+        self.adc.channel.take_error()
+    }
+
+    /// Enable the EOF interrupt of the DMA channel, raised for every
+    /// completed chunk
+    pub fn listen_eof(&mut self) {
+        self.adc.channel.listen_eof();
+    }
+
+    /// Disable the EOF interrupt of the DMA channel
+    pub fn unlisten_eof(&mut self) {
+        self.adc.channel.unlisten_eof();
+    }
+
+    /// Clear the interrupts of the DMA channel
+    pub fn clear_interrupt(&mut self) {
+        self.adc.channel.clear_interrupts();
+    }
+
+    /// Return and clear the pending event of the given threshold monitor, see
+    /// [AdcContinuous::set_monitor]
+    pub fn take_monitor_event(&mut self, monitor: Monitor) -> Option<MonitorEvent> {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+        let raw = sar_adc.int_raw.read();
+
+        let (high, low) = match monitor {
+            Monitor::Monitor0 => (
+                raw.apb_saradc_thres0_high_int_raw().bit_is_set(),
+                raw.apb_saradc_thres0_low_int_raw().bit_is_set(),
+            ),
+            Monitor::Monitor1 => (
+                raw.apb_saradc_thres1_high_int_raw().bit_is_set(),
+                raw.apb_saradc_thres1_low_int_raw().bit_is_set(),
+            ),
+        };
+
+        match monitor {
+            Monitor::Monitor0 => sar_adc.int_clr.write(|w| {
+                w.apb_saradc_thres0_high_int_clr()
+                    .bit(high)
+                    .apb_saradc_thres0_low_int_clr()
+                    .bit(low)
+            }),
+            Monitor::Monitor1 => sar_adc.int_clr.write(|w| {
+                w.apb_saradc_thres1_high_int_clr()
+                    .bit(high)
+                    .apb_saradc_thres1_low_int_clr()
+                    .bit(low)
+            }),
+        }
+
+        if high {
+            Some(MonitorEvent::High)
+        } else if low {
+            Some(MonitorEvent::Low)
+        } else {
+            None
+        }
+    }
+
+    /// Stop converting and return the buffer and the ADC
+    pub fn stop(mut self) -> (BUFFER, AdcContinuous<TX, RX, P>) {
+        self.halt();
+
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`, see `SpiDmaTransfer::wait`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            let adc = core::ptr::read(&self.adc);
+            core::mem::forget(self);
+            (buffer, adc)
+        }
+    }
+
+    fn halt(&mut self) {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+
+        sar_adc.ctrl2.modify(|_, w| w.saradc_timer_en().clear_bit());
+        sar_adc
+            .dma_conf
+            .modify(|_, w| w.apb_adc_trans().clear_bit());
+        self.adc.channel.reset();
+    }
+}
+
+/// Dropping the transfer stops the conversions and the DMA, so the buffer
+/// isn't written to anymore
+#[cfg(esp32c3)]
+impl<TX, RX, P, BUFFER> Drop for AdcContinuousTransfer<TX, RX, P, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: AdcPeripheral,
+{
+    fn drop(&mut self) {
+        self.halt();
+    }
+}
+
 impl<ADCI, WORD, PIN> OneShot<ADCI, WORD, AdcPin<PIN, ADCI>> for ADC<ADCI>
 where
     WORD: From<u16>,
@@ -395,6 +395,56 @@ where
     }
 }
 
+/// Scaling of the slope of the conversion line
+const COEFF_A_SCALE: u32 = 65536;
+
+/// Highest raw reading
+#[cfg(esp32s2)]
+const MAX_READING: u32 = 8191;
+#[cfg(esp32s3)]
+const MAX_READING: u32 = 4095;
+
+impl<ADCI> ADC<ADCI>
+where
+    ADCI: RegisterAccess,
+{
+    /// Read the voltage at `pin` in mV
+    ///
+    /// The factory calibration of the ESP32-S2 and ESP32-S3 isn't applied
+    /// yet, the reading is converted with the nominal input range of the
+    /// attenuation instead, which is off by up to tens of mV.
+    pub fn read_calibrated_mv<PIN: Channel<ADCI, ID = u8>>(
+        &mut self,
+        pin: &mut AdcPin<PIN, ADCI>,
+    ) -> nb::Result<u16, ()> {
+        let raw: u16 = self.read(pin)?;
+
+        let attenuation = self.attenuations[AdcPin::<PIN, ADCI>::channel() as usize].unwrap();
+        // Nominal full scale voltage at the maximum reading
+        let full_scale_mv = match attenuation {
+            #[cfg(esp32s2)]
+            Attenuation::Attenuation0dB => 750,
+            #[cfg(esp32s2)]
+            Attenuation::Attenuation2p5dB => 1050,
+            #[cfg(esp32s2)]
+            Attenuation::Attenuation6dB => 1300,
+            #[cfg(esp32s2)]
+            Attenuation::Attenuation11dB => 2500,
+            #[cfg(esp32s3)]
+            Attenuation::Attenuation0dB => 950,
+            #[cfg(esp32s3)]
+            Attenuation::Attenuation2p5dB => 1250,
+            #[cfg(esp32s3)]
+            Attenuation::Attenuation6dB => 1750,
+            #[cfg(esp32s3)]
+            Attenuation::Attenuation11dB => 3100,
+        };
+        let coeff_a = full_scale_mv * COEFF_A_SCALE / MAX_READING;
+
+        Ok(((coeff_a * raw as u32 + COEFF_A_SCALE / 2) / COEFF_A_SCALE) as u16)
+    }
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_adc_interface {
@@ -6,6 +6,14 @@
 pub mod adc;
 #[cfg(not(any(esp32c2, esp32c3, esp32s3)))]
 pub mod dac;
+#[cfg(not(esp32))]
+pub mod temperature_sensor;
+
+/// The internal temperature sensor, see [temperature_sensor]
+#[cfg(not(esp32))]
+pub struct TSENS {
+    _private: (),
+}
 
 cfg_if::cfg_if! {
     if #[cfg(any(esp32, esp32s2, esp32s3))] {
@@ -33,6 +41,8 @@ cfg_if::cfg_if! {
             pub adc2: ADC2,
             pub dac1: DAC1,
             pub dac2: DAC2,
+            #[cfg(not(esp32))]
+            pub temperature_sensor: super::TSENS,
         }
 
         /// Extension trait to split a SENS peripheral in independent parts
@@ -55,6 +65,8 @@ cfg_if::cfg_if! {
                     dac2: DAC2 {
                         _private: PhantomData,
                     },
+                    #[cfg(not(esp32))]
+                    temperature_sensor: super::TSENS { _private: () },
                 }
             }
         }
@@ -77,6 +89,7 @@ cfg_if::cfg_if! {
         pub struct AvailableAnalog {
             pub adc1: ADC1,
             pub adc2: ADC2,
+            pub temperature_sensor: super::TSENS,
         }
 
         /// Extension trait to split a APB_SARADC peripheral in independent parts
@@ -93,6 +106,7 @@ cfg_if::cfg_if! {
                     adc2: ADC2 {
                         _private: PhantomData,
                     },
+                    temperature_sensor: super::TSENS { _private: () },
                 }
             }
         }
@@ -111,6 +125,7 @@ cfg_if::cfg_if! {
 
         pub struct AvailableAnalog {
             pub adc1: ADC1,
+            pub temperature_sensor: super::TSENS,
         }
 
         /// Extension trait to split a APB_SARADC peripheral in independent parts
@@ -124,6 +139,7 @@ cfg_if::cfg_if! {
                     adc1: ADC1 {
                         _private: PhantomData,
                     },
+                    temperature_sensor: super::TSENS { _private: () },
                 }
             }
         }
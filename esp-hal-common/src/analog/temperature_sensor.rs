@@ -0,0 +1,244 @@
+//! Internal temperature sensor
+//!
+//! The sensor measures the temperature of the chip itself, not the ambient
+//! temperature. It has several measurement ranges, the accuracy is best in
+//! the middle of the selected range.
+//!
+//! The reading is corrected by the factory calibration value stored in the
+//! eFuses. Chips without a (known version of the) calibration value use the
+//! uncorrected reading.
+//!
+//! Example
+//! ```no_run
+//! let analog = peripherals.APB_SARADC.split();
+//! let mut sensor = TemperatureSensor::new(
+//!     analog.temperature_sensor,
+//!     TemperatureRange::Minus10To80,
+//!     &mut system.peripheral_clock_control,
+//! );
+//! let celsius = sensor.read_celsius();
+//! ```
+
+use paste::paste;
+
+#[cfg(any(esp32s2, esp32s3))]
+use crate::pac::SENS;
+use crate::{
+    analog::TSENS,
+    efuse::{Efuse, EfuseField},
+    regi2c_write_mask,
+    rom::regi2c_ctrl_write_reg_mask,
+    system::PeripheralClockControl,
+};
+#[cfg(any(esp32c2, esp32c3))]
+use crate::{pac::APB_SARADC, system::Peripheral};
+
+const I2C_SAR_ADC: u32 = 0x69;
+#[cfg(any(esp32c2, esp32c3))]
+const I2C_SAR_ADC_HOSTID: u32 = 0;
+#[cfg(any(esp32s2, esp32s3))]
+const I2C_SAR_ADC_HOSTID: u32 = 1;
+
+const I2C_SARADC_TSENS_DAC: u32 = 0x6;
+const I2C_SARADC_TSENS_DAC_MSB: u32 = 3;
+const I2C_SARADC_TSENS_DAC_LSB: u32 = 0;
+
+/// Divider of the sensor clock, the reset value of ESP-IDF
+const TSENS_CLK_DIV: u8 = 6;
+
+const ADC_FACTOR: f32 = 0.4386;
+const DAC_FACTOR: f32 = 27.88;
+const OFFSET_FACTOR: f32 = 20.52;
+
+/// Measurement range of the temperature sensor, in °C
+///
+/// The measurement error is about 1 °C in the [TemperatureRange::Minus10To80]
+/// range, 2 °C in the [TemperatureRange::Minus30To50] and
+/// [TemperatureRange::Plus20To100] ranges and 3 °C in the others.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TemperatureRange {
+    /// -40 °C to 20 °C
+    Minus40To20,
+    /// -30 °C to 50 °C
+    Minus30To50,
+    /// -10 °C to 80 °C
+    Minus10To80,
+    /// 20 °C to 100 °C
+    Plus20To100,
+    /// 50 °C to 125 °C
+    Plus50To125,
+}
+
+impl Default for TemperatureRange {
+    fn default() -> Self {
+        TemperatureRange::Minus10To80
+    }
+}
+
+impl TemperatureRange {
+    /// Value of the sensor's DAC for this range
+    fn dac(self) -> u32 {
+        match self {
+            TemperatureRange::Minus40To20 => 10,
+            TemperatureRange::Minus30To50 => 11,
+            TemperatureRange::Minus10To80 => 15,
+            TemperatureRange::Plus20To100 => 7,
+            TemperatureRange::Plus50To125 => 5,
+        }
+    }
+
+    /// Offset of the conversion formula for this range
+    fn offset(self) -> f32 {
+        match self {
+            TemperatureRange::Minus40To20 => 2.0,
+            TemperatureRange::Minus30To50 => 1.0,
+            TemperatureRange::Minus10To80 => 0.0,
+            TemperatureRange::Plus20To100 => -1.0,
+            TemperatureRange::Plus50To125 => -2.0,
+        }
+    }
+}
+
+/// Internal temperature sensor driver
+pub struct TemperatureSensor {
+    _instance: TSENS,
+    range: TemperatureRange,
+    /// Calibration correction in °C, subtracted from the readings
+    delta: f32,
+}
+
+impl TemperatureSensor {
+    /// Powers up the temperature sensor and selects the measurement range
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn new(
+        instance: TSENS,
+        range: TemperatureRange,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Self {
+        peripheral_clock_control.enable(Peripheral::Tsens);
+
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+        // Use XTAL_CLK as the sensor clock
+        sar_adc
+            .tsens_ctrl2
+            .modify(|_, w| w.tsens_clk_sel().set_bit());
+        sar_adc
+            .apb_tsens_ctrl
+            .modify(|_, w| unsafe { w.tsens_clk_div().bits(TSENS_CLK_DIV).tsens_pu().set_bit() });
+
+        Self::init(instance, range)
+    }
+
+    /// Powers up the temperature sensor and selects the measurement range
+    ///
+    /// The sensor's clock is gated in the SENS peripheral on these chips,
+    /// `peripheral_clock_control` is only taken to keep the signature the same
+    /// on all chips.
+    #[cfg(any(esp32s2, esp32s3))]
+    pub fn new(
+        instance: TSENS,
+        range: TemperatureRange,
+        _peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Self {
+        let sens = unsafe { &*SENS::PTR };
+
+        #[cfg(esp32s2)]
+        sens.sar_tctrl2
+            .modify(|_, w| w.sar_tsens_clkgate_en().set_bit());
+        #[cfg(esp32s3)]
+        {
+            sens.sar_peri_clk_gate_conf
+                .modify(|_, w| w.tsens_en().set_bit());
+            sens.sar_tctrl2
+                .modify(|_, w| w.sar_tsens_xpd_force().set_bit());
+            sens.sar_tctrl
+                .modify(|_, w| w.sar_tsens_power_up_force().set_bit());
+        }
+
+        sens.sar_tctrl.modify(|_, w| unsafe {
+            w.sar_tsens_dump_out()
+                .clear_bit()
+                .sar_tsens_clk_div()
+                .bits(TSENS_CLK_DIV)
+                .sar_tsens_power_up()
+                .set_bit()
+        });
+
+        Self::init(instance, range)
+    }
+
+    fn init(instance: TSENS, range: TemperatureRange) -> Self {
+        let mut sensor = TemperatureSensor {
+            _instance: instance,
+            range,
+            delta: calibration(),
+        };
+        sensor.set_range(range);
+
+        sensor
+    }
+
+    /// Selects the measurement range
+    pub fn set_range(&mut self, range: TemperatureRange) {
+        unsafe {
+            regi2c_write_mask!(I2C_SAR_ADC, I2C_SARADC_TSENS_DAC, range.dac());
+        }
+        self.range = range;
+    }
+
+    /// The currently selected measurement range
+    pub fn range(&self) -> TemperatureRange {
+        self.range
+    }
+
+    /// Reads the raw value of the sensor
+    #[cfg(any(esp32c2, esp32c3))]
+    pub fn read_raw(&mut self) -> u8 {
+        let sar_adc = unsafe { &*APB_SARADC::PTR };
+        sar_adc.apb_tsens_ctrl.read().tsens_out().bits()
+    }
+
+    /// Reads the raw value of the sensor
+    #[cfg(any(esp32s2, esp32s3))]
+    pub fn read_raw(&mut self) -> u8 {
+        let sens = unsafe { &*SENS::PTR };
+
+        sens.sar_tctrl
+            .modify(|_, w| w.sar_tsens_dump_out().set_bit());
+        while sens.sar_tctrl.read().sar_tsens_ready().bit_is_clear() {}
+        let raw = sens.sar_tctrl.read().sar_tsens_out().bits();
+        sens.sar_tctrl
+            .modify(|_, w| w.sar_tsens_dump_out().clear_bit());
+
+        raw
+    }
+
+    /// Reads the temperature of the chip in °C
+    pub fn read_celsius(&mut self) -> f32 {
+        let raw = self.read_raw() as f32;
+
+        ADC_FACTOR * raw - DAC_FACTOR * self.range.offset() - OFFSET_FACTOR - self.delta
+    }
+}
+
+/// Calibration correction stored in the eFuses, in °C
+fn calibration() -> f32 {
+    // Only these versions of the BLOCK2 calibration values are known
+    #[cfg(esp32s2)]
+    const KNOWN_VERSIONS: &[u32] = &[1, 2];
+    #[cfg(not(esp32s2))]
+    const KNOWN_VERSIONS: &[u32] = &[1];
+
+    if !KNOWN_VERSIONS.contains(&Efuse::read_field(EfuseField::BlkVersionMinor)) {
+        return 0.0;
+    }
+
+    // Bit 8 is the sign, the lower bits the magnitude in 0.1 °C
+    let value = Efuse::read_field(EfuseField::TempCalib);
+    let delta = (value & 0xff) as f32 / 10.0;
+    if value & (1 << 8) != 0 {
+        -delta
+    } else {
+        delta
+    }
+}
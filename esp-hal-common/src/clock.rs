@@ -1,4 +1,27 @@
 //! # Clock Control
+//!
+//! ## Locked mode
+//!
+//! Timing critical code (e.g. bit-banged protocols) can use
+//! [ClockControl::freeze_locked] instead of [ClockControl::freeze] to pin the
+//! clock configuration. Once locked, the following operations are rejected
+//! with an error:
+//!
+//! - light and deep sleep (`rtc_cntl::sleep::light_sleep` and
+//!   `rtc_cntl::sleep::deep_sleep`), which stop the CPU clock
+//! - changing the RTC clock sources (`Rtc::set_slow_clock` and
+//!   `Rtc::set_fast_clock`)
+//! - flash operations (`flash::read`, `flash::write` and
+//!   `flash::erase_sector`), which stall the CPU while the flash cache is
+//!   unavailable
+//! - changing the SPI clock source (`Spi::change_clock_source`)
+//! - creating a new `Clocks` instance with different frequencies via
+//!   [Clocks::try_from_raw_clocks] (used by other crates, e.g. esp-wifi, which
+//!   may need to reconfigure the clocks)
+//!
+//! The lock can't be released, it stays in effect until the next reset.
+use core::sync::atomic::{AtomicBool, Ordering};
+
 use fugit::HertzU32;
 
 use crate::system::SystemClockControl;
@@ -10,6 +33,21 @@ use crate::system::SystemClockControl;
 #[cfg_attr(esp32s3, path = "clocks_ll/esp32s3.rs")]
 mod clocks_ll;
 
+static CLOCKS_LOCKED: AtomicBool = AtomicBool::new(false);
+
+/// Clock configuration errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The clock configuration was locked via [ClockControl::freeze_locked]
+    Locked,
+}
+
+/// Returns true if the clock configuration was locked via
+/// [ClockControl::freeze_locked]
+pub fn is_locked() -> bool {
+    CLOCKS_LOCKED.load(Ordering::SeqCst)
+}
+
 pub trait Clock {
     fn frequency(&self) -> HertzU32;
 
@@ -116,6 +154,28 @@ pub struct Clocks {
     // TODO chip specific additional ones as needed
 }
 
+impl Clocks {
+    /// Frequency the CPU runs at
+    pub fn cpu_clock(&self) -> HertzU32 {
+        self.cpu_clock
+    }
+
+    /// Frequency of the APB bus, the source clock of most peripherals
+    pub fn apb_clock(&self) -> HertzU32 {
+        self.apb_clock
+    }
+
+    /// Frequency of the crystal
+    pub fn xtal_clock(&self) -> HertzU32 {
+        self.xtal_clock
+    }
+
+    /// Source clock frequency of the I2C peripherals
+    pub fn i2c_clock(&self) -> HertzU32 {
+        self.i2c_clock
+    }
+}
+
 #[doc(hidden)]
 impl Clocks {
     /// This should not be used in user code.
@@ -131,6 +191,21 @@ impl Clocks {
             i2c_clock: raw_clocks.i2c_clock,
         }
     }
+
+    /// This should not be used in user code.
+    /// Like [Clocks::from_raw_clocks] but refuses to hand out a `Clocks`
+    /// instance with frequencies different from the frozen ones if the clock
+    /// configuration was locked.
+    #[doc(hidden)]
+    pub fn try_from_raw_clocks(&self, raw_clocks: RawClocks) -> Result<Clocks, Error> {
+        if is_locked()
+            && (raw_clocks.cpu_clock != self.cpu_clock || raw_clocks.apb_clock != self.apb_clock)
+        {
+            return Err(Error::Locked);
+        }
+
+        Ok(Self::from_raw_clocks(raw_clocks))
+    }
 }
 
 #[doc(hidden)]
@@ -158,6 +233,15 @@ impl ClockControl {
     pub fn freeze(self) -> Clocks {
         Clocks::from_raw_clocks(self.desired_rates)
     }
+
+    /// Like [ClockControl::freeze] but additionally locks the clock
+    /// configuration, guaranteeing a fixed cycle-to-time relationship until
+    /// the next reset. See the [module level documentation](self) for the
+    /// operations which are rejected in locked mode.
+    pub fn freeze_locked(self) -> Clocks {
+        CLOCKS_LOCKED.store(true, Ordering::SeqCst);
+        self.freeze()
+    }
 }
 
 #[cfg(esp32)]
@@ -0,0 +1,75 @@
+//! CPU cycle counter
+//!
+//! [cycles] extends the 32 bit cycle counter of the current core (`CCOUNT` on
+//! Xtensa, the machine performance counter on RISC-V) to 64 bits. The counter
+//! wraps after 2^32 cycles, i.e. after about 18 seconds at 240 MHz. Wraps are
+//! only detected when [cycles] is called, so it needs to be called at least
+//! once per wrap period to stay monotonic.
+//!
+//! The counters of the two cores of the ESP32 and ESP32-S3 aren't
+//! synchronized and the wrap detection is shared, so only use [cycles] on one
+//! core.
+//!
+//! ```no_run
+//! let start = Instant::now();
+//! // ... code to measure
+//! println!("took {} us", start.elapsed(&clocks).to_micros());
+//! ```
+
+use core::cell::Cell;
+
+use critical_section::Mutex;
+use fugit::MicrosDurationU64;
+
+use crate::{clock::Clocks, delay::cycle_count};
+
+/// Last value of the hardware counter and the number of wraps seen so far
+static CYCLES: Mutex<Cell<(u32, u32)>> = Mutex::new(Cell::new((0, 0)));
+
+/// Number of CPU cycles, extended to 64 bits, see the [module level
+/// documentation](self) for the wrap behavior
+pub fn cycles() -> u64 {
+    critical_section::with(|cs| {
+        let cycles = CYCLES.borrow(cs);
+        let (last, mut wraps) = cycles.get();
+
+        let now = cycle_count();
+        if now < last {
+            wraps = wraps.wrapping_add(1);
+        }
+        cycles.set((now, wraps));
+
+        (wraps as u64) << 32 | now as u64
+    })
+}
+
+/// A point in time measured in CPU cycles, see [cycles]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub struct Instant {
+    cycles: u64,
+}
+
+impl Instant {
+    /// The current point in time
+    pub fn now() -> Self {
+        Self { cycles: cycles() }
+    }
+
+    /// Number of CPU cycles since [Instant::now] was called
+    pub fn elapsed_cycles(&self) -> u64 {
+        cycles().wrapping_sub(self.cycles)
+    }
+
+    /// Time since [Instant::now] was called
+    ///
+    /// The cycles are converted with the CPU frequency in `clocks`, which
+    /// needs to match the frequency the CPU was running at in the meantime.
+    pub fn elapsed(&self, clocks: &Clocks) -> MicrosDurationU64 {
+        MicrosDurationU64::micros(self.elapsed_cycles() / clocks.cpu_clock.to_MHz() as u64)
+    }
+
+    /// Raw number of CPU cycles at this point in time
+    pub fn cycles(&self) -> u64 {
+        self.cycles
+    }
+}
@@ -4,10 +4,12 @@ use core::marker::PhantomData;
 
 use xtensa_lx::set_stack_pointer;
 
-use crate::Cpu;
+use crate::{get_core, pac::Interrupt, Cpu};
 
 static mut START_CORE1_FUNCTION: Option<&'static mut (dyn FnMut() + 'static)> = None;
 
+static mut START_CORE1_STACK: Option<*mut u32> = None;
+
 /// Will park the APP (second) core when dropped
 #[must_use]
 pub struct AppCoreGuard<'a> {
@@ -64,10 +66,35 @@ impl CpuControl {
     }
 
     /// Park the given core
+    ///
+    /// The core is stalled until it gets unparked again. Parking an already
+    /// parked core has no effect.
+    ///
+    /// # Safety
+    ///
+    /// Parking the current core stops execution, anything the parked core
+    /// holds (e.g. a lock) stays taken until it is unparked.
     pub unsafe fn park_core(&mut self, core: Cpu) {
         internal_park_core(core);
     }
 
+    /// Check if the given core is parked
+    pub fn is_core_parked(&self, core: Cpu) -> bool {
+        let rtc_control = crate::pac::RTC_CNTL::PTR;
+        let rtc_control = unsafe { &*rtc_control };
+
+        match core {
+            Cpu::ProCpu => {
+                rtc_control.sw_cpu_stall.read().sw_stall_procpu_c1().bits() == 0x21
+                    && rtc_control.options0.read().sw_stall_procpu_c0().bits() == 0x02
+            }
+            Cpu::AppCpu => {
+                rtc_control.sw_cpu_stall.read().sw_stall_appcpu_c1().bits() == 0x21
+                    && rtc_control.options0.read().sw_stall_appcpu_c0().bits() == 0x02
+            }
+        }
+    }
+
     /// Unpark the given core
     pub fn unpark_core(&mut self, core: Cpu) {
         let rtc_control = crate::pac::RTC_CNTL::PTR;
@@ -107,7 +134,10 @@ impl CpuControl {
         xtensa_lx::timer::set_ccompare2(0);
 
         // set stack pointer to end of memory: no need to retain stack up to this point
-        set_stack_pointer(&mut _stack_end_cpu1);
+        match START_CORE1_STACK.take() {
+            Some(stack_end) => set_stack_pointer(stack_end),
+            None => set_stack_pointer(&mut _stack_end_cpu1),
+        }
 
         match START_CORE1_FUNCTION.take() {
             Some(entry) => (*entry)(),
@@ -119,9 +149,23 @@ impl CpuControl {
 
     /// Start the APP (second) core
     ///
-    /// The second core will start running the closure `entry`.
+    /// The second core will start running the closure `entry` on the stack
+    /// reserved for it in the linker script.
     ///
     /// Dropping the returned guard will park the core.
+    ///
+    /// ```rust,ignore
+    /// let mut cpu_control = CpuControl::new(system.cpu_control);
+    /// let mut cpu1_fnctn = || loop {
+    ///     led.toggle().unwrap();
+    ///     delay.delay_ms(500u32);
+    /// };
+    /// let _guard = cpu_control.start_app_core(&mut cpu1_fnctn).unwrap();
+    ///
+    /// loop {
+    ///     // core 0 keeps running here
+    /// }
+    /// ```
     pub fn start_app_core(
         &mut self,
         entry: &mut (dyn FnMut() + Send),
@@ -172,4 +216,90 @@ impl CpuControl {
             phantom: PhantomData::default(),
         })
     }
+
+    /// Start the APP (second) core on the given stack
+    ///
+    /// Like [CpuControl::start_app_core] but the core uses `stack` instead of
+    /// the region reserved in the linker script, e.g. to give it a bigger
+    /// stack.
+    pub fn start_app_core_with_stack(
+        &mut self,
+        stack: &'static mut [u8],
+        entry: &mut (dyn FnMut() + Send),
+    ) -> Result<AppCoreGuard, Error> {
+        // the stack grows downwards and needs to be 16 byte aligned
+        let stack_end = (stack.as_mut_ptr() as usize + stack.len()) & !0xf;
+
+        unsafe {
+            START_CORE1_STACK = Some(stack_end as *mut u32);
+        }
+
+        let res = self.start_app_core(entry);
+        if res.is_err() {
+            unsafe {
+                START_CORE1_STACK = None;
+            }
+        }
+
+        res
+    }
+}
+
+/// Peripheral interrupt raised on the given core by [signal_core]
+///
+/// Define a handler for it on the receiving core (e.g. `#[interrupt] fn
+/// FROM_CPU_INTR1()` for the APP core) and acknowledge the signal with
+/// [clear_signal].
+pub fn signal_interrupt(core: Cpu) -> Interrupt {
+    match core {
+        Cpu::ProCpu => Interrupt::FROM_CPU_INTR0,
+        Cpu::AppCpu => Interrupt::FROM_CPU_INTR1,
+    }
+}
+
+/// Raise the inter-core interrupt of the given core
+pub fn signal_core(core: Cpu) {
+    let system = unsafe { &*crate::pac::SYSTEM::PTR };
+
+    match core {
+        Cpu::ProCpu => system
+            .cpu_intr_from_cpu_0
+            .write(|w| w.cpu_intr_from_cpu_0().set_bit()),
+        Cpu::AppCpu => system
+            .cpu_intr_from_cpu_1
+            .write(|w| w.cpu_intr_from_cpu_1().set_bit()),
+    }
+}
+
+/// Raise the inter-core interrupt of the other core
+pub fn signal_other_core() {
+    match get_core() {
+        Cpu::ProCpu => signal_core(Cpu::AppCpu),
+        Cpu::AppCpu => signal_core(Cpu::ProCpu),
+    }
+}
+
+/// Acknowledge the inter-core interrupt of the current core
+///
+/// Needs to be called from the handler, otherwise the interrupt fires again.
+pub fn clear_signal() {
+    let system = unsafe { &*crate::pac::SYSTEM::PTR };
+
+    match get_core() {
+        Cpu::ProCpu => system
+            .cpu_intr_from_cpu_0
+            .write(|w| w.cpu_intr_from_cpu_0().clear_bit()),
+        Cpu::AppCpu => system
+            .cpu_intr_from_cpu_1
+            .write(|w| w.cpu_intr_from_cpu_1().clear_bit()),
+    }
+}
+
+/// Enable the inter-core interrupt on the current core
+#[cfg(feature = "vectored")]
+pub fn listen_signal(
+    interrupt_controller: &mut crate::interrupt::InterruptController,
+    level: crate::interrupt::Priority,
+) -> Result<(), crate::interrupt::Error> {
+    interrupt_controller.enable(signal_interrupt(get_core()), level)
 }
@@ -0,0 +1,296 @@
+//! # Cyclic Redundancy Checks (CRC)
+//!
+//! ### Summary
+//! None of the supported chips has a CRC peripheral. On the ESP32, ESP32-C3
+//! and ESP32-S3 the checksums are computed by the `crc32_le`, `crc16_le` and
+//! `crc8_le` functions in ROM. The ROM functions of the ESP32-C2 and ESP32-S2
+//! aren't mapped yet, there lookup tables placed in flash implement the same
+//! algorithms. Either way the results match the ROM functions called with an
+//! initial value of 0, so checksums computed by the bootloader or by ESP-IDF
+//! applications can be verified.
+//!
+//! Both one-shot functions and incremental hashers are provided. The
+//! incremental ones allow checking data while it's still being received, e.g.
+//! by feeding every buffer of a circular DMA transfer as soon as its
+//! descriptor completed.
+//!
+//! ### Example
+//! ```no_run
+//! let checksum = crc::crc32(&data);
+//!
+//! let mut crc = crc::Crc32::new();
+//! crc.update(&data[..16]);
+//! crc.update(&data[16..]);
+//! assert_eq!(crc.finish(), checksum);
+//! ```
+
+#![deny(missing_docs)]
+
+#[cfg(any(esp32, esp32c3, esp32s3))]
+use self::rom::{crc16_update, crc32_update, crc8_update};
+#[cfg(not(any(esp32, esp32c3, esp32s3)))]
+use self::table::{crc16_update, crc32_update, crc8_update};
+
+#[cfg(not(any(esp32, esp32c3, esp32s3)))]
+mod table {
+    /// Reversed CRC-32 (IEEE 802.3) polynomial
+    const CRC32_POLY: u32 = 0xEDB8_8320;
+    /// Reversed CRC-16 (CCITT) polynomial
+    const CRC16_POLY: u16 = 0x8408;
+    /// Reversed CRC-8 (0x07) polynomial
+    const CRC8_POLY: u8 = 0xE0;
+
+    static CRC32_TABLE: [u32; 256] = crc32_table();
+    static CRC16_TABLE: [u16; 256] = crc16_table();
+    static CRC8_TABLE: [u8; 256] = crc8_table();
+
+    const fn crc32_table() -> [u32; 256] {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u32;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ CRC32_POLY
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    }
+
+    const fn crc16_table() -> [u16; 256] {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u16;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ CRC16_POLY
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    }
+
+    const fn crc8_table() -> [u8; 256] {
+        let mut table = [0; 256];
+        let mut i = 0;
+        while i < 256 {
+            let mut crc = i as u8;
+            let mut bit = 0;
+            while bit < 8 {
+                crc = if crc & 1 != 0 {
+                    (crc >> 1) ^ CRC8_POLY
+                } else {
+                    crc >> 1
+                };
+                bit += 1;
+            }
+            table[i] = crc;
+            i += 1;
+        }
+        table
+    }
+
+    pub(super) fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+        data.iter().fold(crc, |crc, &byte| {
+            CRC32_TABLE[((crc ^ byte as u32) & 0xff) as usize] ^ (crc >> 8)
+        })
+    }
+
+    pub(super) fn crc16_update(crc: u16, data: &[u8]) -> u16 {
+        data.iter().fold(crc, |crc, &byte| {
+            CRC16_TABLE[((crc ^ byte as u16) & 0xff) as usize] ^ (crc >> 8)
+        })
+    }
+
+    pub(super) fn crc8_update(crc: u8, data: &[u8]) -> u8 {
+        data.iter()
+            .fold(crc, |crc, &byte| CRC8_TABLE[(crc ^ byte) as usize])
+    }
+}
+
+#[cfg(any(esp32, esp32c3, esp32s3))]
+mod rom {
+    // The ROM functions invert the checksum on entry and on return, the hashers
+    // keep it inverted in between.
+    pub(super) fn crc32_update(crc: u32, data: &[u8]) -> u32 {
+        #[cfg(esp32)]
+        const ROM_CRC32_LE: u32 = 0x4005_cfec;
+        #[cfg(esp32c3)]
+        const ROM_CRC32_LE: u32 = 0x4000_060c;
+        #[cfg(esp32s3)]
+        const ROM_CRC32_LE: u32 = 0x4000_1c98;
+
+        // cast to usize is just needed because of the way we run clippy in CI
+        let rom_crc32_le: extern "C" fn(crc: u32, buf: *const u8, len: u32) -> u32 =
+            unsafe { core::mem::transmute(ROM_CRC32_LE as usize) };
+
+        !rom_crc32_le(!crc, data.as_ptr(), data.len() as u32)
+    }
+
+    pub(super) fn crc16_update(crc: u16, data: &[u8]) -> u16 {
+        #[cfg(esp32)]
+        const ROM_CRC16_LE: u32 = 0x4005_d05c;
+        #[cfg(esp32c3)]
+        const ROM_CRC16_LE: u32 = 0x4000_0614;
+        #[cfg(esp32s3)]
+        const ROM_CRC16_LE: u32 = 0x4000_1cb0;
+
+        // cast to usize is just needed because of the way we run clippy in CI
+        let rom_crc16_le: extern "C" fn(crc: u16, buf: *const u8, len: u32) -> u16 =
+            unsafe { core::mem::transmute(ROM_CRC16_LE as usize) };
+
+        !rom_crc16_le(!crc, data.as_ptr(), data.len() as u32)
+    }
+
+    pub(super) fn crc8_update(crc: u8, data: &[u8]) -> u8 {
+        #[cfg(esp32)]
+        const ROM_CRC8_LE: u32 = 0x4005_d0e0;
+        #[cfg(esp32c3)]
+        const ROM_CRC8_LE: u32 = 0x4000_061c;
+        #[cfg(esp32s3)]
+        const ROM_CRC8_LE: u32 = 0x4000_1cc8;
+
+        // cast to usize is just needed because of the way we run clippy in CI
+        let rom_crc8_le: extern "C" fn(crc: u8, buf: *const u8, len: u32) -> u8 =
+            unsafe { core::mem::transmute(ROM_CRC8_LE as usize) };
+
+        !rom_crc8_le(!crc, data.as_ptr(), data.len() as u32)
+    }
+}
+
+/// Incremental CRC-32 (IEEE 802.3, as used by zlib and Ethernet)
+#[derive(Debug, Clone, Copy)]
+pub struct Crc32 {
+    crc: u32,
+}
+
+impl Crc32 {
+    /// Start a new checksum
+    pub const fn new() -> Self {
+        Self::with_initial(0)
+    }
+
+    /// Continue a checksum previously returned by [Crc32::finish]
+    pub const fn with_initial(crc: u32) -> Self {
+        Crc32 { crc: !crc }
+    }
+
+    /// Feed `data` into the checksum
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = crc32_update(self.crc, data);
+    }
+
+    /// Return the checksum of all data fed so far
+    pub fn finish(&self) -> u32 {
+        !self.crc
+    }
+}
+
+impl Default for Crc32 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental CRC-16 (CCITT polynomial, reflected, as used by X.25 and HDLC)
+#[derive(Debug, Clone, Copy)]
+pub struct Crc16 {
+    crc: u16,
+}
+
+impl Crc16 {
+    /// Start a new checksum
+    pub const fn new() -> Self {
+        Self::with_initial(0)
+    }
+
+    /// Continue a checksum previously returned by [Crc16::finish]
+    pub const fn with_initial(crc: u16) -> Self {
+        Crc16 { crc: !crc }
+    }
+
+    /// Feed `data` into the checksum
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = crc16_update(self.crc, data);
+    }
+
+    /// Return the checksum of all data fed so far
+    pub fn finish(&self) -> u16 {
+        !self.crc
+    }
+}
+
+impl Default for Crc16 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Incremental CRC-8 (polynomial 0x07, reflected, inverted like [Crc32])
+#[derive(Debug, Clone, Copy)]
+pub struct Crc8 {
+    crc: u8,
+}
+
+impl Crc8 {
+    /// Start a new checksum
+    pub const fn new() -> Self {
+        Self::with_initial(0)
+    }
+
+    /// Continue a checksum previously returned by [Crc8::finish]
+    pub const fn with_initial(crc: u8) -> Self {
+        Crc8 { crc: !crc }
+    }
+
+    /// Feed `data` into the checksum
+    pub fn update(&mut self, data: &[u8]) {
+        self.crc = crc8_update(self.crc, data);
+    }
+
+    /// Return the checksum of all data fed so far
+    pub fn finish(&self) -> u8 {
+        !self.crc
+    }
+}
+
+impl Default for Crc8 {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Compute the CRC-32 of `data`, see [Crc32]
+pub fn crc32(data: &[u8]) -> u32 {
+    let mut crc = Crc32::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// Compute the CRC-16 of `data`, see [Crc16]
+pub fn crc16(data: &[u8]) -> u16 {
+    let mut crc = Crc16::new();
+    crc.update(data);
+    crc.finish()
+}
+
+/// Compute the CRC-8 of `data`, see [Crc8]
+pub fn crc8(data: &[u8]) -> u8 {
+    let mut crc = Crc8::new();
+    crc.update(data);
+    crc.finish()
+}
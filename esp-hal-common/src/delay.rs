@@ -2,18 +2,64 @@
 //!
 //! Implement the `DelayMs` and `DelayUs` traits from [embedded-hal].
 //!
+//! [Delay] busy-waits and only guarantees a minimum delay: interrupts
+//! occurring while waiting extend it. On RISC-V chips it counts systimer ticks
+//! (16 MHz), so it is independent of the CPU clock and resolves 62.5 ns. On
+//! Xtensa chips it counts CPU cycles at the CPU frequency in the [Clocks]
+//! passed to [Delay::new], create a new instance when the CPU frequency
+//! changes.
+//!
 //! [embedded-hal]: https://docs.rs/embedded-hal/latest/embedded_hal/
 
+pub(crate) use self::delay::cycle_count;
 pub use self::delay::Delay;
+use crate::clock::Clocks;
+
+/// Busy-wait for (at least) `n` CPU cycles
+///
+/// This uses the CPU cycle counter and is meant for very short delays, e.g.
+/// when bit-banging protocols with tight timing. Interrupts occurring while
+/// waiting will extend the delay, so this is not a substitute for timing done
+/// by peripherals.
+#[inline(always)]
+pub fn delay_cycles(n: u32) {
+    let start = delay::cycle_count();
+
+    while delay::cycle_count().wrapping_sub(start) < n {}
+}
+
+/// Busy-wait for (at least) `ns` nanoseconds
+///
+/// The number of cycles is derived from the CPU frequency in `clocks`, this
+/// is accurate down to a few tens of nanoseconds. The same caveats as for
+/// [delay_cycles] apply.
+#[inline(always)]
+pub fn delay_ns_short(ns: u32, clocks: &Clocks) {
+    delay_ns(ns, clocks.cpu_clock.raw() as u64);
+}
+
+#[inline(always)]
+fn delay_ns(ns: u32, cpu_freq: u64) {
+    let cycles = (ns as u64 * cpu_freq + 999_999_999) / 1_000_000_000;
+
+    delay_cycles(cycles as u32);
+}
+
+impl Delay {
+    /// Delay for the specified number of milliseconds
+    pub fn delay_millis(&self, ms: u32) {
+        for _ in 0..ms {
+            self.delay(1000u32);
+        }
+    }
+}
 
 impl<T> embedded_hal::blocking::delay::DelayMs<T> for Delay
 where
     T: Into<u32>,
 {
     fn delay_ms(&mut self, ms: T) {
-        for _ in 0..ms.into() {
-            self.delay(1000u32);
-        }
+        self.delay_millis(ms.into());
     }
 }
 
@@ -48,6 +94,7 @@ mod delay {
     /// how we would normally do this.
     pub struct Delay {
         freq: HertzU64,
+        cpu_freq: HertzU64,
     }
 
     impl Delay {
@@ -59,6 +106,7 @@ mod delay {
 
             Self {
                 freq: HertzU64::MHz((clocks.xtal_clock.to_MHz() * 10 / 25) as u64),
+                cpu_freq: HertzU64::Hz(clocks.cpu_clock.raw() as u64),
             }
         }
 
@@ -69,6 +117,31 @@ mod delay {
 
             while SystemTimer::now().wrapping_sub(t0) <= clocks {}
         }
+
+        /// Delay for (at least) the specified number of nanoseconds
+        ///
+        /// This counts CPU cycles like [delay_ns_short](super::delay_ns_short)
+        /// rather than systimer ticks, which would only resolve 62.5 ns.
+        pub fn delay_nanos(&self, ns: u32) {
+            super::delay_ns(ns, self.cpu_freq.raw());
+        }
+    }
+
+    /// Read the machine performance counter, which is set up to count CPU
+    /// cycles
+    ///
+    /// The ESP32-C2/C3 don't implement `mcycle`, the vendor specific
+    /// performance counter CSRs are used instead.
+    #[inline(always)]
+    pub(crate) fn cycle_count() -> u32 {
+        let count: u32;
+        unsafe {
+            // select counting CPU cycles (MPCER) and enable the counter (MPCMR)
+            core::arch::asm!("csrw 0x7e0, {0}", in(reg) 1);
+            core::arch::asm!("csrw 0x7e1, {0}", in(reg) 1);
+            core::arch::asm!("csrr {0}, 0x7e2", out(reg) count);
+        }
+        count
     }
 }
 
@@ -98,5 +171,16 @@ mod delay {
             let clocks = (us as u64 * self.freq.raw()) / HertzU64::MHz(1).raw();
             xtensa_lx::timer::delay(clocks as u32);
         }
+
+        /// Delay for (at least) the specified number of nanoseconds
+        pub fn delay_nanos(&self, ns: u32) {
+            super::delay_ns(ns, self.freq.raw());
+        }
+    }
+
+    /// Read the CCOUNT register
+    #[inline(always)]
+    pub(crate) fn cycle_count() -> u32 {
+        xtensa_lx::timer::get_cycle_count()
     }
 }
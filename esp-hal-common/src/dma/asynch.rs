@@ -0,0 +1,377 @@
+//! Awaiting DMA transfer completion
+//!
+//! The futures in this module enable the EOF interrupt of their channel while
+//! the transfer is in progress. The interrupt handler(s) of the DMA need to
+//! call [handle_interrupt] which wakes the tasks waiting for a completed
+//! transfer, e.g. on ESP32-C3:
+//!
+//! ```rust,ignore
+//! #[interrupt]
+//! fn DMA_CH0() {
+//!     esp_hal_common::dma::asynch::handle_interrupt();
+//! }
+//! ```
+//!
+//! The handlers are `DMA_CHn` on ESP32-C2 and ESP32-C3, `DMA_IN_CHn` and
+//! `DMA_OUT_CHn` on ESP32-S3 and `SPI2_DMA`/`SPI3_DMA` on ESP32 and ESP32-S2.
+//!
+//! Besides waiting for transfers started by a driver, a single direction of a
+//! channel can run a transfer on its own:
+//!
+//! ```rust,ignore
+//! // SAFETY: the future is awaited to completion and not forgotten
+//! unsafe { channel.tx().transfer_async(DmaPeripheral::Spi2, &buffer) }.await?;
+//! ```
+//!
+//! These transfers are `unsafe`: the DMA only stops accessing the buffer when
+//! the future completes or is dropped. If the future is leaked (e.g. via
+//! `mem::forget`) the DMA keeps using the buffer after its borrow ended.
+
+use core::{
+    cell::RefCell,
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll, Waker},
+};
+
+use critical_section::Mutex;
+
+use super::{
+    private::{ChannelRx, ChannelTx, RegisterAccess, Rx, RxChannel, Tx, TxChannel},
+    Channel,
+    DmaError,
+    DmaInterrupt,
+    DmaPeripheral,
+    PeripheralMarker,
+    CHANNEL_COUNT,
+};
+
+const NO_WAKER: Mutex<RefCell<Option<Waker>>> = Mutex::new(RefCell::new(None));
+
+static TX_WAKERS: [Mutex<RefCell<Option<Waker>>>; CHANNEL_COUNT] = [NO_WAKER; CHANNEL_COUNT];
+static RX_WAKERS: [Mutex<RefCell<Option<Waker>>>; CHANNEL_COUNT] = [NO_WAKER; CHANNEL_COUNT];
+
+fn register(waker: &Mutex<RefCell<Option<Waker>>>, new: &Waker) {
+    critical_section::with(|cs| {
+        let mut waker = waker.borrow_ref_mut(cs);
+        match waker.as_ref() {
+            Some(old) if old.will_wake(new) => {}
+            _ => *waker = Some(new.clone()),
+        }
+    });
+}
+
+fn wake(waker: &Mutex<RefCell<Option<Waker>>>) {
+    if let Some(waker) = critical_section::with(|cs| waker.borrow_ref_mut(cs).take()) {
+        waker.wake();
+    }
+}
+
+/// Future completing once the TX side of a channel is done
+pub struct DmaTxFuture<'a, TX> {
+    tx: &'a mut TX,
+}
+
+impl<'a, TX> DmaTxFuture<'a, TX>
+where
+    TX: Tx,
+{
+    /// Wait for the transfer already started on `tx` to be done
+    ///
+    /// The future doesn't stop the transfer when it's dropped.
+    pub fn new(tx: &'a mut TX) -> Self {
+        Self { tx }
+    }
+}
+
+impl<'a, TX> Future for DmaTxFuture<'a, TX>
+where
+    TX: Tx,
+{
+    type Output = Result<(), DmaError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        poll_tx(self.get_mut().tx, cx)
+    }
+}
+
+fn poll_tx<TX: Tx>(tx: &mut TX, cx: &mut Context<'_>) -> Poll<Result<(), DmaError>> {
+    // register before checking, otherwise a completion in between is missed
+    register(&TX_WAKERS[tx.channel_index()], cx.waker());
+
+    if tx.pending_interrupt() == Some(DmaInterrupt::DescriptorError) {
+        tx.listen_eof(false);
+        Poll::Ready(Err(DmaError::DescriptorError))
+    } else if tx.is_done() {
+        tx.listen_eof(false);
+        Poll::Ready(Ok(()))
+    } else {
+        tx.listen_eof(true);
+        Poll::Pending
+    }
+}
+
+/// Future completing once the RX side of a channel is done
+pub struct DmaRxFuture<'a, RX> {
+    rx: &'a mut RX,
+}
+
+impl<'a, RX> DmaRxFuture<'a, RX>
+where
+    RX: Rx,
+{
+    /// Wait for the transfer already started on `rx` to be done
+    ///
+    /// The future doesn't stop the transfer when it's dropped.
+    pub fn new(rx: &'a mut RX) -> Self {
+        Self { rx }
+    }
+}
+
+impl<'a, RX> Future for DmaRxFuture<'a, RX>
+where
+    RX: Rx,
+{
+    type Output = Result<(), DmaError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        poll_rx(self.get_mut().rx, cx)
+    }
+}
+
+fn poll_rx<RX: Rx>(rx: &mut RX, cx: &mut Context<'_>) -> Poll<Result<(), DmaError>> {
+    // register before checking, otherwise a completion in between is missed
+    register(&RX_WAKERS[rx.channel_index()], cx.waker());
+
+    if rx.pending_interrupt() == Some(DmaInterrupt::DescriptorError) {
+        rx.listen_eof(false);
+        Poll::Ready(Err(DmaError::DescriptorError))
+    } else if rx.is_done() {
+        rx.listen_eof(false);
+        Poll::Ready(Ok(()))
+    } else {
+        rx.listen_eof(true);
+        Poll::Pending
+    }
+}
+
+/// Future returned by [ChannelTx::transfer_async]
+///
+/// Dropping it before the transfer completed resets the TX side of the
+/// channel, so the DMA stops reading the buffer.
+pub struct DmaTxTransferFuture<'a, TX>
+where
+    TX: Tx,
+{
+    tx: &'a mut TX,
+    done: bool,
+}
+
+impl<'a, TX> Future for DmaTxTransferFuture<'a, TX>
+where
+    TX: Tx,
+{
+    type Output = Result<(), DmaError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let result = poll_tx(this.tx, cx);
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, TX> Drop for DmaTxTransferFuture<'a, TX>
+where
+    TX: Tx,
+{
+    fn drop(&mut self) {
+        if !self.done {
+            self.tx.listen_eof(false);
+            self.tx.reset();
+        }
+    }
+}
+
+/// Future returned by [ChannelRx::transfer_async]
+///
+/// Dropping it before the transfer completed resets the RX side of the
+/// channel, so the DMA stops writing to the buffer.
+pub struct DmaRxTransferFuture<'a, RX>
+where
+    RX: Rx,
+{
+    rx: &'a mut RX,
+    done: bool,
+}
+
+impl<'a, RX> Future for DmaRxTransferFuture<'a, RX>
+where
+    RX: Rx,
+{
+    type Output = Result<(), DmaError>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let this = self.get_mut();
+        let result = poll_rx(this.rx, cx);
+        this.done = result.is_ready();
+        result
+    }
+}
+
+impl<'a, RX> Drop for DmaRxTransferFuture<'a, RX>
+where
+    RX: Rx,
+{
+    fn drop(&mut self) {
+        if !self.done {
+            self.rx.listen_eof(false);
+            self.rx.reset();
+        }
+    }
+}
+
+impl<'a, T, R> ChannelTx<'a, T, R>
+where
+    T: TxChannel<R>,
+    R: RegisterAccess,
+{
+    /// Send `buffer` to `peripheral` and wait until the DMA read all of it
+    ///
+    /// The peripheral needs to be set up to consume the data separately.
+    /// Errors of the transfer setup are returned by the future, so are
+    /// descriptor errors while the transfer is running.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must either be polled to completion or be dropped.
+    /// It must not be leaked (e.g. with `mem::forget`), otherwise the DMA
+    /// keeps reading `buffer` after the borrow ended.
+    pub async unsafe fn transfer_async(
+        &mut self,
+        peripheral: DmaPeripheral,
+        buffer: &[u8],
+    ) -> Result<(), DmaError> {
+        self.prepare_transfer(peripheral, buffer.as_ptr(), buffer.len())?;
+
+        DmaTxTransferFuture {
+            tx: self,
+            done: false,
+        }
+        .await
+    }
+}
+
+impl<'a, T, R> ChannelRx<'a, T, R>
+where
+    T: RxChannel<R>,
+    R: RegisterAccess,
+{
+    /// Receive data from `peripheral` into `buffer` and wait until the
+    /// transfer is done
+    ///
+    /// The transfer is done once the buffer is full or the peripheral ended
+    /// the frame earlier, the number of bytes received is returned. The
+    /// peripheral needs to be set up to deliver the data separately.
+    ///
+    /// # Safety
+    ///
+    /// The returned future must either be polled to completion or be dropped.
+    /// It must not be leaked (e.g. with `mem::forget`), otherwise the DMA
+    /// keeps writing to `buffer` after the borrow ended.
+    pub async unsafe fn transfer_async(
+        &mut self,
+        peripheral: DmaPeripheral,
+        buffer: &mut [u8],
+    ) -> Result<usize, DmaError> {
+        self.prepare_transfer(peripheral, buffer.as_mut_ptr(), buffer.len())?;
+
+        DmaRxTransferFuture {
+            rx: self,
+            done: false,
+        }
+        .await?;
+
+        Ok(self.received_length())
+    }
+}
+
+impl<TX, RX, P> Channel<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// The TX side of this channel, to run a transfer with
+    /// [ChannelTx::transfer_async]
+    pub fn tx(&mut self) -> &mut TX {
+        &mut self.tx
+    }
+
+    /// The RX side of this channel, to run a transfer with
+    /// [ChannelRx::transfer_async]
+    pub fn rx(&mut self) -> &mut RX {
+        &mut self.rx
+    }
+
+    /// Wait for both directions of this channel to be done
+    ///
+    /// Like [Channel::is_done] a direction which wasn't started is considered
+    /// to be done.
+    pub async fn wait_async(&mut self) -> Result<(), DmaError> {
+        if self.tx.is_started() {
+            DmaTxFuture::new(&mut self.tx).await?;
+        }
+
+        if self.rx.is_started() {
+            DmaRxFuture::new(&mut self.rx).await?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Disable the EOF interrupts which fired and wake the waiting tasks
+///
+/// The status bits are left untouched so the futures see the completed
+/// transfer when they are polled again.
+fn handle_channel<R: RegisterAccess>() {
+    if R::is_listening_out_eof() && (R::is_out_done() || R::has_out_descriptor_error()) {
+        R::listen_out_eof(false);
+        wake(&TX_WAKERS[R::channel_index()]);
+    }
+
+    if R::is_listening_in_eof() && (R::is_in_done() || R::has_in_descriptor_error()) {
+        R::listen_in_eof(false);
+        wake(&RX_WAKERS[R::channel_index()]);
+    }
+}
+
+/// Wake the tasks waiting for a completed transfer
+///
+/// Needs to be called from the DMA interrupt handler(s), see the [module level
+/// documentation](self).
+pub fn handle_interrupt() {
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    {
+        use super::gdma::private::*;
+
+        handle_channel::<Channel0>();
+        #[cfg(not(esp32c2))]
+        handle_channel::<Channel1>();
+        #[cfg(not(esp32c2))]
+        handle_channel::<Channel2>();
+        #[cfg(esp32s3)]
+        handle_channel::<Channel3>();
+        #[cfg(esp32s3)]
+        handle_channel::<Channel4>();
+    }
+
+    #[cfg(any(esp32, esp32s2))]
+    {
+        use super::pdma::private::*;
+
+        handle_channel::<Spi2DmaChannel>();
+        handle_channel::<Spi3DmaChannel>();
+    }
+}
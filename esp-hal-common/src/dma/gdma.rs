@@ -15,6 +15,10 @@ macro_rules! impl_channel {
                     // nothing special to be done here
                 }
 
+                fn channel_index() -> usize {
+                    $num
+                }
+
                 fn set_out_burstmode(burst_mode: bool) {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -45,6 +49,18 @@ macro_rules! impl_channel {
                     });
                 }
 
+                fn get_out_priority() -> DmaPriority {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<out_pri_ch $num>].read().[<tx_pri_ch $num>]().bits();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<out_pri_ch $num>].read().tx_pri_ch().bits();
+
+                    ret.into()
+                }
+
                 fn clear_out_interrupts() {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -144,6 +160,77 @@ macro_rules! impl_channel {
                     dma.[<out_link_ch $num>].modify(|_, w| w.outlink_start_ch().set_bit());
                 }
 
+                fn is_out_idle() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<out_link_ch $num>].read().[<outlink_park_ch $num>]().bit_is_set();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<out_link_ch $num>].read().outlink_park_ch().bit_is_set();
+
+                    ret
+                }
+
+                fn listen_out_descriptor_done(enable: bool) {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_ena_ch $num>].modify(|_, w| w.[<out_done_ch $num _int_ena>]().bit(enable));
+
+                    #[cfg(esp32s3)]
+                    dma.[<out_int_ena_ch $num>].modify(|_, w| w.out_done_ch_int_ena().bit(enable));
+                }
+
+                fn take_out_descriptor_done() -> Option<u32> {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let done = dma.[<int_raw_ch $num>].read().[<out_done_ch $num _int_raw>]().bit();
+                    #[cfg(esp32s3)]
+                    let done = dma.[<out_int_raw_ch $num>].read().out_done_ch_int_raw().bit();
+
+                    if !done {
+                        return None;
+                    }
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_clr_ch $num>].write(|w| w.[<out_done_ch $num _int_clr>]().set_bit());
+                    #[cfg(esp32s3)]
+                    dma.[<out_int_clr_ch $num>].write(|w| w.out_done_ch_int_clr().set_bit());
+
+                    #[cfg(not(esp32s3))]
+                    let address = dma.[<out_dscr_bf0_ch $num>].read().[<outlink_dscr_bf0_ch $num>]().bits();
+                    #[cfg(esp32s3)]
+                    let address = dma.[<out_dscr_bf0_ch $num>].read().outlink_dscr_bf0_ch().bits();
+
+                    Some(address)
+                }
+
+                fn take_out_fifo_underflow() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_raw_ch $num>].read().[<outfifo_udf_ch $num _int_raw>]().bit();
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_clr_ch $num>].write(|w| w.[<outfifo_udf_ch $num _int_clr>]().set_bit());
+
+                    #[cfg(esp32s3)]
+                    let ret = {
+                        let raw = dma.[<out_int_raw_ch $num>].read();
+                        raw.outfifo_udf_l1_ch_int_raw().bit() || raw.outfifo_udf_l3_ch_int_raw().bit()
+                    };
+                    #[cfg(esp32s3)]
+                    dma.[<out_int_clr_ch $num>].write(|w| {
+                        w.outfifo_udf_l1_ch_int_clr()
+                            .set_bit()
+                            .outfifo_udf_l3_ch_int_clr()
+                            .set_bit()
+                    });
+
+                    ret
+                }
+
                 fn is_out_done() -> bool {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -156,6 +243,38 @@ macro_rules! impl_channel {
                     ret
                 }
 
+                fn listen_out_eof(enable: bool) {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_ena_ch $num>].modify(|_, w| {
+                        w.[<out_total_eof_ch $num _int_ena>]()
+                            .bit(enable)
+                            .[<out_dscr_err_ch $num _int_ena>]()
+                            .bit(enable)
+                    });
+
+                    #[cfg(esp32s3)]
+                    dma.[<out_int_ena_ch $num>].modify(|_, w| {
+                        w.out_total_eof_ch_int_ena()
+                            .bit(enable)
+                            .out_dscr_err_ch_int_ena()
+                            .bit(enable)
+                    });
+                }
+
+                fn is_listening_out_eof() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_ena_ch $num>].read().[<out_total_eof_ch $num _int_ena>]().bit();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<out_int_ena_ch $num>].read().out_total_eof_ch_int_ena().bit();
+
+                    ret
+                }
+
                 fn set_in_burstmode(burst_mode: bool) {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -185,6 +304,18 @@ macro_rules! impl_channel {
                     });
                 }
 
+                fn get_in_priority() -> DmaPriority {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<in_pri_ch $num>].read().[<rx_pri_ch $num>]().bits();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<in_pri_ch $num>].read().rx_pri_ch().bits();
+
+                    ret.into()
+                }
+
                 fn clear_in_interrupts() {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -266,6 +397,30 @@ macro_rules! impl_channel {
                     ret
                 }
 
+                fn has_in_descriptor_empty() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_raw_ch $num>].read().[<in_dscr_empty_ch $num _int_raw>]().bit();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<in_int_raw_ch $num>].read().in_dscr_empty_ch_int_raw().bit();
+
+                    ret
+                }
+
+                fn has_in_err_eof() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_raw_ch $num>].read().[<in_err_eof_ch $num _int_raw>]().bit();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<in_int_raw_ch $num>].read().in_err_eof_ch_int_raw().bit();
+
+                    ret
+                }
+
                 fn set_in_peripheral(peripheral: u8) {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -288,6 +443,87 @@ macro_rules! impl_channel {
                     dma.[<in_link_ch $num>].modify(|_, w| w.inlink_start_ch().set_bit());
                 }
 
+                fn is_in_idle() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<in_link_ch $num>].read().[<inlink_park_ch $num>]().bit_is_set();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<in_link_ch $num>].read().inlink_park_ch().bit_is_set();
+
+                    ret
+                }
+
+                fn listen_in_descriptor_done(enable: bool) {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_ena_ch $num>].modify(|_, w| w.[<in_done_ch $num _int_ena>]().bit(enable));
+
+                    #[cfg(esp32s3)]
+                    dma.[<in_int_ena_ch $num>].modify(|_, w| w.in_done_ch_int_ena().bit(enable));
+                }
+
+                fn set_mem2mem_mode(enable: bool) {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<in_conf0_ch $num>].modify(|_, w| w.[<mem_trans_en_ch $num>]().bit(enable));
+
+                    #[cfg(esp32s3)]
+                    dma.[<in_conf0_ch $num>].modify(|_, w| w.mem_trans_en_ch().bit(enable));
+                }
+
+                fn take_in_descriptor_done() -> Option<u32> {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let done = dma.[<int_raw_ch $num>].read().[<in_done_ch $num _int_raw>]().bit();
+                    #[cfg(esp32s3)]
+                    let done = dma.[<in_int_raw_ch $num>].read().in_done_ch_int_raw().bit();
+
+                    if !done {
+                        return None;
+                    }
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_clr_ch $num>].write(|w| w.[<in_done_ch $num _int_clr>]().set_bit());
+                    #[cfg(esp32s3)]
+                    dma.[<in_int_clr_ch $num>].write(|w| w.in_done_ch_int_clr().set_bit());
+
+                    #[cfg(not(esp32s3))]
+                    let address = dma.[<in_dscr_bf0_ch $num>].read().[<inlink_dscr_bf0_ch $num>]().bits();
+                    #[cfg(esp32s3)]
+                    let address = dma.[<in_dscr_bf0_ch $num>].read().inlink_dscr_bf0_ch().bits();
+
+                    Some(address)
+                }
+
+                fn take_in_fifo_overflow() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_raw_ch $num>].read().[<infifo_ovf_ch $num _int_raw>]().bit();
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_clr_ch $num>].write(|w| w.[<infifo_ovf_ch $num _int_clr>]().set_bit());
+
+                    #[cfg(esp32s3)]
+                    let ret = {
+                        let raw = dma.[<in_int_raw_ch $num>].read();
+                        raw.infifo_ovf_l1_ch_int_raw().bit() || raw.infifo_ovf_l3_ch_int_raw().bit()
+                    };
+                    #[cfg(esp32s3)]
+                    dma.[<in_int_clr_ch $num>].write(|w| {
+                        w.infifo_ovf_l1_ch_int_clr()
+                            .set_bit()
+                            .infifo_ovf_l3_ch_int_clr()
+                            .set_bit()
+                    });
+
+                    ret
+                }
+
                 fn is_in_done() -> bool {
                     let dma = unsafe { &*crate::pac::DMA::PTR };
 
@@ -299,6 +535,38 @@ macro_rules! impl_channel {
 
                     ret
                 }
+
+                fn listen_in_eof(enable: bool) {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    dma.[<int_ena_ch $num>].modify(|_, w| {
+                        w.[<in_suc_eof_ch $num _int_ena>]()
+                            .bit(enable)
+                            .[<in_dscr_err_ch $num _int_ena>]()
+                            .bit(enable)
+                    });
+
+                    #[cfg(esp32s3)]
+                    dma.[<in_int_ena_ch $num>].modify(|_, w| {
+                        w.in_suc_eof_ch_int_ena()
+                            .bit(enable)
+                            .in_dscr_err_ch_int_ena()
+                            .bit(enable)
+                    });
+                }
+
+                fn is_listening_in_eof() -> bool {
+                    let dma = unsafe { &*crate::pac::DMA::PTR };
+
+                    #[cfg(not(esp32s3))]
+                    let ret = dma.[<int_ena_ch $num>].read().[<in_suc_eof_ch $num _int_ena>]().bit();
+
+                    #[cfg(esp32s3)]
+                    let ret = dma.[<in_int_ena_ch $num>].read().in_suc_eof_ch_int_ena().bit();
+
+                    ret
+                }
             }
 
             pub struct [<Channel $num TxImpl>] {}
@@ -312,6 +580,13 @@ macro_rules! impl_channel {
             pub struct [<ChannelCreator $num>] {}
 
             impl [<ChannelCreator $num>] {
+                /// Configure the channel
+                ///
+                /// `priority` only matters while several channels are active at
+                /// the same time, the channel with the higher priority is
+                /// granted the bus first. Giving a continuous stream a higher
+                /// priority avoids FIFO overflows or underflows when other
+                /// channels are busy.
                 pub fn configure<'a>(
                     self,
                     burst_mode: bool,
@@ -326,6 +601,7 @@ macro_rules! impl_channel {
                         descriptors: tx_descriptors,
                         burst_mode,
                         tx_impl: tx_impl,
+                        started: false,
                         _phantom: PhantomData::default(),
                     };
 
@@ -336,23 +612,53 @@ macro_rules! impl_channel {
                         descriptors: rx_descriptors,
                         burst_mode,
                         rx_impl: rx_impl,
+                        started: false,
+                        ring_words: 0,
+                        read_descr: 0,
+                        read_offset: 0,
                         _phantom: PhantomData::default(),
                     };
 
                     Channel {
                         tx: tx_channel,
                         rx: rx_channel,
+                        marked_descriptors: 0,
                         _phantom: PhantomData::default(),
                     }
                 }
             }
 
+            impl<'a> Channel<ChannelTx<'a, [<Channel $num TxImpl>], [<Channel $num>]>, ChannelRx<'a, [<Channel $num RxImpl>], [<Channel $num>]>, [<SuitablePeripheral $num>]> {
+                /// Reset the channel and give back the channel creator
+                ///
+                /// The creator can be configured again, e.g. with other
+                /// descriptors or for another peripheral.
+                pub fn free(mut self) -> [<ChannelCreator $num>] {
+                    self.clear_on_complete();
+                    self.unlisten_descriptor_done();
+                    self.rx.set_mem2mem(false);
+                    self.reset();
+
+                    [<ChannelCreator $num>] {}
+                }
+            }
+
             pub struct [<SuitablePeripheral $num>] {}
             impl PeripheralMarker for [<SuitablePeripheral $num>] {}
 
             // with GDMA every channel can be used for any peripheral
             impl SpiPeripheral for [<SuitablePeripheral $num>] {}
             impl Spi2Peripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(esp32s3)]
+            impl Spi3Peripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(esp32c3)]
+            impl AdcPeripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(any(esp32c3, esp32s3))]
+            impl UhciPeripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(any(esp32c3, esp32s3))]
+            impl I2s0Peripheral for [<SuitablePeripheral $num>] {}
+            #[cfg(esp32s3)]
+            impl LcdCamPeripheral for [<SuitablePeripheral $num>] {}
         }
     };
 }
@@ -372,6 +678,30 @@ pub(crate) mod private {
     impl_channel!(4);
 }
 
+/// Number of GDMA channels available on this chip
+#[cfg(esp32c2)]
+pub(crate) const CHANNEL_COUNT: usize = 1;
+#[cfg(esp32c3)]
+pub(crate) const CHANNEL_COUNT: usize = 3;
+#[cfg(esp32s3)]
+pub(crate) const CHANNEL_COUNT: usize = 5;
+
+/// Any of the available channel creators
+///
+/// Allows code which is meant to run on different chips to use whatever
+/// channel is available instead of naming a specific one.
+pub enum AnyChannelCreator {
+    Channel0(ChannelCreator0),
+    #[cfg(not(esp32c2))]
+    Channel1(ChannelCreator1),
+    #[cfg(not(esp32c2))]
+    Channel2(ChannelCreator2),
+    #[cfg(esp32s3)]
+    Channel3(ChannelCreator3),
+    #[cfg(esp32s3)]
+    Channel4(ChannelCreator4),
+}
+
 /// GDMA Peripheral
 ///
 /// This offers the available DMA channels.
@@ -412,4 +742,46 @@ impl Gdma {
             channel4: ChannelCreator4 {},
         }
     }
+
+    /// Reset the AHB bus master shared by all channels
+    ///
+    /// This pulses `ahbm_rst_inter` like [Gdma::new] does, which resets the
+    /// internal state machine of the DMA's AHB interface. The configuration
+    /// and descriptor pointers of the channels are kept, use
+    /// [Channel::reset](super::Channel::reset) to reset a single channel.
+    ///
+    /// # Safety
+    ///
+    /// The bus master is shared by all channels, including the ones already
+    /// handed to drivers. A transfer in progress on any channel may be left
+    /// incomplete, so no channel may be in use while calling this.
+    pub unsafe fn reset_all() {
+        let dma = unsafe { &*crate::pac::DMA::PTR };
+
+        dma.misc_conf.modify(|_, w| w.ahbm_rst_inter().set_bit());
+        dma.misc_conf.modify(|_, w| w.ahbm_rst_inter().clear_bit());
+        dma.misc_conf.modify(|_, w| w.clk_en().set_bit());
+    }
+
+    /// Number of DMA channels available on this chip
+    pub const fn channel_count() -> usize {
+        CHANNEL_COUNT
+    }
+
+    /// Iterate over all channel creators
+    pub fn into_channel_creators(self) -> impl Iterator<Item = AnyChannelCreator> {
+        let creators: [AnyChannelCreator; CHANNEL_COUNT] = [
+            AnyChannelCreator::Channel0(self.channel0),
+            #[cfg(not(esp32c2))]
+            AnyChannelCreator::Channel1(self.channel1),
+            #[cfg(not(esp32c2))]
+            AnyChannelCreator::Channel2(self.channel2),
+            #[cfg(esp32s3)]
+            AnyChannelCreator::Channel3(self.channel3),
+            #[cfg(esp32s3)]
+            AnyChannelCreator::Channel4(self.channel4),
+        ];
+
+        creators.into_iter()
+    }
 }
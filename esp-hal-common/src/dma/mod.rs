@@ -1,7 +1,11 @@
 //! Direct Memory Access Commons
 
-use core::{marker::PhantomData, sync::atomic::compiler_fence};
+use core::{cell::Cell, marker::PhantomData, sync::atomic::compiler_fence};
 
+use critical_section::Mutex;
+use embedded_dma::{ReadBuffer, WriteBuffer};
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+use gdma::CHANNEL_COUNT;
 use private::*;
 
 #[cfg(any(esp32c2, esp32c3, esp32s3))]
@@ -10,18 +14,70 @@ pub mod gdma;
 #[cfg(any(esp32, esp32s2))]
 pub mod pdma;
 
+#[cfg(feature = "async")]
+pub mod asynch;
+
+/// Number of SPI DMA channels
+#[cfg(any(esp32, esp32s2))]
+const CHANNEL_COUNT: usize = 2;
+
+const NO_CALLBACK: Mutex<Cell<Option<fn()>>> = Mutex::new(Cell::new(None));
+
+/// Callbacks registered via [Channel::set_on_complete], by channel index
+static ON_COMPLETE: [Mutex<Cell<Option<fn()>>>; CHANNEL_COUNT] = [NO_CALLBACK; CHANNEL_COUNT];
+
+fn on_complete(channel_index: usize) -> Option<fn()> {
+    critical_section::with(|cs| ON_COMPLETE[channel_index].borrow(cs).get())
+}
+
 /// DMA Errors
 #[derive(Debug, Clone, Copy)]
 pub enum DmaError {
-    InvalidAlignment,
+    /// Burst mode is enabled but the buffer isn't word aligned
+    ///
+    /// RX transfers need the buffer address and length to be a multiple of 4,
+    /// TX transfers only the buffer address.
+    BufferNotAligned,
     OutOfDescriptors,
     InvalidDescriptorSize,
     DescriptorError,
+    /// The peripheral delivered more data than the RX descriptors could hold,
+    /// the remainder was dropped
+    DescriptorEmpty,
+    /// The channel is still busy with a transfer
+    Busy,
+    /// The peripheral delivered data faster than the DMA could write it to
+    /// memory, data was lost
+    FifoOverflow,
+    /// The peripheral consumed data faster than the DMA could read it from
+    /// memory
+    FifoUnderflow,
+    /// The transfer didn't complete in time
+    Timeout,
+    /// The peripheral ended the received frame with an error
+    ErrorEof,
+}
+
+/// Interrupt source of one direction of a DMA channel
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DmaInterrupt {
+    /// The transfer completed
+    Eof,
+    /// The DMA encountered an invalid descriptor
+    DescriptorError,
 }
 
 /// DMA Priorities
+///
+/// When several channels compete for the bus, the channel with the higher
+/// priority is served first. Channels with equal priority are served in a
+/// round-robin fashion. TX and RX of a channel have their own priority, both
+/// are set to the same value by `configure`.
+///
+/// The GDMA supports priorities 0 to 9, the variants cover exactly that range
+/// so no invalid value can be written to the `tx_pri`/`rx_pri` fields.
 #[cfg(any(esp32c2, esp32c3, esp32s3))]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DmaPriority {
     Priority0 = 0,
     Priority1 = 1,
@@ -35,10 +91,34 @@ pub enum DmaPriority {
     Priority9 = 9,
 }
 
+/// Valid values are 0 to 9. Out of range values trigger a debug assertion and
+/// are clamped to [DmaPriority::Priority9] in release builds.
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+impl From<u8> for DmaPriority {
+    fn from(value: u8) -> Self {
+        debug_assert!(value <= 9, "DMA priority {} is out of range", value);
+
+        match value {
+            0 => DmaPriority::Priority0,
+            1 => DmaPriority::Priority1,
+            2 => DmaPriority::Priority2,
+            3 => DmaPriority::Priority3,
+            4 => DmaPriority::Priority4,
+            5 => DmaPriority::Priority5,
+            6 => DmaPriority::Priority6,
+            7 => DmaPriority::Priority7,
+            8 => DmaPriority::Priority8,
+            _ => DmaPriority::Priority9,
+        }
+    }
+}
+
 /// DMA Priorities
 /// The values need to match the TRM
+///
+/// The SPI DMA of the ESP32 and ESP32-S2 doesn't support priorities.
 #[cfg(any(esp32, esp32s2))]
-#[derive(Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq)]
 pub enum DmaPriority {
     Priority0 = 0,
 }
@@ -48,8 +128,10 @@ pub enum DmaPriority {
 #[cfg(esp32c2)]
 #[derive(Clone, Copy)]
 pub enum DmaPeripheral {
-    Spi2 = 0,
-    Sha  = 7,
+    Spi2    = 0,
+    /// Unused peripheral ID selected for memory-to-memory transfers
+    Mem2Mem = 1,
+    Sha     = 7,
 }
 
 /// DMA capable peripherals
@@ -57,12 +139,14 @@ pub enum DmaPeripheral {
 #[cfg(esp32c3)]
 #[derive(Clone, Copy)]
 pub enum DmaPeripheral {
-    Spi2  = 0,
-    Uhci0 = 2,
-    I2s   = 3,
-    Aes   = 6,
-    Sha   = 7,
-    Adc   = 8,
+    Spi2    = 0,
+    /// Unused peripheral ID selected for memory-to-memory transfers
+    Mem2Mem = 1,
+    Uhci0   = 2,
+    I2s     = 3,
+    Aes     = 6,
+    Sha     = 7,
+    Adc     = 8,
 }
 
 /// DMA capable peripherals
@@ -79,16 +163,18 @@ pub enum DmaPeripheral {
 #[cfg(esp32s3)]
 #[derive(Clone, Copy)]
 pub enum DmaPeripheral {
-    Spi2   = 0,
-    Spi3   = 1,
-    Uhci0  = 2,
-    I2s0   = 3,
-    I2s1   = 4,
-    LcdCam = 5,
-    Aes    = 6,
-    Sha    = 7,
-    Adc    = 8,
-    Rmt    = 9,
+    Spi2    = 0,
+    Spi3    = 1,
+    Uhci0   = 2,
+    I2s0    = 3,
+    I2s1    = 4,
+    LcdCam  = 5,
+    Aes     = 6,
+    Sha     = 7,
+    Adc     = 8,
+    Rmt     = 9,
+    /// Unused peripheral ID selected for memory-to-memory transfers
+    Mem2Mem = 10,
 }
 
 enum Owner {
@@ -184,6 +270,147 @@ impl DmaLinkedListDw0 for &mut u32 {
     }
 }
 
+/// Maximum number of bytes covered by a single descriptor
+pub const MAX_DESCRIPTOR_SIZE: usize = 4092;
+
+/// Number of `u32` words needed for the descriptors of a `len` byte buffer
+///
+/// Every descriptor takes 3 words and covers up to [MAX_DESCRIPTOR_SIZE]
+/// bytes.
+pub const fn descriptor_words(len: usize) -> usize {
+    (len + MAX_DESCRIPTOR_SIZE - 1) / MAX_DESCRIPTOR_SIZE * 3
+}
+
+/// Fill `descriptors` with a linked chain of descriptors sending the data of
+/// `buffer`
+///
+/// The channels do this on their own when a transfer is prepared, this is
+/// meant for drivers which hand descriptors to the hardware directly.
+///
+/// Returns [DmaError::InvalidDescriptorSize] if the length of `descriptors`
+/// isn't a multiple of 3 and [DmaError::OutOfDescriptors] if it's shorter than
+/// [descriptor_words] for the buffer.
+pub fn build_tx_descriptor_chain(descriptors: &mut [u32], buffer: &[u8]) -> Result<(), DmaError> {
+    build_descriptor_chain(descriptors, buffer.as_ptr() as u32, buffer.len(), true)
+}
+
+/// Fill `descriptors` with a linked chain of descriptors receiving into
+/// `buffer`
+///
+/// See [build_tx_descriptor_chain] for the errors.
+pub fn build_rx_descriptor_chain(
+    descriptors: &mut [u32],
+    buffer: &mut [u8],
+) -> Result<(), DmaError> {
+    build_descriptor_chain(descriptors, buffer.as_mut_ptr() as u32, buffer.len(), false)
+}
+
+fn build_descriptor_chain(
+    descriptors: &mut [u32],
+    data: u32,
+    len: usize,
+    tx: bool,
+) -> Result<(), DmaError> {
+    if descriptors.len() % 3 != 0 {
+        return Err(DmaError::InvalidDescriptorSize);
+    }
+
+    if descriptors.len() < descriptor_words(len) {
+        return Err(DmaError::OutOfDescriptors);
+    }
+
+    fill_chain(descriptors, data, len, MAX_DESCRIPTOR_SIZE, tx)?;
+    Ok(())
+}
+
+/// Number of `u32` words needed for a ring of descriptors covering `len` bytes
+/// in pieces of `chunk_size` bytes
+fn ring_descriptor_words(len: usize, chunk_size: usize) -> usize {
+    (len + chunk_size - 1) / chunk_size * 3
+}
+
+/// Fill the descriptors like [fill_chain] and link the last descriptor back to
+/// the first one
+///
+/// No descriptor is marked as end of frame so the DMA keeps cycling through
+/// the buffer until the channel is reset.
+fn fill_ring(
+    descriptors: &mut [u32],
+    data: u32,
+    len: usize,
+    chunk_size: usize,
+    tx: bool,
+) -> Result<(), DmaError> {
+    let used = fill_chain(descriptors, data, len, chunk_size, tx)?;
+
+    #[cfg(not(esp32))]
+    for descr in descriptors[..used].chunks_mut(3) {
+        let mut dw0 = &mut descr[0];
+        dw0.set_suc_eof(false);
+    }
+
+    descriptors[used - 1] = descriptors.as_ptr() as u32;
+    Ok(())
+}
+
+/// Returns the number of descriptor words used
+///
+/// Even an empty buffer needs one descriptor, [DmaError::OutOfDescriptors] is
+/// returned if there is none.
+fn fill_chain(
+    descriptors: &mut [u32],
+    data: u32,
+    len: usize,
+    chunk_size: usize,
+    tx: bool,
+) -> Result<usize, DmaError> {
+    if descriptors.len() < 3 {
+        return Err(DmaError::OutOfDescriptors);
+    }
+
+    for descr in descriptors.iter_mut() {
+        *descr = 0;
+    }
+
+    compiler_fence(core::sync::atomic::Ordering::SeqCst);
+
+    let mut processed = 0;
+    let mut descr = 0;
+    loop {
+        let chunk_size = usize::min(chunk_size, len - processed);
+        let last = processed + chunk_size >= len;
+
+        descriptors[descr + 1] = data + processed as u32;
+
+        let mut dw0 = &mut descriptors[descr];
+
+        #[cfg(not(esp32))]
+        dw0.set_suc_eof(last);
+
+        dw0.set_owner(Owner::Dma);
+        dw0.set_size(chunk_size as u16); // align to 32 bits?
+
+        // for TX the length is the amount of data to send, for RX it's updated by the
+        // DMA
+        dw0.set_length(if tx { chunk_size as u16 } else { 0 });
+
+        if !last {
+            descriptors[descr + 2] = (&descriptors[descr + 3]) as *const _ as *const () as u32;
+        } else {
+            descriptors[descr + 2] = 0;
+        }
+
+        processed += chunk_size;
+        descr += 3;
+
+        if processed >= len {
+            break;
+        }
+    }
+
+    Ok(descr)
+}
+
 /// Crate private implementatin details
 pub(crate) mod private {
     use super::*;
@@ -200,6 +427,22 @@ pub(crate) mod private {
     #[cfg(any(esp32, esp32s2, esp32s3))]
     pub trait Spi3Peripheral: SpiPeripheral + PeripheralMarker {}
 
+    /// Marks channels as useable for the ADC
+    #[cfg(esp32c3)]
+    pub trait AdcPeripheral: PeripheralMarker {}
+
+    /// Marks channels as useable for the UHCI
+    #[cfg(any(esp32c3, esp32s3))]
+    pub trait UhciPeripheral: PeripheralMarker {}
+
+    /// Marks channels as useable for I2S0
+    #[cfg(any(esp32c3, esp32s3))]
+    pub trait I2s0Peripheral: PeripheralMarker {}
+
+    /// Marks channels as useable for the LCD_CAM
+    #[cfg(esp32s3)]
+    pub trait LcdCamPeripheral: PeripheralMarker {}
+
     /// DMA Rx
     ///
     /// The functions here are not meant to be used outside the HAL and will be
@@ -209,6 +452,8 @@ pub(crate) mod private {
 
         fn init_channel(&mut self);
 
+        fn channel_index(&self) -> usize;
+
         fn prepare_transfer(
             &mut self,
             peri: DmaPeripheral,
@@ -217,6 +462,78 @@ pub(crate) mod private {
         ) -> Result<(), DmaError>;
 
         fn is_done(&mut self) -> bool;
+
+        fn priority(&self) -> DmaPriority;
+
+        fn reset(&mut self);
+
+        /// Whether a transfer was started since the channel was created or
+        /// reset
+        fn is_started(&self) -> bool;
+
+        /// Return and clear a FIFO overflow/underflow which occurred since the
+        /// last call
+        fn take_error(&mut self) -> Option<DmaError>;
+
+        fn listen_eof(&mut self, enable: bool);
+
+        fn is_listening_eof(&self) -> bool;
+
+        fn clear_interrupts(&mut self);
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt>;
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool;
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral);
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool);
+
+        /// Index of the most recently completed descriptor, if any descriptor
+        /// completed since the last call
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self) -> Option<usize>;
+
+        /// Route the data of the paired TX channel to this channel instead of
+        /// a peripheral
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_mem2mem(&mut self, enable: bool);
+
+        /// Check if the channel ran out of descriptors while receiving
+        fn has_dscr_empty_error(&mut self) -> bool;
+
+        /// Check if the peripheral ended the received frame with an error
+        fn has_err_eof(&mut self) -> bool;
+
+        /// Number of bytes written by the DMA during the last transfer
+        fn received_length(&mut self) -> usize;
+
+        /// Start receiving into `data` in a loop
+        ///
+        /// The buffer is split into descriptors of `chunk_size` bytes which
+        /// are linked to a ring, the DMA keeps cycling through them until the
+        /// channel is reset. There need to be at least two descriptors so
+        /// one can be read while the DMA fills the next.
+        fn prepare_circular_transfer(
+            &mut self,
+            peri: DmaPeripheral,
+            data: *mut u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError>;
+
+        /// Number of bytes of a circular transfer which were received but not
+        /// popped yet
+        fn circular_available(&mut self) -> usize;
+
+        /// Copy received data of a circular transfer to `buf`
+        ///
+        /// Descriptors which were read completely are handed back to the DMA.
+        /// Returns the number of bytes copied.
+        fn circular_pop(&mut self, buf: &mut [u8]) -> usize;
     }
 
     pub trait RxChannel<R>
@@ -228,6 +545,28 @@ pub(crate) mod private {
             R::set_in_priority(priority);
         }
 
+        fn listen_eof(&mut self, enable: bool) {
+            R::listen_in_eof(enable);
+        }
+
+        fn is_listening_eof(&self) -> bool {
+            R::is_listening_in_eof()
+        }
+
+        fn clear_interrupts(&mut self) {
+            R::clear_in_interrupts();
+        }
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt> {
+            if R::has_in_descriptor_error() {
+                Some(DmaInterrupt::DescriptorError)
+            } else if R::is_in_done() {
+                Some(DmaInterrupt::Eof)
+            } else {
+                None
+            }
+        }
+
         fn prepare_transfer(
             &mut self,
             descriptors: &mut [u32],
@@ -235,43 +574,84 @@ pub(crate) mod private {
             data: *mut u8,
             len: usize,
         ) -> Result<(), DmaError> {
-            for descr in descriptors.iter_mut() {
-                *descr = 0;
-            }
+            fill_chain(descriptors, data as u32, len, MAX_DESCRIPTOR_SIZE, false)?;
 
-            compiler_fence(core::sync::atomic::Ordering::SeqCst);
+            R::clear_in_interrupts();
+            R::reset_in();
+            R::set_in_descriptors(descriptors.as_ptr() as u32);
+            R::set_in_peripheral(peri as u8);
+            R::start_in();
 
-            let mut processed = 0;
-            let mut descr = 0;
-            loop {
-                let chunk_size = usize::min(4092, len - processed);
-                let last = processed + chunk_size >= len;
+            if R::has_in_descriptor_error() {
+                return Err(DmaError::DescriptorError);
+            }
 
-                descriptors[descr + 1] = data as u32 + processed as u32;
+            Ok(())
+        }
 
-                let mut dw0 = &mut descriptors[descr];
+        fn is_done(&mut self) -> bool {
+            R::is_in_done()
+        }
 
-                #[cfg(not(esp32))]
-                dw0.set_suc_eof(last);
+        fn has_dscr_empty_error(&mut self) -> bool {
+            R::has_in_descriptor_empty()
+        }
 
-                dw0.set_owner(Owner::Dma);
-                dw0.set_size(chunk_size as u16); // align to 32 bits?
-                dw0.set_length(0); // actual size of the data!?
+        fn has_err_eof(&mut self) -> bool {
+            R::has_in_err_eof()
+        }
 
-                if !last {
-                    descriptors[descr + 2] =
-                        (&descriptors[descr + 3]) as *const _ as *const () as u32;
-                } else {
-                    descriptors[descr + 2] = 0;
-                }
+        fn priority(&self) -> DmaPriority {
+            R::get_in_priority()
+        }
 
-                processed += chunk_size;
-                descr += 3;
+        fn reset(&mut self) {
+            R::reset_in();
+            R::set_in_descriptors(0);
+        }
 
-                if processed >= len {
-                    break;
-                }
+        fn take_error(&mut self) -> Option<DmaError> {
+            if R::take_in_fifo_overflow() {
+                Some(DmaError::FifoOverflow)
+            } else {
+                None
             }
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool {
+            R::is_in_idle()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral) {
+            R::set_in_peripheral(peri as u8);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool) {
+            R::listen_in_descriptor_done(enable);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self, descriptors: &[u32]) -> Option<usize> {
+            R::take_in_descriptor_done().and_then(|address| descriptor_index(descriptors, address))
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_mem2mem(&mut self, enable: bool) {
+            R::set_mem2mem_mode(enable);
+        }
+
+        fn prepare_circular_transfer(
+            &mut self,
+            descriptors: &mut [u32],
+            peri: DmaPeripheral,
+            data: *mut u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError> {
+            fill_ring(descriptors, data as u32, len, chunk_size, false)?;
 
             R::clear_in_interrupts();
             R::reset_in();
@@ -286,8 +666,18 @@ pub(crate) mod private {
             Ok(())
         }
 
-        fn is_done(&mut self) -> bool {
-            R::is_in_done()
+        fn received_length(&mut self, descriptors: &mut [u32]) -> usize {
+            let mut received = 0;
+            for descr in descriptors.chunks_mut(3) {
+                let next = descr[2];
+                let mut dw0 = &mut descr[0];
+                received += dw0.get_length() as usize;
+
+                if next == 0 {
+                    break;
+                }
+            }
+            received
         }
     }
 
@@ -299,6 +689,14 @@ pub(crate) mod private {
         pub descriptors: &'a mut [u32],
         pub burst_mode: bool,
         pub rx_impl: T,
+        pub started: bool,
+        /// Number of descriptor words in the ring, 0 if no circular transfer
+        /// is running
+        pub ring_words: usize,
+        /// Index of the next descriptor to read in a circular transfer
+        pub read_descr: usize,
+        /// Bytes already read from the next descriptor
+        pub read_offset: usize,
         pub _phantom: PhantomData<R>,
     }
 
@@ -321,16 +719,22 @@ pub(crate) mod private {
                 return Err(DmaError::InvalidDescriptorSize);
             }
 
-            if self.descriptors.len() / 3 < len / 4092 {
+            if self.descriptors.len() < descriptor_words(len) {
                 return Err(DmaError::OutOfDescriptors);
             }
 
             if self.burst_mode && (len % 4 != 0 || data as u32 % 4 != 0) {
-                return Err(DmaError::InvalidAlignment);
+                return Err(DmaError::BufferNotAligned);
             }
 
             self.rx_impl
                 .prepare_transfer(self.descriptors, peri, data, len)?;
+            self.started = true;
+
+            if on_complete(R::channel_index()).is_some() {
+                self.rx_impl.listen_eof(true);
+            }
+
             Ok(())
         }
 
@@ -341,6 +745,162 @@ pub(crate) mod private {
         fn init_channel(&mut self) {
             R::init_channel();
         }
+
+        fn channel_index(&self) -> usize {
+            R::channel_index()
+        }
+
+        fn listen_eof(&mut self, enable: bool) {
+            self.rx_impl.listen_eof(enable);
+        }
+
+        fn is_listening_eof(&self) -> bool {
+            self.rx_impl.is_listening_eof()
+        }
+
+        fn clear_interrupts(&mut self) {
+            self.rx_impl.clear_interrupts();
+        }
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt> {
+            self.rx_impl.pending_interrupt()
+        }
+
+        fn has_dscr_empty_error(&mut self) -> bool {
+            self.rx_impl.has_dscr_empty_error()
+        }
+
+        fn has_err_eof(&mut self) -> bool {
+            self.rx_impl.has_err_eof()
+        }
+
+        fn priority(&self) -> DmaPriority {
+            self.rx_impl.priority()
+        }
+
+        fn reset(&mut self) {
+            self.rx_impl.reset();
+            self.descriptors.fill(0);
+            self.started = false;
+            self.ring_words = 0;
+        }
+
+        fn is_started(&self) -> bool {
+            self.started
+        }
+
+        fn take_error(&mut self) -> Option<DmaError> {
+            self.rx_impl.take_error()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool {
+            self.rx_impl.is_idle()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral) {
+            self.rx_impl.set_peripheral(peri);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_mem2mem(&mut self, enable: bool) {
+            self.rx_impl.set_mem2mem(enable);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool) {
+            self.rx_impl.listen_descriptor_done(enable);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self) -> Option<usize> {
+            self.rx_impl.take_descriptor_done(self.descriptors)
+        }
+
+        fn received_length(&mut self) -> usize {
+            self.rx_impl.received_length(self.descriptors)
+        }
+
+        fn prepare_circular_transfer(
+            &mut self,
+            peri: DmaPeripheral,
+            data: *mut u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError> {
+            let ring_words = check_ring(
+                self.descriptors,
+                self.burst_mode,
+                data as u32,
+                len,
+                chunk_size,
+                false,
+            )?;
+
+            self.ring_words = ring_words;
+            self.read_descr = 0;
+            self.read_offset = 0;
+
+            self.rx_impl.prepare_circular_transfer(
+                self.descriptors,
+                peri,
+                data,
+                len,
+                chunk_size,
+            )?;
+            self.started = true;
+            Ok(())
+        }
+
+        fn circular_available(&mut self) -> usize {
+            let mut available = 0;
+            let mut descr = self.read_descr;
+
+            for _ in 0..self.ring_words / 3 {
+                let mut dw0 = &mut self.descriptors[descr];
+                if !matches!(dw0.get_owner(), Owner::Cpu) {
+                    break;
+                }
+
+                available += dw0.get_length() as usize;
+                descr = (descr + 3) % self.ring_words;
+            }
+
+            available.saturating_sub(self.read_offset)
+        }
+
+        fn circular_pop(&mut self, buf: &mut [u8]) -> usize {
+            let mut copied = 0;
+
+            while self.ring_words != 0 && copied < buf.len() {
+                let descr = self.read_descr;
+                let address = self.descriptors[descr + 1];
+                let mut dw0 = &mut self.descriptors[descr];
+                if !matches!(dw0.get_owner(), Owner::Cpu) {
+                    break;
+                }
+
+                let length = dw0.get_length() as usize;
+                let count = usize::min(length - self.read_offset, buf.len() - copied);
+                let data = unsafe {
+                    core::slice::from_raw_parts((address as *const u8).add(self.read_offset), count)
+                };
+                buf[copied..][..count].copy_from_slice(data);
+                copied += count;
+                self.read_offset += count;
+
+                if self.read_offset == length {
+                    // hand the descriptor back to the DMA
+                    dw0.set_length(0);
+                    dw0.set_owner(Owner::Dma);
+                    self.read_offset = 0;
+                    self.read_descr = (descr + 3) % self.ring_words;
+                }
+            }
+
+            copied
+        }
     }
 
     /// DMA Tx
@@ -352,6 +912,8 @@ pub(crate) mod private {
 
         fn init_channel(&mut self);
 
+        fn channel_index(&self) -> usize;
+
         fn prepare_transfer(
             &mut self,
             peri: DmaPeripheral,
@@ -360,6 +922,54 @@ pub(crate) mod private {
         ) -> Result<(), DmaError>;
 
         fn is_done(&mut self) -> bool;
+
+        fn priority(&self) -> DmaPriority;
+
+        fn reset(&mut self);
+
+        /// Whether a transfer was started since the channel was created or
+        /// reset
+        fn is_started(&self) -> bool;
+
+        /// Return and clear a FIFO overflow/underflow which occurred since the
+        /// last call
+        fn take_error(&mut self) -> Option<DmaError>;
+
+        fn listen_eof(&mut self, enable: bool);
+
+        fn is_listening_eof(&self) -> bool;
+
+        fn clear_interrupts(&mut self);
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt>;
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool;
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral);
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool);
+
+        /// Index of the most recently completed descriptor, if any descriptor
+        /// completed since the last call
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self) -> Option<usize>;
+
+        /// Start sending `data` in a loop
+        ///
+        /// The buffer is split into descriptors of `chunk_size` bytes which
+        /// are linked to a ring, the DMA keeps cycling through them until the
+        /// channel is reset. The buffer contents can be updated while the
+        /// transfer is running.
+        fn prepare_circular_transfer(
+            &mut self,
+            peri: DmaPeripheral,
+            data: *const u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError>;
     }
 
     pub trait TxChannel<R>
@@ -371,6 +981,28 @@ pub(crate) mod private {
             R::set_out_priority(priority);
         }
 
+        fn listen_eof(&mut self, enable: bool) {
+            R::listen_out_eof(enable);
+        }
+
+        fn is_listening_eof(&self) -> bool {
+            R::is_listening_out_eof()
+        }
+
+        fn clear_interrupts(&mut self) {
+            R::clear_out_interrupts();
+        }
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt> {
+            if R::has_out_descriptor_error() {
+                Some(DmaInterrupt::DescriptorError)
+            } else if R::is_out_done() {
+                Some(DmaInterrupt::Eof)
+            } else {
+                None
+            }
+        }
+
         fn prepare_transfer(
             &mut self,
             descriptors: &mut [u32],
@@ -378,43 +1010,7 @@ pub(crate) mod private {
             data: *const u8,
             len: usize,
         ) -> Result<(), DmaError> {
-            for descr in descriptors.iter_mut() {
-                *descr = 0;
-            }
-
-            compiler_fence(core::sync::atomic::Ordering::SeqCst);
-
-            let mut processed = 0;
-            let mut descr = 0;
-            loop {
-                let chunk_size = usize::min(4092, len - processed);
-                let last = processed + chunk_size >= len;
-
-                descriptors[descr + 1] = data as u32 + processed as u32;
-
-                let mut dw0 = &mut descriptors[descr];
-
-                #[cfg(not(esp32))]
-                dw0.set_suc_eof(last);
-
-                dw0.set_owner(Owner::Dma);
-                dw0.set_size(chunk_size as u16); // align to 32 bits?
-                dw0.set_length(chunk_size as u16); // actual size of the data!?
-
-                if !last {
-                    descriptors[descr + 2] =
-                        (&descriptors[descr + 3]) as *const _ as *const () as u32;
-                } else {
-                    descriptors[descr + 2] = 0;
-                }
-
-                processed += chunk_size;
-                descr += 3;
-
-                if processed >= len {
-                    break;
-                }
-            }
+            fill_chain(descriptors, data as u32, len, MAX_DESCRIPTOR_SIZE, true)?;
 
             R::clear_out_interrupts();
             R::reset_out();
@@ -429,9 +1025,69 @@ pub(crate) mod private {
             Ok(())
         }
 
-        fn is_done(&mut self) -> bool {
+        fn prepare_circular_transfer(
+            &mut self,
+            descriptors: &mut [u32],
+            peri: DmaPeripheral,
+            data: *const u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError> {
+            fill_ring(descriptors, data as u32, len, chunk_size, true)?;
+
+            R::clear_out_interrupts();
+            R::reset_out();
+            R::set_out_descriptors(descriptors.as_ptr() as u32);
+            R::set_out_peripheral(peri as u8);
+            R::start_out();
+
+            if R::has_out_descriptor_error() {
+                return Err(DmaError::DescriptorError);
+            }
+
+            Ok(())
+        }
+
+        fn is_done(&mut self) -> bool {
             R::is_out_done()
         }
+
+        fn priority(&self) -> DmaPriority {
+            R::get_out_priority()
+        }
+
+        fn reset(&mut self) {
+            R::reset_out();
+            R::set_out_descriptors(0);
+        }
+
+        fn take_error(&mut self) -> Option<DmaError> {
+            if R::take_out_fifo_underflow() {
+                Some(DmaError::FifoUnderflow)
+            } else {
+                None
+            }
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool {
+            R::is_out_idle()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral) {
+            R::set_out_peripheral(peri as u8);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool) {
+            R::listen_out_descriptor_done(enable);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self, descriptors: &[u32]) -> Option<usize> {
+            R::take_out_descriptor_done().and_then(|address| descriptor_index(descriptors, address))
+        }
     }
 
     pub struct ChannelTx<'a, T, R>
@@ -443,6 +1099,7 @@ pub(crate) mod private {
         #[allow(unused)]
         pub burst_mode: bool,
         pub tx_impl: T,
+        pub started: bool,
         pub _phantom: PhantomData<R>,
     }
 
@@ -459,6 +1116,26 @@ pub(crate) mod private {
             R::init_channel();
         }
 
+        fn channel_index(&self) -> usize {
+            R::channel_index()
+        }
+
+        fn listen_eof(&mut self, enable: bool) {
+            self.tx_impl.listen_eof(enable);
+        }
+
+        fn is_listening_eof(&self) -> bool {
+            self.tx_impl.is_listening_eof()
+        }
+
+        fn clear_interrupts(&mut self) {
+            self.tx_impl.clear_interrupts();
+        }
+
+        fn pending_interrupt(&self) -> Option<DmaInterrupt> {
+            self.tx_impl.pending_interrupt()
+        }
+
         fn prepare_transfer(
             &mut self,
             peri: DmaPeripheral,
@@ -469,12 +1146,23 @@ pub(crate) mod private {
                 return Err(DmaError::InvalidDescriptorSize);
             }
 
-            if self.descriptors.len() / 3 < len / 4092 {
+            if self.descriptors.len() < descriptor_words(len) {
                 return Err(DmaError::OutOfDescriptors);
             }
 
+            // burst reads only need an aligned start address, the length of
+            // the data to send is taken from the descriptors
+            if self.burst_mode && data as u32 % 4 != 0 {
+                return Err(DmaError::BufferNotAligned);
+            }
+
             self.tx_impl
                 .prepare_transfer(self.descriptors, peri, data, len)?;
+            self.started = true;
+
+            if on_complete(R::channel_index()).is_some() {
+                self.tx_impl.listen_eof(true);
+            }
 
             Ok(())
         }
@@ -482,12 +1170,127 @@ pub(crate) mod private {
         fn is_done(&mut self) -> bool {
             self.tx_impl.is_done()
         }
+
+        fn priority(&self) -> DmaPriority {
+            self.tx_impl.priority()
+        }
+
+        fn reset(&mut self) {
+            self.tx_impl.reset();
+            self.descriptors.fill(0);
+            self.started = false;
+        }
+
+        fn is_started(&self) -> bool {
+            self.started
+        }
+
+        fn take_error(&mut self) -> Option<DmaError> {
+            self.tx_impl.take_error()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_idle(&self) -> bool {
+            self.tx_impl.is_idle()
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_peripheral(&mut self, peri: DmaPeripheral) {
+            self.tx_impl.set_peripheral(peri);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_descriptor_done(&mut self, enable: bool) {
+            self.tx_impl.listen_descriptor_done(enable);
+        }
+
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_descriptor_done(&mut self) -> Option<usize> {
+            self.tx_impl.take_descriptor_done(self.descriptors)
+        }
+
+        fn prepare_circular_transfer(
+            &mut self,
+            peri: DmaPeripheral,
+            data: *const u8,
+            len: usize,
+            chunk_size: usize,
+        ) -> Result<(), DmaError> {
+            check_ring(
+                self.descriptors,
+                self.burst_mode,
+                data as u32,
+                len,
+                chunk_size,
+                true,
+            )?;
+
+            self.tx_impl.prepare_circular_transfer(
+                self.descriptors,
+                peri,
+                data,
+                len,
+                chunk_size,
+            )?;
+            self.started = true;
+
+            Ok(())
+        }
+    }
+
+    /// Validate the parameters of a circular transfer, returns the number of
+    /// descriptor words the ring needs
+    fn check_ring(
+        descriptors: &[u32],
+        burst_mode: bool,
+        data: u32,
+        len: usize,
+        chunk_size: usize,
+        tx: bool,
+    ) -> Result<usize, DmaError> {
+        if descriptors.len() % 3 != 0
+            || chunk_size == 0
+            || chunk_size > MAX_DESCRIPTOR_SIZE
+            || len <= chunk_size
+        {
+            return Err(DmaError::InvalidDescriptorSize);
+        }
+
+        let ring_words = ring_descriptor_words(len, chunk_size);
+        if descriptors.len() < ring_words {
+            return Err(DmaError::OutOfDescriptors);
+        }
+
+        // every descriptor needs to start at an aligned address, RX
+        // descriptors also need an aligned size
+        if burst_mode && (data % 4 != 0 || chunk_size % 4 != 0 || (!tx && len % 4 != 0)) {
+            return Err(DmaError::BufferNotAligned);
+        }
+
+        Ok(ring_words)
+    }
+
+    /// Index of the descriptor at `address` within `descriptors`
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn descriptor_index(descriptors: &[u32], address: u32) -> Option<usize> {
+        let base = descriptors.as_ptr() as u32;
+        let offset = address.checked_sub(base)? as usize;
+        let index = offset / (3 * core::mem::size_of::<u32>());
+
+        if index < descriptors.len() / 3 {
+            Some(index)
+        } else {
+            None
+        }
     }
 
     pub trait RegisterAccess {
         fn init_channel();
+        /// Index of the channel, used to look up per-channel state
+        fn channel_index() -> usize;
         fn set_out_burstmode(burst_mode: bool);
         fn set_out_priority(priority: DmaPriority);
+        fn get_out_priority() -> DmaPriority;
         fn clear_out_interrupts();
         fn reset_out();
         fn set_out_descriptors(address: u32);
@@ -495,15 +1298,38 @@ pub(crate) mod private {
         fn set_out_peripheral(peripheral: u8);
         fn start_out();
         fn is_out_done() -> bool;
+        fn take_out_fifo_underflow() -> bool;
+        fn listen_out_eof(enable: bool);
+        fn is_listening_out_eof() -> bool;
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_out_idle() -> bool;
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_out_descriptor_done(enable: bool);
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_out_descriptor_done() -> Option<u32>;
         fn set_in_burstmode(burst_mode: bool);
         fn set_in_priority(priority: DmaPriority);
+        fn get_in_priority() -> DmaPriority;
         fn clear_in_interrupts();
         fn reset_in();
         fn set_in_descriptors(address: u32);
         fn has_in_descriptor_error() -> bool;
+        fn has_in_descriptor_empty() -> bool;
+        fn has_in_err_eof() -> bool;
         fn set_in_peripheral(peripheral: u8);
         fn start_in();
         fn is_in_done() -> bool;
+        fn take_in_fifo_overflow() -> bool;
+        fn listen_in_eof(enable: bool);
+        fn is_listening_in_eof() -> bool;
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn is_in_idle() -> bool;
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn listen_in_descriptor_done(enable: bool);
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn set_mem2mem_mode(enable: bool);
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        fn take_in_descriptor_done() -> Option<u32>;
     }
 }
 
@@ -516,9 +1342,706 @@ where
 {
     pub(crate) tx: TX,
     pub(crate) rx: RX,
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub(crate) marked_descriptors: u32,
     _phantom: PhantomData<P>,
 }
 
+/// A descriptor which completed, see [Channel::listen_descriptor_done]
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DescriptorDone {
+    /// The TX descriptor with the given index was fully read from memory
+    Tx(usize),
+    /// The RX descriptor with the given index was fully written to memory
+    Rx(usize),
+}
+
+impl<TX, RX, P> Channel<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// The effective priority of the TX side as configured in the hardware
+    pub fn tx_priority(&self) -> DmaPriority {
+        self.tx.priority()
+    }
+
+    /// The effective priority of the RX side as configured in the hardware
+    pub fn rx_priority(&self) -> DmaPriority {
+        self.rx.priority()
+    }
+
+    /// Reset only this channel
+    ///
+    /// This pulses the channel's `in_rst`/`out_rst` bits, which discards any
+    /// in-flight transfer of this channel, and clears its pending interrupts.
+    /// Other channels are not affected.
+    ///
+    /// A transfer which was in progress won't complete anymore, its transfer
+    /// token must not be waited on.
+    ///
+    /// Afterwards the channel is in the same state as a freshly configured
+    /// one: the inlink/outlink addresses are cleared, all descriptors are
+    /// zeroed (so none is owned by the DMA or linked to another one) and both
+    /// directions report not being started. The burst mode, priority, enabled
+    /// interrupts and the descriptors marked for descriptor done interrupts are
+    /// kept. The next transfer builds a new descriptor chain.
+    pub fn reset(&mut self) {
+        self.tx.reset();
+        self.rx.reset();
+        self.tx.clear_interrupts();
+        self.rx.clear_interrupts();
+    }
+
+    /// Check if both directions of this channel are done
+    ///
+    /// A direction which wasn't started is considered to be done, so this can
+    /// also be used for transfers which only use TX or RX.
+    pub fn is_done(&mut self) -> bool {
+        (!self.tx.is_started() || self.tx.is_done()) && (!self.rx.is_started() || self.rx.is_done())
+    }
+
+    /// Return and clear a FIFO overflow (RX) or underflow (TX) which occurred
+    /// since the last call
+    ///
+    /// The error is sticky until taken, so a streaming loop can poll this to
+    /// find out whether data was dropped. Taking the error re-arms the
+    /// detection. If both directions have an error, the RX error is returned
+    /// first.
+    pub fn take_error(&mut self) -> Option<DmaError> {
+        self.rx.take_error().or_else(|| self.tx.take_error())
+    }
+
+    /// Check both directions for errors of the current or last transfer
+    ///
+    /// Descriptor errors and an erroneous EOF are reported until the next
+    /// transfer is prepared. FIFO errors are cleared when they are reported,
+    /// like with [Channel::take_error].
+    pub fn check_errors(&mut self) -> Result<(), DmaError> {
+        if self.tx.pending_interrupt() == Some(DmaInterrupt::DescriptorError)
+            || self.rx.pending_interrupt() == Some(DmaInterrupt::DescriptorError)
+        {
+            return Err(DmaError::DescriptorError);
+        }
+
+        if self.rx.has_dscr_empty_error() {
+            return Err(DmaError::DescriptorEmpty);
+        }
+
+        if self.rx.has_err_eof() {
+            return Err(DmaError::ErrorEof);
+        }
+
+        match self.take_error() {
+            Some(err) => Err(err),
+            None => Ok(()),
+        }
+    }
+
+    /// Enable the end-of-transfer and descriptor error interrupts of both
+    /// directions
+    ///
+    /// Together with enabling the channel's interrupt in the interrupt
+    /// controller this allows sleeping until a transfer completed instead of
+    /// polling [Channel::is_done]. Use [Channel::tx_interrupt] and
+    /// [Channel::rx_interrupt] in the handler to find out what happened and
+    /// [Channel::clear_interrupts] to acknowledge it.
+    pub fn listen_eof(&mut self) {
+        self.tx.listen_eof(true);
+        self.rx.listen_eof(true);
+    }
+
+    /// Disable the interrupts enabled by [Channel::listen_eof]
+    pub fn unlisten_eof(&mut self) {
+        self.tx.listen_eof(false);
+        self.rx.listen_eof(false);
+    }
+
+    /// Whether the interrupts enabled by [Channel::listen_eof] are enabled
+    pub fn is_listening_eof(&self) -> bool {
+        self.tx.is_listening_eof() && self.rx.is_listening_eof()
+    }
+
+    /// Call `callback` whenever a transfer of this channel completed
+    ///
+    /// Every following transfer enables the end-of-transfer interrupts of the
+    /// directions it uses. Once all of them are done or failed, the DMA
+    /// interrupt handler calls `callback` via [handle_on_complete]. Circular
+    /// transfers never complete and don't invoke the callback.
+    ///
+    /// The callback runs in interrupt context: it has to be short and can
+    /// only share state with the rest of the program through a critical
+    /// section or atomics. It can't be combined with [Channel::listen_eof] or
+    /// the futures of the `async` feature on the same channel, as they use the
+    /// same interrupts.
+    pub fn set_on_complete(&mut self, callback: fn()) {
+        critical_section::with(|cs| {
+            ON_COMPLETE[self.tx.channel_index()]
+                .borrow(cs)
+                .set(Some(callback))
+        });
+    }
+
+    /// Remove the callback registered via [Channel::set_on_complete]
+    pub fn clear_on_complete(&mut self) {
+        critical_section::with(|cs| ON_COMPLETE[self.tx.channel_index()].borrow(cs).set(None));
+        self.unlisten_eof();
+    }
+
+    /// Clear all pending interrupts of both directions
+    pub fn clear_interrupts(&mut self) {
+        self.tx.clear_interrupts();
+        self.rx.clear_interrupts();
+    }
+
+    /// Pending interrupt of the TX direction, a descriptor error takes
+    /// precedence over the end of the transfer
+    pub fn tx_interrupt(&self) -> Option<DmaInterrupt> {
+        self.tx.pending_interrupt()
+    }
+
+    /// Pending interrupt of the RX direction, a descriptor error takes
+    /// precedence over the end of the transfer
+    pub fn rx_interrupt(&self) -> Option<DmaInterrupt> {
+        self.rx.pending_interrupt()
+    }
+
+    /// Block until both directions of this channel are done, see
+    /// [Channel::is_done]
+    pub fn wait(&mut self) {
+        while !self.is_done() {
+            // wait
+        }
+    }
+
+    /// Block until both directions of this channel are done or `timeout`
+    /// elapsed
+    ///
+    /// Returns [DmaError::Timeout] if the transfer didn't complete in time, in
+    /// that case both directions are reset so the channel can be used for a
+    /// new transfer. A `timeout` of `None` waits forever, like
+    /// [Channel::wait].
+    #[cfg(has_systimer)]
+    pub fn wait_timeout(
+        &mut self,
+        timeout: Option<fugit::MicrosDurationU64>,
+    ) -> Result<(), DmaError> {
+        use crate::systimer::SystemTimer;
+
+        let timeout = match timeout {
+            Some(timeout) => timeout,
+            None => {
+                self.wait();
+                return Ok(());
+            }
+        };
+
+        let deadline =
+            SystemTimer::now() + timeout.ticks() * (SystemTimer::TICKS_PER_SECOND / 1_000_000);
+        while !self.is_done() {
+            if SystemTimer::now() >= deadline {
+                self.tx.reset();
+                self.rx.reset();
+                return Err(DmaError::Timeout);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Enable the per-descriptor done interrupts of both directions
+    ///
+    /// Bit `n` of `marked` selects the descriptor with index `n` (i.e. the
+    /// `n`-th triple of the descriptor array), only those are reported by
+    /// [Channel::handle_descriptor_done]. This allows e.g. processing every
+    /// frame of a continuous stream as soon as it completed.
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn listen_descriptor_done(&mut self, marked: u32) {
+        self.marked_descriptors = marked;
+        self.tx.listen_descriptor_done(true);
+        self.rx.listen_descriptor_done(true);
+    }
+
+    /// Disable the per-descriptor done interrupts
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn unlisten_descriptor_done(&mut self) {
+        self.marked_descriptors = 0;
+        self.tx.listen_descriptor_done(false);
+        self.rx.listen_descriptor_done(false);
+    }
+
+    /// Call `callback` for a completed marked descriptor and clear the
+    /// interrupt
+    ///
+    /// This is meant to be called from the DMA channel's interrupt handler.
+    /// The hardware only keeps track of the most recently completed
+    /// descriptor, if the handler runs late only that one is reported.
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn handle_descriptor_done<F>(&mut self, mut callback: F)
+    where
+        F: FnMut(DescriptorDone),
+    {
+        let marked = self.marked_descriptors;
+        let is_marked = |index: usize| index < 32 && marked & (1 << index) != 0;
+
+        if let Some(index) = self.tx.take_descriptor_done() {
+            if is_marked(index) {
+                callback(DescriptorDone::Tx(index));
+            }
+        }
+
+        if let Some(index) = self.rx.take_descriptor_done() {
+            if is_marked(index) {
+                callback(DescriptorDone::Rx(index));
+            }
+        }
+    }
+
+    /// Hand this channel over to another peripheral
+    ///
+    /// Resets the channel and selects `peripheral` for both TX and RX. This is
+    /// only possible while the channel is idle, otherwise [DmaError::Busy] is
+    /// returned and nothing is changed.
+    ///
+    /// With GDMA every channel can serve every peripheral, this allows to use
+    /// a single channel for different peripherals one after another.
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn rebind(&mut self, peripheral: DmaPeripheral) -> Result<(), DmaError> {
+        if !self.tx.is_idle() || !self.rx.is_idle() {
+            return Err(DmaError::Busy);
+        }
+
+        self.reset();
+        self.tx.set_peripheral(peripheral);
+        self.rx.set_peripheral(peripheral);
+
+        Ok(())
+    }
+
+    /// Copy `src` to `dst` without involving a peripheral
+    ///
+    /// Both buffers need to have the same length, otherwise
+    /// [DmaError::InvalidDescriptorSize] is returned before anything is
+    /// started. A single descriptor covers up to [MAX_DESCRIPTOR_SIZE] bytes,
+    /// longer buffers are split into chained descriptors, so the TX and RX
+    /// descriptor arrays passed to `configure` need [descriptor_words] words.
+    ///
+    /// The returned transfer completes once all data arrived in `dst`. The
+    /// channel can't be used for a peripheral while the transfer is in
+    /// progress.
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn mem2mem<TXBUF, RXBUF>(
+        &mut self,
+        src: TXBUF,
+        mut dst: RXBUF,
+    ) -> Result<Mem2MemTransfer<'_, TX, RX, P, TXBUF, RXBUF>, DmaError>
+    where
+        TXBUF: ReadBuffer<Word = u8>,
+        RXBUF: WriteBuffer<Word = u8>,
+    {
+        let (src_ptr, src_len) = unsafe { src.read_buffer() };
+        let (dst_ptr, dst_len) = unsafe { dst.write_buffer() };
+
+        if src_len != dst_len {
+            return Err(DmaError::InvalidDescriptorSize);
+        }
+
+        if !self.tx.is_idle() || !self.rx.is_idle() {
+            return Err(DmaError::Busy);
+        }
+
+        self.rx.set_mem2mem(true);
+        if let Err(err) = self
+            .rx
+            .prepare_transfer(DmaPeripheral::Mem2Mem, dst_ptr, dst_len)
+            .and_then(|_| {
+                self.tx
+                    .prepare_transfer(DmaPeripheral::Mem2Mem, src_ptr, src_len)
+            })
+        {
+            self.reset();
+            self.rx.set_mem2mem(false);
+            return Err(err);
+        }
+
+        Ok(Mem2MemTransfer {
+            channel: self,
+            src,
+            dst,
+        })
+    }
+
+    /// Start receiving data from `peripheral` into `buffer` in a loop
+    ///
+    /// The buffer is split into descriptors of `chunk_size` bytes which are
+    /// linked to a ring, so the DMA keeps cycling through it without being
+    /// re-armed. There need to be at least two descriptors, so one can be
+    /// consumed via [CircularRxTransfer::pop] while the DMA fills the next.
+    /// The RX descriptor array passed to `configure` needs 3 words per
+    /// descriptor.
+    ///
+    /// The peripheral itself needs to be set up and started separately. The
+    /// transfer runs until it's stopped or dropped.
+    pub fn start_circular_rx<RXBUF>(
+        &mut self,
+        peripheral: DmaPeripheral,
+        mut buffer: RXBUF,
+        chunk_size: usize,
+    ) -> Result<CircularRxTransfer<'_, TX, RX, P, RXBUF>, DmaError>
+    where
+        RXBUF: WriteBuffer<Word = u8>,
+    {
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+
+        if let Err(err) = self
+            .rx
+            .prepare_circular_transfer(peripheral, ptr, len, chunk_size)
+        {
+            self.rx.reset();
+            return Err(err);
+        }
+
+        Ok(CircularRxTransfer {
+            channel: self,
+            buffer,
+        })
+    }
+
+    /// Start sending the data of `buffer` to `peripheral` in a loop
+    ///
+    /// Like [Channel::start_circular_rx], the DMA keeps cycling through the
+    /// descriptors of `chunk_size` bytes until the transfer is stopped or
+    /// dropped.
+    pub fn start_circular_tx<TXBUF>(
+        &mut self,
+        peripheral: DmaPeripheral,
+        buffer: TXBUF,
+        chunk_size: usize,
+    ) -> Result<CircularTxTransfer<'_, TX, RX, P, TXBUF>, DmaError>
+    where
+        TXBUF: ReadBuffer<Word = u8>,
+    {
+        let (ptr, len) = unsafe { buffer.read_buffer() };
+
+        if let Err(err) = self
+            .tx
+            .prepare_circular_transfer(peripheral, ptr, len, chunk_size)
+        {
+            self.tx.reset();
+            return Err(err);
+        }
+
+        Ok(CircularTxTransfer {
+            channel: self,
+            buffer,
+        })
+    }
+}
+
+/// A running circular receive transfer, see [Channel::start_circular_rx]
+///
+/// Dropping the transfer resets the RX channel, which stops the DMA from
+/// writing to the buffer.
+pub struct CircularRxTransfer<'a, TX, RX, P, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    channel: &'a mut Channel<TX, RX, P>,
+    buffer: RXBUF,
+}
+
+impl<'a, TX, RX, P, RXBUF> CircularRxTransfer<'a, TX, RX, P, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// Number of received bytes which weren't popped yet
+    pub fn available(&mut self) -> usize {
+        self.channel.rx.circular_available()
+    }
+
+    /// Copy received data to `data` and return the number of bytes copied
+    ///
+    /// Descriptors which were read completely are handed back to the DMA. If
+    /// the data isn't popped in time, the DMA stalls until a descriptor is
+    /// free again and the peripheral's FIFO overflows, see
+    /// [Channel::take_error].
+    pub fn pop(&mut self, data: &mut [u8]) -> usize {
+        self.channel.rx.circular_pop(data)
+    }
+
+    /// Stop the transfer and return the buffer
+    pub fn stop(mut self) -> RXBUF {
+        self.channel.rx.reset();
+
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`, see `SpiDmaTransfer::wait`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            core::mem::forget(self);
+            buffer
+        }
+    }
+}
+
+impl<'a, TX, RX, P, RXBUF> Drop for CircularRxTransfer<'a, TX, RX, P, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    fn drop(&mut self) {
+        self.channel.rx.reset();
+    }
+}
+
+/// A running circular send transfer, see [Channel::start_circular_tx]
+///
+/// Dropping the transfer resets the TX channel, which stops the DMA from
+/// reading the buffer.
+pub struct CircularTxTransfer<'a, TX, RX, P, TXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    channel: &'a mut Channel<TX, RX, P>,
+    buffer: TXBUF,
+}
+
+impl<'a, TX, RX, P, TXBUF> CircularTxTransfer<'a, TX, RX, P, TXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// Stop the transfer and return the buffer
+    pub fn stop(mut self) -> TXBUF {
+        self.channel.tx.reset();
+
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`, see `SpiDmaTransfer::wait`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            core::mem::forget(self);
+            buffer
+        }
+    }
+}
+
+impl<'a, TX, RX, P, TXBUF> Drop for CircularTxTransfer<'a, TX, RX, P, TXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    fn drop(&mut self) {
+        self.channel.tx.reset();
+    }
+}
+
+/// An in-progress memory-to-memory transfer, see [Channel::mem2mem]
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+pub struct Mem2MemTransfer<'a, TX, RX, P, TXBUF, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    channel: &'a mut Channel<TX, RX, P>,
+    src: TXBUF,
+    dst: RXBUF,
+}
+
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+impl<'a, TX, RX, P, TXBUF, RXBUF> Mem2MemTransfer<'a, TX, RX, P, TXBUF, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    /// Check if all data arrived in the destination buffer
+    pub fn is_done(&mut self) -> bool {
+        self.channel.rx.is_done()
+    }
+
+    /// Wait for the transfer to complete and return the source and
+    /// destination buffers
+    pub fn wait(mut self) -> (TXBUF, RXBUF) {
+        self.finish();
+
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`, see `SpiDmaTransfer::wait`.
+        unsafe {
+            let src = core::ptr::read(&self.src);
+            let dst = core::ptr::read(&self.dst);
+            core::mem::forget(self);
+            (src, dst)
+        }
+    }
+
+    fn finish(&mut self) {
+        while !self.is_done() {
+            // wait
+        }
+
+        self.channel.rx.set_mem2mem(false);
+    }
+}
+
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+impl<'a, TX, RX, P, TXBUF, RXBUF> Drop for Mem2MemTransfer<'a, TX, RX, P, TXBUF, RXBUF>
+where
+    TX: Tx,
+    RX: Rx,
+    P: PeripheralMarker,
+{
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// Disable the end-of-transfer interrupts which fired and call the channel's
+/// callback once no direction is pending anymore
+fn handle_channel_complete<R: RegisterAccess>() {
+    let mut finished = false;
+
+    if R::is_listening_out_eof() && (R::is_out_done() || R::has_out_descriptor_error()) {
+        R::listen_out_eof(false);
+        finished = true;
+    }
+
+    if R::is_listening_in_eof() && (R::is_in_done() || R::has_in_descriptor_error()) {
+        R::listen_in_eof(false);
+        finished = true;
+    }
+
+    if finished && !R::is_listening_out_eof() && !R::is_listening_in_eof() {
+        if let Some(callback) = on_complete(R::channel_index()) {
+            callback();
+        }
+    }
+}
+
+/// Call the callbacks of the channels which completed a transfer, see
+/// [Channel::set_on_complete]
+///
+/// Needs to be called from the DMA interrupt handler(s), e.g. on ESP32-C3:
+///
+/// ```rust,ignore
+/// #[interrupt]
+/// fn DMA_CH0() {
+///     esp_hal_common::dma::handle_on_complete();
+/// }
+/// ```
+///
+/// The handlers are `DMA_CHn` on ESP32-C2 and ESP32-C3, `DMA_IN_CHn` and
+/// `DMA_OUT_CHn` on ESP32-S3 and `SPI2_DMA`/`SPI3_DMA` on ESP32 and ESP32-S2.
+pub fn handle_on_complete() {
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    {
+        use gdma::private::*;
+
+        handle_channel_complete::<Channel0>();
+        #[cfg(not(esp32c2))]
+        handle_channel_complete::<Channel1>();
+        #[cfg(not(esp32c2))]
+        handle_channel_complete::<Channel2>();
+        #[cfg(esp32s3)]
+        handle_channel_complete::<Channel3>();
+        #[cfg(esp32s3)]
+        handle_channel_complete::<Channel4>();
+    }
+
+    #[cfg(any(esp32, esp32s2))]
+    {
+        use pdma::private::*;
+
+        handle_channel_complete::<Spi2DmaChannel>();
+        handle_channel_complete::<Spi3DmaChannel>();
+    }
+}
+
+/// Hand out a statically allocated descriptor array exactly once
+///
+/// Used by [static_descriptors] - not intended to be called directly.
+#[doc(hidden)]
+pub unsafe fn take_static_descriptors(
+    taken: *mut bool,
+    descriptors: *mut u32,
+    len: usize,
+) -> &'static mut [u32] {
+    critical_section::with(|_| {
+        if *taken {
+            panic!("Static DMA descriptors already taken");
+        }
+        *taken = true;
+    });
+
+    core::slice::from_raw_parts_mut(descriptors, len)
+}
+
+/// Declare a static descriptor array big enough for a transfer of
+/// `$buffer_len` bytes and bind it to `$name` as `&'static mut [u32]`
+///
+/// The array is zero-initialized and therefore placed in `.bss` which is
+/// located in internal RAM, so it can be accessed by the DMA. Every
+/// invocation declares its own array; executing the same invocation a second
+/// time will panic.
+///
+/// ```rust,ignore
+/// static_descriptors!(tx_descriptors, 32000);
+/// static_descriptors!(rx_descriptors, 32000);
+///
+/// let mut spi = spi.with_dma(dma_channel.configure(
+///     false,
+///     tx_descriptors,
+///     rx_descriptors,
+///     DmaPriority::Priority0,
+/// ));
+/// ```
+#[macro_export]
+macro_rules! static_descriptors {
+    ($name:ident, $buffer_len:expr) => {
+        let $name: &'static mut [u32] = {
+            const LEN: usize = $crate::dma::descriptor_words($buffer_len);
+            static mut DESCRIPTORS: [u32; LEN] = [0u32; LEN];
+            static mut TAKEN: bool = false;
+
+            unsafe {
+                $crate::dma::take_static_descriptors(
+                    core::ptr::addr_of_mut!(TAKEN),
+                    core::ptr::addr_of_mut!(DESCRIPTORS) as *mut u32,
+                    LEN,
+                )
+            }
+        };
+    };
+}
+
+pub use crate::static_descriptors;
+
+/// Create a zeroed descriptor array big enough for a transfer of
+/// `$buffer_len` bytes
+///
+/// ```rust,ignore
+/// let mut descriptors = dma_descriptors!(10000);
+/// build_tx_descriptor_chain(&mut descriptors, &buffer)?;
+/// ```
+#[macro_export]
+macro_rules! dma_descriptors {
+    ($buffer_len:expr) => {
+        [0u32; $crate::dma::descriptor_words($buffer_len)]
+    };
+}
+
+pub use crate::dma_descriptors;
+
 /// Trait to be implemented for an in progress dma transfer.
 #[allow(drop_bounds)]
 pub trait DmaTransfer<B, T>: Drop {
@@ -33,6 +33,10 @@ macro_rules! ImplSpiChannel {
                     }
                 }
 
+                fn channel_index() -> usize {
+                    $num - 2
+                }
+
                 fn set_out_burstmode(burst_mode: bool) {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_conf
@@ -41,6 +45,10 @@ macro_rules! ImplSpiChannel {
 
                 fn set_out_priority(_priority: DmaPriority) {}
 
+                fn get_out_priority() -> DmaPriority {
+                    DmaPriority::Priority0
+                }
+
                 fn clear_out_interrupts() {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_int_clr.write(|w| {
@@ -81,11 +89,31 @@ macro_rules! ImplSpiChannel {
                     spi.dma_out_link.modify(|_, w| w.outlink_start().set_bit());
                 }
 
+                fn take_out_fifo_underflow() -> bool {
+                    // not reported by the SPI DMA
+                    false
+                }
+
                 fn is_out_done() -> bool {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_int_raw.read().out_done_int_raw().bit()
                 }
 
+                fn listen_out_eof(enable: bool) {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_ena.modify(|_, w| {
+                        w.out_done_int_ena()
+                            .bit(enable)
+                            .outlink_dscr_error_int_ena()
+                            .bit(enable)
+                    });
+                }
+
+                fn is_listening_out_eof() -> bool {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_ena.read().out_done_int_ena().bit()
+                }
+
                 fn set_in_burstmode(burst_mode: bool) {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_conf
@@ -94,6 +122,10 @@ macro_rules! ImplSpiChannel {
 
                 fn set_in_priority(_priority: DmaPriority) {}
 
+                fn get_in_priority() -> DmaPriority {
+                    DmaPriority::Priority0
+                }
+
                 fn clear_in_interrupts() {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_int_clr.write(|w| {
@@ -105,6 +137,8 @@ macro_rules! ImplSpiChannel {
                             .set_bit()
                             .inlink_dscr_error_int_clr()
                             .set_bit()
+                            .inlink_dscr_empty_int_clr()
+                            .set_bit()
                     });
                 }
 
@@ -125,6 +159,16 @@ macro_rules! ImplSpiChannel {
                     spi.dma_int_raw.read().inlink_dscr_error_int_raw().bit()
                 }
 
+                fn has_in_descriptor_empty() -> bool {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_raw.read().inlink_dscr_empty_int_raw().bit()
+                }
+
+                fn has_in_err_eof() -> bool {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_raw.read().in_err_eof_int_raw().bit()
+                }
+
                 fn set_in_peripheral(_peripheral: u8) {
                     // no-op
                 }
@@ -134,10 +178,30 @@ macro_rules! ImplSpiChannel {
                     spi.dma_in_link.modify(|_, w| w.inlink_start().set_bit());
                 }
 
+                fn take_in_fifo_overflow() -> bool {
+                    // not reported by the SPI DMA
+                    false
+                }
+
                 fn is_in_done() -> bool {
                     let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
                     spi.dma_int_raw.read().in_done_int_raw().bit()
                 }
+
+                fn listen_in_eof(enable: bool) {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_ena.modify(|_, w| {
+                        w.in_done_int_ena()
+                            .bit(enable)
+                            .inlink_dscr_error_int_ena()
+                            .bit(enable)
+                    });
+                }
+
+                fn is_listening_in_eof() -> bool {
+                    let spi = unsafe { &*crate::pac::[<SPI $num>]::PTR };
+                    spi.dma_int_ena.read().in_done_int_ena().bit()
+                }
             }
 
             pub struct [<Spi $num DmaChannelTxImpl>] {}
@@ -169,6 +233,7 @@ macro_rules! ImplSpiChannel {
                         descriptors: tx_descriptors,
                         burst_mode,
                         tx_impl: tx_impl,
+                        started: false,
                         _phantom: PhantomData::default(),
                     };
 
@@ -179,6 +244,10 @@ macro_rules! ImplSpiChannel {
                         descriptors: rx_descriptors,
                         burst_mode,
                         rx_impl: rx_impl,
+                        started: false,
+                        ring_words: 0,
+                        read_descr: 0,
+                        read_offset: 0,
                         _phantom: PhantomData::default(),
                     };
 
@@ -189,6 +258,23 @@ macro_rules! ImplSpiChannel {
                     }
                 }
             }
+
+            impl<'a> Channel<
+                ChannelTx<'a,[<Spi $num DmaChannelTxImpl>], [<Spi $num DmaChannel>]>,
+                ChannelRx<'a,[<Spi $num DmaChannelRxImpl>], [<Spi $num DmaChannel>]>,
+                [<Spi $num DmaSuitablePeripheral>],
+            > {
+                /// Reset the channel and give back the channel creator
+                ///
+                /// The creator can be configured again, e.g. with other
+                /// descriptors.
+                pub fn free(mut self) -> [<Spi $num DmaChannelCreator>] {
+                    self.clear_on_complete();
+                    self.reset();
+
+                    [<Spi $num DmaChannelCreator>] {}
+                }
+            }
         }
     };
 }
@@ -1,8 +1,6 @@
-//! Reading of eFuses
-
 use fugit::{HertzU32, RateExtU32};
 
-use crate::pac::EFUSE;
+use crate::pac::{APB_CTRL, EFUSE};
 
 pub struct Efuse;
 
@@ -119,4 +117,113 @@ impl Efuse {
             % 2)
             != 0
     }
+
+    /// Returns the chip revision as (major, minor) version
+    ///
+    /// E.g. `(3, 0)` for revision v3.0. The major version is encoded in two
+    /// eFuse bits and a bit of the `APB_CTRL` date register.
+    pub fn get_chip_revision() -> (u8, u8) {
+        let apb_ctrl = unsafe { &*APB_CTRL::ptr() };
+
+        let eco = (apb_ctrl.date.read().bits() >> 31) << 2
+            | Self::read_field(EfuseField::ChipVerRev2) << 1
+            | Self::read_field(EfuseField::ChipVerRev1);
+        let major = match eco {
+            1 => 1,
+            3 => 2,
+            7 => 3,
+            _ => 0,
+        };
+        let minor = Self::read_field(EfuseField::WaferVersionMinor) as u8;
+
+        (major, minor)
+    }
+}
+
+/// eFuse fields with known locations
+///
+/// The locations follow the eFuse field tables of ESP-IDF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EfuseField {
+    /// Write protection bits
+    WrDis,
+    /// Read protection bits of BLOCK1 to BLOCK3
+    RdDis,
+    /// Flash encryption counter
+    FlashCryptCnt,
+    /// Factory MAC address
+    MacFactory,
+    /// CRC8 of the factory MAC address
+    MacFactoryCrc,
+    /// APP CPU disabled
+    ChipVerDisAppCpu,
+    /// Bluetooth disabled
+    ChipVerDisBt,
+    /// Chip package identifier
+    ChipVerPkg,
+    /// CPU frequency limited to 160 MHz
+    ChipCpuFreqLow,
+    /// CPU frequency rating is valid
+    ChipCpuFreqRated,
+    /// The two point ADC calibration values in BLOCK3 are burned
+    Blk3PartReserve,
+    /// Chip revision 1 flag
+    ChipVerRev1,
+    /// Chip revision 2 flag
+    ChipVerRev2,
+    /// Minor wafer version
+    WaferVersionMinor,
+    /// ADC reference voltage calibration
+    AdcVref,
+    /// Secure boot enabled
+    AbsDoneSecureBoot,
+    /// ADC1 two point calibration, low point
+    Adc1TpLow,
+    /// ADC1 two point calibration, high point
+    Adc1TpHigh,
+    /// ADC2 two point calibration, low point
+    Adc2TpLow,
+    /// ADC2 two point calibration, high point
+    Adc2TpHigh,
+}
+
+impl EfuseField {
+    /// Location of the field as (block, bit offset, bit length)
+    pub const fn descriptor(self) -> (u8, u16, u16) {
+        match self {
+            EfuseField::WrDis => (0, 0, 16),
+            EfuseField::RdDis => (0, 16, 4),
+            EfuseField::FlashCryptCnt => (0, 20, 7),
+            EfuseField::MacFactory => (0, 32, 48),
+            EfuseField::MacFactoryCrc => (0, 80, 8),
+            EfuseField::ChipVerDisAppCpu => (0, 96, 1),
+            EfuseField::ChipVerDisBt => (0, 97, 1),
+            EfuseField::ChipVerPkg => (0, 105, 3),
+            EfuseField::ChipCpuFreqLow => (0, 108, 1),
+            EfuseField::ChipCpuFreqRated => (0, 109, 1),
+            EfuseField::Blk3PartReserve => (0, 110, 1),
+            EfuseField::ChipVerRev1 => (0, 111, 1),
+            EfuseField::ChipVerRev2 => (0, 180, 1),
+            EfuseField::WaferVersionMinor => (0, 184, 2),
+            EfuseField::AdcVref => (0, 136, 5),
+            EfuseField::AbsDoneSecureBoot => (0, 196, 1),
+            EfuseField::Adc1TpLow => (3, 96, 7),
+            EfuseField::Adc1TpHigh => (3, 103, 9),
+            EfuseField::Adc2TpLow => (3, 112, 7),
+            EfuseField::Adc2TpHigh => (3, 119, 9),
+        }
+    }
+}
+
+/// Address of the first read register of the given block
+pub(super) fn block_address(block: u8) -> *const u32 {
+    let efuse = unsafe { &*EFUSE::ptr() };
+
+    match block {
+        0 => efuse.blk0_rdata0.as_ptr() as *const u32,
+        1 => efuse.blk1_rdata0.as_ptr() as *const u32,
+        2 => efuse.blk2_rdata0.as_ptr() as *const u32,
+        3 => efuse.blk3_rdata0.as_ptr() as *const u32,
+        _ => panic!("Invalid eFuse block"),
+    }
 }
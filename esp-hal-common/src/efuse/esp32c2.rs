@@ -1,5 +1,3 @@
-//! Reading of eFuses
-
 use crate::pac::EFUSE;
 
 pub struct Efuse;
@@ -59,4 +57,78 @@ impl Efuse {
         let efuse = unsafe { &*EFUSE::ptr() };
         efuse.rd_repeat_data0.read().wdt_delay_sel().bits()
     }
+
+    /// Returns the chip revision as (major, minor) version
+    ///
+    /// E.g. `(1, 0)` for revision v1.0.
+    pub fn get_chip_revision() -> (u8, u8) {
+        let major = Self::read_field(EfuseField::WaferVersionMajor) as u8;
+        let minor = Self::read_field(EfuseField::WaferVersionMinor) as u8;
+
+        (major, minor)
+    }
+}
+
+/// eFuse fields with known locations
+///
+/// The locations follow the eFuse field tables of ESP-IDF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EfuseField {
+    /// Write protection bits
+    WrDis,
+    /// Read protection bits of the key block
+    RdDis,
+    /// Factory MAC address
+    Mac,
+    /// Minor wafer version
+    WaferVersionMinor,
+    /// Major wafer version
+    WaferVersionMajor,
+    /// Version of the calibration values in BLOCK2
+    BlkVersionMinor,
+    /// ADC OCode
+    Ocode,
+    /// Temperature sensor calibration
+    TempCalib,
+    /// ADC1 init code at 0 dB attenuation
+    Adc1InitCodeAtten0,
+    /// ADC1 init code at 11 dB attenuation
+    Adc1InitCodeAtten3,
+    /// ADC1 calibration voltage at 0 dB attenuation
+    Adc1CalVolAtten0,
+    /// ADC1 calibration voltage at 11 dB attenuation
+    Adc1CalVolAtten3,
+}
+
+impl EfuseField {
+    /// Location of the field as (block, bit offset, bit length)
+    pub const fn descriptor(self) -> (u8, u16, u16) {
+        match self {
+            EfuseField::WrDis => (0, 0, 8),
+            EfuseField::RdDis => (0, 32, 2),
+            EfuseField::Mac => (2, 0, 48),
+            EfuseField::WaferVersionMinor => (2, 48, 4),
+            EfuseField::WaferVersionMajor => (2, 52, 2),
+            EfuseField::BlkVersionMinor => (2, 57, 3),
+            EfuseField::Ocode => (2, 62, 7),
+            EfuseField::TempCalib => (2, 69, 9),
+            EfuseField::Adc1InitCodeAtten0 => (2, 78, 8),
+            EfuseField::Adc1InitCodeAtten3 => (2, 86, 5),
+            EfuseField::Adc1CalVolAtten0 => (2, 91, 8),
+            EfuseField::Adc1CalVolAtten3 => (2, 99, 6),
+        }
+    }
+}
+
+/// Address of the first read register of the given block
+pub(super) fn block_address(block: u8) -> *const u32 {
+    let efuse = unsafe { &*EFUSE::ptr() };
+
+    match block {
+        0 => efuse.rd_wr_dis.as_ptr() as *const u32,
+        1 => efuse.rd_blk1_data0.as_ptr() as *const u32,
+        2 => efuse.rd_blk2_data0.as_ptr() as *const u32,
+        3 => efuse.rd_blk3_data0.as_ptr() as *const u32,
+        _ => panic!("Invalid eFuse block"),
+    }
 }
@@ -1,5 +1,3 @@
-//! Reading of eFuses
-
 use crate::pac::EFUSE;
 
 pub struct Efuse;
@@ -59,4 +57,136 @@ impl Efuse {
         let efuse = unsafe { &*EFUSE::ptr() };
         efuse.rd_repeat_data1.read().wdt_delay_sel().bits()
     }
+
+    /// Returns the chip revision as (major, minor) version
+    ///
+    /// E.g. `(1, 0)` for revision v1.0.
+    pub fn get_chip_revision() -> (u8, u8) {
+        let major = Self::read_field(EfuseField::WaferVersionMajor) as u8;
+        let minor = (Self::read_field(EfuseField::WaferVersionMinorHi) << 3
+            | Self::read_field(EfuseField::WaferVersionMinorLo)) as u8;
+
+        (major, minor)
+    }
+}
+
+/// eFuse fields with known locations
+///
+/// The locations follow the eFuse field tables of ESP-IDF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EfuseField {
+    /// Write protection bits
+    WrDis,
+    /// Read protection bits of BLOCK4 to BLOCK10
+    RdDis,
+    /// RTC watchdog timeout multiplier selection
+    WdtDelaySel,
+    /// SPI boot encryption enable counter
+    SpiBootCryptCnt,
+    /// Secure boot enabled
+    SecureBootEn,
+    /// Download mode disabled
+    DisDownloadMode,
+    /// Factory MAC address
+    Mac,
+    /// Major wafer version
+    WaferVersionMajor,
+    /// Upper bit of the minor wafer version
+    WaferVersionMinorHi,
+    /// Lower bits of the minor wafer version
+    WaferVersionMinorLo,
+    /// Optional unique 128 bit ID
+    OptionalUniqueId,
+    /// Version of the calibration values in BLOCK2
+    BlkVersionMinor,
+    /// Temperature sensor calibration
+    TempCalib,
+    /// ADC1 high calibration point at 0 dB attenuation
+    Adc1CalHighAtten0,
+    /// ADC1 high calibration point at 2.5 dB attenuation
+    Adc1CalHighAtten1,
+    /// ADC1 high calibration point at 6 dB attenuation
+    Adc1CalHighAtten2,
+    /// ADC1 high calibration point at 11 dB attenuation
+    Adc1CalHighAtten3,
+    /// ADC2 high calibration point at 0 dB attenuation
+    Adc2CalHighAtten0,
+    /// ADC2 high calibration point at 2.5 dB attenuation
+    Adc2CalHighAtten1,
+    /// ADC2 high calibration point at 6 dB attenuation
+    Adc2CalHighAtten2,
+    /// ADC2 high calibration point at 11 dB attenuation
+    Adc2CalHighAtten3,
+    /// ADC1 low calibration point at 0 dB attenuation
+    Adc1CalLowAtten0,
+    /// ADC1 low calibration point at 2.5 dB attenuation
+    Adc1CalLowAtten1,
+    /// ADC1 low calibration point at 6 dB attenuation
+    Adc1CalLowAtten2,
+    /// ADC1 low calibration point at 11 dB attenuation
+    Adc1CalLowAtten3,
+    /// ADC2 low calibration point at 0 dB attenuation
+    Adc2CalLowAtten0,
+    /// ADC2 low calibration point at 2.5 dB attenuation
+    Adc2CalLowAtten1,
+    /// ADC2 low calibration point at 6 dB attenuation
+    Adc2CalLowAtten2,
+    /// ADC2 low calibration point at 11 dB attenuation
+    Adc2CalLowAtten3,
+}
+
+impl EfuseField {
+    /// Location of the field as (block, bit offset, bit length)
+    pub const fn descriptor(self) -> (u8, u16, u16) {
+        match self {
+            EfuseField::WrDis => (0, 0, 32),
+            EfuseField::RdDis => (0, 32, 7),
+            EfuseField::WdtDelaySel => (0, 80, 2),
+            EfuseField::SpiBootCryptCnt => (0, 82, 3),
+            EfuseField::SecureBootEn => (0, 116, 1),
+            EfuseField::DisDownloadMode => (0, 128, 1),
+            EfuseField::Mac => (1, 0, 48),
+            EfuseField::WaferVersionMajor => (1, 114, 2),
+            EfuseField::WaferVersionMinorHi => (1, 116, 1),
+            EfuseField::WaferVersionMinorLo => (1, 132, 3),
+            EfuseField::OptionalUniqueId => (2, 0, 128),
+            EfuseField::BlkVersionMinor => (2, 132, 3),
+            EfuseField::TempCalib => (2, 135, 9),
+            EfuseField::Adc1CalHighAtten0 => (2, 144, 8),
+            EfuseField::Adc1CalHighAtten1 => (2, 152, 8),
+            EfuseField::Adc1CalHighAtten2 => (2, 160, 8),
+            EfuseField::Adc1CalHighAtten3 => (2, 168, 8),
+            EfuseField::Adc2CalHighAtten0 => (2, 176, 8),
+            EfuseField::Adc2CalHighAtten1 => (2, 184, 8),
+            EfuseField::Adc2CalHighAtten2 => (2, 192, 8),
+            EfuseField::Adc2CalHighAtten3 => (2, 200, 8),
+            EfuseField::Adc1CalLowAtten0 => (2, 208, 6),
+            EfuseField::Adc1CalLowAtten1 => (2, 214, 6),
+            EfuseField::Adc1CalLowAtten2 => (2, 220, 6),
+            EfuseField::Adc1CalLowAtten3 => (2, 226, 6),
+            EfuseField::Adc2CalLowAtten0 => (2, 232, 6),
+            EfuseField::Adc2CalLowAtten1 => (2, 238, 6),
+            EfuseField::Adc2CalLowAtten2 => (2, 244, 6),
+            EfuseField::Adc2CalLowAtten3 => (2, 250, 6),
+        }
+    }
+}
+
+/// Address of the first read register of the given block
+///
+/// The read registers of BLOCK0 to BLOCK10 are laid out contiguously, BLOCK0
+/// starts six words before BLOCK1.
+pub(super) fn block_address(block: u8) -> *const u32 {
+    let efuse = unsafe { &*EFUSE::ptr() };
+    let block1 = efuse.rd_mac_spi_sys_0.as_ptr() as *const u32;
+
+    let offset: isize = match block {
+        0 => -6,
+        1 => 0,
+        2 => 6,
+        3 => 14,
+        4..=10 => 22 + (block as isize - 4) * 8,
+        _ => panic!("Invalid eFuse block"),
+    };
+    unsafe { block1.offset(offset) }
 }
@@ -1,5 +1,3 @@
-//! Reading of eFuses
-
 use crate::pac::EFUSE;
 
 pub struct Efuse;
@@ -59,4 +57,88 @@ impl Efuse {
         let efuse = unsafe { &*EFUSE::ptr() };
         efuse.rd_repeat_data1.read().wdt_delay_sel().bits()
     }
+
+    /// Returns the chip revision as (major, minor) version
+    ///
+    /// E.g. `(0, 4)` for revision v0.4.
+    pub fn get_chip_revision() -> (u8, u8) {
+        let major = Self::read_field(EfuseField::WaferVersionMajor) as u8;
+        let minor = (Self::read_field(EfuseField::WaferVersionMinorHi) << 3
+            | Self::read_field(EfuseField::WaferVersionMinorLo)) as u8;
+
+        (major, minor)
+    }
+}
+
+/// eFuse fields with known locations
+///
+/// The locations follow the eFuse field tables of ESP-IDF.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EfuseField {
+    /// Write protection bits
+    WrDis,
+    /// Read protection bits of BLOCK4 to BLOCK10
+    RdDis,
+    /// RTC watchdog timeout multiplier selection
+    WdtDelaySel,
+    /// SPI boot encryption enable counter
+    SpiBootCryptCnt,
+    /// Secure boot enabled
+    SecureBootEn,
+    /// Download mode disabled
+    DisDownloadMode,
+    /// Factory MAC address
+    Mac,
+    /// Lower bits of the minor wafer version
+    WaferVersionMinorLo,
+    /// Upper bit of the minor wafer version
+    WaferVersionMinorHi,
+    /// Major wafer version
+    WaferVersionMajor,
+    /// Version of the calibration values in BLOCK2
+    BlkVersionMinor,
+    /// Optional unique 128 bit ID
+    OptionalUniqueId,
+    /// Temperature sensor calibration
+    TempCalib,
+}
+
+impl EfuseField {
+    /// Location of the field as (block, bit offset, bit length)
+    pub const fn descriptor(self) -> (u8, u16, u16) {
+        match self {
+            EfuseField::WrDis => (0, 0, 32),
+            EfuseField::RdDis => (0, 32, 7),
+            EfuseField::WdtDelaySel => (0, 80, 2),
+            EfuseField::SpiBootCryptCnt => (0, 82, 3),
+            EfuseField::SecureBootEn => (0, 116, 1),
+            EfuseField::DisDownloadMode => (0, 128, 1),
+            EfuseField::Mac => (1, 0, 48),
+            EfuseField::WaferVersionMinorLo => (1, 114, 3),
+            EfuseField::WaferVersionMinorHi => (1, 183, 1),
+            EfuseField::WaferVersionMajor => (1, 184, 2),
+            EfuseField::BlkVersionMinor => (1, 120, 3),
+            EfuseField::OptionalUniqueId => (2, 0, 128),
+            EfuseField::TempCalib => (2, 131, 9),
+        }
+    }
+}
+
+/// Address of the first read register of the given block
+///
+/// The read registers of BLOCK0 to BLOCK10 are laid out contiguously, BLOCK0
+/// starts six words before BLOCK1.
+pub(super) fn block_address(block: u8) -> *const u32 {
+    let efuse = unsafe { &*EFUSE::ptr() };
+    let block1 = efuse.rd_mac_spi_sys_0.as_ptr() as *const u32;
+
+    let offset: isize = match block {
+        0 => -6,
+        1 => 0,
+        2 => 6,
+        3 => 14,
+        4..=10 => 22 + (block as isize - 4) * 8,
+        _ => panic!("Invalid eFuse block"),
+    };
+    unsafe { block1.offset(offset) }
 }
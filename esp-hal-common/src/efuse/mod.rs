@@ -0,0 +1,55 @@
+//! Reading of eFuses
+//!
+//! The chip specific modules provide the known fields and where the blocks
+//! are located, reading the fields works the same on all chips.
+
+#[cfg_attr(esp32, path = "esp32.rs")]
+#[cfg_attr(esp32c2, path = "esp32c2.rs")]
+#[cfg_attr(esp32c3, path = "esp32c3.rs")]
+#[cfg_attr(esp32s2, path = "esp32s2.rs")]
+#[cfg_attr(esp32s3, path = "esp32s3.rs")]
+mod chip_specific;
+
+pub use self::chip_specific::*;
+
+impl Efuse {
+    /// Read an eFuse field of up to 32 bits
+    ///
+    /// Longer fields are truncated to their lowest 32 bits, use
+    /// [Efuse::read_field_bytes] to read them completely.
+    pub fn read_field(field: EfuseField) -> u32 {
+        let (block, bit_offset, bit_len) = field.descriptor();
+
+        let mut value = 0;
+        for bit in 0..bit_len.min(32) {
+            if read_bit(block, bit_offset + bit) {
+                value |= 1 << bit;
+            }
+        }
+        value
+    }
+
+    /// Read an eFuse field into `buffer`, least significant byte first
+    ///
+    /// Bits not fitting into `buffer` are ignored, remaining bytes of
+    /// `buffer` are zeroed.
+    pub fn read_field_bytes(field: EfuseField, buffer: &mut [u8]) {
+        let (block, bit_offset, bit_len) = field.descriptor();
+
+        buffer.fill(0);
+        for bit in 0..bit_len.min(buffer.len() as u16 * 8) {
+            if read_bit(block, bit_offset + bit) {
+                buffer[bit as usize / 8] |= 1 << (bit % 8);
+            }
+        }
+    }
+}
+
+fn read_bit(block: u8, bit: u16) -> bool {
+    let word = unsafe {
+        chip_specific::block_address(block)
+            .add(bit as usize / 32)
+            .read_volatile()
+    };
+    word & (1 << (bit % 32)) != 0
+}
@@ -0,0 +1,246 @@
+//! [embassy-time] driver based on the systimer
+//!
+//! The driver counts in systimer ticks (16 MHz), the `embassy` feature selects
+//! the matching tick rate of `embassy-time`. The three alarms of the systimer
+//! are used as embassy alarms.
+//!
+//! [init] needs to be called before using the driver. The interrupt handlers
+//! for `SYSTIMER_TARGET0` - `SYSTIMER_TARGET2` are defined by this module
+//! (the `embassy` feature enables the `vectored` feature), so they can't be
+//! used by the application.
+//!
+//! The hardware counter is 52 bits wide and wraps after more than 8 years. The
+//! driver extends it to 64 bits by counting the wraps (periods) in software,
+//! so its timestamps are monotonic. A wrap is detected when the counter is
+//! read, which happens at least at the end of every period while an alarm is
+//! set. Alarms further away than the end of the current period first fire at
+//! the end of the period and are re-armed from there.
+//!
+//! [embassy-time]: https://docs.rs/embassy-time
+
+use core::cell::Cell;
+
+use critical_section::Mutex;
+use embassy_time::driver::{AlarmHandle, Driver};
+
+use crate::{
+    interrupt::{InterruptController, Priority},
+    pac,
+    systimer::{Alarm, SystemTimer, Target},
+};
+
+/// Number of alarms available to embassy
+pub const ALARM_COUNT: usize = 3;
+
+/// Width of the hardware counter
+const COUNTER_BITS: u32 = 52;
+
+/// Mask of the hardware counter's bits in a timestamp
+const COUNTER_MASK: u64 = (1 << COUNTER_BITS) - 1;
+
+struct AlarmState {
+    callback: Cell<Option<(fn(*mut ()), *mut ())>>,
+    allocated: Cell<bool>,
+    /// The 64 bit timestamp the alarm is set to
+    timestamp: Cell<u64>,
+}
+
+unsafe impl Send for AlarmState {}
+
+impl AlarmState {
+    const fn new() -> Self {
+        Self {
+            callback: Cell::new(None),
+            allocated: Cell::new(false),
+            timestamp: Cell::new(u64::MAX),
+        }
+    }
+}
+
+/// Software extension of the hardware counter
+struct Counter {
+    /// Number of times the hardware counter wrapped
+    periods: Cell<u64>,
+    /// The last value read from the hardware counter
+    last: Cell<u64>,
+}
+
+struct EmbassyTimer {
+    alarms: Mutex<[AlarmState; ALARM_COUNT]>,
+    counter: Mutex<Counter>,
+}
+
+const ALARM_STATE_NONE: AlarmState = AlarmState::new();
+
+embassy_time::time_driver_impl!(static DRIVER: EmbassyTimer = EmbassyTimer {
+    alarms: Mutex::new([ALARM_STATE_NONE; ALARM_COUNT]),
+    counter: Mutex::new(Counter {
+        periods: Cell::new(0),
+        last: Cell::new(0),
+    }),
+});
+
+/// Initialize the embassy-time driver
+///
+/// This takes the [SystemTimer] to make sure its alarms aren't used for
+/// anything else, and enables the systimer interrupts.
+pub fn init(_systimer: SystemTimer, interrupt_controller: &mut InterruptController) {
+    for n in 0..ALARM_COUNT {
+        disable_alarm(n);
+    }
+
+    interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET0, Priority::Priority1)
+        .unwrap();
+    interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET1, Priority::Priority1)
+        .unwrap();
+    interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET2, Priority::Priority1)
+        .unwrap();
+}
+
+fn disable_alarm(n: usize) {
+    match n {
+        0 => Alarm::<Target, 0>::new().disable_interrupt(),
+        1 => Alarm::<Target, 1>::new().disable_interrupt(),
+        2 => Alarm::<Target, 2>::new().disable_interrupt(),
+        _ => unreachable!(),
+    }
+}
+
+fn arm_alarm(n: usize, timestamp: u64) {
+    match n {
+        0 => arm(Alarm::<Target, 0>::new(), timestamp),
+        1 => arm(Alarm::<Target, 1>::new(), timestamp),
+        2 => arm(Alarm::<Target, 2>::new(), timestamp),
+        _ => unreachable!(),
+    }
+}
+
+fn arm<const CHANNEL: u8>(alarm: Alarm<Target, CHANNEL>, timestamp: u64) {
+    alarm.clear_interrupt();
+    alarm.set_target(timestamp);
+    alarm.enable_interrupt();
+}
+
+impl EmbassyTimer {
+    fn on_interrupt(&self, n: usize) {
+        disable_alarm(n);
+
+        let (timestamp, callback) = critical_section::with(|cs| {
+            let alarm = &self.alarms.borrow(cs)[n];
+            (alarm.timestamp.get(), alarm.callback.get())
+        });
+
+        // fired at the end of a period before reaching the timestamp
+        if self.arm_until(n, timestamp) {
+            return;
+        }
+
+        critical_section::with(|cs| self.alarms.borrow(cs)[n].timestamp.set(u64::MAX));
+
+        if let Some((f, ctx)) = callback {
+            f(ctx);
+        }
+    }
+
+    /// Arm alarm `n` for `timestamp` or, if that's in a later period of the
+    /// hardware counter, for the end of the current period
+    ///
+    /// Returns `false` without arming the alarm if `timestamp` was already
+    /// reached.
+    fn arm_until(&self, n: usize, timestamp: u64) -> bool {
+        loop {
+            let now = self.now();
+            if timestamp <= now {
+                disable_alarm(n);
+                return false;
+            }
+
+            let target = timestamp.min(now | COUNTER_MASK);
+            arm_alarm(n, target & COUNTER_MASK);
+
+            // the comparator only fires when the counter passes the target,
+            // check whether it already did while setting it up
+            if target > self.now() || is_alarm_pending(n) {
+                return true;
+            }
+        }
+    }
+}
+
+impl Driver for EmbassyTimer {
+    fn now(&self) -> u64 {
+        critical_section::with(|cs| {
+            let counter = self.counter.borrow(cs);
+
+            let raw = SystemTimer::now() & COUNTER_MASK;
+            if raw < counter.last.get() {
+                counter.periods.set(counter.periods.get() + 1);
+            }
+            counter.last.set(raw);
+
+            counter.periods.get() << COUNTER_BITS | raw
+        })
+    }
+
+    unsafe fn allocate_alarm(&self) -> Option<AlarmHandle> {
+        critical_section::with(|cs| {
+            for (n, alarm) in self.alarms.borrow(cs).iter().enumerate() {
+                if !alarm.allocated.get() {
+                    alarm.allocated.set(true);
+                    return Some(AlarmHandle::new(n as u8));
+                }
+            }
+
+            None
+        })
+    }
+
+    fn set_alarm_callback(&self, alarm: AlarmHandle, callback: fn(*mut ()), ctx: *mut ()) {
+        critical_section::with(|cs| {
+            self.alarms.borrow(cs)[alarm.id() as usize]
+                .callback
+                .set(Some((callback, ctx)));
+        });
+    }
+
+    fn set_alarm(&self, alarm: AlarmHandle, timestamp: u64) -> bool {
+        let n = alarm.id() as usize;
+
+        critical_section::with(|cs| self.alarms.borrow(cs)[n].timestamp.set(timestamp));
+
+        if timestamp == u64::MAX {
+            // never reached, used to disable the alarm
+            disable_alarm(n);
+            return true;
+        }
+
+        self.arm_until(n, timestamp)
+    }
+}
+
+fn is_alarm_pending(n: usize) -> bool {
+    match n {
+        0 => Alarm::<Target, 0>::new().is_interrupt_set(),
+        1 => Alarm::<Target, 1>::new().is_interrupt_set(),
+        2 => Alarm::<Target, 2>::new().is_interrupt_set(),
+        _ => unreachable!(),
+    }
+}
+
+#[export_name = "SYSTIMER_TARGET0"]
+extern "C" fn systimer_target0() {
+    DRIVER.on_interrupt(0);
+}
+
+#[export_name = "SYSTIMER_TARGET1"]
+extern "C" fn systimer_target1() {
+    DRIVER.on_interrupt(1);
+}
+
+#[export_name = "SYSTIMER_TARGET2"]
+extern "C" fn systimer_target2() {
+    DRIVER.on_interrupt(2);
+}
@@ -0,0 +1,276 @@
+//! # SPI flash access
+//!
+//! ### Summary
+//! Reads, writes and erases the flash the firmware is executed from, using the
+//! SPI flash routines of the ROM. This is the base for storing configuration
+//! data or writing OTA images.
+//!
+//! Flash offsets are physical addresses, i.e. the same offsets as used by
+//! `espflash` and the partition table. Offsets and lengths of reads and writes
+//! need to be multiples of 4 bytes, erasing works on whole 4 KiB sectors. The
+//! buffers can have any alignment.
+//!
+//! Like on any NOR flash, writing can only clear bits: the region to write
+//! has to be erased before, unless the data only clears additional bits.
+//!
+//! ### Caveats
+//! - Each operation runs with interrupts disabled and executes the ROM routines
+//!   from RAM, as the flash can't be read by the CPU while it's being
+//!   programmed.
+//! - On the ESP32 the flash cache of the PRO CPU is disabled during each
+//!   operation, the APP CPU must not execute code from flash meanwhile.
+//! - The operations are rejected with [Error::ClocksLocked] while the clock
+//!   configuration is locked, see [clock](crate::clock).
+//! - Data in the flash cache isn't invalidated: a region mapped into the
+//!   address space (e.g. via `include_bytes!` data) can still return the old
+//!   contents, so modified regions should be read back via [read].
+//!
+//! ### Example
+//! ```no_run
+//! const CONFIG_OFFSET: u32 = 0x9000;
+//!
+//! flash::erase_sector(CONFIG_OFFSET).unwrap();
+//! flash::write(CONFIG_OFFSET, &config).unwrap();
+//!
+//! let mut buffer = [0u8; 32];
+//! flash::read(CONFIG_OFFSET, &mut buffer).unwrap();
+//! ```
+
+#![deny(missing_docs)]
+
+use procmacros::ram;
+
+/// Size of a flash sector, the smallest unit which can be erased
+pub const SECTOR_SIZE: u32 = 4096;
+
+/// Alignment required for offsets and lengths of reads and writes
+const WORD_SIZE: u32 = 4;
+
+/// Size of the buffer used to pass data to the ROM routines in words
+const CHUNK_WORDS: usize = 16;
+
+/// Offset of the image header of the second stage bootloader, which contains
+/// the flash size
+#[cfg(any(esp32, esp32s2))]
+const BOOTLOADER_OFFSET: u32 = 0x1000;
+#[cfg(not(any(esp32, esp32s2)))]
+const BOOTLOADER_OFFSET: u32 = 0x0;
+
+#[cfg(esp32)]
+mod rom_addresses {
+    pub const ESP_ROM_SPIFLASH_READ: u32 = 0x4006_2ed8;
+    pub const ESP_ROM_SPIFLASH_WRITE: u32 = 0x4006_2d50;
+    pub const ESP_ROM_SPIFLASH_ERASE_SECTOR: u32 = 0x4006_2ccc;
+    pub const ESP_ROM_SPIFLASH_UNLOCK: u32 = 0x4006_28b0;
+    pub const CACHE_FLUSH: u32 = 0x4000_9a14;
+    pub const CACHE_READ_ENABLE: u32 = 0x4000_9a84;
+    pub const CACHE_READ_DISABLE: u32 = 0x4000_9ab8;
+}
+
+#[cfg(esp32c2)]
+mod rom_addresses {
+    pub const ESP_ROM_SPIFLASH_READ: u32 = 0x4000_0138;
+    pub const ESP_ROM_SPIFLASH_WRITE: u32 = 0x4000_0134;
+    pub const ESP_ROM_SPIFLASH_ERASE_SECTOR: u32 = 0x4000_0130;
+    pub const ESP_ROM_SPIFLASH_UNLOCK: u32 = 0x4000_0148;
+}
+
+#[cfg(esp32c3)]
+mod rom_addresses {
+    pub const ESP_ROM_SPIFLASH_READ: u32 = 0x4000_0130;
+    pub const ESP_ROM_SPIFLASH_WRITE: u32 = 0x4000_012c;
+    pub const ESP_ROM_SPIFLASH_ERASE_SECTOR: u32 = 0x4000_0128;
+    pub const ESP_ROM_SPIFLASH_UNLOCK: u32 = 0x4000_0140;
+}
+
+#[cfg(esp32s2)]
+mod rom_addresses {
+    pub const ESP_ROM_SPIFLASH_READ: u32 = 0x4001_728c;
+    pub const ESP_ROM_SPIFLASH_WRITE: u32 = 0x4001_71cc;
+    pub const ESP_ROM_SPIFLASH_ERASE_SECTOR: u32 = 0x4001_716c;
+    pub const ESP_ROM_SPIFLASH_UNLOCK: u32 = 0x4001_6e88;
+}
+
+#[cfg(esp32s3)]
+mod rom_addresses {
+    pub const ESP_ROM_SPIFLASH_READ: u32 = 0x4000_0a20;
+    pub const ESP_ROM_SPIFLASH_WRITE: u32 = 0x4000_0a14;
+    pub const ESP_ROM_SPIFLASH_ERASE_SECTOR: u32 = 0x4000_09fc;
+    pub const ESP_ROM_SPIFLASH_UNLOCK: u32 = 0x4000_0a2c;
+}
+
+use rom_addresses::*;
+
+/// Flash access errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The offset or length isn't a multiple of 4 bytes for reads and writes,
+    /// or of [SECTOR_SIZE] for erasing
+    Unaligned,
+    /// The access exceeds the size of the flash
+    OutOfBounds,
+    /// The ROM routine returned an error
+    Rom(i32),
+    /// The clock configuration was locked via
+    /// [ClockControl::freeze_locked](crate::clock::ClockControl::freeze_locked)
+    ClocksLocked,
+}
+
+/// Return the size of the flash in bytes, as configured in the header of the
+/// second stage bootloader
+pub fn capacity() -> u32 {
+    let mut header = [0u32; 1];
+    // The header can always be read, a failure leaves the size at 1 MiB
+    critical_section::with(|_| unsafe {
+        rom_read(BOOTLOADER_OFFSET, header.as_mut_ptr(), WORD_SIZE);
+    });
+
+    let size_code = (header[0].to_le_bytes()[3] >> 4) as u32;
+    (1024 * 1024) << size_code.min(7)
+}
+
+/// Read `buffer.len()` bytes starting at `offset`
+pub fn read(offset: u32, buffer: &mut [u8]) -> Result<(), Error> {
+    check_range(offset, buffer.len(), WORD_SIZE)?;
+
+    let mut words = [0u32; CHUNK_WORDS];
+    for (index, chunk) in buffer.chunks_mut(CHUNK_WORDS * 4).enumerate() {
+        let address = offset + (index * CHUNK_WORDS * 4) as u32;
+
+        let result = critical_section::with(|_| unsafe {
+            rom_read(address, words.as_mut_ptr(), chunk.len() as u32)
+        });
+        check_result(result)?;
+
+        for (bytes, word) in chunk.chunks_mut(4).zip(words.iter()) {
+            bytes.copy_from_slice(&word.to_ne_bytes());
+        }
+    }
+
+    Ok(())
+}
+
+/// Write `data` starting at `offset`
+///
+/// The region needs to be erased before, see [erase_sector].
+pub fn write(offset: u32, data: &[u8]) -> Result<(), Error> {
+    check_range(offset, data.len(), WORD_SIZE)?;
+
+    let mut words = [0u32; CHUNK_WORDS];
+    for (index, chunk) in data.chunks(CHUNK_WORDS * 4).enumerate() {
+        let address = offset + (index * CHUNK_WORDS * 4) as u32;
+
+        for (word, bytes) in words.iter_mut().zip(chunk.chunks(4)) {
+            *word = u32::from_ne_bytes(bytes.try_into().unwrap());
+        }
+
+        let result = critical_section::with(|_| unsafe {
+            rom_write(address, words.as_ptr(), chunk.len() as u32)
+        });
+        check_result(result)?;
+    }
+
+    Ok(())
+}
+
+/// Erase the sector starting at `offset`, setting all its bytes to `0xff`
+///
+/// `offset` needs to be a multiple of [SECTOR_SIZE].
+pub fn erase_sector(offset: u32) -> Result<(), Error> {
+    check_range(offset, SECTOR_SIZE as usize, SECTOR_SIZE)?;
+
+    let result = critical_section::with(|_| unsafe { rom_erase_sector(offset / SECTOR_SIZE) });
+    check_result(result)
+}
+
+fn check_range(offset: u32, length: usize, alignment: u32) -> Result<(), Error> {
+    if crate::clock::is_locked() {
+        return Err(Error::ClocksLocked);
+    }
+
+    if offset % alignment != 0 || length as u32 % alignment != 0 {
+        return Err(Error::Unaligned);
+    }
+
+    match offset.checked_add(length as u32) {
+        Some(end) if end <= capacity() => Ok(()),
+        _ => Err(Error::OutOfBounds),
+    }
+}
+
+fn check_result(result: i32) -> Result<(), Error> {
+    match result {
+        0 => Ok(()),
+        error => Err(Error::Rom(error)),
+    }
+}
+
+#[ram]
+unsafe fn rom_read(address: u32, data: *mut u32, length: u32) -> i32 {
+    let esp_rom_spiflash_read: extern "C" fn(u32, *mut u32, u32) -> i32 =
+        core::mem::transmute(ESP_ROM_SPIFLASH_READ as usize);
+
+    cache_disable();
+    let result = esp_rom_spiflash_read(address, data, length);
+    cache_enable();
+
+    result
+}
+
+#[ram]
+unsafe fn rom_write(address: u32, data: *const u32, length: u32) -> i32 {
+    let esp_rom_spiflash_unlock: extern "C" fn() -> i32 =
+        core::mem::transmute(ESP_ROM_SPIFLASH_UNLOCK as usize);
+    let esp_rom_spiflash_write: extern "C" fn(u32, *const u32, u32) -> i32 =
+        core::mem::transmute(ESP_ROM_SPIFLASH_WRITE as usize);
+
+    cache_disable();
+    let mut result = esp_rom_spiflash_unlock();
+    if result == 0 {
+        result = esp_rom_spiflash_write(address, data, length);
+    }
+    cache_enable();
+
+    result
+}
+
+#[ram]
+unsafe fn rom_erase_sector(sector: u32) -> i32 {
+    let esp_rom_spiflash_unlock: extern "C" fn() -> i32 =
+        core::mem::transmute(ESP_ROM_SPIFLASH_UNLOCK as usize);
+    let esp_rom_spiflash_erase_sector: extern "C" fn(u32) -> i32 =
+        core::mem::transmute(ESP_ROM_SPIFLASH_ERASE_SECTOR as usize);
+
+    cache_disable();
+    let mut result = esp_rom_spiflash_unlock();
+    if result == 0 {
+        result = esp_rom_spiflash_erase_sector(sector);
+    }
+    cache_enable();
+
+    result
+}
+
+/// The ESP32 ROM routines expect the flash cache to be disabled, the other
+/// chips arbitrate between the cache and the ROM routines in hardware
+#[inline(always)]
+unsafe fn cache_disable() {
+    #[cfg(esp32)]
+    {
+        let cache_read_disable: extern "C" fn(u32) =
+            core::mem::transmute(CACHE_READ_DISABLE as usize);
+        cache_read_disable(0);
+    }
+}
+
+#[inline(always)]
+unsafe fn cache_enable() {
+    #[cfg(esp32)]
+    {
+        let cache_flush: extern "C" fn(u32) = core::mem::transmute(CACHE_FLUSH as usize);
+        let cache_read_enable: extern "C" fn(u32) =
+            core::mem::transmute(CACHE_READ_ENABLE as usize);
+        cache_flush(0);
+        cache_read_enable(0);
+    }
+}
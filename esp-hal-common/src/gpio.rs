@@ -28,6 +28,66 @@ pub enum Event {
 
 pub struct Unknown {}
 
+/// Boot mode selected by the strapping pins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum BootMode {
+    /// Boot from SPI flash
+    SpiFlash,
+    /// Serial download mode
+    Download,
+}
+
+/// VDD_SDIO voltage selected by the MTDI strapping pin (ESP32) or VDD_SPI
+/// voltage selected by the GPIO45 strapping pin (ESP32-S3)
+#[cfg(any(esp32, esp32s3))]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SdioVoltage {
+    /// 3.3 V
+    V3_3,
+    /// 1.8 V
+    V1_8,
+}
+
+/// Values of the strapping pins latched at reset
+#[derive(Debug, Clone, Copy)]
+pub struct StrappingConfig {
+    /// Raw content of the strapping register
+    pub raw: u16,
+    /// Selected boot mode
+    pub boot_mode: BootMode,
+    /// Selected VDD_SDIO (ESP32) or VDD_SPI (ESP32-S3) voltage
+    ///
+    /// Note that this can be overridden by eFuses.
+    #[cfg(any(esp32, esp32s3))]
+    pub sdio_voltage: SdioVoltage,
+}
+
+/// Read the values of the strapping pins latched at reset
+///
+/// Knowing these is useful to avoid using strapping pins in a way that
+/// prevents the chip from booting.
+pub fn strapping_config() -> StrappingConfig {
+    let gpio = unsafe { &*GPIO::PTR };
+    let raw = gpio.strap.read().strapping().bits();
+
+    let boot_mode = if raw & (1 << types::STRAP_BOOT_BIT) != 0 {
+        BootMode::SpiFlash
+    } else {
+        BootMode::Download
+    };
+
+    StrappingConfig {
+        raw,
+        boot_mode,
+        #[cfg(any(esp32, esp32s3))]
+        sdio_voltage: if raw & (1 << types::STRAP_SDIO_VOLTAGE_BIT) != 0 {
+            SdioVoltage::V1_8
+        } else {
+            SdioVoltage::V3_3
+        },
+    }
+}
+
 pub struct Input<MODE> {
     _mode: PhantomData<MODE>,
 }
@@ -66,6 +126,16 @@ pub struct AF1;
 
 pub struct AF2;
 
+pub struct AF3;
+
+pub struct AF4;
+
+pub struct AF5;
+
+/// Drive strength of an output pad, see [OutputPin::set_drive_strength]
+///
+/// Stronger drive gives faster edges on fast buses and long traces, at the
+/// cost of more ringing and EMI.
 pub enum DriveStrength {
     I5mA  = 0,
     I10mA = 1,
@@ -83,7 +153,25 @@ pub enum AlternateFunction {
     Function5 = 5,
 }
 
-pub trait RTCPin {}
+/// Pins which belong to the RTC IO domain
+///
+/// Only these pins keep working while the digital domain is powered down,
+/// they can hold their state through deep sleep and wake the chip up.
+pub trait RTCPin {
+    /// Number of the pin in the RTC IO domain
+    fn rtc_number(&self) -> u8;
+
+    /// Route the pad through the RTC IO mux (`mux` set) or the regular IO mux
+    /// and select the RTC function `func`
+    #[cfg(not(any(esp32c2, esp32c3)))]
+    fn rtc_set_config(&mut self, input_enable: bool, mux: bool, func: u8);
+
+    /// Latch the current state of the pad
+    ///
+    /// While held, changes to the pin configuration and output level have no
+    /// effect on the pad. The hold stays in effect through deep sleep.
+    fn rtcio_pad_hold(&mut self, enable: bool);
+}
 
 pub trait AnalogPin {}
 
@@ -128,6 +216,13 @@ pub trait InputPin: Pin {
 
     fn enable_input_in_sleep_mode(&mut self, on: bool) -> &mut Self;
 
+    /// Enable the glitch filter of the pad
+    ///
+    /// Pulses shorter than two APB clock cycles are filtered out, e.g. to
+    /// clean up noisy button or encoder lines.
+    #[cfg(not(esp32))]
+    fn enable_input_filter(&mut self, on: bool) -> &mut Self;
+
     fn is_input_high(&self) -> bool;
 
     fn connect_input_to_peripheral(&mut self, signal: InputSignal) -> &mut Self {
@@ -410,6 +505,17 @@ where
     }
 }
 
+/// Detach the given pin from all peripheral inputs it's routed to via the GPIO
+/// matrix
+fn disconnect_peripheral_inputs(pin_num: u8) {
+    for cfg in unsafe { &*GPIO::PTR }.func_in_sel_cfg.iter() {
+        let r = cfg.read();
+        if r.sel().bit_is_set() && r.in_sel().bits() == pin_num {
+            cfg.modify(|_, w| w.sel().clear_bit());
+        }
+    }
+}
+
 #[doc(hidden)]
 pub fn connect_low_to_peripheral(signal: InputSignal) {
     unsafe { &*GPIO::PTR }.func_in_sel_cfg[signal as usize].modify(|_, w| unsafe {
@@ -434,6 +540,34 @@ pub fn connect_high_to_peripheral(signal: InputSignal) {
     });
 }
 
+/// Pending GPIO interrupts, bit `n` is set if GPIO `n` has an interrupt
+/// pending
+///
+/// Meant to be used by the GPIO interrupt handler to find out which pins fired
+/// when several pins are listened to.
+pub fn interrupt_status() -> u64 {
+    let gpio = unsafe { &*GPIO::PTR };
+
+    #[cfg(not(any(esp32c2, esp32c3)))]
+    let status = (gpio.status1.read().bits() as u64) << 32 | gpio.status.read().bits() as u64;
+    #[cfg(any(esp32c2, esp32c3))]
+    let status = gpio.status.read().bits() as u64;
+
+    status
+}
+
+/// Clear the pending interrupts of the pins selected by `mask`, bit `n`
+/// selects GPIO `n`
+pub fn clear_interrupt_status(mask: u64) {
+    let gpio = unsafe { &*GPIO::PTR };
+
+    gpio.status_w1tc
+        .write(|w| unsafe { w.bits((mask & u32::MAX as u64) as u32) });
+    #[cfg(not(any(esp32c2, esp32c3)))]
+    gpio.status1_w1tc
+        .write(|w| unsafe { w.bits((mask >> 32) as u32) });
+}
+
 // Only for ESP32 in order to workaround errata 3.6
 #[doc(hidden)]
 #[macro_export]
@@ -559,8 +693,16 @@ macro_rules! impl_input {
 
                 self.write_out_en_clear(1 << $bit);
 
-                gpio.func_out_sel_cfg[$pin_num]
-                    .modify(|_, w| unsafe { w.out_sel().bits(OutputSignal::GPIO as OutputSignalType) });
+                gpio.func_out_sel_cfg[$pin_num].modify(|_, w| unsafe {
+                    w.out_sel()
+                        .bits(OutputSignal::GPIO as OutputSignalType)
+                        .inv_sel()
+                        .clear_bit()
+                        .oen_sel()
+                        .clear_bit()
+                        .oen_inv_sel()
+                        .clear_bit()
+                });
 
                 impl_errata36!($errata36, pull_down, pull_up);
 
@@ -581,6 +723,7 @@ macro_rules! impl_input {
             }
 
             pub fn into_floating_input(self) -> $pxi<Input<Floating>> {
+                disconnect_peripheral_inputs($pin_num);
                 self.init_input(false, false);
                 $pxi { _mode: PhantomData }
             }
@@ -610,6 +753,16 @@ macro_rules! impl_input {
                 self
             }
 
+            #[cfg(not(esp32))]
+            fn enable_input_filter(&mut self, on: bool) -> &mut Self {
+                paste! {
+                    unsafe { &*IO_MUX::PTR }
+                        .$iomux_reg
+                        .modify(|_, w| w.filter_en().bit(on));
+                }
+                self
+            }
+
             fn is_input_high(&self) -> bool {
                 self.read_input() & (1 << $bit) != 0
             }
@@ -662,6 +815,23 @@ macro_rules! impl_input {
             }
         }
 
+        impl<MODE> $pxi<MODE> {
+            $( $(
+                paste! {
+                    #[doc = "Connect this pin to `" $af_signal "` via the IO_MUX"]
+                    ///
+                    /// Only exists for pins which can be routed to this signal directly
+                    /// via the IO_MUX, so invalid pin/signal combinations don't compile.
+                    pub fn [<into_ $af_signal:lower _input>](self) -> $pxi<Alternate<alternate_marker!($af)>> {
+                        let mut pin = $pxi { _mode: PhantomData };
+                        pin.set_to_input()
+                            .connect_input_to_peripheral(InputSignal::$af_signal);
+                        pin
+                    }
+                }
+            )* )?
+        }
+
         impl<MODE> Pin for $pxi<MODE> {
             fn number(&self) -> u8 {
                 $pin_num
@@ -797,6 +967,17 @@ macro_rules! impl_output {
             type Error = Infallible;
         }
 
+        #[cfg(feature = "eh1")]
+        impl embedded_hal_1::digital::InputPin for $pxi<Output<OpenDrain>> {
+            fn is_high(&self) -> Result<bool, Self::Error> {
+                Ok(self.read_input() & (1 << $bit) != 0)
+            }
+
+            fn is_low(&self) -> Result<bool, Self::Error> {
+                Ok(!self.is_high()?)
+            }
+        }
+
         #[cfg(feature = "eh1")]
         impl<MODE> embedded_hal_1::digital::OutputPin for $pxi<Output<MODE>> {
             fn set_low(&mut self) -> Result<(), Self::Error> {
@@ -844,11 +1025,13 @@ macro_rules! impl_output {
 
         impl<MODE> $pxi<MODE> {
             pub fn into_pull_up_input(self) -> $pxi<Input<PullUp>> {
+                disconnect_peripheral_inputs($pin_num);
                 self.init_input(false, true);
                 $pxi { _mode: PhantomData }
             }
 
             pub fn into_pull_down_input(self) -> $pxi<Input<PullDown>> {
+                disconnect_peripheral_inputs($pin_num);
                 self.init_input(true, false);
                 $pxi { _mode: PhantomData }
             }
@@ -860,8 +1043,16 @@ macro_rules! impl_output {
                 self.write_out_en_set(1 << $bit);
                 gpio.pin[$pin_num].modify(|_, w| w.pad_driver().bit(open_drain));
 
-                gpio.func_out_sel_cfg[$pin_num]
-                    .modify(|_, w| unsafe { w.out_sel().bits(OutputSignal::GPIO as OutputSignalType) });
+                gpio.func_out_sel_cfg[$pin_num].modify(|_, w| unsafe {
+                    w.out_sel()
+                        .bits(OutputSignal::GPIO as OutputSignalType)
+                        .inv_sel()
+                        .clear_bit()
+                        .oen_sel()
+                        .clear_bit()
+                        .oen_inv_sel()
+                        .clear_bit()
+                });
 
                 paste! {
                     iomux.$iomux_reg.modify(|_, w| unsafe {
@@ -882,11 +1073,19 @@ macro_rules! impl_output {
             }
 
             pub fn into_push_pull_output(self) -> $pxi<Output<PushPull>> {
+                disconnect_peripheral_inputs($pin_num);
                 self.init_output(AlternateFunction::$gpio_function, false);
                 $pxi { _mode: PhantomData }
             }
 
+            /// Configure the pin to only drive low and float high
+            ///
+            /// The input stays enabled, so the level on a shared line can be
+            /// read back. Enable the internal pull-up with
+            /// [OutputPin::internal_pull_up] if there's no external one. Pins
+            /// which are input-only don't have this method.
             pub fn into_open_drain_output(self) -> $pxi<Output<OpenDrain>> {
+                disconnect_peripheral_inputs($pin_num);
                 self.init_output(AlternateFunction::$gpio_function, true);
                 $pxi { _mode: PhantomData }
             }
@@ -1017,7 +1216,14 @@ macro_rules! impl_output {
                 self.set_alternate_function(AlternateFunction::$gpio_function);
 
                 unsafe { &*GPIO::PTR }.func_out_sel_cfg[$pin_num].modify(|_, w| unsafe {
-                    w.out_sel().bits(OutputSignal::GPIO as OutputSignalType)
+                    w.out_sel()
+                        .bits(OutputSignal::GPIO as OutputSignalType)
+                        .inv_sel()
+                        .clear_bit()
+                        .oen_sel()
+                        .clear_bit()
+                        .oen_inv_sel()
+                        .clear_bit()
                 });
 
                 self
@@ -1037,6 +1243,23 @@ macro_rules! impl_output {
                 self
             }
         }
+
+        impl<MODE> $pxi<MODE> {
+            $( $(
+                paste! {
+                    #[doc = "Connect `" $af_signal "` to this pin via the IO_MUX"]
+                    ///
+                    /// Only exists for pins which can be routed to this signal directly
+                    /// via the IO_MUX, so invalid pin/signal combinations don't compile.
+                    pub fn [<into_ $af_signal:lower _output>](self) -> $pxi<Alternate<alternate_marker!($af)>> {
+                        let mut pin = $pxi { _mode: PhantomData };
+                        pin.set_to_push_pull_output()
+                            .connect_peripheral_to_output(OutputSignal::$af_signal);
+                        pin
+                    }
+                }
+            )* )?
+        }
     };
 }
 
@@ -1095,6 +1318,29 @@ macro_rules! impl_interrupt_status_register_access {
     };
 }
 
+#[doc(hidden)]
+#[macro_export]
+macro_rules! alternate_marker {
+    (Function0) => {
+        AF0
+    };
+    (Function1) => {
+        AF1
+    };
+    (Function2) => {
+        AF2
+    };
+    (Function3) => {
+        AF3
+    };
+    (Function4) => {
+        AF4
+    };
+    (Function5) => {
+        AF5
+    };
+}
+
 #[doc(hidden)]
 #[macro_export]
 macro_rules! impl_from {
@@ -1262,11 +1508,77 @@ macro_rules! analog {
                 }
             }
 
+            impl<MODE> $crate::gpio::RTCPin for $pxi<MODE> {
+                fn rtc_number(&self) -> u8 {
+                    $pin_num
+                }
+
+                fn rtc_set_config(&mut self, input_enable: bool, mux: bool, func: u8) {
+                    use crate::pac::RTCIO;
+                    let rtcio = unsafe { &*RTCIO::ptr() };
+
+                    $crate::gpio::enable_iomux_clk_gate();
+
+                    paste! {
+                        rtcio.$pin_reg.modify(|_, w| unsafe {
+                            w.$fun_ie()
+                                .bit(input_enable)
+                                .$mux_sel()
+                                .bit(mux)
+                                .$fun_sel()
+                                .bits(func)
+                        });
+                    }
+                }
+
+                fn rtcio_pad_hold(&mut self, enable: bool) {
+                    rtcio_pad_hold!($pin_num, $pin_reg, $hold, enable);
+                }
+            }
+
+            impl<MODE> $pxi<MODE> {
+                /// Keep the current state of the pin, also through deep sleep
+                pub fn hold_enable(&mut self) {
+                    $crate::gpio::RTCPin::rtcio_pad_hold(self, true);
+                }
+
+                /// Release the pin, changes to its configuration take effect again
+                pub fn hold_disable(&mut self) {
+                    $crate::gpio::RTCPin::rtcio_pad_hold(self, false);
+                }
+            }
+
             impl_from!($pxi, Analog, into_analog);
         )+
     }
 }
 
+// The hold bits are part of the pad registers on ESP32, the later chips
+// collect them in the PAD_HOLD register of RTC_CNTL
+#[cfg(esp32)]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! rtcio_pad_hold {
+    ($pin_num:expr, $pin_reg:expr, $hold:ident, $enable:expr) => {
+        let rtcio = unsafe { &*crate::pac::RTCIO::ptr() };
+
+        paste! {
+            rtcio.$pin_reg.modify(|_, w| w.$hold().bit($enable));
+        }
+    };
+}
+
+#[cfg(any(esp32s2, esp32s3))]
+#[doc(hidden)]
+#[macro_export]
+macro_rules! rtcio_pad_hold {
+    ($pin_num:expr, $pin_reg:expr, $hold:ident, $enable:expr) => {
+        let rtc_cntl = unsafe { &*crate::pac::RTC_CNTL::ptr() };
+
+        rtc_cntl.pad_hold.modify(|_, w| w.$hold().bit($enable));
+    };
+}
+
 #[cfg(any(esp32c2, esp32c3))]
 #[doc(hidden)]
 #[macro_export]
@@ -1296,11 +1608,43 @@ macro_rules! analog {
                 }
             }
 
+            impl<MODE> $crate::gpio::RTCPin for $pxi<MODE> {
+                fn rtc_number(&self) -> u8 {
+                    $pin_num
+                }
+
+                fn rtcio_pad_hold(&mut self, enable: bool) {
+                    let rtc_cntl = unsafe { &*crate::pac::RTC_CNTL::ptr() };
+
+                    // bit `n` of PAD_HOLD holds GPIO `n`
+                    rtc_cntl.pad_hold.modify(|r, w| unsafe {
+                        if enable {
+                            w.bits(r.bits() | 1 << $pin_num)
+                        } else {
+                            w.bits(r.bits() & !(1 << $pin_num))
+                        }
+                    });
+                }
+            }
+
+            impl<MODE> $pxi<MODE> {
+                /// Keep the current state of the pin, also through deep sleep
+                pub fn hold_enable(&mut self) {
+                    $crate::gpio::RTCPin::rtcio_pad_hold(self, true);
+                }
+
+                /// Release the pin, changes to its configuration take effect again
+                pub fn hold_disable(&mut self) {
+                    $crate::gpio::RTCPin::rtcio_pad_hold(self, false);
+                }
+            }
+
             impl_from!($pxi, Analog, into_analog);
         )+
     }
 }
 
+pub use alternate_marker;
 pub use analog;
 pub use gpio;
 pub use impl_errata36;
@@ -1310,5 +1654,7 @@ pub use impl_input;
 pub use impl_interrupt_status_register_access;
 pub use impl_output;
 pub use impl_output_wrap;
+#[cfg(not(any(esp32c2, esp32c3)))]
+pub use rtcio_pad_hold;
 
 use self::types::{InputSignal, OutputSignal, ONE_INPUT, ZERO_INPUT};
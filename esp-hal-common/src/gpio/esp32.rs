@@ -5,6 +5,15 @@ pub const INPUT_SIGNAL_MAX: u16 = 539;
 pub const ONE_INPUT: u8 = 0x38;
 pub const ZERO_INPUT: u8 = 0x30;
 
+// From `GPIO_STRAP_REG` in the TRM. The ROM prints the register on every reset,
+// `boot:0x13 (SPI_FAST_FLASH_BOOT)` with GPIO0 high and `boot:0x3 (DOWNLOAD...)`
+// with GPIO0 pulled low.
+/// Bit of the strapping register latching GPIO0, which selects the boot mode
+pub const STRAP_BOOT_BIT: u16 = 4;
+/// Bit of the strapping register latching MTDI (GPIO12), which selects the
+/// VDD_SDIO voltage
+pub const STRAP_SDIO_VOLTAGE_BIT: u16 = 5;
+
 /// Peripheral input signals for the GPIO mux
 #[allow(non_camel_case_types)]
 #[derive(PartialEq, Copy, Clone)]
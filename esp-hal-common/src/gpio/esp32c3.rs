@@ -5,6 +5,12 @@ pub const INPUT_SIGNAL_MAX: u8 = 100;
 pub const ONE_INPUT: u8 = 0x1e;
 pub const ZERO_INPUT: u8 = 0x1f;
 
+// From `GPIO_STRAP_REG` in the TRM. The ROM prints the register on every reset,
+// `boot:0xc (SPI_FAST_FLASH_BOOT)` with GPIO9 high and `boot:0x4 (DOWNLOAD...)`
+// with GPIO9 pulled low.
+/// Bit of the strapping register latching GPIO9, which selects the boot mode
+pub const STRAP_BOOT_BIT: u16 = 3;
+
 #[allow(non_camel_case_types)]
 #[derive(Clone, Copy, PartialEq)]
 pub enum InputSignal {
@@ -70,7 +76,7 @@ pub enum OutputSignal {
     I2S_MCLK         = 12,
     I2SO_BCK         = 13,
     I2SO_WS          = 14,
-    I2SI_SD          = 15,
+    I2SO_SD          = 15,
     I2SI_BCK         = 16,
     I2SI_WS          = 17,
     GPIO_WLAN_PRIO   = 18,
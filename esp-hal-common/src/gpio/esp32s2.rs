@@ -5,6 +5,12 @@ pub const INPUT_SIGNAL_MAX: u16 = 204;
 pub const ONE_INPUT: u8 = 0x38;
 pub const ZERO_INPUT: u8 = 0x3c;
 
+// From `GPIO_STRAP_REG` in the TRM. The ROM prints the register on every reset,
+// `boot:0x8 (SPI_FAST_FLASH_BOOT)` with GPIO0 high and `boot:0x0 (DOWNLOAD...)`
+// with GPIO0 pulled low.
+/// Bit of the strapping register latching GPIO0, which selects the boot mode
+pub const STRAP_BOOT_BIT: u16 = 3;
+
 /// Peripheral input signals for the GPIO mux
 #[allow(non_camel_case_types)]
 #[derive(PartialEq, Copy, Clone)]
@@ -30,6 +36,22 @@ pub enum InputSignal {
     I2S0I_WS          = 28,
     I2CEXT0_SCL       = 29,
     I2CEXT0_SDA       = 30,
+    PCNT_SIG_CH0_0    = 39,
+    PCNT_SIG_CH1_0    = 40,
+    PCNT_CTRL_CH0_0   = 41,
+    PCNT_CTRL_CH1_0   = 42,
+    PCNT_SIG_CH0_1    = 43,
+    PCNT_SIG_CH1_1    = 44,
+    PCNT_CTRL_CH0_1   = 45,
+    PCNT_CTRL_CH1_1   = 46,
+    PCNT_SIG_CH0_2    = 47,
+    PCNT_SIG_CH1_2    = 48,
+    PCNT_CTRL_CH0_2   = 49,
+    PCNT_CTRL_CH1_2   = 50,
+    PCNT_SIG_CH0_3    = 51,
+    PCNT_SIG_CH1_3    = 52,
+    PCNT_CTRL_CH0_3   = 53,
+    PCNT_CTRL_CH1_3   = 54,
     USB_OTG_IDDIG     = 64,
     USB_OTG_AVALID    = 65,
     USB_SRP_BVALID    = 66,
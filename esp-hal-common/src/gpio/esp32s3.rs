@@ -5,6 +5,15 @@ pub const INPUT_SIGNAL_MAX: u16 = 189;
 pub const ONE_INPUT: u8 = 0x38;
 pub const ZERO_INPUT: u8 = 0x3c;
 
+// From `GPIO_STRAP_REG` in the TRM. The ROM prints the register on every reset,
+// `boot:0x8 (SPI_FAST_FLASH_BOOT)` with GPIO0 high and `boot:0x0 (DOWNLOAD...)`
+// with GPIO0 pulled low.
+/// Bit of the strapping register latching GPIO0, which selects the boot mode
+pub const STRAP_BOOT_BIT: u16 = 3;
+/// Bit of the strapping register latching GPIO45, which selects the VDD_SPI
+/// voltage
+pub const STRAP_SDIO_VOLTAGE_BIT: u16 = 5;
+
 /// Peripheral input signals for the GPIO mux
 #[allow(non_camel_case_types)]
 #[derive(PartialEq, Copy, Clone)]
@@ -39,6 +48,22 @@ pub enum InputSignal {
     I2S1I_SD          = 30,
     I2S1I_BCK         = 31,
     I2S1I_WS          = 32,
+    PCNT_SIG_CH0_0    = 33,
+    PCNT_SIG_CH1_0    = 34,
+    PCNT_CTRL_CH0_0   = 35,
+    PCNT_CTRL_CH1_0   = 36,
+    PCNT_SIG_CH0_1    = 37,
+    PCNT_SIG_CH1_1    = 38,
+    PCNT_CTRL_CH0_1   = 39,
+    PCNT_CTRL_CH1_1   = 40,
+    PCNT_SIG_CH0_2    = 41,
+    PCNT_SIG_CH1_2    = 42,
+    PCNT_CTRL_CH0_2   = 43,
+    PCNT_CTRL_CH1_2   = 44,
+    PCNT_SIG_CH0_3    = 45,
+    PCNT_SIG_CH1_3    = 46,
+    PCNT_CTRL_CH0_3   = 47,
+    PCNT_CTRL_CH1_3   = 48,
     I2S0I_SD1         = 51,
     I2S0I_SD2         = 52,
     I2S0I_SD3         = 53,
@@ -71,6 +96,7 @@ pub enum InputSignal {
     FSPIIO6           = 108,
     FSPIIO7           = 109,
     FSPICS0           = 110,
+    TWAI_RX           = 116,
     SUBSPIQ           = 120,
     SUBSPID           = 121,
     SUBSPIHD          = 122,
@@ -185,6 +211,9 @@ pub enum OutputSignal {
     FSPICS3         = 113,
     FSPICS4         = 114,
     FSPICS5         = 115,
+    TWAI_TX         = 116,
+    TWAI_BUS_OFF_ON = 117,
+    TWAI_CLKOUT     = 118,
     SUBSPICLK       = 119,
     SUBSPIQ         = 120,
     SUBSPID         = 121,
@@ -195,11 +224,42 @@ pub enum OutputSignal {
     FSPIDQS         = 126,
     SPI3_CS2        = 127,
     I2S0O_SD1       = 128,
+    LCD_CS          = 132,
+    LCD_DATA_0      = 133,
+    LCD_DATA_1      = 134,
+    LCD_DATA_2      = 135,
+    LCD_DATA_3      = 136,
+    LCD_DATA_4      = 137,
+    LCD_DATA_5      = 138,
+    LCD_DATA_6      = 139,
+    LCD_DATA_7      = 140,
+    LCD_DATA_8      = 141,
+    LCD_DATA_9      = 142,
+    LCD_DATA_10     = 143,
+    LCD_DATA_11     = 144,
+    LCD_DATA_12     = 145,
+    LCD_DATA_13     = 146,
+    LCD_DATA_14     = 147,
+    LCD_DATA_15     = 148,
+    LCD_DC          = 153,
+    LCD_PCLK        = 154,
     SUBSPID4        = 155,
     SUBSPID5        = 156,
     SUBSPID6        = 157,
     SUBSPID7        = 158,
     SUBSPIDQS       = 159,
+    PWM0_0A         = 160,
+    PWM0_0B         = 161,
+    PWM0_1A         = 162,
+    PWM0_1B         = 163,
+    PWM0_2A         = 164,
+    PWM0_2B         = 165,
+    PWM1_0A         = 166,
+    PWM1_0B         = 167,
+    PWM1_1A         = 168,
+    PWM1_1B         = 169,
+    PWM1_2A         = 170,
+    PWM1_2B         = 171,
     SDIO_TOHOST_INT = 177,
     PCMFSYNC        = 194,
     PCMCLK          = 195,
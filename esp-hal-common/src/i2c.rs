@@ -6,12 +6,14 @@ use core::convert::TryInto;
 
 use fugit::HertzU32;
 
+#[cfg(not(any(esp32c2, esp32c3)))]
+use crate::gpio::Bank1GpioRegisterAccess;
 use crate::{
     clock::Clocks,
-    gpio::{InputPin, OutputPin},
-    pac::i2c0::{RegisterBlock, COMD},
+    gpio::{Bank0GpioRegisterAccess, BankGpioRegisterAccess, InputPin, OutputPin},
+    pac::i2c0::RegisterBlock,
     system::PeripheralClockControl,
-    types::{InputSignal, OutputSignal},
+    types::{InputSignal, OutputSignal, OutputSignalType},
 };
 
 cfg_if::cfg_if! {
@@ -27,10 +29,19 @@ cfg_if::cfg_if! {
 pub enum Error {
     ExceedingFifo,
     AckCheckFailed,
+    /// SCL was held low, e.g. by a target stretching the clock, for longer
+    /// than the configured timeout
+    ///
+    /// The controller is reset, so the next transfer can be attempted right
+    /// away.
     TimeOut,
     ArbitrationLost,
     ExecIncomplete,
     CommandNrExceeded,
+    /// SDA is still held low after [I2C::recover] clocked the bus
+    BusStuck,
+    /// A [Address::TenBit] address above `0x3ff` was given
+    InvalidAddress,
 }
 
 #[cfg(feature = "eh1")]
@@ -53,6 +64,93 @@ pub enum SetupError {
     PeripheralDisabled,
 }
 
+/// Address of an I2C target
+///
+/// Plain `u8` values convert into 7-bit addresses, so the methods taking
+/// `impl Into<Address>` can be used with 7-bit addresses as before.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Address {
+    /// 7-bit address
+    SevenBit(u8),
+    /// 10-bit address, sent as the `11110` prefix with the two most
+    /// significant bits followed by a second byte with the remaining bits
+    ///
+    /// Transfers to addresses above `0x3ff` fail with
+    /// [Error::InvalidAddress].
+    TenBit(u16),
+}
+
+impl From<u8> for Address {
+    fn from(address: u8) -> Self {
+        Address::SevenBit(address)
+    }
+}
+
+impl Address {
+    fn check(&self) -> Result<(), Error> {
+        match self {
+            Address::TenBit(addr) if *addr > 0x3ff => Err(Error::InvalidAddress),
+            _ => Ok(()),
+        }
+    }
+
+    /// Number of bytes needed to address the target for writing
+    fn len(&self) -> usize {
+        match self {
+            Address::SevenBit(_) => 1,
+            Address::TenBit(_) => 2,
+        }
+    }
+
+    /// First byte sent after a START, including the R/W bit
+    fn first_byte(&self, operation: OperationType) -> u8 {
+        match self {
+            Address::SevenBit(addr) => addr << 1 | operation as u8,
+            Address::TenBit(addr) => 0xf0 | ((addr >> 7) as u8 & 0x06) | operation as u8,
+        }
+    }
+
+    /// Copy the bytes addressing the target for writing followed by `bytes`
+    /// into `buffer`
+    fn write_header<'a>(&self, bytes: &[u8], buffer: &'a mut [u8; 32]) -> Result<&'a [u8], Error> {
+        let len = self.len() + bytes.len();
+        if len > buffer.len() {
+            return Err(Error::ExceedingFifo);
+        }
+
+        buffer[0] = self.first_byte(OperationType::Write);
+        if let Address::TenBit(addr) = self {
+            buffer[1] = *addr as u8;
+        }
+        buffer[self.len()..len].copy_from_slice(bytes);
+
+        Ok(&buffer[..len])
+    }
+}
+
+/// An entry of a custom I2C command sequence
+///
+/// This mirrors the command register model of the hardware and can be used to
+/// assemble transactions not covered by `read`, `write` and `write_read`, see
+/// [I2C::execute].
+///
+/// Note that address bytes are not added automatically, they need to be part
+/// of a [CommandEntry::Write].
+pub enum CommandEntry<'a> {
+    /// (Repeated) START condition
+    Start,
+    /// STOP condition
+    Stop,
+    /// Write the given bytes, the ACK of every byte is checked
+    Write(&'a [u8]),
+    /// Read bytes into the given buffer. Every byte is ACKed, except for the
+    /// last one if `nack_last` is set.
+    Read {
+        buffer: &'a mut [u8],
+        nack_last: bool,
+    },
+}
+
 /// A generic I2C Command
 enum Command {
     Start,
@@ -163,6 +261,8 @@ enum Opcode {
 /// I2C peripheral container (I2C)
 pub struct I2C<T> {
     peripheral: T,
+    sda_pin: u8,
+    scl_pin: u8,
 }
 
 impl<T> embedded_hal::blocking::i2c::Read for I2C<T>
@@ -172,7 +272,7 @@ where
     type Error = Error;
 
     fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        self.peripheral.master_read(address, buffer)
+        self.peripheral.master_read(address.into(), buffer)
     }
 }
 
@@ -183,7 +283,7 @@ where
     type Error = Error;
 
     fn write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Self::Error> {
-        self.peripheral.master_write(addr, bytes)
+        self.peripheral.master_write(addr.into(), bytes)
     }
 }
 
@@ -199,7 +299,8 @@ where
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        self.peripheral.master_write_read(address, bytes, buffer)
+        self.peripheral
+            .master_write_read(address.into(), bytes, buffer, false)
     }
 }
 
@@ -214,11 +315,11 @@ where
     T: Instance,
 {
     fn read(&mut self, address: u8, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        self.peripheral.master_read(address, buffer)
+        self.peripheral.master_read(address.into(), buffer)
     }
 
     fn write(&mut self, address: u8, bytes: &[u8]) -> Result<(), Self::Error> {
-        self.peripheral.master_write(address, bytes)
+        self.peripheral.master_write(address.into(), bytes)
     }
 
     fn write_iter<B>(&mut self, _address: u8, _bytes: B) -> Result<(), Self::Error>
@@ -234,7 +335,8 @@ where
         bytes: &[u8],
         buffer: &mut [u8],
     ) -> Result<(), Self::Error> {
-        self.peripheral.master_write_read(address, bytes, buffer)
+        self.peripheral
+            .master_write_read(address.into(), bytes, buffer, false)
     }
 
     fn write_iter_read<B>(
@@ -272,17 +374,52 @@ where
     /// Create a new I2C instance
     /// This will enable the peripheral but the peripheral won't get
     /// automatically disabled when this gets dropped.
+    ///
+    /// Uses the default timeout, see [I2C::new_with_timeout].
     pub fn new<SDA: OutputPin + InputPin, SCL: OutputPin + InputPin>(
+        i2c: T,
+        sda: SDA,
+        scl: SCL,
+        frequency: HertzU32,
+        peripheral_clock_control: &mut PeripheralClockControl,
+        clocks: &Clocks,
+    ) -> Result<Self, SetupError> {
+        Self::new_with_timeout(
+            i2c,
+            sda,
+            scl,
+            frequency,
+            None,
+            peripheral_clock_control,
+            clocks,
+        )
+    }
+
+    /// Create a new I2C instance with a timeout for SCL being held low
+    ///
+    /// `timeout` is given in bus clock cycles. If SCL stays low for longer,
+    /// e.g. because a target stretches the clock indefinitely, the transfer is
+    /// aborted with [Error::TimeOut]. The resolution is limited to powers of
+    /// two on the ESP32-C2, ESP32-C3 and ESP32-S3, the timeout is rounded up.
+    ///
+    /// With `None` the timeout is 10 bus clock cycles on the ESP32 and
+    /// disabled on the other chips.
+    pub fn new_with_timeout<SDA: OutputPin + InputPin, SCL: OutputPin + InputPin>(
         i2c: T,
         mut sda: SDA,
         mut scl: SCL,
         frequency: HertzU32,
+        timeout: Option<u32>,
         peripheral_clock_control: &mut PeripheralClockControl,
         clocks: &Clocks,
     ) -> Result<Self, SetupError> {
         enable_peripheral(&i2c, peripheral_clock_control);
 
-        let mut i2c = I2C { peripheral: i2c };
+        let mut i2c = I2C {
+            peripheral: i2c,
+            sda_pin: sda.number(),
+            scl_pin: scl.number(),
+        };
 
         sda.set_to_open_drain_output()
             .enable_input(true)
@@ -296,17 +433,183 @@ where
             .connect_peripheral_to_output(OutputSignal::I2CEXT0_SCL)
             .connect_input_to_peripheral(InputSignal::I2CEXT0_SCL);
 
-        i2c.peripheral.setup(frequency, clocks)?;
+        i2c.peripheral.setup(frequency, clocks, timeout)?;
 
         Ok(i2c)
     }
 
+    /// Read `buffer.len()` bytes from the target with the given address
+    ///
+    /// Accepts 7-bit (`u8` or [Address::SevenBit]) and 10-bit
+    /// ([Address::TenBit]) addresses.
+    pub fn read(&mut self, address: impl Into<Address>, buffer: &mut [u8]) -> Result<(), Error> {
+        self.peripheral.master_read(address.into(), buffer)
+    }
+
+    /// Write `bytes` to the target with the given address
+    ///
+    /// Accepts 7-bit (`u8` or [Address::SevenBit]) and 10-bit
+    /// ([Address::TenBit]) addresses.
+    pub fn write(&mut self, address: impl Into<Address>, bytes: &[u8]) -> Result<(), Error> {
+        self.peripheral.master_write(address.into(), bytes)
+    }
+
+    /// Write `bytes` to the target with the given address and read
+    /// `buffer.len()` bytes back after a repeated START
+    ///
+    /// Accepts 7-bit (`u8` or [Address::SevenBit]) and 10-bit
+    /// ([Address::TenBit]) addresses.
+    pub fn write_read(
+        &mut self,
+        address: impl Into<Address>,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.peripheral
+            .master_write_read(address.into(), bytes, buffer, false)
+    }
+
+    /// Execute a custom sequence of commands
+    ///
+    /// All bytes to be written need to fit into the TX FIFO (32 bytes), all
+    /// bytes to be read into the RX FIFO (31 bytes), and the number of hardware
+    /// commands is limited, otherwise [Error::ExceedingFifo] or
+    /// [Error::CommandNrExceeded] is returned.
+    ///
+    /// ```rust
+    /// // write-then-repeated-start-write without a read
+    /// i2c.execute(&mut [
+    ///     CommandEntry::Start,
+    ///     CommandEntry::Write(&[addr << 1, reg]),
+    ///     CommandEntry::Start,
+    ///     CommandEntry::Write(&[addr << 1, data]),
+    ///     CommandEntry::Stop,
+    /// ])?;
+    /// ```
+    pub fn execute(&mut self, commands: &mut [CommandEntry]) -> Result<(), Error> {
+        self.peripheral.execute_commands(commands)
+    }
+
+    /// Like `write_read`, but with a STOP and a new START between writing and
+    /// reading instead of a repeated START
+    ///
+    /// The I2C specification requires a repeated START, which is what
+    /// `write_read` does. This is only needed for devices which don't handle
+    /// that correctly.
+    ///
+    /// The write and the read are executed as two separate transactions, so
+    /// another controller on the bus may start a transaction in between.
+    pub fn write_read_with_stop(
+        &mut self,
+        address: impl Into<Address>,
+        bytes: &[u8],
+        buffer: &mut [u8],
+    ) -> Result<(), Error> {
+        self.peripheral
+            .master_write_read(address.into(), bytes, buffer, true)
+    }
+
+    /// Free a bus whose SDA line is held low by a target
+    ///
+    /// A target which lost track of a transfer, e.g. because the controller
+    /// was reset in the middle of a read, keeps driving SDA low while it
+    /// waits for more clock pulses. This takes over SDA and SCL as plain
+    /// GPIOs, clocks SCL up to 9 times until the target releases SDA and
+    /// issues a STOP condition. Afterwards the pins are handed back to the I2C
+    /// controller, which is reset.
+    ///
+    /// The bus is clocked at roughly 100kHz. Returns [Error::BusStuck] if SDA
+    /// is still low afterwards.
+    pub fn recover(&mut self) -> Result<(), Error> {
+        let gpio = unsafe { &*crate::pac::GPIO::PTR };
+        let (sda, scl) = (self.sda_pin, self.scl_pin);
+
+        let sda_signal = gpio.func_out_sel_cfg[sda as usize].read().out_sel().bits();
+        let scl_signal = gpio.func_out_sel_cfg[scl as usize].read().out_sel().bits();
+
+        // The pins are still configured as open drain outputs, so driving them
+        // high releases the lines
+        set_pin_level(sda, true);
+        set_pin_level(scl, true);
+        for pin in [sda, scl] {
+            gpio.func_out_sel_cfg[pin as usize]
+                .modify(|_, w| unsafe { w.out_sel().bits(OutputSignal::GPIO as OutputSignalType) });
+        }
+        half_bus_cycle();
+
+        for _ in 0..9 {
+            if pin_level(sda) {
+                break;
+            }
+
+            set_pin_level(scl, false);
+            half_bus_cycle();
+            set_pin_level(scl, true);
+            half_bus_cycle();
+        }
+
+        // STOP condition: SDA goes high while SCL is high
+        set_pin_level(scl, false);
+        half_bus_cycle();
+        set_pin_level(sda, false);
+        half_bus_cycle();
+        set_pin_level(scl, true);
+        half_bus_cycle();
+        set_pin_level(sda, true);
+        half_bus_cycle();
+
+        let released = pin_level(sda);
+
+        gpio.func_out_sel_cfg[sda as usize].modify(|_, w| unsafe { w.out_sel().bits(sda_signal) });
+        gpio.func_out_sel_cfg[scl as usize].modify(|_, w| unsafe { w.out_sel().bits(scl_signal) });
+
+        self.peripheral.reset();
+
+        if released {
+            Ok(())
+        } else {
+            Err(Error::BusStuck)
+        }
+    }
+
     /// Return the raw interface to the underlying peripheral
     pub fn free(self) -> T {
         self.peripheral
     }
 }
 
+/// Wait half a clock cycle of a 100kHz bus
+fn half_bus_cycle() {
+    unsafe { crate::rom::esp_rom_delay_us(5) };
+}
+
+fn set_pin_level(pin: u8, high: bool) {
+    #[cfg(not(any(esp32c2, esp32c3)))]
+    if pin >= 32 {
+        if high {
+            Bank1GpioRegisterAccess::write_output_set(1 << (pin - 32));
+        } else {
+            Bank1GpioRegisterAccess::write_output_clear(1 << (pin - 32));
+        }
+        return;
+    }
+
+    if high {
+        Bank0GpioRegisterAccess::write_output_set(1 << pin);
+    } else {
+        Bank0GpioRegisterAccess::write_output_clear(1 << pin);
+    }
+}
+
+fn pin_level(pin: u8) -> bool {
+    #[cfg(not(any(esp32c2, esp32c3)))]
+    if pin >= 32 {
+        return Bank1GpioRegisterAccess::read_input() & 1 << (pin - 32) != 0;
+    }
+
+    Bank0GpioRegisterAccess::read_input() & 1 << pin != 0
+}
+
 fn enable_peripheral<T: Instance>(i2c: &T, peripheral_clock_control: &mut PeripheralClockControl) {
     // enable peripheral
     match i2c.i2c_number() {
@@ -323,7 +626,12 @@ pub trait Instance {
 
     fn i2c_number(&self) -> usize;
 
-    fn setup(&mut self, frequency: HertzU32, clocks: &Clocks) -> Result<(), SetupError> {
+    fn setup(
+        &mut self,
+        frequency: HertzU32,
+        clocks: &Clocks,
+        timeout: Option<u32>,
+    ) -> Result<(), SetupError> {
         // Reset entire peripheral (also resets fifo)
         self.reset();
 
@@ -357,7 +665,7 @@ pub trait Instance {
         self.set_filter(Some(7), Some(7));
 
         // Configure frequency
-        self.set_frequency(clocks.i2c_clock.convert(), frequency)?;
+        self.set_frequency(clocks.i2c_clock.convert(), frequency, timeout)?;
 
         // Propagate configuration changes (only necessary with C2, C3, and S3)
         #[cfg(any(esp32c2, esp32c3, esp32s3))]
@@ -435,10 +743,13 @@ pub trait Instance {
 
     /// Sets the frequency of the I2C interface by calculating and applying the
     /// associated timings
+    ///
+    /// `timeout` is given in bus clock cycles.
     fn set_frequency(
         &mut self,
         source_clk: HertzU32,
         bus_freq: HertzU32,
+        timeout: Option<u32>,
     ) -> Result<(), SetupError> {
         cfg_if::cfg_if! {
             if #[cfg(any(esp32c2, esp32c3, esp32s3))] {
@@ -477,10 +788,12 @@ pub trait Instance {
                 let scl_high = half_cycle;
                 let sda_hold = half_cycle / 2;
                 let sda_sample = scl_high / 2;
-                let tout = half_cycle * 20;
             }
         }
 
+        // timeout in cycles of the module clock
+        let tout = timeout.map(|timeout| timeout.saturating_mul(2 * half_cycle));
+
         let scl_low = half_cycle;
         let setup = half_cycle;
         let hold = half_cycle;
@@ -583,16 +896,33 @@ pub trait Instance {
             cfg_if::cfg_if! {
                 if #[cfg(esp32)] {
                     // timeout
+                    let tout = tout.unwrap_or(half_cycle * 20).min(0xfffff);
                     self.register_block()
                         .to
-                        .write(|w| w.time_out().bits(tout.into()));
+                        .write(|w| w.time_out().bits(tout));
+                } else if #[cfg(esp32s2)] {
+                    // timeout
+                    self.register_block().to.write(|w| match tout {
+                        Some(tout) => w
+                            .time_out_value()
+                            .bits(tout.min(0xffffff))
+                            .time_out_en()
+                            .set_bit(),
+                        None => w.time_out_en().clear_bit(),
+                    });
                 }
                 else {
-                    // timeout
-                    // FIXME: Enable timout for other chips!
-                    self.register_block()
-                        .to
-                        .write(|w| w.time_out_en().clear_bit());
+                    // timeout, the hardware counts to 2^time_out_value
+                    self.register_block().to.write(|w| match tout {
+                        Some(tout) => {
+                            let exponent = 32 - tout.saturating_sub(1).leading_zeros();
+                            w.time_out_value()
+                                .bits(exponent.min(0x1f) as u8)
+                                .time_out_en()
+                                .set_bit()
+                        }
+                        None => w.time_out_en().clear_bit(),
+                    });
                 }
             }
         }
@@ -631,6 +961,7 @@ pub trait Instance {
                 if #[cfg(esp32)] {
                     // Handle error cases
                     if interrupts.time_out_int_raw().bit_is_set() {
+                        self.reset();
                         return Err(Error::TimeOut);
                     } else if interrupts.ack_err_int_raw().bit_is_set() {
                         return Err(Error::AckCheckFailed);
@@ -641,6 +972,7 @@ pub trait Instance {
                 else {
                     // Handle error cases
                     if interrupts.time_out_int_raw().bit_is_set() {
+                        self.reset();
                         return Err(Error::TimeOut);
                     } else if interrupts.nack_int_raw().bit_is_set() {
                         return Err(Error::AckCheckFailed);
@@ -669,131 +1001,120 @@ pub trait Instance {
         Ok(())
     }
 
-    fn add_write_operation<'a, I>(
-        &self,
-        addr: u8,
-        bytes: &[u8],
-        cmd_iterator: &mut I,
-        include_stop: bool,
-    ) -> Result<(), Error>
-    where
-        I: Iterator<Item = &'a COMD>,
-    {
-        // Check if we have another cmd register ready, otherwise return appropriate
-        // error
-        let cmd_start = cmd_iterator.next().ok_or(Error::CommandNrExceeded)?;
-        // RSTART command
-        cmd_start.write(|w| unsafe { w.command().bits(Command::Start.into()) });
-
-        // Load address and R/W bit into FIFO
-        write_fifo(
-            self.register_block(),
-            addr << 1 | OperationType::Write as u8,
-        );
-        // Load actual data bytes
-        for byte in bytes {
-            write_fifo(self.register_block(), *byte);
+    /// Execute a custom sequence of commands, see [I2C::execute]
+    fn execute_commands(&mut self, commands: &mut [CommandEntry]) -> Result<(), Error> {
+        let mut write_len = 0;
+        let mut read_len = 0;
+        for command in commands.iter() {
+            match command {
+                CommandEntry::Write(bytes) => write_len += bytes.len(),
+                CommandEntry::Read { buffer, .. } => read_len += buffer.len(),
+                _ => (),
+            }
+        }
+
+        if write_len > 32 || read_len > 31 {
+            return Err(Error::ExceedingFifo);
         }
 
-        // Check if we have another cmd register ready, otherwise return appropriate
-        // error
-        let cmd_write = cmd_iterator.next().ok_or(Error::CommandNrExceeded)?;
-        // WRITE command
-        cmd_write.write(|w| unsafe {
-            w.command().bits(
-                Command::Write {
-                    ack_exp: Ack::Ack,
-                    ack_check_en: true,
-                    length: 1 + bytes.len() as u8,
+        // Reset FIFO and command list
+        self.reset_fifo();
+        self.reset_command_list();
+
+        let mut cmd_iterator = self.register_block().comd.iter();
+
+        for command in commands.iter() {
+            match command {
+                CommandEntry::Start => {
+                    cmd_iterator
+                        .next()
+                        .ok_or(Error::CommandNrExceeded)?
+                        .write(|w| unsafe { w.command().bits(Command::Start.into()) });
                 }
-                .into(),
-            )
-        });
+                CommandEntry::Stop => {
+                    cmd_iterator
+                        .next()
+                        .ok_or(Error::CommandNrExceeded)?
+                        .write(|w| unsafe { w.command().bits(Command::Stop.into()) });
+                }
+                CommandEntry::Write(bytes) => {
+                    if bytes.is_empty() {
+                        continue;
+                    }
 
-        if include_stop {
-            // Check if we have another cmd register ready, otherwise return appropriate
-            // error
-            let cmd_stop = cmd_iterator.next().ok_or(Error::CommandNrExceeded)?;
-            // STOP command
-            cmd_stop.write(|w| unsafe { w.command().bits(Command::Stop.into()) });
-        }
+                    for byte in bytes.iter() {
+                        write_fifo(self.register_block(), *byte);
+                    }
 
-        Ok(())
-    }
+                    cmd_iterator
+                        .next()
+                        .ok_or(Error::CommandNrExceeded)?
+                        .write(|w| unsafe {
+                            w.command().bits(
+                                Command::Write {
+                                    ack_exp: Ack::Ack,
+                                    ack_check_en: true,
+                                    length: bytes.len() as u8,
+                                }
+                                .into(),
+                            )
+                        });
+                }
+                CommandEntry::Read { buffer, nack_last } => {
+                    if buffer.is_empty() {
+                        continue;
+                    }
 
-    fn add_read_operation<'a, I>(
-        &self,
-        addr: u8,
-        buffer: &[u8],
-        cmd_iterator: &mut I,
-    ) -> Result<(), Error>
-    where
-        I: Iterator<Item = &'a COMD>,
-    {
-        // Check if we have another cmd register ready, otherwise return appropriate
-        // error
-        cmd_iterator
-            .next()
-            .ok_or(Error::CommandNrExceeded)?
-            .write(|w| unsafe { w.command().bits(Command::Start.into()) });
-
-        // Load address and R/W bit into FIFO
-        write_fifo(self.register_block(), addr << 1 | OperationType::Read as u8);
-
-        // Check if we have another cmd register ready, otherwise return appropriate
-        // error
-        cmd_iterator
-            .next()
-            .ok_or(Error::CommandNrExceeded)?
-            .write(|w| unsafe {
-                w.command().bits(
-                    Command::Write {
-                        ack_exp: Ack::Ack,
-                        ack_check_en: true,
-                        length: 1,
+                    let acked = if *nack_last {
+                        buffer.len() - 1
+                    } else {
+                        buffer.len()
+                    };
+
+                    if acked > 0 {
+                        cmd_iterator
+                            .next()
+                            .ok_or(Error::CommandNrExceeded)?
+                            .write(|w| unsafe {
+                                w.command().bits(
+                                    Command::Read {
+                                        ack_value: Ack::Ack,
+                                        length: acked as u8,
+                                    }
+                                    .into(),
+                                )
+                            });
                     }
-                    .into(),
-                )
-            });
 
-        // For reading bytes prior to the last read byte we need to
-        // configure the ack
-        if buffer.len() > 1 {
-            // READ command for first n - 1 bytes
-            cmd_iterator
-                .next()
-                .ok_or(Error::CommandNrExceeded)?
-                .write(|w| unsafe {
-                    w.command().bits(
-                        Command::Read {
-                            ack_value: Ack::Ack,
-                            length: buffer.len() as u8 - 1,
-                        }
-                        .into(),
-                    )
-                });
+                    if *nack_last {
+                        cmd_iterator
+                            .next()
+                            .ok_or(Error::CommandNrExceeded)?
+                            .write(|w| unsafe {
+                                w.command().bits(
+                                    Command::Read {
+                                        ack_value: Ack::Nack,
+                                        length: 1,
+                                    }
+                                    .into(),
+                                )
+                            });
+                    }
+                }
+            }
         }
 
-        // READ command for (last or only) byte
-        cmd_iterator
-            .next()
-            .ok_or(Error::CommandNrExceeded)?
-            .write(|w| unsafe {
-                w.command().bits(
-                    Command::Read {
-                        ack_value: Ack::Nack,
-                        length: 1,
-                    }
-                    .into(),
-                )
-            });
+        // Start transmission
+        self.execute_transmission()?;
 
-        // Check if we have another cmd register ready, otherwise return appropriate
-        // error
-        cmd_iterator
-            .next()
-            .ok_or(Error::CommandNrExceeded)?
-            .write(|w| unsafe { w.command().bits(Command::Stop.into()) });
+        // Read bytes from FIFO, in the order of the read commands
+        for command in commands.iter_mut() {
+            if let CommandEntry::Read { buffer, .. } = command {
+                for byte in buffer.iter_mut() {
+                    *byte = read_fifo(self.register_block());
+                }
+            }
+        }
 
         Ok(())
     }
@@ -811,21 +1132,20 @@ pub trait Instance {
 
     /// Send data bytes from the `bytes` array to a target slave with the
     /// address `addr`
-    fn master_write(&mut self, addr: u8, bytes: &[u8]) -> Result<(), Error> {
-        // Reset FIFO and command list
-        self.reset_fifo();
-        self.reset_command_list();
+    fn master_write(&mut self, addr: Address, bytes: &[u8]) -> Result<(), Error> {
+        addr.check()?;
+        let mut header = [0u8; 32];
 
         // Split the potentially larger `bytes` array into chunks of (at most) 31
-        // entries Together with the addr/access byte at the beginning of every
-        // transmission, this is the maximum size that we can store in the
-        // (default config) TX FIFO
-        for chunk in bytes.chunks(31) {
-            // Add write operation
-            self.add_write_operation(addr, chunk, &mut self.register_block().comd.iter(), true)?;
-
-            // Start transmission
-            self.execute_transmission()?
+        // entries (30 for 10-bit addresses). Together with the addr/access
+        // byte(s) at the beginning of every transmission, this is the maximum
+        // size that we can store in the (default config) TX FIFO
+        for chunk in bytes.chunks(32 - addr.len()) {
+            self.execute_commands(&mut [
+                CommandEntry::Start,
+                CommandEntry::Write(addr.write_header(chunk, &mut header)?),
+                CommandEntry::Stop,
+            ])?;
         }
 
         Ok(())
@@ -834,74 +1154,76 @@ pub trait Instance {
     /// Read bytes from a target slave with the address `addr`
     /// The number of read bytes is deterimed by the size of the `buffer`
     /// argument
-    fn master_read(&mut self, addr: u8, buffer: &mut [u8]) -> Result<(), Error> {
-        // If the buffer size is > 32 bytes, this signals the
-        // intent to read more than that number of bytes, which we
-        // cannot achieve at this point in time
-        // TODO: Handle the case where we transfer an amount of data that is exceeding
-        // the FIFO size (i.e. > 32 bytes?)
-        if buffer.len() > 31 {
-            return Err(Error::ExceedingFifo);
-        }
-
-        // Reset FIFO and command list
-        self.reset_fifo();
-        self.reset_command_list();
-
-        // Add write operation
-        self.add_read_operation(addr, buffer, &mut self.register_block().comd.iter())?;
-
-        // Start transmission
-        self.execute_transmission()?;
-
-        // Read bytes from FIFO
-        // FIXME: Handle case where less data has been provided by the slave than
-        // requested? Or is this prevented from a protocol perspective?
-        for byte in buffer.iter_mut() {
-            *byte = read_fifo(self.register_block());
+    fn master_read(&mut self, addr: Address, buffer: &mut [u8]) -> Result<(), Error> {
+        addr.check()?;
+        let read_header = [addr.first_byte(OperationType::Read)];
+
+        match addr {
+            Address::SevenBit(_) => self.execute_commands(&mut [
+                CommandEntry::Start,
+                CommandEntry::Write(&read_header),
+                CommandEntry::Read {
+                    buffer,
+                    nack_last: true,
+                },
+                CommandEntry::Stop,
+            ]),
+            // A 10-bit target needs to be addressed for writing before it can
+            // be read from
+            Address::TenBit(_) => {
+                let mut header = [0u8; 32];
+                self.execute_commands(&mut [
+                    CommandEntry::Start,
+                    CommandEntry::Write(addr.write_header(&[], &mut header)?),
+                    CommandEntry::Start,
+                    CommandEntry::Write(&read_header),
+                    CommandEntry::Read {
+                        buffer,
+                        nack_last: true,
+                    },
+                    CommandEntry::Stop,
+                ])
+            }
         }
-
-        Ok(())
     }
 
     /// Write bytes from the `bytes` array first and then read n bytes into
     /// the `buffer` array with n being the size of the array.
+    ///
+    /// The read follows the write with a repeated START, unless `stop_between`
+    /// is set, in which case a STOP and a new START are issued.
     fn master_write_read(
         &mut self,
-        addr: u8,
+        addr: Address,
         bytes: &[u8],
         buffer: &mut [u8],
+        stop_between: bool,
     ) -> Result<(), Error> {
-        // If the buffer or bytes size is > 32 bytes, this signals the
-        // intent to read/write more than that number of bytes, which we
-        // cannot achieve at this point in time
-        // TODO: Handle the case where we transfer an amount of data that is exceeding
-        // the FIFO size (i.e. > 32 bytes?)
-        if buffer.len() > 31 || bytes.len() > 31 {
-            return Err(Error::ExceedingFifo);
+        addr.check()?;
+        let mut header = [0u8; 32];
+        let write = CommandEntry::Write(addr.write_header(bytes, &mut header)?);
+
+        if stop_between {
+            // Two independent transactions, the STOP ends the first one before
+            // the read is set up and started
+            self.execute_commands(&mut [CommandEntry::Start, write, CommandEntry::Stop])?;
+            return self.master_read(addr, buffer);
         }
 
-        // Reset FIFO and command list
-        self.reset_fifo();
-        self.reset_command_list();
-
-        let mut cmd_iterator = self.register_block().comd.iter();
-
-        // Add write operation
-        self.add_write_operation(addr, bytes, &mut cmd_iterator, false)?;
-
-        // Add read operation (which appends commands to the existing command list)
-        self.add_read_operation(addr, buffer, &mut cmd_iterator)?;
-
-        // Start transmission
-        self.execute_transmission()?;
-
-        // Read bytes from FIFO
-        for byte in buffer.iter_mut() {
-            *byte = read_fifo(self.register_block());
-        }
-
-        Ok(())
+        // A 10-bit target stays addressed after the write, the repeated START
+        // only needs the first address byte
+        let read_header = [addr.first_byte(OperationType::Read)];
+        self.execute_commands(&mut [
+            CommandEntry::Start,
+            write,
+            CommandEntry::Start,
+            CommandEntry::Write(&read_header),
+            CommandEntry::Read {
+                buffer,
+                nack_last: true,
+            },
+            CommandEntry::Stop,
+        ])
     }
 }
 
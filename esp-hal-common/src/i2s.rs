@@ -0,0 +1,831 @@
+//! # Inter-IC Sound (I2S)
+//!
+//! ### Summary
+//! Streams audio samples to and from external codecs, DACs, ADCs and MEMS
+//! microphones via the I2S0 peripheral. Both directions run in master mode
+//! and are fed by a GDMA channel in circular mode, so audio keeps flowing
+//! while the CPU refills (TX) or drains (RX) the buffer chunk by chunk.
+//!
+//! The Philips standard and the left-justified (MSB) format are supported,
+//! always with two channels. Samples are interleaved in memory, left channel
+//! first, in little endian byte order.
+//!
+//! [I2s::new] configures the clocks of both directions and splits the
+//! peripheral into a TX and an RX half, which can be used independently
+//! (half-duplex, each with its own bit clock and word select pins) or
+//! together (full-duplex, RX follows the clocks of TX). The master clock
+//! (MCLK) runs at 256 times the sample rate and can be routed to a pin via
+//! [I2s::with_mclk] for codecs which need it.
+//!
+//! The buffer of a transfer is split into chunks, the DMA cycles through
+//! them until the transfer is stopped. With two chunks, every completed chunk
+//! signals that one half of the buffer can be refilled or read, i.e. double
+//! buffering. The chunks raise DMA interrupts when listened to, see
+//! [I2sWriteDmaTransfer::listen_chunk_done] and
+//! [I2sReadDmaTransfer::listen_chunk_done].
+//!
+//! This driver is available on the ESP32-C3 and ESP32-S3, I2S1 of the
+//! ESP32-S3 isn't supported yet.
+//!
+//! ### Example
+//! ```no_run
+//! let i2s = I2s::new(
+//!     peripherals.I2S0,
+//!     Standard::Philips,
+//!     DataFormat::Data16Channel16,
+//!     44100u32.Hz(),
+//!     dma_channel.configure(
+//!         false,
+//!         &mut tx_descriptors,
+//!         &mut rx_descriptors,
+//!         DmaPriority::Priority0,
+//!     ),
+//!     &mut system.peripheral_clock_control,
+//! )
+//! .unwrap();
+//!
+//! let i2s_tx = i2s
+//!     .i2s_tx
+//!     .with_pins(io.pins.gpio2, io.pins.gpio3, io.pins.gpio4);
+//! let mut transfer = i2s_tx.write_dma_circular(buffer, 4000).unwrap();
+//!
+//! loop {
+//!     if let Some(chunk) = transfer.take_completed_chunk() {
+//!         fill_with_samples(transfer.chunk_mut(chunk));
+//!     }
+//! }
+//! ```
+
+#![deny(missing_docs)]
+
+use core::marker::PhantomData;
+
+use embedded_dma::WriteBuffer;
+use fugit::HertzU32;
+
+#[cfg(esp32c3)]
+use crate::pac::{i2s::RegisterBlock, I2S as I2S0};
+#[cfg(esp32s3)]
+use crate::pac::{i2s0::RegisterBlock, I2S0};
+use crate::{
+    dma::{
+        private::{I2s0Peripheral, Rx, Tx},
+        Channel,
+        DmaError,
+        DmaPeripheral,
+    },
+    gpio::{InputPin, InputSignal, OutputPin, OutputSignal},
+    system::{Peripheral, PeripheralClockControl},
+};
+
+#[cfg(esp32c3)]
+const DMA_PERIPHERAL: DmaPeripheral = DmaPeripheral::I2s;
+#[cfg(esp32s3)]
+const DMA_PERIPHERAL: DmaPeripheral = DmaPeripheral::I2s0;
+
+#[cfg(esp32c3)]
+mod signals {
+    use super::{InputSignal, OutputSignal};
+
+    pub const MCLK: OutputSignal = OutputSignal::I2S_MCLK;
+    pub const TX_BCK: OutputSignal = OutputSignal::I2SO_BCK;
+    pub const TX_WS: OutputSignal = OutputSignal::I2SO_WS;
+    pub const TX_SD: OutputSignal = OutputSignal::I2SO_SD;
+    pub const RX_BCK: OutputSignal = OutputSignal::I2SI_BCK;
+    pub const RX_WS: OutputSignal = OutputSignal::I2SI_WS;
+    pub const RX_SD: InputSignal = InputSignal::I2SI_SD;
+}
+
+#[cfg(esp32s3)]
+mod signals {
+    use super::{InputSignal, OutputSignal};
+
+    pub const MCLK: OutputSignal = OutputSignal::I2S0_MCLK;
+    pub const TX_BCK: OutputSignal = OutputSignal::I2S0O_BCK;
+    pub const TX_WS: OutputSignal = OutputSignal::I2S0O_WS;
+    pub const TX_SD: OutputSignal = OutputSignal::I2S0O_SD;
+    pub const RX_BCK: OutputSignal = OutputSignal::I2S0I_BCK;
+    pub const RX_WS: OutputSignal = OutputSignal::I2S0I_WS;
+    pub const RX_SD: InputSignal = InputSignal::I2S0I_SD;
+}
+
+/// Frequency of the PLL_F160M clock the I2S clocks are derived from
+const I2S_SCLK: u32 = 160_000_000;
+
+/// Ratio between the master clock and the sample rate
+const MCLK_MULTIPLE: u32 = 256;
+
+/// Value of `TX_CLK_SEL`/`RX_CLK_SEL` selecting PLL_F160M
+const CLK_SEL_PLL_F160M: u8 = 2;
+
+/// I2S errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The sample rate can't be derived from the 160 MHz PLL clock, the
+    /// supported range is about 2.5 kHz to 312 kHz
+    UnsupportedSampleRate,
+}
+
+/// Frame format
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Standard {
+    /// Philips I2S: WS is low for the left channel, the data is delayed by
+    /// one bit clock cycle after the WS edge
+    Philips,
+    /// Left-justified (MSB) format: WS is high for the left channel, the data
+    /// starts with the WS edge
+    LeftJustified,
+}
+
+/// Sample width in memory and channel (slot) width on the bus
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DataFormat {
+    /// 16 bit samples in 16 bit channels
+    Data16Channel16,
+    /// 16 bit samples in 32 bit channels, padded with zeros
+    Data16Channel32,
+    /// 32 bit samples in 32 bit channels
+    Data32Channel32,
+}
+
+impl DataFormat {
+    fn data_bits(&self) -> u8 {
+        match self {
+            DataFormat::Data16Channel16 | DataFormat::Data16Channel32 => 16,
+            DataFormat::Data32Channel32 => 32,
+        }
+    }
+
+    fn channel_bits(&self) -> u8 {
+        match self {
+            DataFormat::Data16Channel16 => 16,
+            DataFormat::Data16Channel32 | DataFormat::Data32Channel32 => 32,
+        }
+    }
+}
+
+/// Divider settings for MCLK and the bit clock
+struct ClockDivider {
+    /// Integral part of the MCLK divider
+    mclk_divider: u8,
+    /// Denominator of the fractional part of the MCLK divider
+    denominator: u32,
+    /// Numerator of the fractional part of the MCLK divider
+    numerator: u32,
+    /// Divider from MCLK to the bit clock
+    bclk_divider: u8,
+}
+
+impl ClockDivider {
+    fn new(sample_rate: u32, data_format: DataFormat) -> Result<Self, Error> {
+        let mclk = sample_rate
+            .checked_mul(MCLK_MULTIPLE)
+            .ok_or(Error::UnsupportedSampleRate)?;
+        if mclk == 0 {
+            return Err(Error::UnsupportedSampleRate);
+        }
+
+        let mut mclk_divider = I2S_SCLK / mclk;
+        let remainder = (I2S_SCLK - mclk * mclk_divider) as u64;
+        let mclk = mclk as u64;
+
+        let mut denominator = 1;
+        let mut numerator = 0;
+        if remainder * 126 > mclk * 125 {
+            // the fractional part is too close to 1 for a denominator <= 63
+            mclk_divider += 1;
+        } else if remainder != 0 {
+            // find the fraction b / a closest to remainder / mclk
+            let mut min = u64::MAX;
+            for a in 2..=63 {
+                let b = (a * remainder + mclk / 2) / mclk;
+                let error = (a * remainder).abs_diff(b * mclk);
+                if error < min {
+                    denominator = a as u32;
+                    numerator = b as u32;
+                    min = error;
+                }
+                if error == 0 {
+                    break;
+                }
+            }
+        }
+
+        if !(2..=255).contains(&mclk_divider) {
+            return Err(Error::UnsupportedSampleRate);
+        }
+
+        Ok(ClockDivider {
+            mclk_divider: mclk_divider as u8,
+            denominator,
+            numerator,
+            bclk_divider: (MCLK_MULTIPLE / (2 * data_format.channel_bits() as u32)) as u8,
+        })
+    }
+
+    /// Values of the `X`, `Y`, `Z` and `YN1` fields of `TX_CLKM_DIV_CONF` and
+    /// `RX_CLKM_DIV_CONF` implementing the fractional part
+    fn fractional(&self) -> (u16, u16, u16, bool) {
+        let a = self.denominator;
+        let b = self.numerator;
+
+        if b == 0 {
+            (0, 0, 0, false)
+        } else if b <= a / 2 {
+            ((a / b - 1) as u16, (a % b) as u16, b as u16, false)
+        } else {
+            (
+                (a / (a - b) - 1) as u16,
+                (a % (a - b)) as u16,
+                (a - b) as u16,
+                true,
+            )
+        }
+    }
+}
+
+fn register_block() -> &'static RegisterBlock {
+    unsafe { &*I2S0::PTR }
+}
+
+/// I2S0 with configured clocks, split into its TX and RX halves
+pub struct I2s<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    /// The TX half, see [TxCreator::with_pins]
+    pub i2s_tx: TxCreator<TX, P>,
+    /// The RX half, see [RxCreator::with_pins] and
+    /// [RxCreator::with_tx_clocks]
+    pub i2s_rx: RxCreator<RX, P>,
+}
+
+impl<TX, RX, P> I2s<TX, RX, P>
+where
+    TX: Tx,
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    /// Configure the clocks and frame format of both directions
+    ///
+    /// The TX half uses the TX side of `channel`, the RX half its RX side.
+    pub fn new(
+        _i2s: I2S0,
+        standard: Standard,
+        data_format: DataFormat,
+        sample_rate: HertzU32,
+        channel: Channel<TX, RX, P>,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Result<Self, Error> {
+        let divider = ClockDivider::new(sample_rate.raw(), data_format)?;
+
+        peripheral_clock_control.enable(Peripheral::I2s0);
+
+        let Channel { mut tx, rx, .. } = channel;
+        tx.init_channel(); // no need to call this for both, TX and RX
+
+        configure_tx(standard, data_format, &divider);
+        configure_rx(standard, data_format, &divider);
+
+        Ok(I2s {
+            i2s_tx: TxCreator {
+                tx,
+                _phantom: PhantomData::default(),
+            },
+            i2s_rx: RxCreator {
+                rx,
+                _phantom: PhantomData::default(),
+            },
+        })
+    }
+
+    /// Output the master clock on `pin`
+    pub fn with_mclk<PIN: OutputPin>(self, mut pin: PIN) -> Self {
+        pin.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::MCLK);
+
+        self
+    }
+}
+
+fn configure_tx(standard: Standard, data_format: DataFormat, divider: &ClockDivider) {
+    let i2s = register_block();
+    let (x, y, z, yn1) = divider.fractional();
+    let data_bits = data_format.data_bits();
+    let channel_bits = data_format.channel_bits();
+
+    i2s.tx_clkm_div_conf.write(|w| unsafe {
+        w.tx_clkm_div_x()
+            .bits(x)
+            .tx_clkm_div_y()
+            .bits(y)
+            .tx_clkm_div_z()
+            .bits(z)
+            .tx_clkm_div_yn1()
+            .bit(yn1)
+    });
+    i2s.tx_clkm_conf.modify(|_, w| unsafe {
+        w.clk_en()
+            .set_bit()
+            .tx_clk_active()
+            .set_bit()
+            .tx_clk_sel()
+            .bits(CLK_SEL_PLL_F160M)
+            .tx_clkm_div_num()
+            .bits(divider.mclk_divider)
+    });
+
+    i2s.tx_conf1.modify(|_, w| unsafe {
+        w.tx_tdm_ws_width()
+            .bits(channel_bits - 1)
+            .tx_bck_div_num()
+            .bits(divider.bclk_divider - 1)
+            .tx_bits_mod()
+            .bits(data_bits - 1)
+            .tx_half_sample_bits()
+            .bits(channel_bits - 1)
+            .tx_tdm_chan_bits()
+            .bits(channel_bits - 1)
+            .tx_msb_shift()
+            .bit(standard == Standard::Philips)
+            .tx_bck_no_dly()
+            .set_bit()
+    });
+
+    i2s.tx_conf.modify(|_, w| {
+        w.tx_slave_mod()
+            .clear_bit()
+            .tx_mono()
+            .clear_bit()
+            .tx_big_endian()
+            .clear_bit()
+            .tx_bit_order()
+            .clear_bit()
+            .tx_left_align()
+            .set_bit()
+            .tx_ws_idle_pol()
+            .bit(standard == Standard::LeftJustified)
+            .tx_pcm_bypass()
+            .set_bit()
+            .tx_pdm_en()
+            .clear_bit()
+            .tx_tdm_en()
+            .set_bit()
+            .sig_loopback()
+            .clear_bit()
+    });
+
+    // two channels, both enabled
+    i2s.tx_tdm_ctrl
+        .write(|w| unsafe { w.bits(TDM_TWO_CHANNELS) });
+}
+
+fn configure_rx(standard: Standard, data_format: DataFormat, divider: &ClockDivider) {
+    let i2s = register_block();
+    let (x, y, z, yn1) = divider.fractional();
+    let data_bits = data_format.data_bits();
+    let channel_bits = data_format.channel_bits();
+
+    i2s.rx_clkm_div_conf.write(|w| unsafe {
+        w.rx_clkm_div_x()
+            .bits(x)
+            .rx_clkm_div_y()
+            .bits(y)
+            .rx_clkm_div_z()
+            .bits(z)
+            .rx_clkm_div_yn1()
+            .bit(yn1)
+    });
+    i2s.rx_clkm_conf.modify(|_, w| unsafe {
+        w.rx_clk_active()
+            .set_bit()
+            .rx_clk_sel()
+            .bits(CLK_SEL_PLL_F160M)
+            .rx_clkm_div_num()
+            .bits(divider.mclk_divider)
+            // MCLK is taken from TX
+            .mclk_sel()
+            .clear_bit()
+    });
+
+    i2s.rx_conf1.modify(|_, w| unsafe {
+        w.rx_tdm_ws_width()
+            .bits(channel_bits - 1)
+            .rx_bck_div_num()
+            .bits(divider.bclk_divider - 1)
+            .rx_bits_mod()
+            .bits(data_bits - 1)
+            .rx_half_sample_bits()
+            .bits(channel_bits - 1)
+            .rx_tdm_chan_bits()
+            .bits(channel_bits - 1)
+            .rx_msb_shift()
+            .bit(standard == Standard::Philips)
+    });
+
+    i2s.rx_conf.modify(|_, w| {
+        w.rx_slave_mod()
+            .clear_bit()
+            .rx_mono()
+            .clear_bit()
+            .rx_big_endian()
+            .clear_bit()
+            .rx_bit_order()
+            .clear_bit()
+            .rx_left_align()
+            .set_bit()
+            .rx_ws_idle_pol()
+            .bit(standard == Standard::LeftJustified)
+            .rx_pcm_bypass()
+            .set_bit()
+            .rx_pdm_en()
+            .clear_bit()
+            .rx_tdm_en()
+            .set_bit()
+    });
+
+    // two channels, both enabled
+    i2s.rx_tdm_ctrl
+        .write(|w| unsafe { w.bits(TDM_TWO_CHANNELS) });
+}
+
+/// `TX_TDM_CTRL`/`RX_TDM_CTRL` value enabling channels 0 and 1 with a total
+/// of two channels (`TDM_TOT_CHAN_NUM` + 1)
+const TDM_TWO_CHANNELS: u32 = 0b11 | (1 << 16);
+
+/// The TX half of the I2S peripheral before its pins are assigned
+pub struct TxCreator<TX, P>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    tx: TX,
+    _phantom: PhantomData<P>,
+}
+
+impl<TX, P> TxCreator<TX, P>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    /// Output the bit clock, word select and data signals on the given pins
+    pub fn with_pins<BCLK: OutputPin, WS: OutputPin, DOUT: OutputPin>(
+        self,
+        mut bclk: BCLK,
+        mut ws: WS,
+        mut dout: DOUT,
+    ) -> I2sTx<TX, P> {
+        bclk.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::TX_BCK);
+        ws.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::TX_WS);
+        dout.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::TX_SD);
+
+        I2sTx {
+            tx: self.tx,
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+/// The RX half of the I2S peripheral before its pins are assigned
+pub struct RxCreator<RX, P>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    rx: RX,
+    _phantom: PhantomData<P>,
+}
+
+impl<RX, P> RxCreator<RX, P>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    /// Output the bit clock and word select signals on the given pins and
+    /// receive data from `din`, independent of TX (half-duplex)
+    pub fn with_pins<BCLK: OutputPin, WS: OutputPin, DIN: InputPin>(
+        self,
+        mut bclk: BCLK,
+        mut ws: WS,
+        mut din: DIN,
+    ) -> I2sRx<RX, P> {
+        bclk.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::RX_BCK);
+        ws.set_to_push_pull_output()
+            .connect_peripheral_to_output(signals::RX_WS);
+        din.set_to_input()
+            .connect_input_to_peripheral(signals::RX_SD);
+
+        I2sRx {
+            rx: self.rx,
+            _phantom: PhantomData::default(),
+        }
+    }
+
+    /// Receive data from `din`, clocked by the bit clock and word select
+    /// signals of TX (full-duplex)
+    ///
+    /// The data is only received while TX is running.
+    pub fn with_tx_clocks<DIN: InputPin>(self, mut din: DIN) -> I2sRx<RX, P> {
+        din.set_to_input()
+            .connect_input_to_peripheral(signals::RX_SD);
+
+        let i2s = register_block();
+        i2s.tx_conf.modify(|_, w| w.sig_loopback().set_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_slave_mod().set_bit());
+
+        I2sRx {
+            rx: self.rx,
+            _phantom: PhantomData::default(),
+        }
+    }
+}
+
+/// The TX half of the I2S peripheral, see [TxCreator::with_pins]
+pub struct I2sTx<TX, P>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    tx: TX,
+    _phantom: PhantomData<P>,
+}
+
+impl<TX, P> I2sTx<TX, P>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    /// Start sending `buffer` in a loop
+    ///
+    /// The buffer is split into chunks of `chunk_size` bytes, at most 4092,
+    /// and needs to hold at least two of them. It is sent over and over again
+    /// until the transfer is stopped, a chunk can be refilled once it
+    /// completed, see [I2sWriteDmaTransfer::take_completed_chunk].
+    pub fn write_dma_circular<BUFFER>(
+        mut self,
+        mut buffer: BUFFER,
+        chunk_size: usize,
+    ) -> Result<I2sWriteDmaTransfer<TX, P, BUFFER>, DmaError>
+    where
+        BUFFER: WriteBuffer<Word = u8>,
+    {
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+        let i2s = register_block();
+
+        i2s.tx_conf.modify(|_, w| w.tx_reset().set_bit());
+        i2s.tx_conf.modify(|_, w| w.tx_reset().clear_bit());
+        i2s.tx_conf.modify(|_, w| w.tx_fifo_reset().set_bit());
+        i2s.tx_conf.modify(|_, w| w.tx_fifo_reset().clear_bit());
+
+        self.tx
+            .prepare_circular_transfer(DMA_PERIPHERAL, ptr, len, chunk_size)?;
+        // discard a chunk completion left from an earlier transfer
+        self.tx.take_descriptor_done();
+
+        i2s.tx_conf.modify(|_, w| w.tx_update().clear_bit());
+        i2s.tx_conf.modify(|_, w| w.tx_update().set_bit());
+        i2s.tx_conf.modify(|_, w| w.tx_start().set_bit());
+
+        Ok(I2sWriteDmaTransfer {
+            i2s_tx: self,
+            buffer,
+            ptr,
+            chunk_size,
+            len,
+        })
+    }
+}
+
+/// A running circular TX transfer, see [I2sTx::write_dma_circular]
+pub struct I2sWriteDmaTransfer<TX, P, BUFFER>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    i2s_tx: I2sTx<TX, P>,
+    buffer: BUFFER,
+    ptr: *mut u8,
+    chunk_size: usize,
+    len: usize,
+}
+
+impl<TX, P, BUFFER> I2sWriteDmaTransfer<TX, P, BUFFER>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    /// Return the index of the chunk which was sent since the last call
+    ///
+    /// The chunk isn't read again until the DMA wrapped around the buffer, so
+    /// it can be refilled via [I2sWriteDmaTransfer::chunk_mut] now. Only the
+    /// most recently completed chunk is reported, so this needs to be called
+    /// at least once per chunk.
+    pub fn take_completed_chunk(&mut self) -> Option<usize> {
+        self.i2s_tx.tx.take_descriptor_done()
+    }
+
+    /// The contents of the chunk with the given index
+    ///
+    /// The last chunk is shorter if the buffer length isn't a multiple of the
+    /// chunk size.
+    pub fn chunk_mut(&mut self, index: usize) -> &mut [u8] {
+        let start = usize::min(index * self.chunk_size, self.len);
+        let len = usize::min(self.chunk_size, self.len - start);
+
+        unsafe { core::slice::from_raw_parts_mut(self.ptr.add(start), len) }
+    }
+
+    /// Enable the DMA interrupt raised for every completed chunk
+    pub fn listen_chunk_done(&mut self) {
+        self.i2s_tx.tx.listen_descriptor_done(true);
+    }
+
+    /// Disable the DMA interrupt raised for every completed chunk
+    pub fn unlisten_chunk_done(&mut self) {
+        self.i2s_tx.tx.listen_descriptor_done(false);
+    }
+
+    /// Return and clear a FIFO underflow, i.e. the DMA couldn't fetch the
+    /// samples in time
+    pub fn take_error(&mut self) -> Option<DmaError> {
+        self.i2s_tx.tx.take_error()
+    }
+
+    /// Stop sending and return the buffer and the TX half
+    pub fn stop(mut self) -> (BUFFER, I2sTx<TX, P>) {
+        self.halt();
+
+        // `Drop` is implemented, so we can't move out of the fields, see
+        // `SpiDmaTransfer::wait`
+        //
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            let i2s_tx = core::ptr::read(&self.i2s_tx);
+            core::mem::forget(self);
+            (buffer, i2s_tx)
+        }
+    }
+
+    fn halt(&mut self) {
+        register_block()
+            .tx_conf
+            .modify(|_, w| w.tx_start().clear_bit());
+        self.i2s_tx.tx.listen_descriptor_done(false);
+        self.i2s_tx.tx.reset();
+    }
+}
+
+impl<TX, P, BUFFER> Drop for I2sWriteDmaTransfer<TX, P, BUFFER>
+where
+    TX: Tx,
+    P: I2s0Peripheral,
+{
+    fn drop(&mut self) {
+        // the DMA must not read from the buffer anymore once it's dropped
+        self.halt();
+    }
+}
+
+/// The RX half of the I2S peripheral, see [RxCreator::with_pins] and
+/// [RxCreator::with_tx_clocks]
+pub struct I2sRx<RX, P>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    rx: RX,
+    _phantom: PhantomData<P>,
+}
+
+impl<RX, P> I2sRx<RX, P>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    /// Start receiving into `buffer`, which is used as a ring buffer
+    ///
+    /// The buffer is split into chunks of `chunk_size` bytes, at most 4092,
+    /// and needs to hold at least two of them. Received data is taken out
+    /// via [I2sReadDmaTransfer::pop], which hands the chunks back to the DMA.
+    pub fn read_dma_circular<BUFFER>(
+        mut self,
+        mut buffer: BUFFER,
+        chunk_size: usize,
+    ) -> Result<I2sReadDmaTransfer<RX, P, BUFFER>, DmaError>
+    where
+        BUFFER: WriteBuffer<Word = u8>,
+    {
+        let (ptr, len) = unsafe { buffer.write_buffer() };
+        let i2s = register_block();
+
+        i2s.rx_conf.modify(|_, w| w.rx_reset().set_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_reset().clear_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_fifo_reset().set_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_fifo_reset().clear_bit());
+
+        // close a descriptor after every chunk, so the EOF interrupt is
+        // raised per chunk
+        i2s.rx_eof_num
+            .write(|w| unsafe { w.rx_eof_num().bits(chunk_size as u16) });
+
+        self.rx
+            .prepare_circular_transfer(DMA_PERIPHERAL, ptr, len, chunk_size)?;
+
+        i2s.rx_conf.modify(|_, w| w.rx_update().clear_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_update().set_bit());
+        i2s.rx_conf.modify(|_, w| w.rx_start().set_bit());
+
+        Ok(I2sReadDmaTransfer {
+            i2s_rx: self,
+            buffer,
+        })
+    }
+}
+
+/// A running circular RX transfer, see [I2sRx::read_dma_circular]
+pub struct I2sReadDmaTransfer<RX, P, BUFFER>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    i2s_rx: I2sRx<RX, P>,
+    buffer: BUFFER,
+}
+
+impl<RX, P, BUFFER> I2sReadDmaTransfer<RX, P, BUFFER>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    /// Number of bytes which were received but not popped yet
+    pub fn available(&mut self) -> usize {
+        self.i2s_rx.rx.circular_available()
+    }
+
+    /// Copy received data to `data`, returns the number of bytes copied
+    pub fn pop(&mut self, data: &mut [u8]) -> usize {
+        self.i2s_rx.rx.circular_pop(data)
+    }
+
+    /// Enable the DMA EOF interrupt raised for every completed chunk
+    pub fn listen_chunk_done(&mut self) {
+        self.i2s_rx.rx.listen_eof(true);
+    }
+
+    /// Disable the DMA EOF interrupt raised for every completed chunk
+    pub fn unlisten_chunk_done(&mut self) {
+        self.i2s_rx.rx.listen_eof(false);
+    }
+
+    /// Clear the interrupts of the RX side of the DMA channel
+    pub fn clear_interrupt(&mut self) {
+        self.i2s_rx.rx.clear_interrupts();
+    }
+
+    /// Return and clear a FIFO overflow, i.e. samples were lost because they
+    /// weren't popped in time
+    pub fn take_error(&mut self) -> Option<DmaError> {
+        self.i2s_rx.rx.take_error()
+    }
+
+    /// Stop receiving and return the buffer and the RX half
+    pub fn stop(mut self) -> (BUFFER, I2sRx<RX, P>) {
+        self.halt();
+
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`, see `I2sWriteDmaTransfer::stop`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            let i2s_rx = core::ptr::read(&self.i2s_rx);
+            core::mem::forget(self);
+            (buffer, i2s_rx)
+        }
+    }
+
+    fn halt(&mut self) {
+        register_block()
+            .rx_conf
+            .modify(|_, w| w.rx_start().clear_bit());
+        self.i2s_rx.rx.listen_eof(false);
+        self.i2s_rx.rx.reset();
+    }
+}
+
+impl<RX, P, BUFFER> Drop for I2sReadDmaTransfer<RX, P, BUFFER>
+where
+    RX: Rx,
+    P: I2s0Peripheral,
+{
+    fn drop(&mut self) {
+        // the DMA must not write to the buffer anymore once it's dropped
+        self.halt();
+    }
+}
@@ -145,34 +145,40 @@ pub unsafe fn enable_cpu_interrupt(which: CpuInterrupt) {
 }
 
 /// Disable the given peripheral interrupt.
-pub fn disable(_core: Cpu, interrupt: Interrupt) {
-    unsafe {
-        let interrupt_number = interrupt as isize;
-        let intr = &*crate::pac::INTERRUPT_CORE0::PTR;
-        let intr_map_base = intr.mac_intr_map.as_ptr();
-        intr_map_base.offset(interrupt_number).write_volatile(0);
-    }
+///
+/// # Safety
+///
+/// This bypasses the [InterruptController], the caller needs to make sure it
+/// doesn't interfere with the configuration done by its owner. Use
+/// [InterruptController::disable] instead.
+pub unsafe fn disable(_core: Cpu, interrupt: Interrupt) {
+    let interrupt_number = interrupt as isize;
+    let intr = &*crate::pac::INTERRUPT_CORE0::PTR;
+    let intr_map_base = intr.mac_intr_map.as_ptr();
+    intr_map_base.offset(interrupt_number).write_volatile(0);
 }
 
 /// Set the interrupt kind (i.e. level or edge) of an CPU interrupt
 ///
 /// This is safe to call when the `vectored` feature is enabled. The vectored
 /// interrupt handler will take care of clearing edge interrupt bits.
-pub fn set_kind(_core: Cpu, which: CpuInterrupt, kind: InterruptKind) {
-    unsafe {
-        let intr = &*crate::pac::INTERRUPT_CORE0::PTR;
-        let cpu_interrupt_number = which as isize;
+///
+/// # Safety
+///
+/// This bypasses the [InterruptController], the caller needs to make sure it
+/// doesn't interfere with the configuration done by its owner. Use
+/// [InterruptController::set_kind] instead.
+pub unsafe fn set_kind(_core: Cpu, which: CpuInterrupt, kind: InterruptKind) {
+    let intr = &*crate::pac::INTERRUPT_CORE0::PTR;
+    let cpu_interrupt_number = which as isize;
 
-        let interrupt_type = match kind {
-            InterruptKind::Level => 0,
-            InterruptKind::Edge => 1,
-        };
-        intr.cpu_int_type.modify(|r, w| {
-            w.bits(
-                r.bits() & !(1 << cpu_interrupt_number) | (interrupt_type << cpu_interrupt_number),
-            )
-        });
-    }
+    let interrupt_type = match kind {
+        InterruptKind::Level => 0,
+        InterruptKind::Edge => 1,
+    };
+    intr.cpu_int_type.modify(|r, w| {
+        w.bits(r.bits() & !(1 << cpu_interrupt_number) | (interrupt_type << cpu_interrupt_number))
+    });
 }
 
 /// Set the priority level of an CPU interrupt
@@ -190,6 +196,24 @@ pub unsafe fn set_priority(_core: Cpu, which: CpuInterrupt, priority: Priority)
         .write_volatile(priority as u32);
 }
 
+/// Set the interrupt threshold of the core
+///
+/// CPU interrupts with a priority below the threshold are masked. The
+/// threshold resets to 0, which services all enabled interrupts just like
+/// [Priority::Priority1] as priority 0 disables an interrupt. Raising it allows
+/// masking the lower priority interrupts without touching their
+/// configuration.
+///
+/// # Safety
+///
+/// This bypasses the [InterruptController], the caller needs to make sure it
+/// doesn't interfere with the configuration done by its owner. Use
+/// [InterruptController::set_threshold] instead.
+pub unsafe fn set_threshold(_core: Cpu, threshold: Priority) {
+    let intr = &*crate::pac::INTERRUPT_CORE0::PTR;
+    intr.cpu_int_thresh.write(|w| w.bits(threshold as u32));
+}
+
 /// Clear a CPU interrupt
 #[inline]
 pub fn clear(_core: Cpu, which: CpuInterrupt) {
@@ -217,6 +241,60 @@ pub fn get_status(_core: Cpu) -> u128 {
     }
 }
 
+/// Owner of the interrupt matrix configuration
+///
+/// There is only one instance, handed out via `SystemParts`, so only one owner
+/// can configure the interrupt matrix. The free functions in this module which
+/// change the configuration are `unsafe`, since they bypass this owner.
+pub struct InterruptController {
+    pub(crate) _private: (),
+}
+
+impl InterruptController {
+    /// Enables a interrupt at a given priority
+    ///
+    /// Note that interrupts still need to be enabled globally for interrupts
+    /// to be serviced.
+    #[cfg(feature = "vectored")]
+    pub fn enable(&mut self, interrupt: Interrupt, level: Priority) -> Result<(), Error> {
+        unsafe { enable(interrupt, level) }
+    }
+
+    /// Disable the given peripheral interrupt
+    pub fn disable(&mut self, core: Cpu, interrupt: Interrupt) {
+        unsafe { disable(core, interrupt) }
+    }
+
+    /// Set the interrupt kind (i.e. level or edge) of an CPU interrupt, see
+    /// [set_kind]
+    pub fn set_kind(&mut self, core: Cpu, which: CpuInterrupt, kind: InterruptKind) {
+        unsafe { set_kind(core, which, kind) }
+    }
+
+    /// Set the interrupt threshold of the core, see [set_threshold]
+    pub fn set_threshold(&mut self, core: Cpu, threshold: Priority) {
+        unsafe { set_threshold(core, threshold) }
+    }
+
+    /// Set the priority level of an CPU interrupt, see [set_priority]
+    ///
+    /// # Safety
+    ///
+    /// See [set_priority]
+    pub unsafe fn set_priority(&mut self, core: Cpu, which: CpuInterrupt, priority: Priority) {
+        set_priority(core, which, priority)
+    }
+
+    /// Map a peripheral interrupt to a CPU interrupt, see [map]
+    ///
+    /// # Safety
+    ///
+    /// See [map]
+    pub unsafe fn map(&mut self, core: Cpu, interrupt: Interrupt, which: CpuInterrupt) {
+        map(core, interrupt, which)
+    }
+}
+
 #[cfg(feature = "vectored")]
 pub use vectored::*;
 
@@ -282,15 +360,19 @@ mod vectored {
     ///
     /// Note that interrupts still need to be enabled globally for interrupts
     /// to be serviced.
-    pub fn enable(interrupt: Interrupt, level: Priority) -> Result<(), Error> {
+    ///
+    /// # Safety
+    ///
+    /// This bypasses the [InterruptController], the caller needs to make sure
+    /// it doesn't interfere with the configuration done by its owner. Use
+    /// [InterruptController::enable] instead.
+    pub unsafe fn enable(interrupt: Interrupt, level: Priority) -> Result<(), Error> {
         if matches!(level, Priority::None) {
             return Err(Error::InvalidInterruptPriority);
         }
-        unsafe {
-            let cpu_interrupt = core::mem::transmute(level as u8 as u32);
-            map(crate::get_core(), interrupt, cpu_interrupt);
-            enable_cpu_interrupt(cpu_interrupt);
-        }
+        let cpu_interrupt = core::mem::transmute(level as u8 as u32);
+        map(crate::get_core(), interrupt, cpu_interrupt);
+        enable_cpu_interrupt(cpu_interrupt);
         Ok(())
     }
 
@@ -76,18 +76,22 @@ pub unsafe fn map(core: Cpu, interrupt: Interrupt, which: CpuInterrupt) {
 }
 
 /// Disable the given peripheral interrupt.
-pub fn disable(core: Cpu, interrupt: Interrupt) {
-    unsafe {
-        let interrupt_number = interrupt as isize;
-        let intr_map_base = match core {
-            Cpu::ProCpu => (*core0_interrupt_peripheral()).pro_mac_intr_map.as_ptr(),
-            #[cfg(multi_core)]
-            Cpu::AppCpu => (*core1_interrupt_peripheral()).app_mac_intr_map.as_ptr(),
-            #[cfg(single_core)]
-            Cpu::AppCpu => (*core0_interrupt_peripheral()).pro_mac_intr_map.as_ptr(),
-        };
-        intr_map_base.offset(interrupt_number).write_volatile(0);
-    }
+///
+/// # Safety
+///
+/// This bypasses the [InterruptController], the caller needs to make sure it
+/// doesn't interfere with the configuration done by its owner. Use
+/// [InterruptController::disable] instead.
+pub unsafe fn disable(core: Cpu, interrupt: Interrupt) {
+    let interrupt_number = interrupt as isize;
+    let intr_map_base = match core {
+        Cpu::ProCpu => (*core0_interrupt_peripheral()).pro_mac_intr_map.as_ptr(),
+        #[cfg(multi_core)]
+        Cpu::AppCpu => (*core1_interrupt_peripheral()).app_mac_intr_map.as_ptr(),
+        #[cfg(single_core)]
+        Cpu::AppCpu => (*core0_interrupt_peripheral()).pro_mac_intr_map.as_ptr(),
+    };
+    intr_map_base.offset(interrupt_number).write_volatile(0);
 }
 
 /// Clear the given CPU interrupt
@@ -180,6 +184,40 @@ unsafe fn core1_interrupt_peripheral() -> *const crate::pac::interrupt_core1::Re
     crate::pac::INTERRUPT_CORE1::PTR
 }
 
+/// Owner of the interrupt matrix configuration
+///
+/// There is only one instance, handed out via `SystemParts`, so only one owner
+/// can configure the interrupt matrix. The free functions in this module which
+/// change the configuration are `unsafe`, since they bypass this owner.
+pub struct InterruptController {
+    pub(crate) _private: (),
+}
+
+impl InterruptController {
+    /// Enables a interrupt at a given priority
+    ///
+    /// Note that interrupts still need to be enabled globally for interrupts
+    /// to be serviced.
+    #[cfg(feature = "vectored")]
+    pub fn enable(&mut self, interrupt: Interrupt, level: Priority) -> Result<(), Error> {
+        unsafe { enable(interrupt, level) }
+    }
+
+    /// Disable the given peripheral interrupt
+    pub fn disable(&mut self, core: Cpu, interrupt: Interrupt) {
+        unsafe { disable(core, interrupt) }
+    }
+
+    /// Map a peripheral interrupt to a CPU interrupt, see [map]
+    ///
+    /// # Safety
+    ///
+    /// See [map]
+    pub unsafe fn map(&mut self, core: Cpu, interrupt: Interrupt, which: CpuInterrupt) {
+        map(core, interrupt, which)
+    }
+}
+
 #[cfg(feature = "vectored")]
 pub use vectored::*;
 
@@ -280,17 +318,22 @@ mod vectored {
         }
     }
 
-    pub fn enable(interrupt: Interrupt, level: Priority) -> Result<(), Error> {
+    /// Enables a interrupt at a given priority
+    ///
+    /// # Safety
+    ///
+    /// This bypasses the [InterruptController], the caller needs to make sure
+    /// it doesn't interfere with the configuration done by its owner. Use
+    /// [InterruptController::enable] instead.
+    pub unsafe fn enable(interrupt: Interrupt, level: Priority) -> Result<(), Error> {
         let cpu_interrupt =
             interrupt_level_to_cpu_interrupt(level, chip_specific::interrupt_is_edge(interrupt))?;
 
-        unsafe {
-            map(get_core(), interrupt, cpu_interrupt);
+        map(get_core(), interrupt, cpu_interrupt);
 
-            xtensa_lx::interrupt::enable_mask(
-                xtensa_lx::interrupt::get_mask() | 1 << cpu_interrupt as u32,
-            );
-        }
+        xtensa_lx::interrupt::enable_mask(
+            xtensa_lx::interrupt::get_mask() | 1 << cpu_interrupt as u32,
+        );
         Ok(())
     }
 
@@ -0,0 +1,454 @@
+//! # LCD_CAM - Intel 8080 parallel output
+//!
+//! ### Summary
+//! Drives displays with an Intel 8080 (i8080, "MCU") parallel interface via
+//! the LCD part of the LCD_CAM peripheral of the ESP32-S3. Data is streamed
+//! from memory by a GDMA channel on 8 or 16 data lines, clocked by the write
+//! strobe (WR/PCLK). The data/command line (DC) is driven by the peripheral:
+//! low while the command is sent, high for the data.
+//!
+//! A transfer consists of an optional command followed by data of any length,
+//! e.g. a whole frame buffer. Buffers longer than a single DMA descriptor are
+//! split into chained descriptors, so the descriptor array passed to
+//! `configure` needs [descriptor_words](crate::dma::descriptor_words) words
+//! for the largest transfer. The DMA can only read from internal RAM, data in
+//! flash (e.g. `const` arrays) needs to be copied to RAM first.
+//!
+//! RGB (pixel/line/frame clock) mode and the camera part of the peripheral
+//! aren't supported yet.
+//!
+//! ### Example
+//! ```no_run
+//! let mut i8080 = I8080::new(
+//!     peripherals.LCD_CAM,
+//!     dma_channel.configure(
+//!         false,
+//!         &mut tx_descriptors,
+//!         &mut rx_descriptors,
+//!         DmaPriority::Priority0,
+//!     ),
+//!     TxEightBits::new(d0, d1, d2, d3, d4, d5, d6, d7),
+//!     wr,
+//!     dc,
+//!     20u32.MHz(),
+//!     &mut system.peripheral_clock_control,
+//! )
+//! .unwrap()
+//! .with_cs(cs);
+//!
+//! // RAMWR, followed by the pixel data
+//! let transfer = i8080.send_dma(Some(0x2c), frame_buffer).unwrap();
+//! let (frame_buffer, i8080) = transfer.wait();
+//! ```
+
+#![deny(missing_docs)]
+
+use embedded_dma::ReadBuffer;
+use fugit::HertzU32;
+
+use crate::{
+    dma::{
+        private::{LcdCamPeripheral, Rx, Tx},
+        Channel,
+        DmaError,
+        DmaPeripheral,
+    },
+    gpio::{OutputPin, OutputSignal},
+    pac::LCD_CAM,
+    system::{Peripheral, PeripheralClockControl},
+};
+
+/// Frequency of LCD_CLK, PLL_F160M divided by two
+const LCD_CLK: u32 = 80_000_000;
+
+/// Value of `LCD_CLK_SEL` selecting PLL_F160M
+const CLK_SEL_PLL_F160M: u8 = 3;
+
+/// Divider from PLL_F160M to LCD_CLK
+const LCD_CLKM_DIV_NUM: u8 = 2;
+
+/// Largest divider from LCD_CLK to the pixel clock
+const MAX_PCLK_DIVIDER: u32 = 64;
+
+/// LCD_CAM errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The frequency can't be derived from the 80 MHz LCD clock, the supported
+    /// range is 1.25 MHz to 80 MHz
+    UnsupportedFrequency,
+}
+
+/// Data lines of the parallel bus
+pub trait TxPins {
+    /// Whether the bus has 16 data lines
+    const SIXTEEN_BITS: bool;
+
+    /// Connect the pins to the data outputs of the peripheral
+    fn configure(&mut self);
+}
+
+macro_rules! tx_pins {
+    ($name:ident, $sixteen_bits:literal, $($pin:ident: $field:ident => $signal:ident),+) => {
+        impl<$($pin: OutputPin),+> $name<$($pin),+> {
+            /// Use the given pins as data lines, in order from the least to
+            /// the most significant bit
+            #[allow(clippy::too_many_arguments)]
+            pub fn new($($field: $pin),+) -> Self {
+                Self { $($field),+ }
+            }
+        }
+
+        impl<$($pin: OutputPin),+> TxPins for $name<$($pin),+> {
+            const SIXTEEN_BITS: bool = $sixteen_bits;
+
+            fn configure(&mut self) {
+                $(
+                    self.$field
+                        .set_to_push_pull_output()
+                        .connect_peripheral_to_output(OutputSignal::$signal);
+                )+
+            }
+        }
+    };
+}
+
+/// 8 data lines
+pub struct TxEightBits<P0, P1, P2, P3, P4, P5, P6, P7> {
+    d0: P0,
+    d1: P1,
+    d2: P2,
+    d3: P3,
+    d4: P4,
+    d5: P5,
+    d6: P6,
+    d7: P7,
+}
+
+tx_pins!(
+    TxEightBits,
+    false,
+    P0: d0 => LCD_DATA_0,
+    P1: d1 => LCD_DATA_1,
+    P2: d2 => LCD_DATA_2,
+    P3: d3 => LCD_DATA_3,
+    P4: d4 => LCD_DATA_4,
+    P5: d5 => LCD_DATA_5,
+    P6: d6 => LCD_DATA_6,
+    P7: d7 => LCD_DATA_7
+);
+
+/// 16 data lines
+pub struct TxSixteenBits<P0, P1, P2, P3, P4, P5, P6, P7, P8, P9, P10, P11, P12, P13, P14, P15> {
+    d0: P0,
+    d1: P1,
+    d2: P2,
+    d3: P3,
+    d4: P4,
+    d5: P5,
+    d6: P6,
+    d7: P7,
+    d8: P8,
+    d9: P9,
+    d10: P10,
+    d11: P11,
+    d12: P12,
+    d13: P13,
+    d14: P14,
+    d15: P15,
+}
+
+tx_pins!(
+    TxSixteenBits,
+    true,
+    P0: d0 => LCD_DATA_0,
+    P1: d1 => LCD_DATA_1,
+    P2: d2 => LCD_DATA_2,
+    P3: d3 => LCD_DATA_3,
+    P4: d4 => LCD_DATA_4,
+    P5: d5 => LCD_DATA_5,
+    P6: d6 => LCD_DATA_6,
+    P7: d7 => LCD_DATA_7,
+    P8: d8 => LCD_DATA_8,
+    P9: d9 => LCD_DATA_9,
+    P10: d10 => LCD_DATA_10,
+    P11: d11 => LCD_DATA_11,
+    P12: d12 => LCD_DATA_12,
+    P13: d13 => LCD_DATA_13,
+    P14: d14 => LCD_DATA_14,
+    P15: d15 => LCD_DATA_15
+);
+
+/// Intel 8080 parallel output, see the [module level documentation](self)
+pub struct I8080<TX, RX, P, PINS>
+where
+    TX: Tx,
+    RX: Rx,
+    P: LcdCamPeripheral,
+    PINS: TxPins,
+{
+    lcd_cam: LCD_CAM,
+    channel: Channel<TX, RX, P>,
+    pins: PINS,
+}
+
+impl<TX, RX, P, PINS> I8080<TX, RX, P, PINS>
+where
+    TX: Tx,
+    RX: Rx,
+    P: LcdCamPeripheral,
+    PINS: TxPins,
+{
+    /// Configure the LCD for i8080 output with the write strobe running at
+    /// `frequency`
+    ///
+    /// The frequency is rounded down to 80 MHz divided by an integer.
+    pub fn new<WR: OutputPin, DC: OutputPin>(
+        lcd_cam: LCD_CAM,
+        mut channel: Channel<TX, RX, P>,
+        mut pins: PINS,
+        mut wr: WR,
+        mut dc: DC,
+        frequency: HertzU32,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Result<Self, Error> {
+        let divider = match frequency.raw() {
+            frequency if frequency == 0 || frequency > LCD_CLK => {
+                return Err(Error::UnsupportedFrequency)
+            }
+            frequency => (LCD_CLK + frequency - 1) / frequency,
+        };
+        if divider > MAX_PCLK_DIVIDER {
+            return Err(Error::UnsupportedFrequency);
+        }
+
+        peripheral_clock_control.enable(Peripheral::LcdCam);
+        channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+        lcd_cam.lcd_clock.write(|w| unsafe {
+            w.clk_en()
+                .set_bit()
+                .lcd_clk_sel()
+                .bits(CLK_SEL_PLL_F160M)
+                .lcd_clkm_div_num()
+                .bits(LCD_CLKM_DIV_NUM)
+                .lcd_clkm_div_a()
+                .bits(0)
+                .lcd_clkm_div_b()
+                .bits(0)
+                .lcd_clk_equ_sysclk()
+                .bit(divider == 1)
+                .lcd_clkcnt_n()
+                .bits(divider.saturating_sub(1) as u8)
+                .lcd_ck_idle_edge()
+                .clear_bit()
+                .lcd_ck_out_edge()
+                .clear_bit()
+        });
+
+        lcd_cam
+            .lcd_ctrl
+            .modify(|_, w| w.lcd_rgb_mode_en().clear_bit());
+
+        // DC is high while idle and for data, low for the command
+        lcd_cam.lcd_misc.write(|w| {
+            w.lcd_cd_idle_edge()
+                .set_bit()
+                .lcd_cd_cmd_set()
+                .set_bit()
+                .lcd_cd_dummy_set()
+                .set_bit()
+                .lcd_cd_data_set()
+                .clear_bit()
+                .lcd_bk_en()
+                .set_bit()
+                .lcd_next_frame_en()
+                .clear_bit()
+        });
+
+        // The data phase lasts until the DMA reached the end of the data
+        lcd_cam.lcd_user.write(|w| {
+            w.lcd_2byte_en()
+                .bit(PINS::SIXTEEN_BITS)
+                .lcd_8bits_order()
+                .clear_bit()
+                .lcd_bit_order()
+                .clear_bit()
+                .lcd_byte_order()
+                .clear_bit()
+                .lcd_always_out_en()
+                .set_bit()
+        });
+
+        lcd_cam
+            .lc_dma_int_ena
+            .modify(|_, w| w.lcd_trans_done_int_ena().clear_bit());
+
+        pins.configure();
+        wr.set_to_push_pull_output()
+            .connect_peripheral_to_output(OutputSignal::LCD_PCLK);
+        dc.set_to_push_pull_output()
+            .connect_peripheral_to_output(OutputSignal::LCD_DC);
+
+        Ok(I8080 {
+            lcd_cam,
+            channel,
+            pins,
+        })
+    }
+
+    /// Drive the chip select line `cs`, which is low during transfers
+    pub fn with_cs<CS: OutputPin>(self, mut cs: CS) -> Self {
+        cs.set_to_push_pull_output()
+            .connect_peripheral_to_output(OutputSignal::LCD_CS);
+
+        self
+    }
+
+    /// Send `command` (if any) followed by `data` and wait for the transfer
+    /// to complete
+    ///
+    /// On an 8 bit bus, only the lower byte of the command is sent.
+    pub fn send(&mut self, command: Option<u16>, data: &[u8]) -> Result<(), DmaError> {
+        self.start(command, data.as_ptr(), data.len())?;
+        self.wait_done();
+
+        Ok(())
+    }
+
+    /// Start sending `command` (if any) followed by `buffer`
+    ///
+    /// On an 8 bit bus, only the lower byte of the command is sent. The
+    /// returned transfer gives back the buffer once it completed.
+    pub fn send_dma<BUFFER>(
+        mut self,
+        command: Option<u16>,
+        buffer: BUFFER,
+    ) -> Result<I8080Transfer<TX, RX, P, PINS, BUFFER>, DmaError>
+    where
+        BUFFER: ReadBuffer<Word = u8>,
+    {
+        let (ptr, len) = unsafe { buffer.read_buffer() };
+        self.start(command, ptr, len)?;
+
+        Ok(I8080Transfer {
+            i8080: self,
+            buffer,
+        })
+    }
+
+    /// Give back the LCD_CAM peripheral, the DMA channel and the data pins
+    pub fn free(self) -> (LCD_CAM, Channel<TX, RX, P>, PINS) {
+        (self.lcd_cam, self.channel, self.pins)
+    }
+
+    fn start(&mut self, command: Option<u16>, data: *const u8, len: usize) -> Result<(), DmaError> {
+        let lcd_cam = &self.lcd_cam;
+
+        lcd_cam
+            .lc_dma_int_clr
+            .write(|w| w.lcd_trans_done_int_clr().set_bit());
+
+        lcd_cam.lcd_user.modify(|_, w| w.lcd_reset().set_bit());
+        lcd_cam
+            .lcd_misc
+            .modify(|_, w| w.lcd_afifo_reset().set_bit());
+
+        if let Some(command) = command {
+            lcd_cam
+                .lcd_cmd_val
+                .write(|w| unsafe { w.lcd_cmd_value().bits(command as u32) });
+        }
+
+        if len != 0 {
+            self.channel
+                .tx
+                .prepare_transfer(DmaPeripheral::LcdCam, data, len)?;
+        }
+
+        lcd_cam.lcd_user.modify(|_, w| {
+            w.lcd_cmd()
+                .bit(command.is_some())
+                .lcd_cmd_2_cycle_en()
+                .clear_bit()
+                .lcd_dummy()
+                .clear_bit()
+                .lcd_dout()
+                .bit(len != 0)
+                .lcd_update()
+                .set_bit()
+        });
+        lcd_cam.lcd_user.modify(|_, w| w.lcd_start().set_bit());
+
+        Ok(())
+    }
+
+    fn is_done(&self) -> bool {
+        self.lcd_cam
+            .lc_dma_int_raw
+            .read()
+            .lcd_trans_done_int_raw()
+            .bit_is_set()
+    }
+
+    fn wait_done(&self) {
+        while !self.is_done() {
+            // wait
+        }
+    }
+}
+
+/// An in-progress transfer, see [I8080::send_dma]
+pub struct I8080Transfer<TX, RX, P, PINS, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: LcdCamPeripheral,
+    PINS: TxPins,
+{
+    i8080: I8080<TX, RX, P, PINS>,
+    buffer: BUFFER,
+}
+
+impl<TX, RX, P, PINS, BUFFER> I8080Transfer<TX, RX, P, PINS, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: LcdCamPeripheral,
+    PINS: TxPins,
+{
+    /// Check if all data was sent
+    pub fn is_done(&self) -> bool {
+        self.i8080.is_done()
+    }
+
+    /// Wait for the transfer to complete and return the buffer and the
+    /// driver
+    pub fn wait(self) -> (BUFFER, I8080<TX, RX, P, PINS>) {
+        self.i8080.wait_done();
+
+        // `Drop` is implemented, so we can't move out of the fields, see
+        // `SpiDmaTransfer::wait`
+        //
+        // NOTE(unsafe) There is no panic branch between getting the resources
+        // and forgetting `self`.
+        unsafe {
+            let buffer = core::ptr::read(&self.buffer);
+            let i8080 = core::ptr::read(&self.i8080);
+            core::mem::forget(self);
+            (buffer, i8080)
+        }
+    }
+}
+
+impl<TX, RX, P, PINS, BUFFER> Drop for I8080Transfer<TX, RX, P, PINS, BUFFER>
+where
+    TX: Tx,
+    RX: Rx,
+    P: LcdCamPeripheral,
+    PINS: TxPins,
+{
+    fn drop(&mut self) {
+        // the DMA must not read from the buffer anymore once it's dropped
+        self.i8080.wait_done();
+    }
+}
@@ -20,8 +20,15 @@ pub enum Error {
     Timer,
     /// Channel not configured
     Channel,
+    /// The fade can't be done with the frequency and duty resolution of the
+    /// timer, e.g. because the duration is too long or too short
+    Fade,
 }
 
+/// Maximum value of the number of steps, the cycles per step and the duty
+/// increment per step of a fade
+const MAX_FADE_VALUE: u32 = 0x3ff;
+
 /// Channel number
 #[derive(PartialEq, Eq, Copy, Clone, Debug)]
 pub enum Number {
@@ -59,6 +66,38 @@ where
 
     /// Set channel duty HW
     fn set_duty(&self, duty_pct: u8) -> Result<(), Error>;
+
+    /// Fade the duty from `start_duty_pct` to `end_duty_pct` within
+    /// `duration_ms`, done by the hardware
+    ///
+    /// If both duties are the same, the duty is set right away without a
+    /// fade. Returns [Error::Fade] if the timer can't produce the fade, i.e.
+    /// if it needs more than 1023 PWM cycles per step or more than 1023 duty
+    /// values per step.
+    fn start_duty_fade(
+        &self,
+        start_duty_pct: u8,
+        end_duty_pct: u8,
+        duration_ms: u16,
+    ) -> Result<(), Error>;
+
+    /// Check if the fade started by [Self::start_duty_fade] is still running
+    fn is_duty_fade_running(&self) -> bool;
+
+    /// Listen for the interrupt signalling the end of a fade
+    fn listen_fade_end(&self);
+
+    /// Stop listening for the interrupt signalling the end of a fade
+    fn unlisten_fade_end(&self);
+
+    /// Check if the fade end interrupt of this channel is pending
+    ///
+    /// All channels share the LEDC interrupt, the handler uses this to find
+    /// the channel(s) which completed a fade.
+    fn is_interrupt_set(&self) -> bool;
+
+    /// Clear the fade end interrupt of this channel
+    fn clear_interrupt(&self);
 }
 
 /// Channel HW interface
@@ -69,6 +108,23 @@ pub trait ChannelHW<O: OutputPin> {
 
     /// Set channel duty HW
     fn set_duty_hw(&self, duty: u32);
+
+    /// Start a fade in the channel HW
+    ///
+    /// Starting at `start_duty`, the duty is changed by `duty_scale` every
+    /// `duty_cycle` PWM cycles, `duty_num` times.
+    fn start_duty_fade_hw(
+        &self,
+        start_duty: u32,
+        duty_inc: bool,
+        duty_num: u16,
+        duty_cycle: u16,
+        duty_scale: u16,
+    );
+
+    /// Bit of the fade end interrupt of this channel in the interrupt
+    /// registers
+    fn fade_interrupt_mask(&self) -> u32;
 }
 
 /// Channel struct
@@ -132,6 +188,106 @@ where
 
         Ok(())
     }
+
+    /// Fade the duty from `start_duty_pct` to `end_duty_pct` within
+    /// `duration_ms`
+    fn start_duty_fade(
+        &self,
+        start_duty_pct: u8,
+        end_duty_pct: u8,
+        duration_ms: u16,
+    ) -> Result<(), Error> {
+        let timer = self.timer.ok_or(Error::Channel)?;
+        let duty_exp = timer.get_duty().ok_or(Error::Timer)? as u32;
+        let frequency = timer.get_freq().ok_or(Error::Timer)?;
+
+        if start_duty_pct > 100 || end_duty_pct > 100 {
+            return Err(Error::Duty);
+        }
+
+        let duty_range = 2u32.pow(duty_exp);
+        let start_duty = duty_range * start_duty_pct as u32 / 100;
+        let end_duty = duty_range * end_duty_pct as u32 / 100;
+
+        if start_duty == end_duty {
+            self.set_duty_hw(end_duty);
+            return Ok(());
+        }
+
+        let duty_inc = end_duty > start_duty;
+        let delta = end_duty.abs_diff(start_duty);
+        let total_cycles = frequency.raw() as u64 * duration_ms as u64 / 1000;
+
+        if total_cycles == 0 {
+            return Err(Error::Fade);
+        }
+
+        // Use the smallest duty increment which keeps the number of steps
+        // within range and doesn't need less than one PWM cycle per step
+        let duty_scale = u32::max(
+            (delta + MAX_FADE_VALUE - 1) / MAX_FADE_VALUE,
+            ((delta as u64 + total_cycles - 1) / total_cycles) as u32,
+        );
+        let duty_num = delta / duty_scale;
+        let duty_cycle = total_cycles / duty_num as u64;
+
+        if duty_scale > MAX_FADE_VALUE || duty_cycle > MAX_FADE_VALUE as u64 {
+            return Err(Error::Fade);
+        }
+
+        // Start where `duty_num` steps of `duty_scale` end exactly at `end_duty`
+        let start_duty = if duty_inc {
+            end_duty - duty_num * duty_scale
+        } else {
+            end_duty + duty_num * duty_scale
+        };
+
+        self.clear_interrupt();
+        self.start_duty_fade_hw(
+            start_duty,
+            duty_inc,
+            duty_num as u16,
+            duty_cycle as u16,
+            duty_scale as u16,
+        );
+
+        Ok(())
+    }
+
+    /// Check if the fade is still running
+    ///
+    /// Only meaningful once a fade was started.
+    fn is_duty_fade_running(&self) -> bool {
+        self.ledc.int_raw.read().bits() & self.fade_interrupt_mask() == 0
+    }
+
+    /// Listen for the fade end interrupt
+    fn listen_fade_end(&self) {
+        let mask = self.fade_interrupt_mask();
+        self.ledc
+            .int_ena
+            .modify(|r, w| unsafe { w.bits(r.bits() | mask) });
+    }
+
+    /// Stop listening for the fade end interrupt
+    fn unlisten_fade_end(&self) {
+        let mask = self.fade_interrupt_mask();
+        self.ledc
+            .int_ena
+            .modify(|r, w| unsafe { w.bits(r.bits() & !mask) });
+    }
+
+    /// Check if the fade end interrupt is pending
+    fn is_interrupt_set(&self) -> bool {
+        self.ledc.int_st.read().bits() & self.fade_interrupt_mask() != 0
+    }
+
+    /// Clear the fade end interrupt
+    fn clear_interrupt(&self) {
+        self.ledc
+            .int_clr
+            .write(|w| unsafe { w.bits(self.fade_interrupt_mask()) });
+    }
 }
 
 #[cfg(esp32)]
@@ -216,6 +372,54 @@ macro_rules! set_duty {
     };
 }
 
+#[cfg(esp32)]
+/// Macro to start a duty fade in hw
+macro_rules! start_duty_fade {
+    ($self: ident, $speed: ident, $num: literal, $start_duty: ident, $duty_inc: ident, $duty_num: ident, $duty_cycle: ident, $duty_scale: ident) => {
+        paste! {
+            $self.ledc
+                .[<$speed sch $num _duty>]
+                .write(|w| unsafe { w.[<duty>]().bits($start_duty << 4) });
+            $self.ledc.[<$speed sch $num _conf1>].write(|w| unsafe {
+                w.[<duty_start>]()
+                    .set_bit()
+                    .[<duty_inc>]()
+                    .bit($duty_inc)
+                    .[<duty_num>]()
+                    .bits($duty_num)
+                    .[<duty_cycle>]()
+                    .bits($duty_cycle)
+                    .[<duty_scale>]()
+                    .bits($duty_scale)
+                });
+        }
+    };
+}
+
+#[cfg(not(esp32))]
+/// Macro to start a duty fade in hw
+macro_rules! start_duty_fade {
+    ($self: ident, $speed: ident, $num: literal, $start_duty: ident, $duty_inc: ident, $duty_num: ident, $duty_cycle: ident, $duty_scale: ident) => {
+        paste! {
+            $self.ledc
+                .[<ch $num _duty>]
+                .write(|w| unsafe { w.[<duty>]().bits($start_duty << 4) });
+            $self.ledc.[<ch $num _conf1>].write(|w| unsafe {
+                w.[<duty_start>]()
+                    .set_bit()
+                    .[<duty_inc>]()
+                    .bit($duty_inc)
+                    .[<duty_num>]()
+                    .bits($duty_num)
+                    .[<duty_cycle>]()
+                    .bits($duty_cycle)
+                    .[<duty_scale>]()
+                    .bits($duty_scale)
+                });
+        }
+    };
+}
+
 #[cfg(esp32)]
 /// Macro to update channel configuration (only for LowSpeed channels)
 macro_rules! update_channel {
@@ -319,6 +523,48 @@ where
             Number::Channel7 => set_duty!(self, h, 7, duty),
         };
     }
+
+    /// Start a fade in channel HW
+    fn start_duty_fade_hw(
+        &self,
+        start_duty: u32,
+        duty_inc: bool,
+        duty_num: u16,
+        duty_cycle: u16,
+        duty_scale: u16,
+    ) {
+        match self.number {
+            Number::Channel0 => {
+                start_duty_fade!(self, h, 0, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel1 => {
+                start_duty_fade!(self, h, 1, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel2 => {
+                start_duty_fade!(self, h, 2, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel3 => {
+                start_duty_fade!(self, h, 3, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel4 => {
+                start_duty_fade!(self, h, 4, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel5 => {
+                start_duty_fade!(self, h, 5, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel6 => {
+                start_duty_fade!(self, h, 6, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+            Number::Channel7 => {
+                start_duty_fade!(self, h, 7, start_duty, duty_inc, duty_num, duty_cycle, duty_scale)
+            }
+        };
+    }
+
+    /// The fade end interrupts of the HighSpeed channels start at bit 8
+    fn fade_interrupt_mask(&self) -> u32 {
+        1 << (8 + self.number as u32)
+    }
 }
 
 /// Channel HW interface for LowSpeed channels
@@ -410,4 +656,78 @@ where
             Number::Channel7 => set_duty!(self, l, 7, duty),
         };
     }
+
+    /// Start a fade in channel HW
+    fn start_duty_fade_hw(
+        &self,
+        start_duty: u32,
+        duty_inc: bool,
+        duty_num: u16,
+        duty_cycle: u16,
+        duty_scale: u16,
+    ) {
+        match self.number {
+            Number::Channel0 => {
+                start_duty_fade!(
+                    self, l, 0, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 0);
+            }
+            Number::Channel1 => {
+                start_duty_fade!(
+                    self, l, 1, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 1);
+            }
+            Number::Channel2 => {
+                start_duty_fade!(
+                    self, l, 2, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 2);
+            }
+            Number::Channel3 => {
+                start_duty_fade!(
+                    self, l, 3, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 3);
+            }
+            Number::Channel4 => {
+                start_duty_fade!(
+                    self, l, 4, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 4);
+            }
+            Number::Channel5 => {
+                start_duty_fade!(
+                    self, l, 5, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 5);
+            }
+            #[cfg(not(esp32c3))]
+            Number::Channel6 => {
+                start_duty_fade!(
+                    self, l, 6, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 6);
+            }
+            #[cfg(not(esp32c3))]
+            Number::Channel7 => {
+                start_duty_fade!(
+                    self, l, 7, start_duty, duty_inc, duty_num, duty_cycle, duty_scale
+                );
+                update_channel!(self, 7);
+            }
+        };
+    }
+
+    /// The fade end interrupts of the LowSpeed channels start at bit 16 on
+    /// the ESP32 (after the HighSpeed ones) and at bit 4 on the other chips
+    fn fade_interrupt_mask(&self) -> u32 {
+        #[cfg(esp32)]
+        let base = 16;
+        #[cfg(not(esp32))]
+        let base = 4;
+
+        1 << (base + self.number as u32)
+    }
 }
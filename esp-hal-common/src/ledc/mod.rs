@@ -1,9 +1,10 @@
 //! LEDC (LED PWM Controller) peripheral control
 //!
-//! Currently only supports fixed-frequency output. Hardware fade support and
-//! interrupts are not currently implemented. High Speed channels are availble
-//! for the ESP32 only, while Low Speed channels are available for all supported
-//! chips.
+//! Currently only supports fixed-frequency output. The duty can be faded by the
+//! hardware, see [channel::ChannelIFace::start_duty_fade], and the end of a
+//! fade can raise an interrupt, see [channel::ChannelIFace::listen_fade_end].
+//! High Speed channels are availble for the ESP32 only, while Low Speed
+//! channels are available for all supported chips.
 //!
 //! # LowSpeed Example:
 //!
@@ -61,8 +62,7 @@
 //! # TODO
 //!
 //! - Source clock selection
-//! - Hardware fade support
-//! - Interrupts
+//! - Timer overflow interrupts
 
 use self::{
     channel::Channel,
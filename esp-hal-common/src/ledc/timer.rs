@@ -73,6 +73,49 @@ pub mod config {
         Duty20Bit,
     }
 
+    impl Duty {
+        /// Highest duty resolution supported by the chip
+        #[cfg(esp32)]
+        pub const MAX: Duty = Duty::Duty20Bit;
+        /// Highest duty resolution supported by the chip
+        #[cfg(not(esp32))]
+        pub const MAX: Duty = Duty::Duty14Bit;
+
+        /// Duty resolution with the given number of bits, `None` if the chip
+        /// doesn't support it
+        pub fn from_bits(bits: u32) -> Option<Duty> {
+            Some(match bits {
+                1 => Duty::Duty1Bit,
+                2 => Duty::Duty2Bit,
+                3 => Duty::Duty3Bit,
+                4 => Duty::Duty4Bit,
+                5 => Duty::Duty5Bit,
+                6 => Duty::Duty6Bit,
+                7 => Duty::Duty7Bit,
+                8 => Duty::Duty8Bit,
+                9 => Duty::Duty9Bit,
+                10 => Duty::Duty10Bit,
+                11 => Duty::Duty11Bit,
+                12 => Duty::Duty12Bit,
+                13 => Duty::Duty13Bit,
+                14 => Duty::Duty14Bit,
+                #[cfg(esp32)]
+                15 => Duty::Duty15Bit,
+                #[cfg(esp32)]
+                16 => Duty::Duty16Bit,
+                #[cfg(esp32)]
+                17 => Duty::Duty17Bit,
+                #[cfg(esp32)]
+                18 => Duty::Duty18Bit,
+                #[cfg(esp32)]
+                19 => Duty::Duty19Bit,
+                #[cfg(esp32)]
+                20 => Duty::Duty20Bit,
+                _ => return None,
+            })
+        }
+    }
+
     /// Timer configuration
     #[derive(Copy, Clone)]
     pub struct Config<CS> {
@@ -104,8 +147,20 @@ pub trait TimerIFace<S: TimerSpeed> {
     fn get_freq(&self) -> Option<HertzU32>;
 
     /// Configure the timer
+    ///
+    /// Fails with [Error::Divisor] if the frequency can't be reached with the
+    /// requested duty resolution.
     fn configure(&mut self, config: config::Config<S::ClockSourceType>) -> Result<(), Error>;
 
+    /// Configure the timer, reducing the duty resolution if the frequency
+    /// can't be reached with the requested one
+    ///
+    /// Returns the duty resolution actually used.
+    fn configure_reduced_duty(
+        &mut self,
+        config: config::Config<S::ClockSourceType>,
+    ) -> Result<config::Duty, Error>;
+
     /// Check if the timer has been configured
     fn is_configured(&self) -> bool;
 
@@ -128,6 +183,32 @@ pub trait TimerHW<S: TimerSpeed> {
     fn update_hw(&self);
 }
 
+/// Calculate the clock divisor for the given source frequency, output
+/// frequency and duty resolution
+///
+/// Falls back to REF_TICK if the divisor would be too high for the source
+/// clock. Returns the divisor (8 fractional bits) and whether REF_TICK has to
+/// be used.
+fn divisor(src_freq: u32, frequency: u32, duty: config::Duty) -> Result<(u32, bool), Error> {
+    let precision = 1u64 << duty as u32;
+
+    let mut use_ref_tick = false;
+    let mut divisor = ((src_freq as u64) << 8) / frequency as u64 / precision;
+
+    if divisor > LEDC_TIMER_DIV_NUM_MAX {
+        // APB_CLK results in divisor which too high. Try using REF_TICK as clock
+        // source.
+        use_ref_tick = true;
+        divisor = ((1_000_000 as u64) << 8) / frequency as u64 / precision;
+    }
+
+    if divisor >= LEDC_TIMER_DIV_NUM_MAX || divisor < 256 {
+        return Err(Error::Divisor);
+    }
+
+    Ok((divisor as u32, use_ref_tick))
+}
+
 /// Timer struct
 pub struct Timer<'a, S: TimerSpeed> {
     ledc: &'a crate::pac::ledc::RegisterBlock,
@@ -150,28 +231,15 @@ where
 
     /// Configure the timer
     fn configure(&mut self, config: config::Config<S::ClockSourceType>) -> Result<(), Error> {
-        self.duty = Some(config.duty);
         self.clock_source = Some(config.clock_source);
 
         // TODO: we should return some error here if `unwrap()` fails
         let src_freq: u32 = self.get_freq().unwrap().to_Hz();
-        let precision = 1 << config.duty as u32;
-        let frequency: u32 = config.frequency.raw();
-
-        let mut divisor = ((src_freq as u64) << 8) / frequency as u64 / precision as u64;
-
-        if divisor > LEDC_TIMER_DIV_NUM_MAX {
-            // APB_CLK results in divisor which too high. Try using REF_TICK as clock
-            // source.
-            self.use_ref_tick = true;
-            divisor = ((1_000_000 as u64) << 8) / frequency as u64 / precision as u64;
-        }
-
-        if divisor >= LEDC_TIMER_DIV_NUM_MAX || divisor < 256 {
-            return Err(Error::Divisor);
-        }
+        let (divisor, use_ref_tick) = divisor(src_freq, config.frequency.raw(), config.duty)?;
 
-        self.configure_hw(divisor as u32);
+        self.duty = Some(config.duty);
+        self.use_ref_tick = use_ref_tick;
+        self.configure_hw(divisor);
         self.update_hw();
 
         self.configured = true;
@@ -179,6 +247,25 @@ where
         Ok(())
     }
 
+    /// Configure the timer, reducing the duty resolution if the frequency
+    /// can't be reached with the requested one
+    fn configure_reduced_duty(
+        &mut self,
+        mut config: config::Config<S::ClockSourceType>,
+    ) -> Result<config::Duty, Error> {
+        loop {
+            match self.configure(config) {
+                Ok(()) => return Ok(config.duty),
+                Err(Error::Divisor) if config.duty as u32 > 1 => {
+                    // fewer duty bits result in a bigger divisor, which helps if the
+                    // frequency was too high for the requested resolution
+                    config.duty = config::Duty::from_bits(config.duty as u32 - 1).unwrap();
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
+
     /// Check if the timer has been configured
     fn is_configured(&self) -> bool {
         self.configured
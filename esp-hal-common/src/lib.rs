@@ -48,28 +48,52 @@ pub use self::{
     timer::Timer,
 };
 
+#[cfg(not(esp32c2))]
+pub mod aes;
 pub mod analog;
 pub mod clock;
+pub mod cpu;
+pub mod crc;
 pub mod delay;
 pub mod dma;
+#[cfg(all(feature = "embassy", any(esp32c2, esp32c3, esp32s3)))]
+pub mod embassy;
+pub mod flash;
 pub mod gpio;
 pub mod i2c;
+#[cfg(any(esp32c3, esp32s3))]
+pub mod i2s;
+#[cfg(esp32s3)]
+pub mod lcd_cam;
 // FIXME: While the ESP32-C2 *does* have LEDC, it is not currently available in
 //        the SVD.
 #[cfg(not(esp32c2))]
 pub mod ledc;
+#[cfg(feature = "log")]
+pub mod logger;
+#[cfg(any(esp32, esp32s3))]
+pub mod mcpwm;
+#[cfg(any(esp32, esp32s2, esp32s3))]
+pub mod pcnt;
 pub mod prelude;
+#[cfg(esp32s3)]
+pub mod psram;
 #[cfg(not(esp32c2))]
 pub mod pulse_control;
 pub mod rng;
 pub mod rom;
 pub mod rtc_cntl;
 pub mod serial;
+pub mod sha;
 pub mod spi;
+#[cfg(any(esp32c2, esp32c3, esp32s3))]
+pub mod spi_slave;
 pub mod system;
 #[cfg(has_systimer)]
 pub mod systimer;
 pub mod timer;
+#[cfg(any(esp32c3, esp32s3))]
+pub mod twai;
 #[cfg(has_usb_serial_jtag)]
 pub mod usb_serial_jtag;
 #[cfg(not(esp32c2))]
@@ -80,11 +104,6 @@ pub mod utils;
 #[cfg_attr(esp32s3, path = "cpu_control/esp32s3.rs")]
 pub mod cpu_control;
 
-#[cfg_attr(esp32, path = "efuse/esp32.rs")]
-#[cfg_attr(esp32c2, path = "efuse/esp32c2.rs")]
-#[cfg_attr(esp32c3, path = "efuse/esp32c3.rs")]
-#[cfg_attr(esp32s2, path = "efuse/esp32s2.rs")]
-#[cfg_attr(esp32s3, path = "efuse/esp32s3.rs")]
 pub mod efuse;
 
 #[cfg_attr(riscv, path = "interrupt/riscv.rs")]
@@ -138,17 +157,17 @@ mod critical_section_impl {
             }
 
             unsafe fn release(token: critical_section::RawRestoreState) {
-                if token != 0 {
-                    #[cfg(multi_core)]
-                    {
-                        debug_assert!(super::multicore::MULTICORE_LOCK.is_owned_by_current_thread());
-                        // safety: we logically own the mutex from acquire()
-                        super::multicore::MULTICORE_LOCK.force_unlock();
-                    }
-                    core::arch::asm!(
-                        "wsr.ps {0}",
-                        "rsync", in(reg) token)
+                // The lock is taken by every acquire(), so it has to be released
+                // independently of the restored interrupt level
+                #[cfg(multi_core)]
+                {
+                    debug_assert!(super::multicore::MULTICORE_LOCK.is_owned_by_current_thread());
+                    // safety: we logically own the mutex from acquire()
+                    super::multicore::MULTICORE_LOCK.force_unlock();
                 }
+                core::arch::asm!(
+                    "wsr.ps {0}",
+                    "rsync", in(reg) token)
             }
         }
     }
@@ -162,7 +181,7 @@ mod critical_section_impl {
                 let interrupts_active = (mstatus & 0b1000) != 0;
                 #[cfg(multi_core)]
                 {
-                    let guard = multicore::MULTICORE_LOCK.lock();
+                    let guard = super::multicore::MULTICORE_LOCK.lock();
                     core::mem::forget(guard); // forget it so drop doesn't run
                 }
 
@@ -170,13 +189,13 @@ mod critical_section_impl {
             }
 
             unsafe fn release(token: critical_section::RawRestoreState) {
+                #[cfg(multi_core)]
+                {
+                    debug_assert!(super::multicore::MULTICORE_LOCK.is_owned_by_current_thread());
+                    // safety: we logically own the mutex from acquire()
+                    super::multicore::MULTICORE_LOCK.force_unlock();
+                }
                 if token != 0 {
-                    #[cfg(multi_core)]
-                    {
-                        debug_assert!(multicore::MULTICORE_LOCK.is_owned_by_current_thread());
-                        // safety: we logically own the mutex from acquire()
-                        multicore::MULTICORE_LOCK.force_unlock();
-                    }
                     riscv::interrupt::enable();
                 }
             }
@@ -191,11 +210,13 @@ mod critical_section_impl {
 
         use crate::get_core;
 
-        /// Reentrant Mutex
+        /// Reentrant Mutex shared by both cores
         ///
-        /// Currently implemented using an atomic spin lock.
-        /// In the future we can optimize this raw mutex to use some hardware
-        /// features.
+        /// Taken by every critical section after masking the interrupts of the
+        /// current core, so a critical section excludes the other core as well.
+        /// Nested critical sections on the same core only increase the lock
+        /// count. Implemented using an atomic spin lock, which is backed by the
+        /// `S32C1I` instruction on the Xtensa cores.
         pub(crate) static MULTICORE_LOCK: lock_api::ReentrantMutex<RawSpinlock, RawThreadId, ()> =
             lock_api::ReentrantMutex::const_new(RawSpinlock::INIT, RawThreadId::INIT, ());
 
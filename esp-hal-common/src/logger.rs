@@ -0,0 +1,76 @@
+//! # `log` backend
+//!
+//! ### Summary
+//! Implements the `log` crate's `Log` trait, writing every record as
+//! `LEVEL - message` followed by a line break to a [core::fmt::Write]
+//! implementation, e.g. a [Serial](crate::Serial) or the USB Serial/JTAG
+//! controller. Records are written inside a critical section, so messages
+//! from interrupt handlers don't interleave.
+//!
+//! The level passed to [init] can be changed at runtime with
+//! `log::set_max_level`. Records above the compile-time maximum selected by
+//! the `max_level_*` and `release_max_level_*` features of the `log` crate
+//! are removed entirely by the `log` macros.
+//!
+//! This module is only available with the `log` feature.
+//!
+//! ### Example
+//! ```no_run
+//! static mut SERIAL: Option<Serial<UART0>> = None;
+//!
+//! let serial = unsafe { SERIAL.insert(Serial::new(peripherals.UART0, &clocks)) };
+//! logger::init(LevelFilter::Info, serial);
+//!
+//! log::info!("Hello world!");
+//! ```
+
+#![deny(missing_docs)]
+
+use core::{cell::RefCell, fmt::Write};
+
+use critical_section::Mutex;
+use log::LevelFilter;
+
+type Writer = &'static mut (dyn Write + Send);
+
+static WRITER: Mutex<RefCell<Option<Writer>>> = Mutex::new(RefCell::new(None));
+
+struct Logger;
+
+static LOGGER: Logger = Logger;
+
+impl log::Log for Logger {
+    fn enabled(&self, _metadata: &log::Metadata) -> bool {
+        true
+    }
+
+    fn log(&self, record: &log::Record) {
+        if !self.enabled(record.metadata()) {
+            return;
+        }
+
+        critical_section::with(|cs| {
+            if let Some(writer) = WRITER.borrow_ref_mut(cs).as_mut() {
+                writeln!(writer, "{} - {}", record.level(), record.args()).ok();
+            }
+        });
+    }
+
+    fn flush(&self) {}
+}
+
+/// Route `log` output to `writer`
+///
+/// Records above `level` are discarded. Calling this again replaces the
+/// writer and the level.
+pub fn init(level: LevelFilter, writer: &'static mut (dyn Write + Send)) {
+    critical_section::with(|cs| {
+        WRITER.borrow_ref_mut(cs).replace(writer);
+
+        unsafe {
+            // `set_logger` is not available on targets without atomic CAS
+            log::set_logger_racy(&LOGGER).ok();
+            log::set_max_level(level);
+        }
+    });
+}
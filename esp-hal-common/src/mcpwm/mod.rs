@@ -0,0 +1,316 @@
+//! MCPWM (Motor Control Pulse Width Modulator) peripheral control
+//!
+//! Each MCPWM peripheral has three timers, three operators and three fault
+//! inputs. An operator is clocked by one of the timers and drives a pair of
+//! outputs (A and B) from its two generators. The outputs can be linked as a
+//! complementary pair with dead-time and modulated with a high frequency
+//! carrier. Fault inputs force the outputs of the operators into a safe state,
+//! either until the fault is cleared in software (one-shot mode) or until the
+//! next period in which the fault isn't present (cycle-by-cycle mode).
+//!
+//! Synchronizing the timers to each other or to external signals and the
+//! capture unit are not supported.
+//!
+//! # Example
+//!
+//! Drive a half bridge with a 20 kHz complementary PWM at 25% duty and 500 ns
+//! dead-time, switching both outputs off while GPIO6 is pulled low:
+//!
+//! ```rust,ignore
+//! let clock_cfg = PeripheralClockConfig::with_frequency(40u32.MHz()).unwrap();
+//! let mut mcpwm = Mcpwm::new(
+//!     peripherals.MCPWM0,
+//!     clock_cfg,
+//!     &mut system.peripheral_clock_control,
+//! );
+//!
+//! let timer_cfg = clock_cfg
+//!     .timer_clock_with_frequency(1999, PwmWorkingMode::Increase, 20u32.kHz())
+//!     .unwrap();
+//! mcpwm.timer0.start(timer_cfg);
+//!
+//! let mut fault_pin = io.pins.gpio6;
+//! mcpwm.fault0.enable(&mut fault_pin, FaultPolarity::ActiveLow);
+//!
+//! mcpwm.operator0.set_timer(&mcpwm.timer0);
+//! let mut pins = mcpwm.operator0.with_linked_pins(
+//!     io.pins.gpio4,
+//!     PwmPinConfig::UP_ACTIVE_HIGH,
+//!     io.pins.gpio5,
+//!     DeadTimeCfg::new(DeadTimeMode::ActiveHighComplementary, 20, 20),
+//! );
+//! pins.set_fault_handling(Some(FaultConfig {
+//!     mode: FaultMode::OneShot,
+//!     fault0: true,
+//!     fault1: false,
+//!     fault2: false,
+//!     action_a: GeneratorAction::SetLow,
+//!     action_b: GeneratorAction::SetLow,
+//! }));
+//! pins.set_timestamp_a(500);
+//! ```
+
+use core::marker::PhantomData;
+
+use fugit::HertzU32;
+
+use self::{
+    operator::Operator,
+    timer::{PwmWorkingMode, Timer, TimerClockConfig},
+};
+use crate::{
+    gpio::{InputPin, InputSignal, OutputSignal},
+    pac::mcpwm0::RegisterBlock,
+    system::{Peripheral, PeripheralClockControl},
+};
+
+pub mod operator;
+pub mod timer;
+
+/// Frequency of the clock source of the MCPWM peripherals
+const PWM_CLOCK: u32 = 160_000_000;
+
+/// Distance between the registers of two consecutive timers
+const TIMER_STRIDE: usize = 0x10;
+/// Distance between the registers of two consecutive operators
+const OPERATOR_STRIDE: usize = 0x38;
+
+/// The requested frequency can't be derived from the clock source
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FrequencyError;
+
+/// Clock configuration of an MCPWM peripheral
+///
+/// The peripheral clock is derived from the 160 MHz clock source by an 8 bit
+/// prescaler. It clocks the timers and the dead-time generators.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PeripheralClockConfig {
+    frequency: HertzU32,
+    prescaler: u8,
+}
+
+impl PeripheralClockConfig {
+    /// Divides the clock source by `prescaler + 1`
+    pub fn with_prescaler(prescaler: u8) -> Self {
+        PeripheralClockConfig {
+            frequency: HertzU32::Hz(PWM_CLOCK / (prescaler as u32 + 1)),
+            prescaler,
+        }
+    }
+
+    /// Divides the clock source down to exactly `target_freq`
+    pub fn with_frequency(target_freq: HertzU32) -> Result<Self, FrequencyError> {
+        let target = target_freq.raw();
+        if target == 0 || PWM_CLOCK % target != 0 {
+            return Err(FrequencyError);
+        }
+
+        let prescaler = PWM_CLOCK / target - 1;
+        if prescaler > u8::MAX as u32 {
+            return Err(FrequencyError);
+        }
+
+        Ok(Self::with_prescaler(prescaler as u8))
+    }
+
+    /// The frequency of the peripheral clock
+    pub fn frequency(&self) -> HertzU32 {
+        self.frequency
+    }
+
+    /// Timer configuration with the given period, working mode and prescaler
+    ///
+    /// The timer counts at the peripheral clock divided by `prescaler + 1`.
+    pub fn timer_clock_with_prescaler(
+        &self,
+        period: u16,
+        mode: PwmWorkingMode,
+        prescaler: u8,
+    ) -> TimerClockConfig {
+        let ticks = mode.ticks_per_period(period);
+        let frequency = self.frequency.raw() / (prescaler as u32 + 1) / ticks;
+
+        TimerClockConfig {
+            frequency: HertzU32::Hz(frequency),
+            period,
+            prescaler,
+            mode,
+        }
+    }
+
+    /// Timer configuration with the given period and working mode, with the
+    /// prescaler chosen to get a PWM frequency of exactly `target_freq`
+    pub fn timer_clock_with_frequency(
+        &self,
+        period: u16,
+        mode: PwmWorkingMode,
+        target_freq: HertzU32,
+    ) -> Result<TimerClockConfig, FrequencyError> {
+        let ticks = target_freq.raw() * mode.ticks_per_period(period);
+        if ticks == 0 || self.frequency.raw() % ticks != 0 {
+            return Err(FrequencyError);
+        }
+
+        let prescaler = self.frequency.raw() / ticks - 1;
+        if prescaler > u8::MAX as u32 {
+            return Err(FrequencyError);
+        }
+
+        Ok(self.timer_clock_with_prescaler(period, mode, prescaler as u8))
+    }
+}
+
+/// MCPWM peripheral
+pub struct Mcpwm<PWM> {
+    _inner: PWM,
+    pub timer0: Timer<0, PWM>,
+    pub timer1: Timer<1, PWM>,
+    pub timer2: Timer<2, PWM>,
+    pub operator0: Operator<0, PWM>,
+    pub operator1: Operator<1, PWM>,
+    pub operator2: Operator<2, PWM>,
+    pub fault0: FaultInput<0, PWM>,
+    pub fault1: FaultInput<1, PWM>,
+    pub fault2: FaultInput<2, PWM>,
+}
+
+impl<PWM: PwmPeripheral> Mcpwm<PWM> {
+    /// Enables the peripheral and configures its clock
+    pub fn new(
+        peripheral: PWM,
+        peripheral_clock: PeripheralClockConfig,
+        system: &mut PeripheralClockControl,
+    ) -> Self {
+        PWM::enable(system);
+
+        let block = PWM::block();
+        block
+            .clk_cfg
+            .write(|w| unsafe { w.bits(peripheral_clock.prescaler as u32) });
+        block.clk.write(|w| w.en().set_bit());
+
+        Mcpwm {
+            _inner: peripheral,
+            timer0: Timer::new(),
+            timer1: Timer::new(),
+            timer2: Timer::new(),
+            operator0: Operator::new(),
+            operator1: Operator::new(),
+            operator2: Operator::new(),
+            fault0: FaultInput::new(),
+            fault1: FaultInput::new(),
+            fault2: FaultInput::new(),
+        }
+    }
+}
+
+/// Active level of a fault input
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultPolarity {
+    ActiveLow,
+    ActiveHigh,
+}
+
+/// Fault input of an MCPWM peripheral
+///
+/// Which operators react to the fault, and how, is configured with
+/// [operator::PwmPins::set_fault_handling].
+pub struct FaultInput<const F: u8, PWM> {
+    phantom: PhantomData<PWM>,
+}
+
+impl<const F: u8, PWM: PwmPeripheral> FaultInput<F, PWM> {
+    fn new() -> Self {
+        FaultInput {
+            phantom: PhantomData,
+        }
+    }
+
+    /// Detects faults on the given pin
+    pub fn enable<P: InputPin>(&mut self, pin: &mut P, polarity: FaultPolarity) {
+        pin.set_to_input()
+            .connect_input_to_peripheral(PWM::fault_signal(F));
+
+        let pole = match polarity {
+            FaultPolarity::ActiveLow => 0,
+            FaultPolarity::ActiveHigh => 1 << (F + 3),
+        };
+        PWM::block()
+            .fault_detect
+            .modify(|r, w| unsafe { w.bits(r.bits() & !(1 << (F + 3)) | pole | 1 << F) });
+    }
+
+    /// Stops detecting faults
+    pub fn disable(&mut self) {
+        PWM::block()
+            .fault_detect
+            .modify(|r, w| unsafe { w.bits(r.bits() & !(1 << F)) });
+    }
+
+    /// Whether a fault is currently detected
+    pub fn is_active(&self) -> bool {
+        // EVENT_F0 to EVENT_F2 follow the enable and polarity bits
+        PWM::block().fault_detect.read().bits() & 1 << (F + 6) != 0
+    }
+}
+
+/// Register access of one of the timers or operators
+///
+/// The register groups of the timers and of the operators are laid out with a
+/// fixed stride, this gives the register of the group at `index` from the
+/// corresponding register of the first group.
+unsafe fn nth<R>(first: &'static R, index: u8, stride: usize) -> &'static R {
+    &*((first as *const R as *const u8).add(index as usize * stride) as *const R)
+}
+
+/// An MCPWM peripheral
+pub trait PwmPeripheral {
+    /// Enables the peripheral's clock and takes it out of reset
+    fn enable(system: &mut PeripheralClockControl);
+    /// The register block of the peripheral
+    fn block() -> &'static RegisterBlock;
+    /// Output signal of output A or B of the operator
+    fn output_signal(operator: u8, is_a: bool) -> OutputSignal;
+    /// Input signal of the fault input
+    fn fault_signal(fault: u8) -> InputSignal;
+}
+
+macro_rules! impl_pwm_peripheral {
+    ($pwm:ident, $peripheral:ident, $num:literal) => {
+        paste::paste! {
+            impl PwmPeripheral for crate::pac::$pwm {
+                fn enable(system: &mut PeripheralClockControl) {
+                    system.enable(Peripheral::$peripheral);
+                }
+
+                fn block() -> &'static RegisterBlock {
+                    unsafe { &*crate::pac::$pwm::PTR }
+                }
+
+                fn output_signal(operator: u8, is_a: bool) -> OutputSignal {
+                    match (operator, is_a) {
+                        (0, true) => OutputSignal::[<PWM $num _0A>],
+                        (0, false) => OutputSignal::[<PWM $num _0B>],
+                        (1, true) => OutputSignal::[<PWM $num _1A>],
+                        (1, false) => OutputSignal::[<PWM $num _1B>],
+                        (2, true) => OutputSignal::[<PWM $num _2A>],
+                        (2, false) => OutputSignal::[<PWM $num _2B>],
+                        _ => unreachable!(),
+                    }
+                }
+
+                fn fault_signal(fault: u8) -> InputSignal {
+                    match fault {
+                        0 => InputSignal::[<PWM $num _F0>],
+                        1 => InputSignal::[<PWM $num _F1>],
+                        2 => InputSignal::[<PWM $num _F2>],
+                        _ => unreachable!(),
+                    }
+                }
+            }
+        }
+    };
+}
+
+impl_pwm_peripheral!(MCPWM0, Mcpwm0, 0);
+impl_pwm_peripheral!(MCPWM1, Mcpwm1, 1);
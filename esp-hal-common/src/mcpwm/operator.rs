@@ -0,0 +1,474 @@
+//! MCPWM operators and their outputs
+
+use core::marker::PhantomData;
+
+use super::{nth, timer::Timer, PwmPeripheral, OPERATOR_STRIDE};
+use crate::gpio::OutputPin;
+
+/// Action of a generator on its output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GeneratorAction {
+    Nothing = 0,
+    SetLow  = 1,
+    SetHigh = 2,
+    Toggle  = 3,
+}
+
+/// When a new timestamp takes effect
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum UpdateMethod {
+    Immediately       = 0,
+    TimerEqualsZero   = 1,
+    TimerEqualsPeriod = 2,
+}
+
+/// Actions of a generator on the timer events
+///
+/// The timestamp events are the ones of the generator's own timestamp, e.g.
+/// [PwmPins::set_timestamp_a] for output A.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PwmActions {
+    up_zero: GeneratorAction,
+    up_period: GeneratorAction,
+    up_timestamp: GeneratorAction,
+    down_zero: GeneratorAction,
+    down_period: GeneratorAction,
+    down_timestamp: GeneratorAction,
+}
+
+impl PwmActions {
+    /// No actions at all, the output keeps its level
+    pub const fn empty() -> Self {
+        PwmActions {
+            up_zero: GeneratorAction::Nothing,
+            up_period: GeneratorAction::Nothing,
+            up_timestamp: GeneratorAction::Nothing,
+            down_zero: GeneratorAction::Nothing,
+            down_period: GeneratorAction::Nothing,
+            down_timestamp: GeneratorAction::Nothing,
+        }
+    }
+
+    pub const fn on_up_counting_timer_equals_zero(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            up_zero: action,
+            ..self
+        }
+    }
+
+    pub const fn on_up_counting_timer_equals_period(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            up_period: action,
+            ..self
+        }
+    }
+
+    pub const fn on_up_counting_timer_equals_timestamp(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            up_timestamp: action,
+            ..self
+        }
+    }
+
+    pub const fn on_down_counting_timer_equals_zero(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            down_zero: action,
+            ..self
+        }
+    }
+
+    pub const fn on_down_counting_timer_equals_period(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            down_period: action,
+            ..self
+        }
+    }
+
+    pub const fn on_down_counting_timer_equals_timestamp(self, action: GeneratorAction) -> Self {
+        PwmActions {
+            down_timestamp: action,
+            ..self
+        }
+    }
+
+    /// Value of the GENx_A or GENx_B register
+    fn bits(&self, is_a: bool) -> u32 {
+        // The timestamp events of generator B follow the ones of generator A
+        let timestamp = if is_a { 4 } else { 6 };
+
+        (self.up_zero as u32)
+            | (self.up_period as u32) << 2
+            | (self.up_timestamp as u32) << timestamp
+            | (self.down_zero as u32) << 12
+            | (self.down_period as u32) << 14
+            | (self.down_timestamp as u32) << (timestamp + 12)
+    }
+}
+
+/// Configuration of a PWM output
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PwmPinConfig {
+    actions: PwmActions,
+    update_method: UpdateMethod,
+}
+
+impl PwmPinConfig {
+    /// High from the start of the period until the timestamp, for timers in
+    /// [super::timer::PwmWorkingMode::Increase] mode
+    pub const UP_ACTIVE_HIGH: Self = Self::new(
+        PwmActions::empty()
+            .on_up_counting_timer_equals_zero(GeneratorAction::SetHigh)
+            .on_up_counting_timer_equals_timestamp(GeneratorAction::SetLow),
+        UpdateMethod::TimerEqualsZero,
+    );
+
+    /// High while the timer is below the timestamp, for timers in
+    /// [super::timer::PwmWorkingMode::UpDown] mode
+    pub const UP_DOWN_ACTIVE_HIGH: Self = Self::new(
+        PwmActions::empty()
+            .on_up_counting_timer_equals_timestamp(GeneratorAction::SetLow)
+            .on_down_counting_timer_equals_timestamp(GeneratorAction::SetHigh),
+        UpdateMethod::TimerEqualsZero,
+    );
+
+    /// Output with the given actions, changes of the timestamp take effect
+    /// according to `update_method`
+    pub const fn new(actions: PwmActions, update_method: UpdateMethod) -> Self {
+        PwmPinConfig {
+            actions,
+            update_method,
+        }
+    }
+}
+
+/// How the outputs of a linked pair are derived from output A's generator
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DeadTimeMode {
+    /// A is delayed on rising edges, B is A inverted and delayed on falling
+    /// edges, e.g. for the high and low side of a half bridge
+    ActiveHighComplementary,
+    /// Like [DeadTimeMode::ActiveHighComplementary] with A inverted instead
+    /// of B
+    ActiveLowComplementary,
+    /// A is delayed on rising edges, B on falling edges, none is inverted
+    ActiveHigh,
+    /// Like [DeadTimeMode::ActiveHigh] with both outputs inverted
+    ActiveLow,
+}
+
+/// Dead-time configuration of a linked pair of outputs
+///
+/// The delays are counted in cycles of the peripheral clock, see
+/// [super::PeripheralClockConfig].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct DeadTimeCfg {
+    pub mode: DeadTimeMode,
+    pub rising_edge_delay: u16,
+    pub falling_edge_delay: u16,
+}
+
+impl DeadTimeCfg {
+    pub const fn new(mode: DeadTimeMode, rising_edge_delay: u16, falling_edge_delay: u16) -> Self {
+        DeadTimeCfg {
+            mode,
+            rising_edge_delay,
+            falling_edge_delay,
+        }
+    }
+
+    /// Value of the DTx_CFG register
+    fn bits(&self) -> u32 {
+        // Both delay paths take generator A as input, none is bypassed
+        let (red_invert, fed_invert) = match self.mode {
+            DeadTimeMode::ActiveHighComplementary => (false, true),
+            DeadTimeMode::ActiveLowComplementary => (true, false),
+            DeadTimeMode::ActiveHigh => (false, false),
+            DeadTimeMode::ActiveLow => (true, true),
+        };
+
+        (red_invert as u32) << 13 | (fed_invert as u32) << 14
+    }
+}
+
+/// Bypass the dead-time generator, the outputs follow their generators
+const DT_CFG_BYPASS: u32 = 1 << 15 | 1 << 16;
+
+/// Carrier modulation of the outputs of an operator
+///
+/// The outputs are chopped by a carrier of the peripheral clock divided by
+/// `8 * (prescaler + 1)`. The first pulse after a rising edge is
+/// `first_pulse_width + 1` carrier periods long, the following ones have a
+/// duty of `duty / 8`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CarrierConfig {
+    /// 0 to 15
+    pub prescaler: u8,
+    /// 0 to 7
+    pub duty: u8,
+    /// 0 to 15
+    pub first_pulse_width: u8,
+}
+
+/// How long a fault keeps the outputs in their safe state
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FaultMode {
+    /// Until the fault is gone and [PwmPins::clear_one_shot_fault] is called
+    OneShot,
+    /// Until the fault is gone at the start of a period
+    CycleByCycle,
+}
+
+/// Reaction of an operator to faults
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct FaultConfig {
+    pub mode: FaultMode,
+    /// React to [super::Mcpwm::fault0]
+    pub fault0: bool,
+    /// React to [super::Mcpwm::fault1]
+    pub fault1: bool,
+    /// React to [super::Mcpwm::fault2]
+    pub fault2: bool,
+    /// Action on output A while the fault is active
+    pub action_a: GeneratorAction,
+    /// Action on output B while the fault is active
+    pub action_b: GeneratorAction,
+}
+
+impl FaultConfig {
+    /// Value of the FHx_CFG0 register
+    fn bits(&self) -> u32 {
+        // The software trigger is always enabled, see PwmPins::trigger_fault
+        let sources =
+            1 | (self.fault2 as u32) << 1 | (self.fault1 as u32) << 2 | (self.fault0 as u32) << 3;
+        // The actions for counting down and up
+        let action_a = self.action_a as u32 * 0b0101;
+        let action_b = self.action_b as u32 * 0b0101;
+
+        match self.mode {
+            FaultMode::CycleByCycle => sources | action_a << 8 | action_b << 16,
+            FaultMode::OneShot => sources << 4 | action_a << 12 | action_b << 20,
+        }
+    }
+}
+
+/// Operator of an MCPWM peripheral
+pub struct Operator<const OP: u8, PWM> {
+    phantom: PhantomData<PWM>,
+}
+
+impl<const OP: u8, PWM: PwmPeripheral> Operator<OP, PWM> {
+    pub(super) fn new() -> Self {
+        Operator {
+            phantom: PhantomData,
+        }
+    }
+
+    /// Clocks the operator by the given timer
+    ///
+    /// Several operators can share a timer, their outputs are synchronous
+    /// then.
+    pub fn set_timer<const TIM: u8>(&mut self, _timer: &Timer<TIM, PWM>) {
+        let shift = OP * 2;
+        PWM::block()
+            .operator_timersel
+            .modify(|r, w| unsafe { w.bits(r.bits() & !(0b11 << shift) | (TIM as u32) << shift) });
+    }
+
+    /// Uses the generators of the operator for two independent outputs
+    pub fn with_pins<PA: OutputPin, PB: OutputPin>(
+        self,
+        pin_a: PA,
+        config_a: PwmPinConfig,
+        pin_b: PB,
+        config_b: PwmPinConfig,
+    ) -> PwmPins<OP, PWM, PA, PB> {
+        let mut pins = PwmPins::new(self, pin_a, pin_b);
+        pins.configure_generators(config_a, config_b);
+        pins.set_dead_time(None);
+
+        pins
+    }
+
+    /// Uses generator A of the operator for a linked pair of outputs
+    ///
+    /// Both outputs are derived from the signal of generator A by the
+    /// dead-time generator, e.g. as a complementary pair.
+    pub fn with_linked_pins<PA: OutputPin, PB: OutputPin>(
+        self,
+        pin_a: PA,
+        config_a: PwmPinConfig,
+        pin_b: PB,
+        dead_time: DeadTimeCfg,
+    ) -> PwmPins<OP, PWM, PA, PB> {
+        let mut pins = PwmPins::new(self, pin_a, pin_b);
+        pins.configure_generators(
+            config_a,
+            PwmPinConfig::new(PwmActions::empty(), UpdateMethod::Immediately),
+        );
+        pins.set_dead_time(Some(dead_time));
+
+        pins
+    }
+}
+
+/// The outputs A and B of an operator
+pub struct PwmPins<const OP: u8, PWM, PA, PB> {
+    operator: Operator<OP, PWM>,
+    pin_a: PA,
+    pin_b: PB,
+}
+
+impl<const OP: u8, PWM: PwmPeripheral, PA: OutputPin, PB: OutputPin> PwmPins<OP, PWM, PA, PB> {
+    fn new(operator: Operator<OP, PWM>, mut pin_a: PA, mut pin_b: PB) -> Self {
+        pin_a
+            .set_to_push_pull_output()
+            .connect_peripheral_to_output(PWM::output_signal(OP, true));
+        pin_b
+            .set_to_push_pull_output()
+            .connect_peripheral_to_output(PWM::output_signal(OP, false));
+
+        PwmPins {
+            operator,
+            pin_a,
+            pin_b,
+        }
+    }
+
+    fn configure_generators(&mut self, config_a: PwmPinConfig, config_b: PwmPinConfig) {
+        let block = PWM::block();
+        unsafe {
+            nth(&block.gen0_stmp_cfg, OP, OPERATOR_STRIDE).write(|w| {
+                w.bits(config_a.update_method as u32 | (config_b.update_method as u32) << 4)
+            });
+            nth(&block.gen0_a, OP, OPERATOR_STRIDE).write(|w| w.bits(config_a.actions.bits(true)));
+            nth(&block.gen0_b, OP, OPERATOR_STRIDE).write(|w| w.bits(config_b.actions.bits(false)));
+        }
+    }
+
+    /// Sets the timestamp of output A, in timer ticks
+    pub fn set_timestamp_a(&mut self, value: u16) {
+        let block = PWM::block();
+        unsafe { nth(&block.gen0_tstmp_a, OP, OPERATOR_STRIDE) }
+            .write(|w| unsafe { w.bits(value as u32) });
+    }
+
+    /// Sets the timestamp of output B, in timer ticks
+    pub fn set_timestamp_b(&mut self, value: u16) {
+        let block = PWM::block();
+        unsafe { nth(&block.gen0_tstmp_b, OP, OPERATOR_STRIDE) }
+            .write(|w| unsafe { w.bits(value as u32) });
+    }
+
+    /// The timestamp of output A
+    pub fn timestamp_a(&self) -> u16 {
+        let block = PWM::block();
+        unsafe { nth(&block.gen0_tstmp_a, OP, OPERATOR_STRIDE) }
+            .read()
+            .bits() as u16
+    }
+
+    /// The timestamp of output B
+    pub fn timestamp_b(&self) -> u16 {
+        let block = PWM::block();
+        unsafe { nth(&block.gen0_tstmp_b, OP, OPERATOR_STRIDE) }
+            .read()
+            .bits() as u16
+    }
+
+    /// Configures the dead-time generator
+    ///
+    /// With `None` the outputs follow their generators directly. This is only
+    /// meaningful for outputs created by [Operator::with_linked_pins], for
+    /// those generator B has no actions.
+    pub fn set_dead_time(&mut self, dead_time: Option<DeadTimeCfg>) {
+        let block = PWM::block();
+        unsafe {
+            match dead_time {
+                Some(dead_time) => {
+                    nth(&block.dt0_red_cfg, OP, OPERATOR_STRIDE)
+                        .write(|w| w.bits(dead_time.rising_edge_delay as u32));
+                    nth(&block.dt0_fed_cfg, OP, OPERATOR_STRIDE)
+                        .write(|w| w.bits(dead_time.falling_edge_delay as u32));
+                    nth(&block.dt0_cfg, OP, OPERATOR_STRIDE).write(|w| w.bits(dead_time.bits()));
+                }
+                None => {
+                    nth(&block.dt0_cfg, OP, OPERATOR_STRIDE).write(|w| w.bits(DT_CFG_BYPASS));
+                }
+            }
+        }
+    }
+
+    /// Enables or disables the carrier modulation of the outputs
+    pub fn set_carrier(&mut self, carrier: Option<CarrierConfig>) {
+        let bits = match carrier {
+            Some(carrier) => {
+                1 | (carrier.prescaler as u32 & 0xf) << 1
+                    | (carrier.duty as u32 & 0x7) << 5
+                    | (carrier.first_pulse_width as u32 & 0xf) << 8
+            }
+            None => 0,
+        };
+
+        let block = PWM::block();
+        unsafe { nth(&block.carrier0_cfg, OP, OPERATOR_STRIDE) }.write(|w| unsafe { w.bits(bits) });
+    }
+
+    /// Configures the reaction of the operator to faults
+    ///
+    /// With `None` the operator ignores all faults.
+    pub fn set_fault_handling(&mut self, config: Option<FaultConfig>) {
+        let block = PWM::block();
+        let bits = config.map_or(0, |config| config.bits());
+        unsafe { nth(&block.fh0_cfg0, OP, OPERATOR_STRIDE) }.write(|w| unsafe { w.bits(bits) });
+
+        // Cycle-by-cycle faults end at the start of a period without fault
+        let cfg1 = unsafe { nth(&block.fh0_cfg1, OP, OPERATOR_STRIDE) };
+        cfg1.modify(|r, w| unsafe { w.bits(r.bits() & !(0b11 << 1) | 1 << 1) });
+    }
+
+    /// Triggers a fault from software, with the reaction configured by
+    /// [PwmPins::set_fault_handling]
+    pub fn trigger_fault(&mut self, mode: FaultMode) {
+        // Every toggle of the force bit triggers a fault
+        let force = match mode {
+            FaultMode::CycleByCycle => 1 << 3,
+            FaultMode::OneShot => 1 << 4,
+        };
+
+        let block = PWM::block();
+        let cfg1 = unsafe { nth(&block.fh0_cfg1, OP, OPERATOR_STRIDE) };
+        cfg1.modify(|r, w| unsafe { w.bits(r.bits() ^ force) });
+    }
+
+    /// Ends a one-shot fault
+    ///
+    /// If the fault is still present, it is triggered again immediately.
+    pub fn clear_one_shot_fault(&mut self) {
+        let block = PWM::block();
+        let cfg1 = unsafe { nth(&block.fh0_cfg1, OP, OPERATOR_STRIDE) };
+        cfg1.modify(|r, w| unsafe { w.bits(r.bits() | 1) });
+        cfg1.modify(|r, w| unsafe { w.bits(r.bits() & !1) });
+    }
+
+    /// Whether a fault of the given mode currently holds the outputs in their
+    /// safe state
+    pub fn is_fault_active(&self, mode: FaultMode) -> bool {
+        let block = PWM::block();
+        let status = unsafe { nth(&block.fh0_status, OP, OPERATOR_STRIDE) }
+            .read()
+            .bits();
+
+        match mode {
+            FaultMode::CycleByCycle => status & 1 != 0,
+            FaultMode::OneShot => status & 1 << 1 != 0,
+        }
+    }
+
+    /// Releases the operator and the pins
+    ///
+    /// The outputs keep being driven by the operator until the pins are
+    /// reconfigured.
+    pub fn free(self) -> (Operator<OP, PWM>, PA, PB) {
+        (self.operator, self.pin_a, self.pin_b)
+    }
+}
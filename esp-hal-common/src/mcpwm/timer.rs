@@ -0,0 +1,113 @@
+//! MCPWM timers
+
+use core::marker::PhantomData;
+
+use fugit::HertzU32;
+
+use super::{nth, PwmPeripheral, TIMER_STRIDE};
+
+/// Counting mode of a timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PwmWorkingMode {
+    /// Counts up from 0 to the period, then restarts at 0
+    Increase = 1,
+    /// Counts down from the period to 0, then restarts at the period
+    Decrease = 2,
+    /// Counts up from 0 to the period, then down to 0 again
+    UpDown   = 3,
+}
+
+impl PwmWorkingMode {
+    /// Number of timer ticks of one PWM period
+    pub(super) fn ticks_per_period(self, period: u16) -> u32 {
+        match self {
+            PwmWorkingMode::Increase | PwmWorkingMode::Decrease => period as u32 + 1,
+            PwmWorkingMode::UpDown => period as u32 * 2,
+        }
+    }
+}
+
+/// Counting direction of a timer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CounterDirection {
+    Increasing,
+    Decreasing,
+}
+
+/// Configuration of a timer, see
+/// [super::PeripheralClockConfig::timer_clock_with_prescaler] and
+/// [super::PeripheralClockConfig::timer_clock_with_frequency]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TimerClockConfig {
+    pub(super) frequency: HertzU32,
+    pub(super) period: u16,
+    pub(super) prescaler: u8,
+    pub(super) mode: PwmWorkingMode,
+}
+
+impl TimerClockConfig {
+    /// The frequency of the PWM signals generated from the timer
+    pub fn frequency(&self) -> HertzU32 {
+        self.frequency
+    }
+
+    /// The period of the timer in timer ticks
+    pub fn period(&self) -> u16 {
+        self.period
+    }
+}
+
+/// Timer of an MCPWM peripheral
+pub struct Timer<const TIM: u8, PWM> {
+    phantom: PhantomData<PWM>,
+}
+
+impl<const TIM: u8, PWM: PwmPeripheral> Timer<TIM, PWM> {
+    pub(super) fn new() -> Self {
+        Timer {
+            phantom: PhantomData,
+        }
+    }
+
+    /// Applies the configuration and starts the timer
+    pub fn start(&mut self, config: TimerClockConfig) {
+        let block = PWM::block();
+        let (cfg0, cfg1) = unsafe {
+            (
+                nth(&block.timer0_cfg0, TIM, TIMER_STRIDE),
+                nth(&block.timer0_cfg1, TIM, TIMER_STRIDE),
+            )
+        };
+
+        // Update the period immediately, not at the next timer event
+        cfg0.write(|w| unsafe { w.bits(config.prescaler as u32 | (config.period as u32) << 8) });
+        // Free running in the selected mode
+        cfg1.write(|w| unsafe { w.bits(2 | (config.mode as u32) << 3) });
+    }
+
+    /// Stops the timer immediately
+    ///
+    /// The outputs of the operators clocked by the timer keep their current
+    /// level.
+    pub fn stop(&mut self) {
+        let block = PWM::block();
+        let cfg1 = unsafe { nth(&block.timer0_cfg1, TIM, TIMER_STRIDE) };
+        cfg1.write(|w| unsafe { w.bits(0) });
+    }
+
+    /// The current counter value and counting direction of the timer
+    pub fn status(&self) -> (u16, CounterDirection) {
+        let block = PWM::block();
+        let status = unsafe { nth(&block.timer0_status, TIM, TIMER_STRIDE) }
+            .read()
+            .bits();
+
+        let direction = if status & 1 << 16 == 0 {
+            CounterDirection::Increasing
+        } else {
+            CounterDirection::Decreasing
+        };
+
+        (status as u16, direction)
+    }
+}
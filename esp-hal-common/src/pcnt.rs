@@ -0,0 +1,410 @@
+//! # Pulse Counter (PCNT)
+//!
+//! ### Summary
+//! The ESP32 contains a pulse counter with 8 units, the ESP32-S2 and ESP32-S3
+//! with 4 units. Each unit has a signed 16 bit counter and two channels. Every
+//! channel has a pulse (signal) input whose edges are counted and a control
+//! input whose level modifies how the edges are counted.
+//!
+//! For every channel the action on positive and negative edges of the pulse
+//! input can be configured ([EdgeMode]) as well as how the level of the
+//! control input modifies that action ([CtrlMode]). Using both channels of a
+//! unit with the pulse and control inputs swapped gives a 4x quadrature
+//! decoder.
+//!
+//! The counter is reset to zero when it reaches one of its limits
+//! ([Unit0::set_limits]). Reaching a limit, zero or one of two thresholds
+//! ([Unit0::set_thresholds]) can raise the PCNT interrupt, e.g. to keep track
+//! of counter overflows.
+//!
+//! ### Example
+//! ```no_run
+//! let pcnt = PulseCounter::new(peripherals.PCNT, &mut system.peripheral_clock_control);
+//! let mut unit = pcnt.unit0;
+//!
+//! let mut pin_a = io.pins.gpio5.into_pull_up_input();
+//! let mut pin_b = io.pins.gpio18.into_pull_up_input();
+//!
+//! unit.configure_channel(
+//!     Channel::Channel0,
+//!     ChannelConfig {
+//!         pos_edge: EdgeMode::Decrement,
+//!         neg_edge: EdgeMode::Increment,
+//!         high_ctrl: CtrlMode::Keep,
+//!         low_ctrl: CtrlMode::Reverse,
+//!     },
+//! );
+//! unit.set_signal_input(Channel::Channel0, &mut pin_a);
+//! unit.set_ctrl_input(Channel::Channel0, &mut pin_b);
+//! unit.clear();
+//! unit.resume();
+//!
+//! let position = unit.get_count();
+//! ```
+
+#![deny(missing_docs)]
+
+use paste::paste;
+
+use crate::{
+    gpio::{types::InputSignal, InputPin},
+    pac::PCNT,
+    system::PeripheralClockControl,
+};
+
+/// Action taken on an edge of the pulse input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum EdgeMode {
+    /// Don't change the counter
+    Hold      = 0,
+    /// Increment the counter
+    Increment = 1,
+    /// Decrement the counter
+    Decrement = 2,
+}
+
+/// Modification of the [EdgeMode] depending on the level of the control input
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CtrlMode {
+    /// Keep the configured edge action
+    Keep    = 0,
+    /// Reverse the configured edge action (increment becomes decrement and
+    /// vice versa)
+    Reverse = 1,
+    /// Disable counting, regardless of the configured edge action
+    Disable = 2,
+}
+
+/// Channel of a pulse counter unit
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Channel {
+    /// Channel 0
+    Channel0,
+    /// Channel 1
+    Channel1,
+}
+
+/// Events of a unit which raised its interrupt, see [Unit0::get_events]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Events {
+    /// The counter reached zero
+    pub zero: bool,
+    /// The counter reached threshold 0
+    pub threshold0: bool,
+    /// The counter reached threshold 1
+    pub threshold1: bool,
+    /// The counter reached the low limit and was reset to zero
+    pub low_limit: bool,
+    /// The counter reached the high limit and was reset to zero
+    pub high_limit: bool,
+}
+
+/// Count mode configuration of a single channel
+///
+/// The edge modes are applied to the pulse input, the control modes select
+/// how the edge modes are modified while the control input is high or low.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ChannelConfig {
+    /// Action on a positive edge of the pulse input
+    pub pos_edge: EdgeMode,
+    /// Action on a negative edge of the pulse input
+    pub neg_edge: EdgeMode,
+    /// Modification while the control input is high
+    pub high_ctrl: CtrlMode,
+    /// Modification while the control input is low
+    pub low_ctrl: CtrlMode,
+}
+
+impl Default for ChannelConfig {
+    fn default() -> Self {
+        ChannelConfig {
+            pos_edge: EdgeMode::Increment,
+            neg_edge: EdgeMode::Hold,
+            high_ctrl: CtrlMode::Keep,
+            low_ctrl: CtrlMode::Keep,
+        }
+    }
+}
+
+macro_rules! unit_instance {
+    ($num:literal) => {
+        paste! {
+            /// Pulse counter unit
+            pub struct [<Unit $num>] {
+                _private: (),
+            }
+
+            impl [<Unit $num>] {
+                fn new() -> Self {
+                    let unit = [<Unit $num>] { _private: () };
+                    unit.pause();
+                    unit.clear();
+                    unit
+                }
+
+                /// Configure the count mode of the given channel
+                pub fn configure_channel(&mut self, channel: Channel, config: ChannelConfig) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+
+                    match channel {
+                        Channel::Channel0 => {
+                            pcnt.[<u $num _conf0>].modify(|_, w| unsafe {
+                                w.[<ch0_pos_mode_u $num>]()
+                                    .bits(config.pos_edge as u8)
+                                    .[<ch0_neg_mode_u $num>]()
+                                    .bits(config.neg_edge as u8)
+                                    .[<ch0_hctrl_mode_u $num>]()
+                                    .bits(config.high_ctrl as u8)
+                                    .[<ch0_lctrl_mode_u $num>]()
+                                    .bits(config.low_ctrl as u8)
+                            });
+                        }
+                        Channel::Channel1 => {
+                            pcnt.[<u $num _conf0>].modify(|_, w| unsafe {
+                                w.[<ch1_pos_mode_u $num>]()
+                                    .bits(config.pos_edge as u8)
+                                    .[<ch1_neg_mode_u $num>]()
+                                    .bits(config.neg_edge as u8)
+                                    .[<ch1_hctrl_mode_u $num>]()
+                                    .bits(config.high_ctrl as u8)
+                                    .[<ch1_lctrl_mode_u $num>]()
+                                    .bits(config.low_ctrl as u8)
+                            });
+                        }
+                    }
+                }
+
+                /// Route the given pin to the pulse input of the given channel
+                ///
+                /// The pin is only connected via the GPIO matrix, the same pin
+                /// can therefore also be used as an input of another channel.
+                pub fn set_signal_input<P: InputPin>(&mut self, channel: Channel, pin: &mut P) {
+                    let signal = match channel {
+                        Channel::Channel0 => InputSignal::[<PCNT_SIG_CH0_ $num>],
+                        Channel::Channel1 => InputSignal::[<PCNT_SIG_CH1_ $num>],
+                    };
+                    pin.set_to_input().connect_input_to_peripheral(signal);
+                }
+
+                /// Route the given pin to the control input of the given channel
+                ///
+                /// The pin is only connected via the GPIO matrix, the same pin
+                /// can therefore also be used as an input of another channel.
+                pub fn set_ctrl_input<P: InputPin>(&mut self, channel: Channel, pin: &mut P) {
+                    let signal = match channel {
+                        Channel::Channel0 => InputSignal::[<PCNT_CTRL_CH0_ $num>],
+                        Channel::Channel1 => InputSignal::[<PCNT_CTRL_CH1_ $num>],
+                    };
+                    pin.set_to_input().connect_input_to_peripheral(signal);
+                }
+
+                /// Configure the glitch filter
+                ///
+                /// Pulses shorter than `threshold` APB clock cycles are
+                /// ignored, `None` disables the filter. The threshold is
+                /// limited to 10 bits.
+                pub fn set_filter(&mut self, threshold: Option<u16>) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+
+                    pcnt.[<u $num _conf0>].modify(|_, w| unsafe {
+                        match threshold {
+                            Some(threshold) => w
+                                .[<filter_thres_u $num>]()
+                                .bits(threshold.min(1023))
+                                .[<filter_en_u $num>]()
+                                .set_bit(),
+                            None => w.[<filter_en_u $num>]().clear_bit(),
+                        }
+                    });
+                }
+
+                /// Set the limits at which the counter is reset to zero
+                pub fn set_limits(&mut self, low: i16, high: i16) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+
+                    pcnt.[<u $num _conf2>].write(|w| unsafe {
+                        w.[<cnt_l_lim_u $num>]()
+                            .bits(low as u16)
+                            .[<cnt_h_lim_u $num>]()
+                            .bits(high as u16)
+                    });
+                    pcnt.[<u $num _conf0>].modify(|_, w| {
+                        w.[<thr_l_lim_en_u $num>]()
+                            .bit(low != 0)
+                            .[<thr_h_lim_en_u $num>]()
+                            .bit(high != 0)
+                    });
+                }
+
+                /// Set the thresholds which raise an event when reached
+                ///
+                /// `None` disables the event of a threshold.
+                pub fn set_thresholds(&mut self, threshold0: Option<i16>, threshold1: Option<i16>) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+
+                    pcnt.[<u $num _conf1>].write(|w| unsafe {
+                        w.[<cnt_thres0_u $num>]()
+                            .bits(threshold0.unwrap_or(0) as u16)
+                            .[<cnt_thres1_u $num>]()
+                            .bits(threshold1.unwrap_or(0) as u16)
+                    });
+                    pcnt.[<u $num _conf0>].modify(|_, w| {
+                        w.[<thr_thres0_en_u $num>]()
+                            .bit(threshold0.is_some())
+                            .[<thr_thres1_en_u $num>]()
+                            .bit(threshold1.is_some())
+                    });
+                }
+
+                /// Enable or disable the event raised when the counter
+                /// reaches zero
+                pub fn set_zero_event(&mut self, enable: bool) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.[<u $num _conf0>].modify(|_, w| w.[<thr_zero_en_u $num>]().bit(enable));
+                }
+
+                /// Raise the PCNT interrupt on the enabled events of this unit
+                ///
+                /// The limit events are enabled by [Self::set_limits], the
+                /// others by [Self::set_thresholds] and [Self::set_zero_event].
+                pub fn listen(&mut self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.int_ena.modify(|_, w| w.[<cnt_thr_event_u $num _int_ena>]().set_bit());
+                }
+
+                /// Stop raising the PCNT interrupt for this unit
+                pub fn unlisten(&mut self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.int_ena.modify(|_, w| w.[<cnt_thr_event_u $num _int_ena>]().clear_bit());
+                }
+
+                /// Whether an event of this unit raised the interrupt
+                pub fn interrupt_set(&self) -> bool {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.int_raw.read().[<cnt_thr_event_u $num _int_raw>]().bit_is_set()
+                }
+
+                /// Clear the interrupt of this unit
+                pub fn reset_interrupt(&mut self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.int_clr.write(|w| w.[<cnt_thr_event_u $num _int_clr>]().set_bit());
+                }
+
+                /// The events which raised the last interrupt of this unit
+                pub fn get_events(&self) -> Events {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    let status = pcnt.[<u $num _status>].read().bits();
+
+                    Events {
+                        zero: status & 1 << 6 != 0,
+                        threshold0: status & 1 << 3 != 0,
+                        threshold1: status & 1 << 2 != 0,
+                        low_limit: status & 1 << 4 != 0,
+                        high_limit: status & 1 << 5 != 0,
+                    }
+                }
+
+                /// Stop counting, the counter keeps its value
+                pub fn pause(&self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.ctrl.modify(|_, w| w.[<cnt_pause_u $num>]().set_bit());
+                }
+
+                /// Continue counting
+                pub fn resume(&self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    pcnt.ctrl.modify(|_, w| w.[<cnt_pause_u $num>]().clear_bit());
+                }
+
+                /// Reset the counter to zero
+                pub fn clear(&self) {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    #[cfg(esp32)]
+                    {
+                        pcnt.ctrl.modify(|_, w| w.[<plus_cnt_rst_u $num>]().set_bit());
+                        pcnt.ctrl.modify(|_, w| w.[<plus_cnt_rst_u $num>]().clear_bit());
+                    }
+                    #[cfg(any(esp32s2, esp32s3))]
+                    {
+                        pcnt.ctrl.modify(|_, w| w.[<pulse_cnt_rst_u $num>]().set_bit());
+                        pcnt.ctrl.modify(|_, w| w.[<pulse_cnt_rst_u $num>]().clear_bit());
+                    }
+                }
+
+                /// Get the current count of the counter
+                pub fn get_count(&self) -> i16 {
+                    let pcnt = unsafe { &*PCNT::PTR };
+                    #[cfg(esp32)]
+                    let value = pcnt.[<u $num _cnt>].read().[<plus_cnt_u $num>]().bits();
+                    #[cfg(any(esp32s2, esp32s3))]
+                    let value = pcnt.[<u $num _cnt>].read().[<pulse_cnt_u $num>]().bits();
+
+                    value as i16
+                }
+
+                /// Get the current value of the counter, same as
+                /// [Self::get_count]
+                pub fn get_value(&self) -> i16 {
+                    self.get_count()
+                }
+            }
+        }
+    };
+}
+
+macro_rules! pcnt {
+    ($( ($num:literal, $name:ident) ),+) => {
+        paste! {
+            /// Pulse counter peripheral (PCNT)
+            pub struct PulseCounter {
+                reg: PCNT,
+                $(
+                    #[doc = concat!("Pulse counter unit ", stringify!($num))]
+                    pub $name: [<Unit $num>],
+                )+
+            }
+
+            impl PulseCounter {
+                /// Create a new pulse counter instance, all units are paused
+                /// and cleared
+                pub fn new(
+                    instance: PCNT,
+                    peripheral_clock_control: &mut PeripheralClockControl,
+                ) -> Self {
+                    peripheral_clock_control.enable(crate::system::Peripheral::Pcnt);
+
+                    PulseCounter {
+                        reg: instance,
+                        $(
+                            $name: [<Unit $num>]::new(),
+                        )+
+                    }
+                }
+
+                /// Return the raw interface to the underlying PCNT peripheral
+                pub fn free(self) -> PCNT {
+                    self.reg
+                }
+            }
+        }
+
+        $(
+            unit_instance!($num);
+        )+
+    };
+}
+
+#[cfg(esp32)]
+pcnt!(
+    (0, unit0),
+    (1, unit1),
+    (2, unit2),
+    (3, unit3),
+    (4, unit4),
+    (5, unit5),
+    (6, unit6),
+    (7, unit7)
+);
+
+#[cfg(any(esp32s2, esp32s3))]
+pcnt!((0, unit0), (1, unit1), (2, unit2), (3, unit3));
@@ -0,0 +1,334 @@
+//! # External PSRAM
+//!
+//! ### Summary
+//! [init_psram] brings up a quad SPI PSRAM connected to the second chip select
+//! of the flash SPI bus (SPICS1, GPIO26) and maps it into the data address
+//! space, e.g. the PSRAM of ESP32-S3 modules with an `R2` suffix. Octal PSRAM
+//! (`R8` modules) isn't supported yet.
+//!
+//! The PSRAM is clocked at 40 MHz and mapped at the end of the data bus
+//! address range, below `0x3E00_0000`. The returned [PsramRegion] describes
+//! the mapped memory and can be handed to a heap allocator, e.g.
+//!
+//! ```no_run
+//! let psram = psram::init_psram().unwrap();
+//! unsafe { ALLOCATOR.init(psram.start() as usize, psram.size()) };
+//! ```
+//!
+//! ### Constraints
+//! - The PSRAM is accessed through the data cache, so it's only available via
+//!   the data bus: code can't be executed from it.
+//! - Accesses are done in cache lines, there are no alignment requirements
+//!   beyond the ones of the stored types, but performance is best when large
+//!   buffers are 32 byte aligned.
+//! - The GDMA accesses the PSRAM directly, bypassing the cache. Buffers in
+//!   PSRAM which are used for DMA need to be written back before a TX transfer
+//!   and invalidated after an RX transfer, and have to be aligned to the data
+//!   cache line size (32 bytes). The DMA drivers of the HAL don't do this, so
+//!   only use buffers in internal RAM with them.
+//! - The `.external.*` sections of the linker script are not placed in the
+//!   region mapped by this driver.
+
+#![deny(missing_docs)]
+
+use crate::pac::{EXTMEM, IO_MUX, SPI0, SPI1};
+
+/// Start of the data bus address range mapped by the MMU
+const DBUS_VADDR_START: u32 = 0x3C00_0000;
+/// Number of MMU entries, each one maps a 64 KiB page
+const MMU_ENTRY_COUNT: usize = 512;
+/// Size of a page mapped by an MMU entry
+const MMU_PAGE_SIZE: usize = 0x1_0000;
+/// Address of the MMU table
+const MMU_TABLE: *mut u32 = 0x600C_5000 as *mut u32;
+/// MMU entry bit marking the entry as unused
+const MMU_INVALID: u32 = 1 << 14;
+/// MMU entry bit selecting the PSRAM instead of the flash
+const MMU_ACCESS_SPIRAM: u32 = 1 << 15;
+
+/// GPIO connected to the chip select of the PSRAM
+const PSRAM_CS_GPIO: usize = 26;
+
+const PSRAM_RESET_ENABLE: u8 = 0x66;
+const PSRAM_RESET: u8 = 0x99;
+const PSRAM_READ_ID: u8 = 0x9F;
+const PSRAM_ENTER_QMODE: u8 = 0x35;
+const PSRAM_QUAD_WRITE: u16 = 0x38;
+const PSRAM_FAST_READ_QUAD: u16 = 0xEB;
+const PSRAM_FAST_READ_QUAD_DUMMY: u8 = 6;
+
+/// Known good die marker returned as the second byte of the ID
+const PSRAM_ID_KGD: u8 = 0x5D;
+
+/// PSRAM errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// No PSRAM answered, or it reported a failed die test
+    NotDetected,
+    /// The PSRAM reported an unknown density
+    UnsupportedSize,
+    /// The MMU entries at the end of the data bus address range are already
+    /// in use
+    AddressRangeInUse,
+}
+
+/// Memory region the PSRAM is mapped to
+#[derive(Debug, Clone, Copy)]
+pub struct PsramRegion {
+    start: *mut u8,
+    size: usize,
+}
+
+impl PsramRegion {
+    /// Start of the region
+    pub fn start(&self) -> *mut u8 {
+        self.start
+    }
+
+    /// Size of the region in bytes
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Access the region as a byte slice
+    ///
+    /// # Safety
+    ///
+    /// The region must not be accessed in any other way while the slice is
+    /// alive, in particular it must not be handed to an allocator as well.
+    pub unsafe fn as_mut_slice(&self) -> &'static mut [u8] {
+        core::slice::from_raw_parts_mut(self.start, self.size)
+    }
+}
+
+static mut REGION: Option<PsramRegion> = None;
+
+/// Initialize the PSRAM and map it into the data address space
+///
+/// Calling this again returns the region mapped by the first call.
+pub fn init_psram() -> Result<PsramRegion, Error> {
+    critical_section::with(|_| unsafe {
+        if let Some(region) = REGION {
+            return Ok(region);
+        }
+
+        configure_cs_pin();
+
+        psram_exec_cmd(PSRAM_RESET_ENABLE, None, &mut []);
+        psram_exec_cmd(PSRAM_RESET, None, &mut []);
+
+        let mut id = [0u8; 3];
+        psram_exec_cmd(PSRAM_READ_ID, Some(0), &mut id);
+        if id[1] != PSRAM_ID_KGD {
+            return Err(Error::NotDetected);
+        }
+
+        let size = match (id[2] >> 5) & 0x7 {
+            0 => 2 * 1024 * 1024,
+            1 => 4 * 1024 * 1024,
+            2 => 8 * 1024 * 1024,
+            _ => return Err(Error::UnsupportedSize),
+        };
+
+        psram_exec_cmd(PSRAM_ENTER_QMODE, None, &mut []);
+
+        configure_spi0();
+
+        let start = map(size)?;
+        let region = PsramRegion { start, size };
+        REGION = Some(region);
+
+        Ok(region)
+    })
+}
+
+/// Connect GPIO26 to SPICS1
+fn configure_cs_pin() {
+    let io_mux = unsafe { &*IO_MUX::PTR };
+
+    io_mux.gpio[PSRAM_CS_GPIO].modify(|_, w| unsafe { w.mcu_sel().bits(0).fun_drv().bits(3) });
+}
+
+/// Send a command to the PSRAM in single line SPI mode via SPI1, reading
+/// `read.len()` bytes afterwards
+///
+/// The PSRAM is selected via CS1, the flash on CS0 is deselected meanwhile.
+/// The transfer mode of SPI1 is restored afterwards, so it doesn't interfere
+/// with flash accesses done via SPI1.
+fn psram_exec_cmd(command: u8, address: Option<u32>, read: &mut [u8]) {
+    let spi1 = unsafe { &*SPI1::PTR };
+
+    let ctrl = spi1.ctrl.read().bits();
+    let user = spi1.user.read().bits();
+    let user1 = spi1.user1.read().bits();
+    let user2 = spi1.user2.read().bits();
+    let misc = spi1.misc.read().bits();
+
+    spi1.ctrl.modify(|_, w| {
+        w.fread_qio()
+            .clear_bit()
+            .fread_dio()
+            .clear_bit()
+            .fread_quad()
+            .clear_bit()
+            .fread_dual()
+            .clear_bit()
+            .fcmd_quad()
+            .clear_bit()
+            .fcmd_dual()
+            .clear_bit()
+            .faddr_quad()
+            .clear_bit()
+            .faddr_dual()
+            .clear_bit()
+    });
+    spi1.misc
+        .modify(|_, w| w.cs0_dis().set_bit().cs1_dis().clear_bit());
+
+    spi1.user.modify(|_, w| {
+        w.usr_command()
+            .set_bit()
+            .usr_addr()
+            .bit(address.is_some())
+            .usr_dummy()
+            .clear_bit()
+            .usr_mosi()
+            .clear_bit()
+            .usr_miso()
+            .bit(!read.is_empty())
+            .fwrite_qio()
+            .clear_bit()
+            .fwrite_dio()
+            .clear_bit()
+            .fwrite_quad()
+            .clear_bit()
+            .fwrite_dual()
+            .clear_bit()
+    });
+    spi1.user2.modify(|_, w| unsafe {
+        w.usr_command_bitlen()
+            .bits(7)
+            .usr_command_value()
+            .bits(command as u16)
+    });
+
+    if let Some(address) = address {
+        spi1.user1
+            .modify(|_, w| unsafe { w.usr_addr_bitlen().bits(23) });
+        spi1.addr
+            .write(|w| unsafe { w.usr_addr_value().bits(address) });
+    }
+
+    if !read.is_empty() {
+        spi1.miso_dlen
+            .write(|w| unsafe { w.usr_miso_dbitlen().bits(read.len() as u32 * 8 - 1) });
+    }
+
+    spi1.cmd.modify(|_, w| w.usr().set_bit());
+    while spi1.cmd.read().usr().bit_is_set() {}
+
+    let data = unsafe { (spi1.w0.as_ptr() as *const u32).read_volatile() }.to_le_bytes();
+    let count = usize::min(read.len(), data.len());
+    read[..count].copy_from_slice(&data[..count]);
+
+    spi1.ctrl.write(|w| unsafe { w.bits(ctrl) });
+    spi1.user.write(|w| unsafe { w.bits(user) });
+    spi1.user1.write(|w| unsafe { w.bits(user1) });
+    spi1.user2.write(|w| unsafe { w.bits(user2) });
+    spi1.misc.write(|w| unsafe { w.bits(misc) });
+}
+
+/// Configure the SPI0 cache accesses to the PSRAM: quad read/write commands,
+/// 24 bit addresses and a 40 MHz clock derived from the 80 MHz MSPI core
+/// clock
+fn configure_spi0() {
+    let spi0 = unsafe { &*SPI0::PTR };
+
+    spi0.sram_clk.write(|w| unsafe {
+        w.sclk_equ_sysclk()
+            .clear_bit()
+            .sclkcnt_n()
+            .bits(1)
+            .sclkcnt_h()
+            .bits(0)
+            .sclkcnt_l()
+            .bits(1)
+    });
+
+    spi0.spi_smem_ac.modify(|_, w| unsafe {
+        w.spi_smem_cs_hold()
+            .set_bit()
+            .spi_smem_cs_setup()
+            .set_bit()
+            .spi_smem_cs_hold_time()
+            .bits(0)
+            .spi_smem_cs_setup_time()
+            .bits(0)
+    });
+
+    spi0.sram_dwr_cmd.modify(|_, w| unsafe {
+        w.cache_sram_usr_wr_cmd_bitlen()
+            .bits(7)
+            .cache_sram_usr_wr_cmd_value()
+            .bits(PSRAM_QUAD_WRITE)
+    });
+    spi0.sram_drd_cmd.modify(|_, w| unsafe {
+        w.cache_sram_usr_rd_cmd_bitlen()
+            .bits(7)
+            .cache_sram_usr_rd_cmd_value()
+            .bits(PSRAM_FAST_READ_QUAD)
+    });
+
+    spi0.cache_sctrl.modify(|_, w| unsafe {
+        w.usr_sram_dio()
+            .clear_bit()
+            .usr_sram_qio()
+            .set_bit()
+            .cache_sram_usr_rcmd()
+            .set_bit()
+            .cache_sram_usr_wcmd()
+            .set_bit()
+            .sram_addr_bitlen()
+            .bits(23)
+            .usr_rd_sram_dummy()
+            .set_bit()
+            .sram_rdummy_cyclelen()
+            .bits(PSRAM_FAST_READ_QUAD_DUMMY - 1)
+    });
+
+    // CS0 selects the flash, CS1 the PSRAM
+    spi0.misc.modify(|_, w| w.cs1_dis().clear_bit());
+}
+
+/// Map `size` bytes of PSRAM to the end of the data bus address range
+///
+/// No cache lines need to be invalidated: the virtual addresses weren't
+/// mapped before, so they can't be cached.
+fn map(size: usize) -> Result<*mut u8, Error> {
+    let pages = size / MMU_PAGE_SIZE;
+    let first = MMU_ENTRY_COUNT - pages;
+
+    let entry = |index: usize| unsafe { MMU_TABLE.add(index) };
+
+    if (first..MMU_ENTRY_COUNT)
+        .any(|index| unsafe { entry(index).read_volatile() } & MMU_INVALID == 0)
+    {
+        return Err(Error::AddressRangeInUse);
+    }
+
+    for page in 0..pages {
+        unsafe {
+            entry(first + page).write_volatile(MMU_ACCESS_SPIRAM | page as u32);
+        }
+    }
+
+    let extmem = unsafe { &*EXTMEM::PTR };
+    extmem.dcache_ctrl1.modify(|_, w| {
+        w.dcache_shut_core0_bus()
+            .clear_bit()
+            .dcache_shut_core1_bus()
+            .clear_bit()
+    });
+
+    Ok((DBUS_VADDR_START as usize + first * MMU_PAGE_SIZE) as *mut u8)
+}
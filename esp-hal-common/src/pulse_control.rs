@@ -31,8 +31,11 @@
 //! * FIFO mode is not supported (there appear to be some issues with FIFO mode
 //!   in some variants and for consistency all variants therefore we use
 //!   NON-FIFO mode everywhere)
-//! * Non-blocking mode is currently not supported!
-//! * Input channels are currently not supported!
+//! * Transmitting can be done without blocking, longer sequences are then fed
+//!   from the threshold interrupt (see
+//!   [ConfiguredChannel::start_pulse_sequence_raw])
+//! * Input channels are supported on the ESP32-C3 and ESP32-S3, a received
+//!   sequence has to fit into the RAM of the channel (48 pulse codes)
 //!
 //! ### Example (for ESP32-C3)
 //! ```
@@ -74,6 +77,19 @@
 //! rmt_channel0
 //!     .send_pulse_sequence(RepeatMode::SingleShot, &seq)
 //!     .unwrap();
+//!
+//! // Set up a receiver channel, a sequence ends once the input didn't change
+//! // for 1000 clock cycles
+//! let mut rmt_channel2 = pulse.channel2;
+//! rmt_channel2
+//!     .set_channel_divider(1)
+//!     .set_idle_threshold(1000)
+//!     .set_filter_threshold(Some(10));
+//!
+//! let mut rmt_channel2 = rmt_channel2.assign_pin(io.pins.gpio9);
+//!
+//! let mut received = [PulseCode::default(); 48];
+//! let len = rmt_channel2.receive_pulse_sequence(&mut received).unwrap();
 //! ```
 
 #![deny(missing_docs)]
@@ -83,6 +99,8 @@ use core::slice::Iter;
 use fugit::NanosDurationU32;
 pub use paste::paste;
 
+#[cfg(any(esp32c3, esp32s3))]
+use crate::gpio::{types::InputSignal, InputPin};
 use crate::{
     gpio::{types::OutputSignal, OutputPin},
     pac::RMT,
@@ -110,6 +128,17 @@ pub enum TransmissionError {
     IncompatibleRepeatMode,
 }
 
+/// Errors that can occur while receiving
+#[cfg(any(esp32c3, esp32s3))]
+#[derive(Debug)]
+pub enum ReceiveError {
+    /// The RAM of the channel was full before the input was idle, the
+    /// sequence is too long to be received
+    Overflow,
+    /// The provided buffer can't hold the received sequence
+    BufferTooSmall,
+}
+
 /// Specifies the mode with which pulses are sent out in transmitter channels
 #[derive(Debug, Copy, Clone, PartialEq)]
 pub enum RepeatMode {
@@ -162,10 +191,21 @@ const RMT_RAM_START: usize = 0x3ff56800;
 #[cfg(esp32s3)]
 const RMT_RAM_START: usize = 0x60016800;
 
+// Position of the first receiver channel's bit of the receiver interrupts in
+// the interrupt registers
+#[cfg(esp32c3)]
+const RX_END_INT_BIT: u32 = 2;
+#[cfg(esp32c3)]
+const RX_ERR_INT_BIT: u32 = 6;
+#[cfg(esp32s3)]
+const RX_END_INT_BIT: u32 = 16;
+#[cfg(esp32s3)]
+const RX_ERR_INT_BIT: u32 = 20;
+
 /// Object representing the state of one pulse code per ESP32-C3 TRM
 ///
 /// Allows for the assignment of two levels and their lenghts
-#[derive(Clone, Copy, Debug)]
+#[derive(Clone, Copy, Debug, Default)]
 pub struct PulseCode {
     /// Logical output level in the first pulse code interval
     pub level1: bool,
@@ -209,6 +249,19 @@ impl From<PulseCode> for u32 {
     }
 }
 
+/// Convert a value read from the RMT RAM into a pulse code structure
+impl From<u32> for PulseCode {
+    #[inline(always)]
+    fn from(entry: u32) -> PulseCode {
+        PulseCode {
+            level1: entry & (1 << 15) != 0,
+            length1: NanosDurationU32::from_ticks(entry & 0x7fff),
+            level2: entry & (1 << 31) != 0,
+            length2: NanosDurationU32::from_ticks((entry >> 16) & 0x7fff),
+        }
+    }
+}
+
 /// Functionality that every OutputChannel must support
 pub trait OutputChannel<CC> {
     /// Set the logical level that the connected pin is pulled to
@@ -258,6 +311,46 @@ pub trait ConfiguredChannel {
         sequence: &[u32; N],
     ) -> Result<(), TransmissionError>;
 
+    /// Start sending a raw pulse sequence without waiting for completion
+    ///
+    /// Writes as much of `sequence` as fits into the RAM of the channel and
+    /// returns the number of pulse codes written. Longer sequences are sent
+    /// in wrap mode: each time half of the RAM was sent, the threshold
+    /// interrupt is raised (see [Self::listen_threshold]) and the next part
+    /// has to be written with [Self::refill].
+    fn start_pulse_sequence_raw(
+        &mut self,
+        repeat_mode: RepeatMode,
+        sequence: &[u32],
+    ) -> Result<usize, TransmissionError>;
+
+    /// Write the pulse codes of `sequence` starting at `offset` into the
+    /// half of the RAM which was already sent and clear the threshold
+    /// interrupt
+    ///
+    /// Returns the offset of the first pulse code which wasn't written yet.
+    fn refill(&mut self, sequence: &[u32], offset: usize) -> usize;
+
+    /// Check if a transmission started by [Self::start_pulse_sequence_raw]
+    /// completed
+    fn is_transmission_done(&self) -> Result<bool, TransmissionError>;
+
+    /// Listen for the threshold interrupt, raised whenever half of the RAM
+    /// was sent
+    fn listen_threshold(&mut self);
+
+    /// Stop listening for the threshold interrupt
+    fn unlisten_threshold(&mut self);
+
+    /// Check if the threshold interrupt is pending
+    fn is_threshold_set(&self) -> bool;
+
+    /// Clear the threshold interrupt
+    ///
+    /// [Self::refill] does this as well, this is needed to acknowledge the
+    /// interrupt when there is nothing left to refill.
+    fn clear_threshold_interrupt(&mut self);
+
     /// Stop any ongoing (repetitive) transmission
     ///
     /// This function needs to be called to stop sending when
@@ -265,6 +358,60 @@ pub trait ConfiguredChannel {
     fn stop_transmission(&self);
 }
 
+/// Functionality that every InputChannel must support
+#[cfg(any(esp32c3, esp32s3))]
+pub trait InputChannel<CC> {
+    /// Set channel clock divider value
+    fn set_channel_divider(&mut self, divider: u8) -> &mut Self;
+
+    /// Set the number of clock cycles without an edge on the input after
+    /// which the reception ends (at most `0x7fff`)
+    fn set_idle_threshold(&mut self, threshold: u16) -> &mut Self;
+
+    /// Ignore pulses shorter than `threshold` clock cycles of the peripheral
+    /// clock, `None` disables the filter
+    fn set_filter_threshold(&mut self, threshold: Option<u8>) -> &mut Self;
+
+    /// Assign a pin that this channel should receive from
+    fn assign_pin<RmtPin: InputPin>(self, pin: RmtPin) -> CC;
+}
+
+/// Functionality that is allowed only on a configured input channel
+#[cfg(any(esp32c3, esp32s3))]
+pub trait ConfiguredInputChannel {
+    /// Start receiving without waiting for the sequence
+    ///
+    /// Use [Self::is_receive_done] to check for the end of the sequence and
+    /// [Self::read_received_raw] to fetch it.
+    fn start_receive(&mut self);
+
+    /// Check if the input was idle long enough to end the reception (or the
+    /// reception failed)
+    fn is_receive_done(&self) -> bool;
+
+    /// Stop receiving and copy the received sequence into `buffer`
+    ///
+    /// Returns the number of pulse codes, the last one contains the end
+    /// marker (a length of 0).
+    fn read_received_raw(&mut self, buffer: &mut [u32]) -> Result<usize, ReceiveError>;
+
+    /// Receive a pulse sequence in a blocking fashion
+    ///
+    /// Returns the number of pulse codes, the last one contains the end
+    /// marker (a length of 0).
+    fn receive_pulse_sequence(&mut self, buffer: &mut [PulseCode]) -> Result<usize, ReceiveError>;
+
+    /// Receive a raw pulse sequence in a blocking fashion
+    ///
+    /// The `buffer` is filled with the values read from the RMT RAM, see
+    /// [Self::receive_pulse_sequence] for a variant returning `PulseCode`
+    /// objects.
+    fn receive_pulse_sequence_raw(&mut self, buffer: &mut [u32]) -> Result<usize, ReceiveError>;
+
+    /// Stop an ongoing reception
+    fn stop_receive(&mut self);
+}
+
 macro_rules! channel_instance {
     ($num:literal, $cxi:ident, $output_signal:path
         ) => {
@@ -362,32 +509,17 @@ macro_rules! channel_instance {
                 channel: $cxi,
             }
 
-            impl ConfiguredChannel for [<Configured $cxi>] {
-                /// Send a pulse sequence in a blocking fashion
-                fn send_pulse_sequence<const N: usize>(
-                    &mut self,
-                    repeat_mode: RepeatMode,
-                    sequence: &[PulseCode; N],
-                ) -> Result<(), TransmissionError> {
-                    let precomputed_sequence = sequence.map(|x| u32::from(x));
-
-                    self.send_pulse_sequence_raw(repeat_mode, &precomputed_sequence)
-                }
-
-            /// Send a raw pulse sequence in a blocking fashion
+            impl [<Configured $cxi>] {
+            /// Configure the channel, write the first part of `sequence` into
+            /// the RAM and start the transmission
             ///
-            /// In this function we expect the `sequence` elements to be already
-            /// in the correct u32 format that is understood by the RMT.
-            /// Please refer to the reference manual or use the variant which
-            /// accepts `PulseCode` objects instead.
-            ///
-            /// We expect that the end marker is already part of the provided
-            /// sequence and to be provided in all modes!
-            fn send_pulse_sequence_raw<const N: usize>(
+            /// Returns the iterator over the part of the sequence which still
+            /// needs to be written.
+            fn start_sequence<'s>(
                 &mut self,
                 repeat_mode: RepeatMode,
-                sequence: &[u32; N],
-            ) -> Result<(), TransmissionError> {
+                sequence: &'s [u32],
+            ) -> Result<Iter<'s, u32>, TransmissionError> {
                 // Check for any configuration error states
                 match repeat_mode {
                     #[cfg(not(esp32))]
@@ -556,6 +688,38 @@ macro_rules! channel_instance {
                     }
                 }
 
+                Ok(sequence_iter)
+            }
+            }
+
+            impl ConfiguredChannel for [<Configured $cxi>] {
+                /// Send a pulse sequence in a blocking fashion
+                fn send_pulse_sequence<const N: usize>(
+                    &mut self,
+                    repeat_mode: RepeatMode,
+                    sequence: &[PulseCode; N],
+                ) -> Result<(), TransmissionError> {
+                    let precomputed_sequence = sequence.map(|x| u32::from(x));
+
+                    self.send_pulse_sequence_raw(repeat_mode, &precomputed_sequence)
+                }
+
+            /// Send a raw pulse sequence in a blocking fashion
+            ///
+            /// In this function we expect the `sequence` elements to be already
+            /// in the correct u32 format that is understood by the RMT.
+            /// Please refer to the reference manual or use the variant which
+            /// accepts `PulseCode` objects instead.
+            ///
+            /// We expect that the end marker is already part of the provided
+            /// sequence and to be provided in all modes!
+            fn send_pulse_sequence_raw<const N: usize>(
+                &mut self,
+                repeat_mode: RepeatMode,
+                sequence: &[u32; N],
+            ) -> Result<(), TransmissionError> {
+                let mut sequence_iter = self.start_sequence(repeat_mode, sequence)?;
+
                 // If we're in forever mode, we return right away, otherwise we wait
                 // for completion
                 if repeat_mode != RepeatMode::Forever {
@@ -620,6 +784,88 @@ macro_rules! channel_instance {
                 Ok(())
             }
 
+            /// Start sending a raw pulse sequence without waiting for
+            /// completion
+            fn start_pulse_sequence_raw(
+                &mut self,
+                repeat_mode: RepeatMode,
+                sequence: &[u32],
+            ) -> Result<usize, TransmissionError> {
+                let sequence_iter = self.start_sequence(repeat_mode, sequence)?;
+
+                Ok(sequence.len() - sequence_iter.len())
+            }
+
+            /// Write the next part of `sequence` into the RAM
+            fn refill(&mut self, sequence: &[u32], offset: usize) -> usize {
+                let mut sequence_iter = sequence[offset.min(sequence.len())..].iter();
+                let remaining = sequence_iter.len();
+
+                self.channel.write_sequence(&mut sequence_iter, CHANNEL_RAM_SIZE / 2);
+                self.clear_threshold_interrupt();
+
+                offset + remaining - sequence_iter.len()
+            }
+
+            /// Check if the transmission completed
+            fn is_transmission_done(&self) -> Result<bool, TransmissionError> {
+                let interrupts = unsafe { &*RMT::PTR }.int_raw.read();
+
+                // The C3/S3 have a slightly different interrupt naming scheme
+                #[cfg(any(esp32, esp32s2))]
+                let error = unsafe { interrupts.ch_err_int_raw($num).bit() };
+                #[cfg(any(esp32c3, esp32s3))]
+                let error = unsafe { interrupts.ch_tx_err_int_raw($num).bit() };
+
+                if error {
+                    return Err(TransmissionError::Failure(
+                        unsafe { interrupts.ch_tx_end_int_raw($num).bit() },
+                        // The ESP32 variant does not support the loop functionality
+                        #[cfg(not(esp32))]
+                        unsafe {interrupts.ch_tx_loop_int_raw($num).bit()},
+                        #[cfg(esp32)]
+                        false,
+                        error,
+                        unsafe { interrupts.ch_tx_thr_event_int_raw($num).bit() },
+                    ));
+                }
+
+                Ok(unsafe { interrupts.ch_tx_end_int_raw($num).bit() })
+            }
+
+            /// Listen for the threshold interrupt
+            fn listen_threshold(&mut self) {
+                unsafe { &*RMT::PTR }.int_ena.modify(|_, w| {
+                    paste!(w.[<ch $num _tx_thr_event_int_ena>]().set_bit())
+                });
+            }
+
+            /// Stop listening for the threshold interrupt
+            fn unlisten_threshold(&mut self) {
+                unsafe { &*RMT::PTR }.int_ena.modify(|_, w| {
+                    paste!(w.[<ch $num _tx_thr_event_int_ena>]().clear_bit())
+                });
+            }
+
+            /// Check if the threshold interrupt is pending
+            fn is_threshold_set(&self) -> bool {
+                unsafe {
+                    (&*RMT::PTR)
+                        .int_raw
+                        .read()
+                        .ch_tx_thr_event_int_raw($num)
+                        .bit()
+                }
+            }
+
+            /// Clear the threshold interrupt
+            fn clear_threshold_interrupt(&mut self) {
+                // write-through
+                unsafe { &*RMT::PTR }.int_clr.write(|w| {
+                    paste!(w.[<ch $num _tx_thr_event_int_clr>]().set_bit())
+                });
+            }
+
             /// Stop any ongoing (repetitive) transmission
             ///
             /// This function needs to be called to stop sending when
@@ -753,6 +999,212 @@ macro_rules! output_channel {
     };
 }
 
+#[cfg(any(esp32c3, esp32s3))]
+macro_rules! input_channel {
+    ($num:literal, $idx:literal, $cxi:ident, $input_signal:path
+        ) => {
+        /// RX Input Channel
+        pub struct $cxi {}
+
+        impl $cxi {
+            /// Create a new channel instance
+            pub fn new() -> Self {
+                let mut channel = $cxi {};
+
+                // Apply default configuration
+                unsafe { &*RMT::PTR }.ch_rx_conf0[$idx].modify(|_, w| unsafe {
+                    // Configure memory block size
+                    w.mem_size()
+                        .bits(1)
+                        // Disable carrier demodulation
+                        .carrier_en()
+                        .clear_bit()
+                });
+
+                channel.set_channel_divider(1);
+                channel.set_idle_threshold(0x7fff);
+                channel.set_filter_threshold(None);
+
+                channel
+            }
+
+            /// Read an entry of the RMT RAM section of this channel
+            #[inline(always)]
+            fn read_ram(&self, index: usize) -> u32 {
+                let base_ptr: usize = RMT_RAM_START + ($num * CHANNEL_RAM_SIZE as usize * 4);
+                let ram_ptr = (base_ptr + index * 4) as *const u32;
+                unsafe { ram_ptr.read_volatile() }
+            }
+        }
+
+        paste!(
+            #[doc = "Wrapper for`" $cxi "` object."]
+            pub struct [<Configured $cxi>] {
+                channel: $cxi,
+            }
+
+            impl ConfiguredInputChannel for [<Configured $cxi>] {
+                /// Start receiving without waiting for the sequence
+                fn start_receive(&mut self) {
+                    // Clear the relevant interrupts (write-through)
+                    unsafe { &*RMT::PTR }.int_clr.write(|w| unsafe {
+                        w.bits(1 << (RX_END_INT_BIT + $idx) | 1 << (RX_ERR_INT_BIT + $idx))
+                    });
+
+                    unsafe { &*RMT::PTR }.ch_rx_conf1[$idx].modify(|_, w| {
+                        // Hand the RAM to the receiver and reset the write pointer
+                        w.mem_owner()
+                            .set_bit()
+                            .mem_wr_rst()
+                            .set_bit()
+                            .apb_mem_rst()
+                            .set_bit()
+                            // A sequence has to fit into the RAM in one go
+                            .mem_rx_wrap_en()
+                            .clear_bit()
+                            .rx_en()
+                            .set_bit()
+                    });
+
+                    // apply configuration updates
+                    unsafe { &*RMT::PTR }.ch_rx_conf1[$idx].modify(|_, w| {
+                        w.conf_update()
+                            .set_bit()
+                    });
+                }
+
+                /// Check if the reception ended
+                fn is_receive_done(&self) -> bool {
+                    unsafe { &*RMT::PTR }.int_raw.read().bits()
+                        & (1 << (RX_END_INT_BIT + $idx) | 1 << (RX_ERR_INT_BIT + $idx))
+                        != 0
+                }
+
+                /// Stop receiving and copy the received sequence into `buffer`
+                fn read_received_raw(&mut self, buffer: &mut [u32]) -> Result<usize, ReceiveError> {
+                    let overflow = unsafe { &*RMT::PTR }.int_raw.read().bits()
+                        & 1 << (RX_ERR_INT_BIT + $idx)
+                        != 0;
+
+                    self.stop_receive();
+
+                    if overflow {
+                        return Err(ReceiveError::Overflow);
+                    }
+
+                    for index in 0..CHANNEL_RAM_SIZE as usize {
+                        let entry = self.channel.read_ram(index);
+
+                        if index >= buffer.len() {
+                            return Err(ReceiveError::BufferTooSmall);
+                        }
+                        buffer[index] = entry;
+
+                        // A length of 0 marks the end of the sequence
+                        if entry & 0x7fff == 0 || (entry >> 16) & 0x7fff == 0 {
+                            return Ok(index + 1);
+                        }
+                    }
+
+                    Err(ReceiveError::Overflow)
+                }
+
+                /// Receive a pulse sequence in a blocking fashion
+                fn receive_pulse_sequence(
+                    &mut self,
+                    buffer: &mut [PulseCode],
+                ) -> Result<usize, ReceiveError> {
+                    let mut raw = [0u32; CHANNEL_RAM_SIZE as usize];
+                    let len = self.receive_pulse_sequence_raw(&mut raw)?;
+
+                    if len > buffer.len() {
+                        return Err(ReceiveError::BufferTooSmall);
+                    }
+
+                    for (code, entry) in buffer.iter_mut().zip(raw[..len].iter()) {
+                        *code = PulseCode::from(*entry);
+                    }
+
+                    Ok(len)
+                }
+
+                /// Receive a raw pulse sequence in a blocking fashion
+                fn receive_pulse_sequence_raw(
+                    &mut self,
+                    buffer: &mut [u32],
+                ) -> Result<usize, ReceiveError> {
+                    self.start_receive();
+
+                    while !self.is_receive_done() {}
+
+                    self.read_received_raw(buffer)
+                }
+
+                /// Stop an ongoing reception
+                fn stop_receive(&mut self) {
+                    unsafe { &*RMT::PTR }.ch_rx_conf1[$idx].modify(|_, w| {
+                        w.rx_en()
+                            .clear_bit()
+                    });
+
+                    // apply configuration updates
+                    unsafe { &*RMT::PTR }.ch_rx_conf1[$idx].modify(|_, w| {
+                        w.conf_update()
+                            .set_bit()
+                    });
+                }
+            }
+
+            impl InputChannel<[<Configured $cxi>]> for $cxi {
+                /// Set channel clock divider value
+                #[inline(always)]
+                fn set_channel_divider(&mut self, divider: u8) -> &mut Self {
+                    unsafe { &*RMT::PTR }
+                        .ch_rx_conf0[$idx]
+                        .modify(|_, w| unsafe { w.div_cnt().bits(divider) });
+                    self
+                }
+
+                /// Set the number of clock cycles without an edge on the
+                /// input after which the reception ends
+                #[inline(always)]
+                fn set_idle_threshold(&mut self, threshold: u16) -> &mut Self {
+                    unsafe { &*RMT::PTR }
+                        .ch_rx_conf0[$idx]
+                        .modify(|_, w| unsafe { w.idle_thres().bits(threshold & 0x7fff) });
+                    self
+                }
+
+                /// Ignore pulses shorter than `threshold` clock cycles
+                #[inline(always)]
+                fn set_filter_threshold(&mut self, threshold: Option<u8>) -> &mut Self {
+                    unsafe { &*RMT::PTR }.ch_rx_conf1[$idx].modify(|_, w| unsafe {
+                        w.rx_filter_en()
+                            .bit(threshold.is_some())
+                            .rx_filter_thres()
+                            .bits(threshold.unwrap_or(0))
+                    });
+                    self
+                }
+
+                /// Assign a pin that this channel should receive from
+                fn assign_pin<RmtPin: InputPin>(
+                    self,
+                    mut pin: RmtPin,
+                ) -> [<Configured $cxi>] {
+                    // Configure Pin as input and connect to signal
+                    pin.set_to_input()
+                        .connect_input_to_peripheral($input_signal);
+
+                    [<Configured $cxi>] {
+                        channel: self,
+                    }
+                }
+            }
+        );
+    };
+}
+
 #[cfg(esp32)]
 macro_rules! conf0 {
     ($channel: literal) => {
@@ -819,6 +1271,12 @@ macro_rules! rmt {
         $(
             ($num:literal, $cxi:ident, $obj_name:ident, $output_signal:path),
         )+
+        $(
+            ;
+            $(
+                ($rx_num:literal, $rx_idx:literal, $rx_cxi:ident, $rx_obj_name:ident, $input_signal:path),
+            )+
+        )?
     )
  => {
     /// RMT peripheral (RMT)
@@ -829,6 +1287,10 @@ macro_rules! rmt {
             /// RMT channel $cxi
             pub $obj_name: $cxi,
         )+
+        $($(
+            /// RMT channel $rx_cxi
+            pub $rx_obj_name: $rx_cxi,
+        )+)?
     }
 
     impl PulseControl {
@@ -848,6 +1310,9 @@ macro_rules! rmt {
                 $(
                     $obj_name: $cxi::new(),
                 )+
+                $($(
+                    $rx_obj_name: $rx_cxi::new(),
+                )+)?
             };
 
             pc.enable_peripheral(peripheral_clock_control);
@@ -868,6 +1333,9 @@ macro_rules! rmt {
                 $(
                     $obj_name: $cxi::new(),
                 )+
+                $($(
+                    $rx_obj_name: $rx_cxi::new(),
+                )+)?
             };
 
             pc.enable_peripheral(peripheral_clock_control);
@@ -1006,6 +1474,9 @@ macro_rules! rmt {
         channel_instance!($num, $cxi, $output_signal);
         output_channel!($num, $cxi, $output_signal);
     )+
+    $($(
+        input_channel!($rx_num, $rx_idx, $rx_cxi, $input_signal);
+    )+)?
  };
 }
 
@@ -1014,6 +1485,9 @@ rmt!(
     sys_conf,
     (0, Channel0, channel0, OutputSignal::RMT_SIG_0),
     (1, Channel1, channel1, OutputSignal::RMT_SIG_1),
+    ;
+    (2, 0, Channel2, channel2, InputSignal::RMT_SIG_0),
+    (3, 1, Channel3, channel3, InputSignal::RMT_SIG_1),
 );
 
 #[cfg(esp32s2)]
@@ -1045,4 +1519,9 @@ rmt!(
     (1, Channel1, channel1, OutputSignal::RMT_SIG_OUT1),
     (2, Channel2, channel2, OutputSignal::RMT_SIG_OUT2),
     (3, Channel3, channel3, OutputSignal::RMT_SIG_OUT3),
+    ;
+    (4, 0, Channel4, channel4, InputSignal::RMT_SIG_IN0),
+    (5, 1, Channel5, channel5, InputSignal::RMT_SIG_IN1),
+    (6, 2, Channel6, channel6, InputSignal::RMT_SIG_IN2),
+    (7, 3, Channel7, channel7, InputSignal::RMT_SIG_IN3),
 );
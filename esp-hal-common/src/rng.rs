@@ -4,7 +4,7 @@ use core::convert::Infallible;
 
 use embedded_hal::blocking::rng::Read;
 
-use crate::pac::RNG;
+use crate::{pac::RNG, system::PeripheralClockControl};
 
 /// Random Number Generator
 ///
@@ -24,7 +24,11 @@ use crate::pac::RNG;
 /// When any of these conditions are true, samples of physical noise are
 /// continuously mixed into the internal hardware RNG state to provide entropy.
 /// If none of the above conditions are true, the output of the RNG should be
-/// considered pseudo-random only.
+/// considered pseudo-random only. [Rng::is_entropy_source_active] can be used
+/// to check whether the RF subsystem is clocked.
+///
+/// With the `rand` feature enabled, the `rand_core::RngCore` and
+/// `rand_core::CryptoRng` traits are implemented.
 ///
 /// For more information, please refer to the ESP-IDF documentation:
 /// <https://docs.espressif.com/projects/esp-idf/en/latest/esp32/api-reference/system/random.html>
@@ -45,6 +49,23 @@ impl Rng {
         self.rng.data.read().bits()
     }
 
+    /// Fills `buffer` with random bytes
+    pub fn read(&mut self, buffer: &mut [u8]) {
+        for chunk in buffer.chunks_mut(4) {
+            let bytes = self.random().to_le_bytes();
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    /// Returns whether the RF subsystem is clocked, i.e. physical noise is
+    /// mixed into the RNG state and the output is truly random
+    ///
+    /// An entropy source enabled by the bootloader can't be detected, in that
+    /// case this returns `false` even though the output is truly random.
+    pub fn is_entropy_source_active(&self) -> bool {
+        PeripheralClockControl::is_radio_clock_enabled()
+    }
+
     /// Return the raw interface to the underlying `Rng` instance
     pub fn free(self) -> RNG {
         self.rng
@@ -55,11 +76,32 @@ impl Read for Rng {
     type Error = Infallible;
 
     fn read(&mut self, buffer: &mut [u8]) -> Result<(), Self::Error> {
-        for chunk in buffer.chunks_mut(4) {
-            let bytes = self.random().to_le_bytes();
-            chunk.copy_from_slice(&bytes[..chunk.len()]);
-        }
+        Rng::read(self, buffer);
 
         Ok(())
     }
 }
+
+#[cfg(feature = "rand")]
+impl rand_core::RngCore for Rng {
+    fn next_u32(&mut self) -> u32 {
+        self.random()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        (self.random() as u64) << 32 | self.random() as u64
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        Rng::read(self, dest);
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand_core::Error> {
+        Rng::read(self, dest);
+
+        Ok(())
+    }
+}
+
+#[cfg(feature = "rand")]
+impl rand_core::CryptoRng for Rng {}
@@ -1,5 +1,7 @@
 use embedded_hal::watchdog::{Watchdog, WatchdogDisable, WatchdogEnable};
 use fugit::{HertzU32, MicrosDurationU64};
+#[cfg(not(esp32))]
+use paste::paste;
 
 #[cfg(not(esp32))]
 use crate::efuse::Efuse;
@@ -8,6 +10,8 @@ use crate::{
     pac::{RTC_CNTL, TIMG0},
     rom::esp_rom_delay_us,
 };
+#[cfg(not(esp32))]
+use crate::{regi2c_write_mask, rom::regi2c_ctrl_write_reg_mask};
 
 #[cfg_attr(esp32, path = "rtc/esp32.rs")]
 #[cfg_attr(esp32c2, path = "rtc/esp32c2.rs")]
@@ -16,10 +20,12 @@ use crate::{
 #[cfg_attr(esp32s3, path = "rtc/esp32s3.rs")]
 mod rtc;
 
+pub mod sleep;
+
 #[allow(unused)]
 #[derive(Debug, Clone, Copy)]
-/// RTC SLOW_CLK frequency values
-pub(crate) enum RtcFastClock {
+/// RTC FAST_CLK frequency values
+pub enum RtcFastClock {
     /// Main XTAL, divided by 4
     RtcFastClockXtalD4 = 0,
     /// Internal fast RC oscillator
@@ -41,7 +47,7 @@ impl Clock for RtcFastClock {
 #[allow(unused)]
 #[derive(Debug, Clone, Copy)]
 /// RTC SLOW_CLK frequency values
-pub(crate) enum RtcSlowClock {
+pub enum RtcSlowClock {
     /// Internal slow RC oscillator
     RtcSlowClockRtc     = 0,
     /// External 32 KHz XTAL
@@ -83,11 +89,23 @@ pub(crate) enum RtcCalSel {
     RtcCalInternalOsc = 3,
 }
 
+/// RTC errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The external 32 kHz crystal doesn't oscillate, e.g. because none is
+    /// connected
+    Xtal32kNotRunning,
+    /// The clock configuration was locked via
+    /// [ClockControl::freeze_locked](crate::clock::ClockControl::freeze_locked)
+    ClocksLocked,
+}
+
 pub struct Rtc {
     _inner: RTC_CNTL,
     pub rwdt: Rwdt,
     #[cfg(any(esp32c2, esp32c3, esp32s3))]
     pub swd: Swd,
+    pub brownout: BrownoutDetector,
 }
 
 impl Rtc {
@@ -100,12 +118,215 @@ impl Rtc {
             rwdt: Rwdt::default(),
             #[cfg(any(esp32c2, esp32c3, esp32s3))]
             swd: Swd::new(),
+            brownout: BrownoutDetector::new(),
         }
     }
 
     pub fn estimate_xtal_frequency(&mut self) -> u32 {
         RtcClock::estimate_xtal_frequency()
     }
+
+    /// Select the source of RTC_SLOW_CLK, which drives the RTC timer and the
+    /// RTC watchdog
+    ///
+    /// The oscillator of the external 32 kHz crystal is powered up for
+    /// [RtcSlowClock::RtcSlowClock32kXtal] (on the ESP32-C2 an external 32 kHz
+    /// clock needs to be supplied instead). If it doesn't start oscillating,
+    /// [Error::Xtal32kNotRunning] is returned and the previous source is kept.
+    pub fn set_slow_clock(&mut self, source: RtcSlowClock) -> Result<(), Error> {
+        if crate::clock::is_locked() {
+            return Err(Error::ClocksLocked);
+        }
+
+        match source {
+            RtcSlowClock::RtcSlowClock8mD256 => RtcClock::enable_8m(true, true),
+            RtcSlowClock::RtcSlowClock32kXtal => {
+                RtcClock::enable_32k();
+
+                // the crystal takes a while to start up, the calibration times
+                // out until it oscillates
+                if !(0..3).any(|_| RtcClock::calibrate(RtcCalSel::RtcCal32kXtal, 3000) != 0) {
+                    return Err(Error::Xtal32kNotRunning);
+                }
+            }
+            RtcSlowClock::RtcSlowClockRtc => {}
+        }
+
+        RtcClock::set_slow_freq(source);
+
+        Ok(())
+    }
+
+    /// The currently selected source of RTC_SLOW_CLK
+    pub fn slow_clock(&self) -> RtcSlowClock {
+        RtcClock::get_slow_freq()
+    }
+
+    /// Select the source of RTC_FAST_CLK
+    pub fn set_fast_clock(&mut self, source: RtcFastClock) -> Result<(), Error> {
+        if crate::clock::is_locked() {
+            return Err(Error::ClocksLocked);
+        }
+
+        RtcClock::set_fast_freq(source);
+
+        Ok(())
+    }
+
+    /// Measure the frequency of RTC_SLOW_CLK against the main XTAL
+    ///
+    /// The nominal frequencies of the internal oscillators vary considerably
+    /// between chips and with temperature, durations in RTC_SLOW_CLK cycles
+    /// (e.g. the sleep timer) are based on this measurement.
+    pub fn estimate_slow_clock_freq(&mut self) -> HertzU32 {
+        let period = RtcClock::calibrate(RtcCalSel::RtcCalRtcMux, 1024) as u64;
+        if period == 0 {
+            return HertzU32::Hz(0);
+        }
+
+        HertzU32::Hz(((1_000_000u64 << RtcClock::CAL_FRACT) / period) as u32)
+    }
+}
+
+/// Cause of the last reset
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ResetReason {
+    /// The chip was powered on
+    PowerOn,
+    /// Software reset of the whole digital system
+    Software,
+    /// Software reset of the CPU
+    SoftwareCpu,
+    /// Wakeup from deep sleep
+    DeepSleep,
+    /// Reset by one of the timer group watchdogs
+    TimerGroupWatchdog,
+    /// Reset by the RTC watchdog
+    RtcWatchdog,
+    /// Reset by the super watchdog
+    SuperWatchdog,
+    /// The supply voltage dropped below the brownout threshold
+    Brownout,
+    /// Reset by the SDIO peripheral
+    #[cfg(esp32)]
+    Sdio,
+    /// Reset by the other core
+    #[cfg(esp32)]
+    OtherCpu,
+    /// A glitch on the clock was detected
+    #[cfg(not(esp32))]
+    ClockGlitch,
+    /// The eFuse CRC check failed
+    #[cfg(not(esp32))]
+    EfuseCrc,
+    /// Reset by the USB Serial/JTAG controller, e.g. by a host tool
+    #[cfg(not(esp32))]
+    UsbJtag,
+    /// A glitch on the power supply was detected
+    #[cfg(not(esp32))]
+    PowerGlitch,
+    /// A reset cause not covered by the other variants, the raw value of the
+    /// reset cause field
+    Other(u8),
+}
+
+impl From<u8> for ResetReason {
+    #[cfg(esp32)]
+    fn from(value: u8) -> Self {
+        match value {
+            1 => ResetReason::PowerOn,
+            3 => ResetReason::Software,
+            5 => ResetReason::DeepSleep,
+            6 => ResetReason::Sdio,
+            4 | 7 | 8 | 11 => ResetReason::TimerGroupWatchdog,
+            9 | 13 | 16 => ResetReason::RtcWatchdog,
+            12 => ResetReason::SoftwareCpu,
+            14 => ResetReason::OtherCpu,
+            15 => ResetReason::Brownout,
+            other => ResetReason::Other(other),
+        }
+    }
+
+    #[cfg(not(esp32))]
+    fn from(value: u8) -> Self {
+        match value {
+            0x01 => ResetReason::PowerOn,
+            0x03 => ResetReason::Software,
+            0x05 => ResetReason::DeepSleep,
+            0x07 | 0x08 | 0x0b | 0x11 => ResetReason::TimerGroupWatchdog,
+            0x09 | 0x0d | 0x10 => ResetReason::RtcWatchdog,
+            0x0c => ResetReason::SoftwareCpu,
+            0x0f => ResetReason::Brownout,
+            0x12 => ResetReason::SuperWatchdog,
+            0x13 => ResetReason::ClockGlitch,
+            0x14 => ResetReason::EfuseCrc,
+            0x16 => ResetReason::UsbJtag,
+            0x17 => ResetReason::PowerGlitch,
+            other => ResetReason::Other(other),
+        }
+    }
+}
+
+/// Cause of the last reset of the given core
+#[cfg(multi_core)]
+pub fn reset_reason(cpu: crate::Cpu) -> ResetReason {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+    let reset_state = rtc_cntl.reset_state.read();
+
+    let cause = match cpu {
+        crate::Cpu::ProCpu => reset_state.reset_cause_procpu().bits(),
+        crate::Cpu::AppCpu => reset_state.reset_cause_appcpu().bits(),
+    };
+
+    cause.into()
+}
+
+/// Cause of the last reset
+#[cfg(single_core)]
+pub fn reset_reason() -> ResetReason {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    rtc_cntl
+        .reset_state
+        .read()
+        .reset_cause_procpu()
+        .bits()
+        .into()
+}
+
+/// Reset the whole chip
+///
+/// The reset cause reported by [reset_reason] afterwards is
+/// [ResetReason::Software]. The bootloader runs again, e.g. to boot an
+/// image which was just written by an OTA update:
+///
+/// ```rust,ignore
+/// // the new image was written and the OTA data partition updated
+/// system::software_reset();
+/// ```
+pub fn software_reset() -> ! {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+    rtc_cntl.options0.modify(|_, w| w.sw_sys_rst().set_bit());
+
+    loop {}
+}
+
+/// Reset the current core only
+///
+/// Peripherals and the other core (if any) keep their state. The reset cause
+/// reported by [reset_reason] afterwards is [ResetReason::SoftwareCpu].
+pub fn software_reset_cpu() -> ! {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    match crate::get_core() {
+        crate::Cpu::ProCpu => rtc_cntl.options0.modify(|_, w| w.sw_procpu_rst().set_bit()),
+        #[cfg(multi_core)]
+        crate::Cpu::AppCpu => rtc_cntl.options0.modify(|_, w| w.sw_appcpu_rst().set_bit()),
+        #[cfg(single_core)]
+        crate::Cpu::AppCpu => unreachable!(),
+    }
+
+    loop {}
 }
 
 /// RTC Watchdog Timer
@@ -152,6 +373,63 @@ impl RtcClock {
         }
     }
 
+    /// Power up the oscillator of the external 32 kHz crystal, with the same
+    /// settings as ESP-IDF's `rtc_clk_32k_enable`
+    fn enable_32k() {
+        #[cfg(esp32)]
+        {
+            let rtcio = unsafe { &*crate::pac::RTCIO::PTR };
+
+            // route the pads to the oscillator and remove their pulls
+            rtcio.xtal_32k_pad.modify(|_, w| unsafe {
+                w.x32n_mux_sel()
+                    .set_bit()
+                    .x32p_mux_sel()
+                    .set_bit()
+                    .x32n_rde()
+                    .clear_bit()
+                    .x32n_rue()
+                    .clear_bit()
+                    .x32p_rde()
+                    .clear_bit()
+                    .x32p_rue()
+                    .clear_bit()
+                    .x32n_fun_ie()
+                    .clear_bit()
+                    .x32p_fun_ie()
+                    .clear_bit()
+                    .dac_xtal_32k()
+                    .bits(1)
+                    .dres_xtal_32k()
+                    .bits(3)
+                    .dbias_xtal_32k()
+                    .bits(0)
+                    .xpd_xtal_32k()
+                    .set_bit()
+            });
+        }
+
+        #[cfg(any(esp32c3, esp32s2, esp32s3))]
+        {
+            let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+            rtc_cntl.ext_xtl_conf.modify(|_, w| unsafe {
+                w.dac_xtal_32k()
+                    .bits(3)
+                    .dres_xtal_32k()
+                    .bits(3)
+                    .dgm_xtal_32k()
+                    .bits(3)
+                    .dbuf_xtal_32k()
+                    .set_bit()
+                    .xtal32k_xpd_force()
+                    .clear_bit()
+                    .xpd_xtal_32k()
+                    .set_bit()
+            });
+        }
+    }
+
     /// Get main XTAL frequency
     /// This is the value stored in RTC register RTC_XTAL_FREQ_REG by the
     /// bootloader, as passed to rtc_clk_init function.
@@ -452,7 +730,7 @@ impl RtcClock {
 /// Behavior of the RWDT stage if it times out
 #[allow(unused)]
 #[derive(Debug, Clone, Copy)]
-enum RwdtStageAction {
+pub enum RwdtStageAction {
     RwdtStageActionOff         = 0,
     RwdtStageActionInterrupt   = 1,
     RwdtStageActionResetCpu    = 2,
@@ -460,6 +738,18 @@ enum RwdtStageAction {
     RwdtStageActionResetRtc    = 4,
 }
 
+/// RWDT stages
+///
+/// Once the watchdog isn't fed in time, the stages run one after the other,
+/// each performing its action when its timeout expired.
+#[derive(Debug, Clone, Copy)]
+pub enum RwdtStage {
+    Stage0,
+    Stage1,
+    Stage2,
+    Stage3,
+}
+
 /// RTC Watchdog Timer
 pub struct Rwdt {
     stg0_action: RwdtStageAction,
@@ -550,6 +840,55 @@ impl Rwdt {
         }
     }
 
+    /// Set the action of a stage, takes effect the next time the watchdog is
+    /// started
+    ///
+    /// E.g. an interrupt in stage 0 followed by a reset in stage 1 allows
+    /// logging the problem before the reset.
+    pub fn set_stage_action(&mut self, stage: RwdtStage, action: RwdtStageAction) {
+        match stage {
+            RwdtStage::Stage0 => self.stg0_action = action,
+            RwdtStage::Stage1 => self.stg1_action = action,
+            RwdtStage::Stage2 => self.stg2_action = action,
+            RwdtStage::Stage3 => self.stg3_action = action,
+        }
+    }
+
+    /// Set the timeout of a stage
+    ///
+    /// The timeout of stage 0 is also set by starting the watchdog.
+    pub fn set_stage_timeout(&mut self, stage: RwdtStage, timeout: MicrosDurationU64) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+        let timeout_raw = (timeout.to_millis() * (RtcClock::cycles_to_1ms() as u64)) as u32;
+
+        self.set_write_protection(false);
+
+        unsafe {
+            match stage {
+                #[cfg(esp32)]
+                RwdtStage::Stage0 => rtc_cntl
+                    .wdtconfig1
+                    .modify(|_, w| w.wdt_stg0_hold().bits(timeout_raw)),
+                #[cfg(not(esp32))]
+                RwdtStage::Stage0 => rtc_cntl.wdtconfig1.modify(|_, w| {
+                    w.wdt_stg0_hold()
+                        .bits(timeout_raw >> (1 + Efuse::get_rwdt_multiplier()))
+                }),
+                RwdtStage::Stage1 => rtc_cntl
+                    .wdtconfig2
+                    .modify(|_, w| w.wdt_stg1_hold().bits(timeout_raw)),
+                RwdtStage::Stage2 => rtc_cntl
+                    .wdtconfig3
+                    .modify(|_, w| w.wdt_stg2_hold().bits(timeout_raw)),
+                RwdtStage::Stage3 => rtc_cntl
+                    .wdtconfig4
+                    .modify(|_, w| w.wdt_stg3_hold().bits(timeout_raw)),
+            }
+        }
+
+        self.set_write_protection(true);
+    }
+
     /// Enable/disable write protection for WDT registers
     fn set_write_protection(&mut self, enable: bool) {
         let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
@@ -665,3 +1004,210 @@ impl WatchdogDisable for Swd {
         self.set_write_protection(true);
     }
 }
+
+#[cfg(not(esp32))]
+const I2C_BOD: u32 = 0x61;
+#[cfg(not(esp32))]
+const I2C_BOD_HOSTID: u32 = 1;
+
+#[cfg(not(esp32))]
+const I2C_BOD_THRESHOLD: u32 = 0x5;
+#[cfg(not(esp32))]
+const I2C_BOD_THRESHOLD_MSB: u32 = 2;
+#[cfg(not(esp32))]
+const I2C_BOD_THRESHOLD_LSB: u32 = 0;
+
+// Fields of the `BROWN_OUT` register
+const BROWN_OUT_INT_WAIT_SHIFT: u32 = 4;
+const BROWN_OUT_PD_RF_ENA: u32 = 1 << 15;
+const BROWN_OUT_RST_WAIT_SHIFT: u32 = 16;
+const BROWN_OUT_RST_ENA: u32 = 1 << 26;
+#[cfg(esp32)]
+const BROWN_OUT_THRES_SHIFT: u32 = 27;
+/// Reset the whole chip rather than only the digital system
+#[cfg(not(esp32))]
+const BROWN_OUT_RST_SEL: u32 = 1 << 27;
+#[cfg(not(esp32))]
+const BROWN_OUT_CNT_CLR: u32 = 1 << 29;
+const BROWN_OUT_ENA: u32 = 1 << 30;
+const BROWN_OUT_DET: u32 = 1 << 31;
+
+/// Threshold of the brownout detector
+///
+/// The voltages differ between the chips, see the datasheet. On the ESP32
+/// [BrownoutLevel::Level0] is the lowest threshold (about 2.43 V) and
+/// [BrownoutLevel::Level7] the highest (about 2.80 V), on the other chips it's
+/// the other way round, with [BrownoutLevel::Level7] at about 2.51 V.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BrownoutLevel {
+    Level0 = 0,
+    Level1 = 1,
+    Level2 = 2,
+    Level3 = 3,
+    Level4 = 4,
+    Level5 = 5,
+    Level6 = 6,
+    Level7 = 7,
+}
+
+/// Brownout detector
+///
+/// Watches the supply voltage and raises the RTC_CORE interrupt and/or resets
+/// the chip once it drops below the threshold. After such a reset
+/// [reset_reason] reports [ResetReason::Brownout].
+///
+/// The interrupt leaves some time to save critical state before the voltage
+/// gets too low to keep running:
+///
+/// ```rust,ignore
+/// rtc.brownout.set_threshold(BrownoutLevel::Level7);
+/// rtc.brownout.set_reset(false);
+/// rtc.brownout.listen();
+/// rtc.brownout.enable();
+///
+/// system
+///     .interrupt_controller
+///     .enable(pac::Interrupt::RTC_CORE, interrupt::Priority::Priority1)
+///     .unwrap();
+/// ```
+pub struct BrownoutDetector {
+    reset: bool,
+}
+
+impl BrownoutDetector {
+    fn new() -> Self {
+        Self { reset: true }
+    }
+
+    /// Set the threshold voltage
+    pub fn set_threshold(&mut self, level: BrownoutLevel) {
+        #[cfg(esp32)]
+        {
+            let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+            rtc_cntl.brown_out.modify(|r, w| unsafe {
+                w.bits(
+                    r.bits() & !(0x7 << BROWN_OUT_THRES_SHIFT)
+                        | (level as u32) << BROWN_OUT_THRES_SHIFT,
+                )
+            });
+        }
+
+        #[cfg(not(esp32))]
+        unsafe {
+            regi2c_write_mask!(I2C_BOD, I2C_BOD_THRESHOLD, level as u32);
+        }
+    }
+
+    /// Whether a brownout resets the chip, takes effect the next time the
+    /// detector is enabled
+    ///
+    /// The reset is enabled by default. Without it only the interrupt can
+    /// react to a brownout, see [BrownoutDetector::listen].
+    pub fn set_reset(&mut self, reset: bool) {
+        self.reset = reset;
+    }
+
+    /// Start watching the supply voltage
+    ///
+    /// The RF circuits are powered down on a brownout to reduce the load on
+    /// the supply.
+    pub fn enable(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        // Wait 2 slow clock cycles before the interrupt and the maximum of
+        // 1023 cycles before the reset, the same as ESP-IDF
+        let mut bits = BROWN_OUT_ENA
+            | BROWN_OUT_PD_RF_ENA
+            | 2 << BROWN_OUT_INT_WAIT_SHIFT
+            | 0x3ff << BROWN_OUT_RST_WAIT_SHIFT;
+        if self.reset {
+            bits |= BROWN_OUT_RST_ENA;
+            #[cfg(not(esp32))]
+            {
+                bits |= BROWN_OUT_RST_SEL;
+            }
+        }
+
+        #[cfg(esp32)]
+        rtc_cntl
+            .brown_out
+            .modify(|r, w| unsafe { w.bits(r.bits() & (0x7 << BROWN_OUT_THRES_SHIFT) | bits) });
+
+        #[cfg(not(esp32))]
+        {
+            rtc_cntl
+                .brown_out
+                .write(|w| unsafe { w.bits(bits | BROWN_OUT_CNT_CLR) });
+            rtc_cntl.brown_out.write(|w| unsafe { w.bits(bits) });
+        }
+    }
+
+    /// Stop watching the supply voltage
+    pub fn disable(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        rtc_cntl
+            .brown_out
+            .modify(|r, w| unsafe { w.bits(r.bits() & !(BROWN_OUT_ENA | BROWN_OUT_RST_ENA)) });
+    }
+
+    /// Whether the supply voltage is currently below the threshold
+    pub fn is_brownout(&self) -> bool {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        rtc_cntl.brown_out.read().bits() & BROWN_OUT_DET != 0
+    }
+
+    /// Raise the RTC_CORE interrupt on a brownout
+    pub fn listen(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        #[cfg(esp32)]
+        rtc_cntl
+            .int_ena
+            .modify(|_, w| w.brown_out_int_ena().set_bit());
+        #[cfg(not(esp32))]
+        rtc_cntl
+            .int_ena_rtc
+            .modify(|_, w| w.brown_out_int_ena().set_bit());
+    }
+
+    /// Stop raising the RTC_CORE interrupt on a brownout
+    pub fn unlisten(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        #[cfg(esp32)]
+        rtc_cntl
+            .int_ena
+            .modify(|_, w| w.brown_out_int_ena().clear_bit());
+        #[cfg(not(esp32))]
+        rtc_cntl
+            .int_ena_rtc
+            .modify(|_, w| w.brown_out_int_ena().clear_bit());
+    }
+
+    /// Clear the brownout interrupt
+    pub fn clear_interrupt(&mut self) {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        #[cfg(esp32)]
+        rtc_cntl.int_clr.write(|w| w.brown_out_int_clr().set_bit());
+        #[cfg(not(esp32))]
+        rtc_cntl
+            .int_clr_rtc
+            .write(|w| w.brown_out_int_clr().set_bit());
+    }
+
+    /// Whether the brownout interrupt is pending
+    pub fn is_interrupt_set(&self) -> bool {
+        let rtc_cntl = unsafe { &*RTC_CNTL::ptr() };
+
+        cfg_if::cfg_if! {
+            if #[cfg(esp32)] {
+                rtc_cntl.int_st.read().brown_out_int_st().bit_is_set()
+            } else {
+                rtc_cntl.int_st_rtc.read().brown_out_int_st().bit_is_set()
+            }
+        }
+    }
+}
@@ -0,0 +1,486 @@
+//! Light and deep sleep
+//!
+//! In light sleep the CPU is stopped while the digital peripherals and the
+//! RAM keep their state. Execution continues after the call to
+//! [light_sleep] once one of the wake sources triggered.
+//!
+//! In deep sleep the digital domain is powered down, waking up resets the
+//! chip and the application starts from the beginning, with
+//! [ResetReason::DeepSleep](super::ResetReason::DeepSleep) as the reset
+//! reason and [wakeup_cause] telling which source triggered. Only the RTC
+//! domain survives: the RTC timer, the RTC IOs and the RTC memories. State
+//! which needs to survive can be placed in RTC memory with the `ram`
+//! attribute:
+//!
+//! - `#[ram(rtc_fast)]` / `#[ram(rtc_slow)]`: initialized on every boot,
+//!   including deep sleep wakeups, so the value does *not* survive.
+//! - `#[ram(rtc_fast, zeroed)]`: zeroed on every boot, likewise.
+//! - `#[ram(rtc_fast, uninitialized)]`: never initialized, the value survives
+//!   deep sleep and resets, but contains garbage after power-on.
+//!
+//! [RtcRam] wraps the latter case: it tells a cold boot apart from retained
+//! contents, so a value can be read safely after waking up:
+//!
+//! ```rust,ignore
+//! #[ram(rtc_fast, uninitialized)]
+//! static BOOT_COUNT: RtcRam<u32> = RtcRam::new();
+//!
+//! let count = BOOT_COUNT.get().unwrap_or(0) + 1;
+//! BOOT_COUNT.set(count);
+//! ```
+//!
+//! The RTC slow memory is not available on the ESP32-C2 and ESP32-C3. On the
+//! ESP32 the RTC fast memory can only be accessed by the PRO CPU.
+//!
+//! Output levels are lost when the digital domain powers down. RTC IOs can
+//! latch their state with `hold_enable()` before entering deep sleep, e.g. to
+//! keep an enable line asserted.
+
+use core::{cell::UnsafeCell, convert::Infallible, mem::MaybeUninit};
+
+use fugit::MicrosDurationU64;
+
+use super::{Error, Rtc, RtcCalSel, RtcClock, RtcSlowClock, RTC_CNTL};
+use crate::clock::{Clock, Clocks};
+#[cfg(any(esp32, esp32s2, esp32s3))]
+use crate::gpio::RTCPin;
+
+// Bits of the wake sources in the `wakeup_ena` and `wakeup_cause` fields
+#[cfg(any(esp32, esp32s2, esp32s3))]
+const EXT0_TRIG_EN: u32 = 1 << 0;
+#[cfg(any(esp32, esp32s2, esp32s3))]
+const EXT1_TRIG_EN: u32 = 1 << 1;
+const TIMER_TRIG_EN: u32 = 1 << 3;
+
+/// Sources which end the sleep
+#[derive(Debug, Clone, Copy)]
+pub enum WakeSource {
+    /// Wake up after the given time
+    Timer(MicrosDurationU64),
+    /// Wake up when the RTC IO with the given number has the given level
+    ///
+    /// The pin needs to be configured as an RTC IO input, which
+    /// [WakeSource::ext0] takes care of. Keeps the RTC peripherals powered in
+    /// deep sleep.
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Ext0 { rtc_pin: u8, level: bool },
+    /// Wake up on the level of several RTC IOs, bit `n` of `rtc_pins`
+    /// selects RTC IO `n`
+    ///
+    /// With `level` set the chip wakes up if any of the pins is high.
+    /// Otherwise it wakes up if all pins are low (ESP32) or any pin is low
+    /// (ESP32-S2/S3). See [WakeSource::ext1] for building this from pins.
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Ext1 { rtc_pins: u32, level: bool },
+}
+
+#[cfg(any(esp32, esp32s2, esp32s3))]
+impl WakeSource {
+    /// Wake up when `pin` has the given level
+    ///
+    /// Routes the pin through the RTC IO mux as an input.
+    pub fn ext0<P: RTCPin>(pin: &mut P, level: bool) -> Self {
+        pin.rtc_set_config(true, true, 0);
+
+        WakeSource::Ext0 {
+            rtc_pin: pin.rtc_number(),
+            level,
+        }
+    }
+
+    /// Wake up on the level of `pins`, see [WakeSource::Ext1]
+    ///
+    /// Routes the pins through the RTC IO mux as inputs.
+    pub fn ext1(pins: &mut [&mut dyn RTCPin], level: bool) -> Self {
+        let mut rtc_pins = 0;
+
+        for pin in pins.iter_mut() {
+            pin.rtc_set_config(true, true, 0);
+            rtc_pins |= 1 << pin.rtc_number();
+        }
+
+        WakeSource::Ext1 { rtc_pins, level }
+    }
+}
+
+/// Reason the chip woke up from sleep
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum WakeReason {
+    /// The timer expired
+    Timer,
+    /// The EXT0 pin reached its level
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Ext0,
+    /// The EXT1 pins reached their level
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Ext1,
+    /// The sleep was rejected because a wake source was already pending
+    Rejected,
+    /// Another source woke the chip up, the raw value of the wakeup cause
+    /// field
+    Other(u32),
+}
+
+/// Value in RTC memory which survives deep sleep and resets
+///
+/// Place it in uninitialized RTC memory, see the
+/// [module level documentation](self). A marker stored next to the value tells
+/// whether it was written before: after power-on [RtcRam::get] returns `None`
+/// (unless the random memory contents happen to match the 32 bit marker).
+/// The marker doesn't protect against a firmware update changing the type or
+/// location of the value, so `T` is limited to types for which any bit pattern
+/// is valid, see [AnyBitPattern].
+#[repr(C)]
+pub struct RtcRam<T> {
+    marker: UnsafeCell<u32>,
+    value: UnsafeCell<MaybeUninit<T>>,
+}
+
+/// Marker identifying initialized contents, combined with the size of the
+/// value
+const RTC_RAM_MARKER: u32 = 0x5254_4352;
+
+/// Types for which any bit pattern is a valid value
+///
+/// Implemented for the integer and floating point types and arrays of them.
+///
+/// # Safety
+///
+/// Every bit pattern of `size_of::<Self>()` bytes needs to be a valid value
+/// of the type, i.e. it can't contain padding, references, `bool`s, `char`s or
+/// enums.
+pub unsafe trait AnyBitPattern: Copy {}
+
+macro_rules! impl_any_bit_pattern {
+    ($($ty:ty),*) => {
+        $(
+            unsafe impl AnyBitPattern for $ty {}
+        )*
+    };
+}
+
+impl_any_bit_pattern!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize, f32, f64);
+
+unsafe impl<T: AnyBitPattern, const N: usize> AnyBitPattern for [T; N] {}
+
+unsafe impl<T: Send> Sync for RtcRam<T> {}
+
+impl<T: AnyBitPattern> RtcRam<T> {
+    /// Create an empty instance
+    pub const fn new() -> Self {
+        Self {
+            marker: UnsafeCell::new(0),
+            value: UnsafeCell::new(MaybeUninit::uninit()),
+        }
+    }
+
+    /// Return the stored value, or `None` if none was stored since power-on
+    pub fn get(&self) -> Option<T> {
+        critical_section::with(|_| unsafe {
+            if self.marker.get().read_volatile() != Self::marker() {
+                return None;
+            }
+
+            // the contents might be left over from another firmware, which is
+            // fine as any bit pattern is a valid `T`
+            Some(self.value.get().read_volatile().assume_init())
+        })
+    }
+
+    /// Store `value`
+    pub fn set(&self, value: T) {
+        critical_section::with(|_| unsafe {
+            self.value.get().write_volatile(MaybeUninit::new(value));
+            self.marker.get().write_volatile(Self::marker());
+        });
+    }
+
+    /// Forget the stored value, [RtcRam::get] returns `None` afterwards
+    pub fn clear(&self) {
+        critical_section::with(|_| unsafe {
+            self.marker.get().write_volatile(0);
+        });
+    }
+
+    fn marker() -> u32 {
+        RTC_RAM_MARKER ^ core::mem::size_of::<T>() as u32
+    }
+}
+
+impl<T: AnyBitPattern> Default for RtcRam<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Enter light sleep until one of `wake_sources` triggers
+///
+/// The PLL and the crystal are kept powered during the sleep, so the clock
+/// configuration of `clocks` is still in effect after waking up. This draws
+/// more current than powering them down but avoids reconfiguring the clocks.
+///
+/// Returns [Error::ClocksLocked] if the clock configuration was locked.
+pub fn light_sleep(
+    _rtc: &mut Rtc,
+    _clocks: &Clocks,
+    wake_sources: &[WakeSource],
+) -> Result<WakeReason, Error> {
+    if crate::clock::is_locked() {
+        return Err(Error::ClocksLocked);
+    }
+
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    let wakeup_ena = configure_wake_sources(wake_sources);
+
+    rtc_cntl.options0.modify(|_, w| {
+        w.bbpll_force_pu()
+            .set_bit()
+            .bbpll_i2c_force_pu()
+            .set_bit()
+            .bb_i2c_force_pu()
+            .set_bit()
+            .xtl_force_pu()
+            .set_bit()
+    });
+
+    rtc_cntl
+        .wakeup_state
+        .modify(|_, w| unsafe { w.wakeup_ena().bits(wakeup_ena as _) });
+
+    #[cfg(esp32)]
+    let (int_raw, int_clr) = (&rtc_cntl.int_raw, &rtc_cntl.int_clr);
+    #[cfg(not(esp32))]
+    let (int_raw, int_clr) = (&rtc_cntl.int_raw_rtc, &rtc_cntl.int_clr_rtc);
+
+    int_clr.write(|w| {
+        w.slp_reject_int_clr()
+            .set_bit()
+            .slp_wakeup_int_clr()
+            .set_bit()
+    });
+
+    rtc_cntl.state0.modify(|_, w| w.sleep_en().set_bit());
+
+    let rejected = loop {
+        let raw = int_raw.read();
+        if raw.slp_wakeup_int_raw().bit_is_set() {
+            break false;
+        }
+        if raw.slp_reject_int_raw().bit_is_set() {
+            break true;
+        }
+    };
+
+    int_clr.write(|w| {
+        w.slp_reject_int_clr()
+            .set_bit()
+            .slp_wakeup_int_clr()
+            .set_bit()
+    });
+
+    rtc_cntl.state0.modify(|_, w| w.sleep_en().clear_bit());
+    rtc_cntl
+        .slp_timer1
+        .modify(|_, w| w.main_timer_alarm_en().clear_bit());
+
+    rtc_cntl.options0.modify(|_, w| {
+        w.bbpll_force_pu()
+            .clear_bit()
+            .bbpll_i2c_force_pu()
+            .clear_bit()
+            .bb_i2c_force_pu()
+            .clear_bit()
+            .xtl_force_pu()
+            .clear_bit()
+    });
+
+    if rejected {
+        return Ok(WakeReason::Rejected);
+    }
+
+    Ok(wakeup_cause())
+}
+
+/// Enter deep sleep until one of `wake_sources` triggers
+///
+/// The digital domain is powered down, the chip resets when waking up. See the
+/// [module level documentation](self) for the state which survives.
+///
+/// Only returns if the clock configuration was locked, with
+/// [Error::ClocksLocked].
+pub fn deep_sleep(_rtc: &mut Rtc, wake_sources: &[WakeSource]) -> Result<Infallible, Error> {
+    if crate::clock::is_locked() {
+        return Err(Error::ClocksLocked);
+    }
+
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    let wakeup_ena = configure_wake_sources(wake_sources);
+
+    // keep the RTC memories powered, power down the digital domain
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    rtc_cntl
+        .pwc
+        .modify(|_, w| w.fastmem_pd_en().clear_bit().slowmem_pd_en().clear_bit());
+    #[cfg(not(any(esp32, esp32s2, esp32s3)))]
+    rtc_cntl.pwc.modify(|_, w| w.fastmem_pd_en().clear_bit());
+    rtc_cntl.dig_pwc.modify(|_, w| w.dg_wrap_pd_en().set_bit());
+
+    rtc_cntl
+        .wakeup_state
+        .modify(|_, w| unsafe { w.wakeup_ena().bits(wakeup_ena as _) });
+
+    #[cfg(esp32)]
+    rtc_cntl.int_clr.write(|w| {
+        w.slp_reject_int_clr()
+            .set_bit()
+            .slp_wakeup_int_clr()
+            .set_bit()
+    });
+    #[cfg(not(esp32))]
+    rtc_cntl.int_clr_rtc.write(|w| {
+        w.slp_reject_int_clr()
+            .set_bit()
+            .slp_wakeup_int_clr()
+            .set_bit()
+    });
+
+    rtc_cntl.state0.modify(|_, w| w.sleep_en().set_bit());
+
+    loop {}
+}
+
+/// Source which ended the last sleep
+///
+/// After waking up from deep sleep this tells which source triggered.
+pub fn wakeup_cause() -> WakeReason {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    #[cfg(esp32)]
+    let cause = rtc_cntl.wakeup_state.read().wakeup_cause().bits() as u32;
+    #[cfg(not(esp32))]
+    let cause = rtc_cntl.slp_wakeup_cause.read().wakeup_cause().bits() as u32;
+
+    if cause & TIMER_TRIG_EN != 0 {
+        return WakeReason::Timer;
+    }
+
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    if cause & EXT0_TRIG_EN != 0 {
+        return WakeReason::Ext0;
+    }
+
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    if cause & EXT1_TRIG_EN != 0 {
+        return WakeReason::Ext1;
+    }
+
+    WakeReason::Other(cause)
+}
+
+/// Set up the given wake sources, returns the value for the `wakeup_ena` field
+fn configure_wake_sources(wake_sources: &[WakeSource]) -> u32 {
+    let mut wakeup_ena = 0;
+
+    for source in wake_sources {
+        match source {
+            WakeSource::Timer(duration) => {
+                set_sleep_timer(*duration);
+                wakeup_ena |= TIMER_TRIG_EN;
+            }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            WakeSource::Ext0 { rtc_pin, level } => {
+                let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+                let rtcio = unsafe { &*crate::pac::RTCIO::PTR };
+
+                rtcio
+                    .ext_wakeup0
+                    .modify(|_, w| unsafe { w.sel().bits(*rtc_pin) });
+                rtc_cntl
+                    .ext_wakeup_conf
+                    .modify(|_, w| w.ext_wakeup0_lv().bit(*level));
+
+                // the RTC IOs need to stay powered to detect the level
+                rtc_cntl.pwc.modify(|_, w| w.pd_en().clear_bit());
+
+                wakeup_ena |= EXT0_TRIG_EN;
+            }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            WakeSource::Ext1 { rtc_pins, level } => {
+                let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+                rtc_cntl
+                    .ext_wakeup1
+                    .modify(|_, w| unsafe { w.sel().bits(*rtc_pins) });
+                rtc_cntl
+                    .ext_wakeup_conf
+                    .modify(|_, w| w.ext_wakeup1_lv().bit(*level));
+
+                wakeup_ena |= EXT1_TRIG_EN;
+            }
+        }
+    }
+
+    wakeup_ena
+}
+
+/// Current value of the RTC timer in RTC_SLOW_CLK cycles
+fn rtc_time() -> u64 {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    #[cfg(esp32)]
+    {
+        rtc_cntl.time_update.write(|w| w.time_update().set_bit());
+        while rtc_cntl.time_update.read().time_valid().bit_is_clear() {}
+
+        let low = rtc_cntl.time0.read().bits() as u64;
+        let high = rtc_cntl.time1.read().bits() as u64;
+
+        (high << 32) | low
+    }
+
+    #[cfg(not(esp32))]
+    {
+        rtc_cntl.time_update.write(|w| w.time_update().set_bit());
+
+        let low = rtc_cntl.time_low0.read().bits() as u64;
+        let high = rtc_cntl.time_high0.read().bits() as u64;
+
+        (high << 32) | low
+    }
+}
+
+/// Arm the RTC timer to expire `duration` from now
+fn set_sleep_timer(duration: MicrosDurationU64) {
+    let rtc_cntl = unsafe { &*RTC_CNTL::PTR };
+
+    // period of RTC_SLOW_CLK in microseconds, 13Q19 fixed point
+    let slow_clock = RtcClock::get_slow_freq();
+    let mut period = RtcClock::calibrate(
+        match slow_clock {
+            RtcSlowClock::RtcSlowClockRtc => RtcCalSel::RtcCalRtcMux,
+            RtcSlowClock::RtcSlowClock32kXtal => RtcCalSel::RtcCal32kXtal,
+            RtcSlowClock::RtcSlowClock8mD256 => RtcCalSel::RtcCal8mD256,
+        },
+        1024,
+    ) as u64;
+
+    // the calibration timed out, fall back to the nominal frequency
+    if period == 0 {
+        period = (1_000_000u64 << RtcClock::CAL_FRACT) / slow_clock.frequency().raw() as u64;
+    }
+
+    let ticks = (duration.to_micros() << RtcClock::CAL_FRACT) / period;
+    let target = rtc_time() + ticks;
+
+    rtc_cntl
+        .slp_timer0
+        .write(|w| unsafe { w.bits((target & u32::MAX as u64) as u32) });
+    rtc_cntl.slp_timer1.write(|w| unsafe {
+        w.slp_val_hi()
+            .bits(((target >> 32) & 0xffff) as u16)
+            .main_timer_alarm_en()
+            .set_bit()
+    });
+}
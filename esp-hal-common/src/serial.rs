@@ -21,6 +21,22 @@ const UART_FIFO_SIZE: u16 = 128;
 #[derive(Debug)]
 pub enum Error {}
 
+/// UART interrupt sources
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum UartEvent {
+    /// The RX-FIFO holds more bytes than the threshold set by
+    /// [Serial::set_rx_fifo_full_threshold]
+    RxFifoFull,
+    /// The configured AT-CMD character sequence was detected, see
+    /// [Serial::set_at_cmd]
+    AtCmd,
+    /// The RX line was idle for the time set by [Serial::set_rx_timeout]
+    /// while the RX-FIFO holds data
+    RxTimeout,
+    /// All data was transmitted
+    TxDone,
+}
+
 /// UART configuration
 pub mod config {
     /// Number of data bits
@@ -51,13 +67,39 @@ pub mod config {
         STOP2   = 3,
     }
 
+    /// Hardware flow control
+    ///
+    /// The RTS and CTS pins need to be assigned via [AllPins](super::AllPins).
+    #[derive(PartialEq, Eq, Copy, Clone, Debug)]
+    pub enum FlowControl {
+        /// No flow control
+        None,
+        /// RTS is deasserted while the RX-FIFO holds at least `threshold`
+        /// bytes
+        Rts { threshold: u16 },
+        /// The transmitter pauses while CTS is deasserted
+        Cts,
+        /// Both RTS and CTS flow control
+        RtsCts { threshold: u16 },
+    }
+
     /// UART configuration
+    ///
+    /// Fields might be added in the future, build the configuration from
+    /// [Config::default] with the builder functions or `..Default::default()`:
+    ///
+    /// ```rust,ignore
+    /// let config = Config::default()
+    ///     .baudrate(9600)
+    ///     .flow_control(FlowControl::RtsCts { threshold: 64 });
+    /// ```
     #[derive(Debug, Copy, Clone)]
     pub struct Config {
         pub baudrate: u32,
         pub data_bits: DataBits,
         pub parity: Parity,
         pub stop_bits: StopBits,
+        pub flow_control: FlowControl,
     }
 
     impl Config {
@@ -90,6 +132,11 @@ pub mod config {
             self.stop_bits = stop_bits;
             self
         }
+
+        pub fn flow_control(mut self, flow_control: FlowControl) -> Self {
+            self.flow_control = flow_control;
+            self
+        }
     }
 
     impl Default for Config {
@@ -99,6 +146,7 @@ pub mod config {
                 data_bits: DataBits::DataBits8,
                 parity: Parity::ParityNone,
                 stop_bits: StopBits::STOP1,
+                flow_control: FlowControl::None,
             }
         }
     }
@@ -232,6 +280,13 @@ impl embedded_hal_1::serial::Error for Error {
     }
 }
 
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Error for Error {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::Other
+    }
+}
+
 /// UART driver
 pub struct Serial<T> {
     uart: T,
@@ -268,6 +323,7 @@ where
             serial.change_data_bits(config.data_bits);
             serial.change_parity(config.parity);
             serial.change_stop_bits(config.stop_bits);
+            serial.change_flow_control(config.flow_control);
             serial.change_baud(config.baudrate, clocks);
         });
 
@@ -275,11 +331,21 @@ where
     }
 
     /// Create a new UART instance with defaults
-    pub fn new(uart: T) -> Self {
+    ///
+    /// The baudrate is set to the default of 115200 baud, the divider is
+    /// computed from the actual APB clock frequency in `clocks`.
+    ///
+    /// Migration note: previously this didn't take `clocks` and relied on the
+    /// divider set up by the bootloader, which results in a wrong baudrate
+    /// when the APB clock isn't running at 80 MHz. Pass `&clocks` as
+    /// the second argument.
+    pub fn new(uart: T, clocks: &Clocks) -> Self {
         let mut serial = Serial { uart };
         serial.uart.disable_rx_interrupts();
         serial.uart.disable_tx_interrupts();
 
+        serial.change_baud(Config::default().baudrate, clocks);
+
         serial
     }
 
@@ -348,6 +414,105 @@ where
             .modify(|_, w| unsafe { w.rxfifo_full_thrhd().bits(threshold) });
     }
 
+    /// Configures the RX timeout in symbols (i.e. frames including start,
+    /// parity and stop bits at the current configuration), `None` disables
+    /// it
+    ///
+    /// The timeout raises [UartEvent::RxTimeout] when the RX line was idle
+    /// for the given time while the RX-FIFO holds data, which allows to
+    /// process the last bytes of a message received with
+    /// [UartEvent::RxFifoFull].
+    ///
+    /// The timeout is limited to 127 symbols on the ESP32 and to 1023 bit
+    /// times on the other chips.
+    pub fn set_rx_timeout(&mut self, timeout: Option<u8>) {
+        let reg_block = self.uart.register_block();
+
+        if let Some(timeout) = timeout {
+            // the ESP32 counts the timeout in symbols, the other chips in
+            // bit times
+            #[cfg(esp32)]
+            reg_block
+                .conf1
+                .modify(|_, w| unsafe { w.rx_tout_thrhd().bits(timeout.min(0x7f)) });
+            #[cfg(not(esp32))]
+            {
+                let bit_times = u16::min(timeout as u16 * self.symbol_length(), 0x3ff);
+                reg_block
+                    .mem_conf
+                    .modify(|_, w| unsafe { w.rx_tout_thrhd().bits(bit_times) });
+            }
+        }
+
+        reg_block
+            .conf1
+            .modify(|_, w| w.rx_tout_en().bit(timeout.is_some()));
+    }
+
+    /// Length of a symbol in bit times at the current configuration
+    #[cfg(not(esp32))]
+    pub(crate) fn symbol_length(&self) -> u16 {
+        let conf0 = self.uart.register_block().conf0.read();
+        let stop_bits = match conf0.stop_bit_num().bits() {
+            3 => 2,
+            _ => 1,
+        };
+
+        1 + 5 + conf0.bit_num().bits() as u16 + conf0.parity_en().bit() as u16 + stop_bits
+    }
+
+    /// Listen for the given interrupt
+    pub fn listen(&mut self, event: UartEvent) {
+        self.uart
+            .register_block()
+            .int_ena
+            .modify(|_, w| match event {
+                UartEvent::RxFifoFull => w.rxfifo_full_int_ena().set_bit(),
+                UartEvent::AtCmd => w.at_cmd_char_det_int_ena().set_bit(),
+                UartEvent::RxTimeout => w.rxfifo_tout_int_ena().set_bit(),
+                UartEvent::TxDone => w.tx_done_int_ena().set_bit(),
+            });
+    }
+
+    /// Stop listening for the given interrupt
+    pub fn unlisten(&mut self, event: UartEvent) {
+        self.uart
+            .register_block()
+            .int_ena
+            .modify(|_, w| match event {
+                UartEvent::RxFifoFull => w.rxfifo_full_int_ena().clear_bit(),
+                UartEvent::AtCmd => w.at_cmd_char_det_int_ena().clear_bit(),
+                UartEvent::RxTimeout => w.rxfifo_tout_int_ena().clear_bit(),
+                UartEvent::TxDone => w.tx_done_int_ena().clear_bit(),
+            });
+    }
+
+    /// Checks if the given interrupt is set
+    pub fn is_interrupt_set(&self, event: UartEvent) -> bool {
+        let int_raw = self.uart.register_block().int_raw.read();
+
+        match event {
+            UartEvent::RxFifoFull => int_raw.rxfifo_full_int_raw().bit_is_set(),
+            UartEvent::AtCmd => int_raw.at_cmd_char_det_int_raw().bit_is_set(),
+            UartEvent::RxTimeout => int_raw.rxfifo_tout_int_raw().bit_is_set(),
+            UartEvent::TxDone => int_raw.tx_done_int_raw().bit_is_set(),
+        }
+    }
+
+    /// Clears the given interrupt
+    ///
+    /// [UartEvent::RxFifoFull] is raised again right away if the RX-FIFO
+    /// still holds more bytes than the threshold, drain it first, e.g. via
+    /// [Serial::read_available].
+    pub fn clear_interrupt(&mut self, event: UartEvent) {
+        self.uart.register_block().int_clr.write(|w| match event {
+            UartEvent::RxFifoFull => w.rxfifo_full_int_clr().set_bit(),
+            UartEvent::AtCmd => w.at_cmd_char_det_int_clr().set_bit(),
+            UartEvent::RxTimeout => w.rxfifo_tout_int_clr().set_bit(),
+            UartEvent::TxDone => w.tx_done_int_clr().set_bit(),
+        });
+    }
+
     /// Listen for AT-CMD interrupts
     pub fn listen_at_cmd(&mut self) {
         self.uart
@@ -361,7 +526,7 @@ where
         self.uart
             .register_block()
             .int_ena
-            .modify(|_, w| w.at_cmd_char_det_int_ena().set_bit());
+            .modify(|_, w| w.at_cmd_char_det_int_ena().clear_bit());
     }
 
     /// Listen for TX-DONE interrupts
@@ -377,7 +542,7 @@ where
         self.uart
             .register_block()
             .int_ena
-            .modify(|_, w| w.tx_done_int_ena().set_bit());
+            .modify(|_, w| w.tx_done_int_ena().clear_bit());
     }
 
     /// Listen for RX-FIFO-FULL interrupts
@@ -393,7 +558,7 @@ where
         self.uart
             .register_block()
             .int_ena
-            .modify(|_, w| w.rxfifo_full_int_ena().set_bit());
+            .modify(|_, w| w.rxfifo_full_int_ena().clear_bit());
     }
 
     /// Checks if AT-CMD interrupt is set
@@ -471,6 +636,32 @@ where
         }
     }
 
+    /// Write as many bytes as fit into the TX-FIFO, returns the number of
+    /// bytes written
+    #[cfg(feature = "embedded-io")]
+    fn write_available(&mut self, data: &[u8]) -> usize {
+        data.iter()
+            .take_while(|b| self.write_byte(**b).is_ok())
+            .count()
+    }
+
+    /// Read the bytes currently in the RX-FIFO without blocking, returns the
+    /// number of bytes read
+    ///
+    /// This can be used to drain the RX-FIFO in an interrupt handler.
+    pub fn read_available(&mut self, buffer: &mut [u8]) -> usize {
+        let mut count = 0;
+        while count < buffer.len() {
+            match self.read_byte() {
+                Ok(byte) => buffer[count] = byte,
+                Err(_) => break,
+            }
+            count += 1;
+        }
+
+        count
+    }
+
     fn read_byte(&mut self) -> nb::Result<u8, Error> {
         #[allow(unused_variables)]
         let offset = 0;
@@ -492,6 +683,46 @@ where
         }
     }
 
+    /// Change the hardware flow control
+    ///
+    /// With CTS flow control the transmitter pauses while CTS is deasserted,
+    /// writes return `nb::Error::WouldBlock` once the TX-FIFO is full. The RTS
+    /// threshold is limited to the size of the RX-FIFO.
+    pub fn change_flow_control(&mut self, flow_control: config::FlowControl) -> &mut Self {
+        let (rts_threshold, cts) = match flow_control {
+            config::FlowControl::None => (None, false),
+            config::FlowControl::Rts { threshold } => (Some(threshold), false),
+            config::FlowControl::Cts => (None, true),
+            config::FlowControl::RtsCts { threshold } => (Some(threshold), true),
+        };
+
+        if let Some(threshold) = rts_threshold {
+            let threshold = u16::min(threshold, UART_FIFO_SIZE - 1);
+
+            #[cfg(esp32)]
+            self.uart
+                .register_block()
+                .conf1
+                .modify(|_, w| unsafe { w.rx_flow_thrhd().bits(threshold as u8) });
+            #[cfg(not(esp32))]
+            self.uart
+                .register_block()
+                .mem_conf
+                .modify(|_, w| unsafe { w.rx_flow_thrhd().bits(threshold) });
+        }
+
+        self.uart
+            .register_block()
+            .conf1
+            .modify(|_, w| w.rx_flow_en().bit(rts_threshold.is_some()));
+        self.uart
+            .register_block()
+            .conf0
+            .modify(|_, w| w.tx_flow_en().bit(cts));
+
+        self
+    }
+
     /// Change the number of stop bits
     pub fn change_stop_bits(&mut self, stop_bits: config::StopBits) -> &mut Self {
         // workaround for hardware issue, when UART stop bit set as 2-bit mode.
@@ -584,6 +815,38 @@ where
             .write(|w| unsafe { w.clkdiv().bits(divider).frag().bits(0) });
     }
 
+    /// Get the baudrate as currently configured in the hardware
+    ///
+    /// This reads back the dividers and computes the resulting baudrate from
+    /// the APB clock frequency in `clocks`.
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    pub fn get_baudrate(&self, clocks: &Clocks) -> u32 {
+        let clk = clocks.apb_clock.to_Hz();
+        let clk_div = self
+            .uart
+            .register_block()
+            .clk_conf
+            .read()
+            .sclk_div_num()
+            .bits() as u32
+            + 1;
+        let divider = self.uart.register_block().clkdiv.read().clkdiv().bits() as u32;
+
+        clk / clk_div / divider
+    }
+
+    /// Get the baudrate as currently configured in the hardware
+    ///
+    /// This reads back the divider and computes the resulting baudrate from
+    /// the APB clock frequency in `clocks`.
+    #[cfg(any(esp32, esp32s2))]
+    pub fn get_baudrate(&self, clocks: &Clocks) -> u32 {
+        let clk = clocks.apb_clock.to_Hz();
+        let divider = self.uart.register_block().clkdiv.read().clkdiv().bits();
+
+        clk / divider
+    }
+
     #[cfg(any(esp32, esp32s2))]
     fn change_baud(&self, baudrate: u32, clocks: &Clocks) {
         // we force the clock source to be APB and don't use the decimal part of the
@@ -687,6 +950,9 @@ pub trait Instance {
         idle
     }
 
+    /// Number of the UART, e.g. to select it in the UHCI
+    fn uart_number(&self) -> usize;
+
     fn tx_signal(&self) -> OutputSignal;
 
     fn rx_signal(&self) -> InputSignal;
@@ -702,6 +968,10 @@ impl Instance for UART0 {
         self
     }
 
+    fn uart_number(&self) -> usize {
+        0
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U0TXD
     }
@@ -725,6 +995,10 @@ impl Instance for UART1 {
         self
     }
 
+    fn uart_number(&self) -> usize {
+        1
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U1TXD
     }
@@ -749,6 +1023,10 @@ impl Instance for UART2 {
         self
     }
 
+    fn uart_number(&self) -> usize {
+        2
+    }
+
     fn tx_signal(&self) -> OutputSignal {
         OutputSignal::U2TXD
     }
@@ -766,6 +1044,239 @@ impl Instance for UART2 {
     }
 }
 
+/// Receiving via DMA
+///
+/// The UART is connected to a GDMA channel via the UHCI. A transfer completes
+/// when the buffer is full or when the RX line was idle for the configured
+/// number of symbols, which allows receiving frames of unknown length without
+/// an interrupt per byte.
+#[cfg(any(esp32c3, esp32s3))]
+pub mod dma {
+    use core::mem;
+
+    use embedded_dma::WriteBuffer;
+
+    use super::{Instance, Serial};
+    use crate::{
+        dma::{
+            private::{Rx, Tx, UhciPeripheral},
+            Channel,
+            DmaError,
+            DmaPeripheral,
+        },
+        pac::UHCI0,
+        system::{Peripheral, PeripheralClockControl},
+    };
+
+    /// Largest amount of data a single transfer can receive, limited by the
+    /// UHCI packet length threshold
+    pub const MAX_DMA_RX_SIZE: usize = 8191;
+
+    /// Highest supported idle threshold in bit times
+    const MAX_IDLE_THRESHOLD: u32 = 0x3ff;
+
+    impl<T> Serial<T>
+    where
+        T: Instance,
+    {
+        /// Receive via the UHCI and the given DMA channel
+        ///
+        /// A transfer completes after the RX line was idle for
+        /// `idle_symbols` symbols (i.e. frames including start, parity and
+        /// stop bits at the current configuration), the threshold is limited
+        /// to 1023 bit times.
+        pub fn with_rx_dma<TX, RX, P>(
+            self,
+            uhci: UHCI0,
+            mut channel: Channel<TX, RX, P>,
+            idle_symbols: u16,
+            peripheral_clock_control: &mut PeripheralClockControl,
+        ) -> SerialRxDma<T, TX, RX, P>
+        where
+            TX: Tx,
+            RX: Rx,
+            P: UhciPeripheral,
+        {
+            peripheral_clock_control.enable(Peripheral::Uhci0);
+            channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+            let threshold = u32::min(
+                idle_symbols as u32 * self.symbol_length() as u32,
+                MAX_IDLE_THRESHOLD,
+            );
+            self.uart
+                .register_block()
+                .idle_conf
+                .modify(|_, w| unsafe { w.rx_idle_thrhd().bits(threshold as u16) });
+
+            // Pass the data through unmodified, i.e. without SLIP framing,
+            // escaping, headers or CRCs
+            uhci.escape_conf.write(|w| unsafe { w.bits(0) });
+            uhci.conf1.write(|w| unsafe { w.bits(0) });
+            let uart_number = self.uart.uart_number();
+            uhci.conf0.write(|w| {
+                #[cfg(esp32s3)]
+                w.uart2_ce().bit(uart_number == 2);
+
+                w.uart0_ce()
+                    .bit(uart_number == 0)
+                    .uart1_ce()
+                    .bit(uart_number == 1)
+                    .uart_idle_eof_en()
+                    .set_bit()
+                    .len_eof_en()
+                    .set_bit()
+                    .clk_en()
+                    .set_bit()
+            });
+
+            SerialRxDma {
+                serial: self,
+                uhci,
+                channel,
+            }
+        }
+    }
+
+    /// A UART receiving via DMA, see [Serial::with_rx_dma]
+    pub struct SerialRxDma<T, TX, RX, P>
+    where
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        serial: Serial<T>,
+        uhci: UHCI0,
+        channel: Channel<TX, RX, P>,
+    }
+
+    impl<T, TX, RX, P> SerialRxDma<T, TX, RX, P>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        /// Start receiving into `buffer`
+        ///
+        /// The transfer completes when the buffer is full or the RX line went
+        /// idle, at most [MAX_DMA_RX_SIZE] bytes are received.
+        pub fn read<BUFFER>(
+            mut self,
+            mut buffer: BUFFER,
+        ) -> Result<SerialRxDmaTransfer<T, TX, RX, P, BUFFER>, DmaError>
+        where
+            BUFFER: WriteBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { buffer.write_buffer() };
+            let len = usize::min(len, MAX_DMA_RX_SIZE);
+
+            self.uhci.conf0.modify(|_, w| w.rx_rst().set_bit());
+            self.uhci.conf0.modify(|_, w| w.rx_rst().clear_bit());
+            self.uhci
+                .pkt_thres
+                .write(|w| unsafe { w.pkt_thrs().bits(len as u16) });
+
+            self.channel
+                .rx
+                .prepare_transfer(DmaPeripheral::Uhci0, ptr, len)?;
+
+            Ok(SerialRxDmaTransfer {
+                serial_dma: self,
+                buffer,
+            })
+        }
+
+        /// Detach the UART from the UHCI and return the UART, the UHCI and
+        /// the DMA channel
+        pub fn free(self) -> (Serial<T>, UHCI0, Channel<TX, RX, P>) {
+            self.uhci.conf0.modify(|_, w| {
+                #[cfg(esp32s3)]
+                w.uart2_ce().clear_bit();
+
+                w.uart0_ce().clear_bit().uart1_ce().clear_bit()
+            });
+
+            (self.serial, self.uhci, self.channel)
+        }
+    }
+
+    /// An in-progress DMA reception, see [SerialRxDma::read]
+    pub struct SerialRxDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        serial_dma: SerialRxDma<T, TX, RX, P>,
+        buffer: BUFFER,
+    }
+
+    impl<T, TX, RX, P, BUFFER> SerialRxDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        /// Check if the buffer is full or the RX line went idle
+        pub fn is_done(&mut self) -> bool {
+            self.serial_dma.channel.is_done()
+        }
+
+        /// Wait for the transfer to complete
+        ///
+        /// Returns the number of received bytes, the buffer and the UART.
+        pub fn wait(mut self) -> (Result<usize, DmaError>, BUFFER, SerialRxDma<T, TX, RX, P>) {
+            while !self.is_done() {
+                if let Err(err) = self.serial_dma.channel.check_errors() {
+                    self.serial_dma.channel.reset();
+                    return self.release(Err(err));
+                }
+            }
+
+            let result = self
+                .serial_dma
+                .channel
+                .check_errors()
+                .map(|_| self.serial_dma.channel.rx.received_length());
+
+            self.release(result)
+        }
+
+        fn release(
+            self,
+            result: Result<usize, DmaError>,
+        ) -> (Result<usize, DmaError>, BUFFER, SerialRxDma<T, TX, RX, P>) {
+            // `Drop` is implemented, so we can't move out of the fields, see
+            // `SpiDmaTransfer::wait`
+            //
+            // NOTE(unsafe) There is no panic branch between getting the resources
+            // and forgetting `self`.
+            unsafe {
+                let buffer = core::ptr::read(&self.buffer);
+                let serial_dma = core::ptr::read(&self.serial_dma);
+                mem::forget(self);
+                (result, buffer, serial_dma)
+            }
+        }
+    }
+
+    impl<T, TX, RX, P, BUFFER> Drop for SerialRxDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: UhciPeripheral,
+    {
+        fn drop(&mut self) {
+            // the DMA must not write to the buffer anymore once it's dropped
+            self.serial_dma.channel.reset();
+        }
+    }
+}
+
 #[cfg(feature = "ufmt")]
 impl<T> ufmt_write::uWrite for Serial<T>
 where
@@ -849,3 +1360,316 @@ where
         self.flush_tx()
     }
 }
+
+#[cfg(feature = "embedded-io")]
+impl<T> embedded_io::Io for Serial<T> {
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T> embedded_io::blocking::Read for Serial<T>
+where
+    T: Instance,
+{
+    /// Blocks until at least one byte was received, then returns the bytes
+    /// which are available
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        buf[0] = nb::block!(self.read_byte())?;
+
+        Ok(1 + self.read_available(&mut buf[1..]))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl<T> embedded_io::blocking::Write for Serial<T>
+where
+    T: Instance,
+{
+    /// Blocks until at least one byte fits into the TX-FIFO, then writes as
+    /// many bytes as possible
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        nb::block!(self.write_byte(buf[0]))?;
+
+        Ok(1 + self.write_available(&buf[1..]))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        nb::block!(self.flush_tx())
+    }
+}
+
+/// Async `embedded-io` implementation
+///
+/// The futures enable the UART interrupts they wait for while they are
+/// pending. The UART interrupt handler(s) need to call [handle_interrupt]
+/// which wakes the waiting tasks:
+///
+/// ```rust,ignore
+/// #[interrupt]
+/// fn UART0() {
+///     esp_hal_common::serial::asynch::handle_interrupt();
+/// }
+/// ```
+///
+/// Reading waits for [UartEvent::RxFifoFull] or [UartEvent::RxTimeout], so
+/// the RX-FIFO threshold and the RX timeout decide when a read completes. Set
+/// the threshold to 1 to complete reads with every received byte.
+#[cfg(all(feature = "embedded-io", feature = "async"))]
+pub mod asynch {
+    use core::{
+        cell::RefCell,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use critical_section::Mutex;
+
+    use super::{Error, Instance, RegisterBlock, Serial};
+    #[cfg(any(esp32, esp32s3))]
+    use crate::pac::UART2;
+    use crate::pac::{UART0, UART1};
+
+    #[cfg(any(esp32, esp32s3))]
+    const UART_COUNT: usize = 3;
+    #[cfg(not(any(esp32, esp32s3)))]
+    const UART_COUNT: usize = 2;
+
+    const NO_WAKER: Mutex<RefCell<Option<Waker>>> = Mutex::new(RefCell::new(None));
+
+    static RX_WAKERS: [Mutex<RefCell<Option<Waker>>>; UART_COUNT] = [NO_WAKER; UART_COUNT];
+    static TX_WAKERS: [Mutex<RefCell<Option<Waker>>>; UART_COUNT] = [NO_WAKER; UART_COUNT];
+
+    fn register(waker: &Mutex<RefCell<Option<Waker>>>, new: &Waker) {
+        critical_section::with(|cs| {
+            let mut waker = waker.borrow_ref_mut(cs);
+            match waker.as_ref() {
+                Some(old) if old.will_wake(new) => {}
+                _ => *waker = Some(new.clone()),
+            }
+        });
+    }
+
+    fn wake(waker: &Mutex<RefCell<Option<Waker>>>) {
+        if let Some(waker) = critical_section::with(|cs| waker.borrow_ref_mut(cs).take()) {
+            waker.wake();
+        }
+    }
+
+    /// Future completing once at least one byte was received
+    pub struct ReadFuture<'a, T> {
+        serial: &'a mut Serial<T>,
+        buf: &'a mut [u8],
+    }
+
+    impl<'a, T> Future for ReadFuture<'a, T>
+    where
+        T: Instance,
+    {
+        type Output = Result<usize, Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = this.serial.uart.register_block();
+
+            // register and clear before checking, otherwise data arriving in
+            // between is missed
+            register(&RX_WAKERS[this.serial.uart.uart_number()], cx.waker());
+            reg_block.int_clr.write(|w| {
+                w.rxfifo_full_int_clr()
+                    .set_bit()
+                    .rxfifo_tout_int_clr()
+                    .set_bit()
+            });
+
+            let count = this.serial.read_available(this.buf);
+            let ready = count > 0 || this.buf.is_empty();
+
+            critical_section::with(|_| {
+                reg_block.int_ena.modify(|_, w| {
+                    w.rxfifo_full_int_ena()
+                        .bit(!ready)
+                        .rxfifo_tout_int_ena()
+                        .bit(!ready)
+                })
+            });
+
+            if ready {
+                Poll::Ready(Ok(count))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Future completing once at least one byte was written to the TX-FIFO
+    pub struct WriteFuture<'a, T> {
+        serial: &'a mut Serial<T>,
+        buf: &'a [u8],
+    }
+
+    impl<'a, T> Future for WriteFuture<'a, T>
+    where
+        T: Instance,
+    {
+        type Output = Result<usize, Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = this.serial.uart.register_block();
+
+            register(&TX_WAKERS[this.serial.uart.uart_number()], cx.waker());
+            reg_block
+                .int_clr
+                .write(|w| w.txfifo_empty_int_clr().set_bit());
+
+            let count = this.serial.write_available(this.buf);
+            let ready = count > 0 || this.buf.is_empty();
+
+            // raised once the TX-FIFO drained below its threshold
+            critical_section::with(|_| {
+                reg_block
+                    .int_ena
+                    .modify(|_, w| w.txfifo_empty_int_ena().bit(!ready))
+            });
+
+            if ready {
+                Poll::Ready(Ok(count))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Future completing once all data has been sent
+    pub struct FlushFuture<'a, T> {
+        serial: &'a mut Serial<T>,
+    }
+
+    impl<'a, T> Future for FlushFuture<'a, T>
+    where
+        T: Instance,
+    {
+        type Output = Result<(), Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = this.serial.uart.register_block();
+
+            register(&TX_WAKERS[this.serial.uart.uart_number()], cx.waker());
+            reg_block.int_clr.write(|w| w.tx_done_int_clr().set_bit());
+
+            let result = this.serial.flush_tx();
+            let ready = !matches!(result, Err(nb::Error::WouldBlock));
+
+            critical_section::with(|_| {
+                reg_block
+                    .int_ena
+                    .modify(|_, w| w.tx_done_int_ena().bit(!ready))
+            });
+
+            match result {
+                Err(nb::Error::WouldBlock) => Poll::Pending,
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+                Ok(()) => Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    impl<T> embedded_io::asynch::Read for Serial<T>
+    where
+        T: Instance,
+    {
+        type ReadFuture<'a>
+            = ReadFuture<'a, T>
+        where
+            Self: 'a;
+
+        fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> Self::ReadFuture<'a> {
+            ReadFuture { serial: self, buf }
+        }
+    }
+
+    impl<T> embedded_io::asynch::Write for Serial<T>
+    where
+        T: Instance,
+    {
+        type WriteFuture<'a>
+            = WriteFuture<'a, T>
+        where
+            Self: 'a;
+
+        fn write<'a>(&'a mut self, buf: &'a [u8]) -> Self::WriteFuture<'a> {
+            WriteFuture { serial: self, buf }
+        }
+
+        type FlushFuture<'a>
+            = FlushFuture<'a, T>
+        where
+            Self: 'a;
+
+        fn flush<'a>(&'a mut self) -> Self::FlushFuture<'a> {
+            FlushFuture { serial: self }
+        }
+    }
+
+    /// Disable the interrupts which fired and wake the waiting tasks
+    ///
+    /// The status bits are left untouched, the futures clear them before
+    /// they check the FIFOs again.
+    fn handle_uart(uart_number: usize, reg_block: &RegisterBlock) {
+        let (rx, tx) = critical_section::with(|_| {
+            let raw = reg_block.int_raw.read();
+            let ena = reg_block.int_ena.read();
+
+            let rx = (raw.rxfifo_full_int_raw().bit_is_set()
+                && ena.rxfifo_full_int_ena().bit_is_set())
+                || (raw.rxfifo_tout_int_raw().bit_is_set()
+                    && ena.rxfifo_tout_int_ena().bit_is_set());
+            let tx = (raw.txfifo_empty_int_raw().bit_is_set()
+                && ena.txfifo_empty_int_ena().bit_is_set())
+                || (raw.tx_done_int_raw().bit_is_set() && ena.tx_done_int_ena().bit_is_set());
+
+            reg_block.int_ena.modify(|_, w| {
+                if rx {
+                    w.rxfifo_full_int_ena().clear_bit();
+                    w.rxfifo_tout_int_ena().clear_bit();
+                }
+                if tx {
+                    w.txfifo_empty_int_ena().clear_bit();
+                    w.tx_done_int_ena().clear_bit();
+                }
+                w
+            });
+
+            (rx, tx)
+        });
+
+        if rx {
+            wake(&RX_WAKERS[uart_number]);
+        }
+
+        if tx {
+            wake(&TX_WAKERS[uart_number]);
+        }
+    }
+
+    /// Wake the tasks waiting for the UARTs
+    ///
+    /// Needs to be called from the UART interrupt handler(s), see the [module
+    /// level documentation](self).
+    pub fn handle_interrupt() {
+        handle_uart(0, unsafe { &*UART0::PTR });
+        handle_uart(1, unsafe { &*(UART1::PTR as *const RegisterBlock) });
+        #[cfg(any(esp32, esp32s3))]
+        handle_uart(2, unsafe { &*(UART2::PTR as *const RegisterBlock) });
+    }
+}
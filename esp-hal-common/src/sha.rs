@@ -0,0 +1,354 @@
+//! # Secure Hash Algorithm (SHA) Accelerator
+//!
+//! ### Summary
+//! The SHA peripheral computes message digests in hardware, which is
+//! considerably faster than a software implementation, e.g. when verifying the
+//! signature of an OTA image.
+//!
+//! The algorithm is selected by the type parameter of [Sha]:
+//!
+//! | Algorithm | ESP32 | ESP32-C2/C3 | ESP32-S2/S3 |
+//! |-----------|-------|-------------|-------------|
+//! | [Sha1]    | ✓     | ✓           | ✓           |
+//! | [Sha224]  |       | ✓           | ✓           |
+//! | [Sha256]  | ✓     | ✓           | ✓           |
+//! | [Sha384]  | ✓     |             | ✓           |
+//! | [Sha512]  | ✓     |             | ✓           |
+//!
+//! Data passed to [Sha::update] is collected into blocks which are fed to the
+//! peripheral as soon as they are complete. [Sha::finish] appends the padding
+//! and the message length, processes the final block(s) and returns the
+//! digest. Afterwards the driver is ready to hash the next message.
+//!
+//! With the `digest` feature enabled, the `Update`, `FixedOutput`,
+//! `FixedOutputReset` and `Reset` traits of the `digest` crate are
+//! implemented.
+//!
+//! ### Example
+//! ```no_run
+//! let mut sha = Sha::<Sha256>::new(peripherals.SHA, &mut system.peripheral_clock_control);
+//! sha.update(b"hello ");
+//! sha.update(b"world");
+//! let digest: [u8; 32] = sha.finish();
+//! ```
+
+#![deny(missing_docs)]
+
+use core::marker::PhantomData;
+
+use crate::{
+    pac::SHA,
+    system::{Peripheral, PeripheralClockControl},
+};
+
+/// Length of the largest supported block in bytes
+const MAX_BLOCK_LENGTH: usize = 128;
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// A hash algorithm supported by the SHA peripheral
+pub trait ShaAlgorithm: sealed::Sealed {
+    /// The digest returned by [Sha::finish]
+    type Digest: AsMut<[u8]> + AsRef<[u8]>;
+
+    /// Length of a message block in bytes
+    const BLOCK_LENGTH: usize;
+
+    /// Length of the big-endian message length appended by the padding in
+    /// bytes
+    const LENGTH_FIELD_LENGTH: usize;
+
+    #[doc(hidden)]
+    const EMPTY_DIGEST: Self::Digest;
+
+    #[doc(hidden)]
+    #[cfg(not(esp32))]
+    const MODE: u8;
+
+    #[doc(hidden)]
+    #[cfg(esp32)]
+    fn start(sha: &SHA);
+
+    #[doc(hidden)]
+    #[cfg(esp32)]
+    fn continue_(sha: &SHA);
+
+    #[doc(hidden)]
+    #[cfg(esp32)]
+    fn load(sha: &SHA);
+
+    #[doc(hidden)]
+    #[cfg(esp32)]
+    fn is_busy(sha: &SHA) -> bool;
+}
+
+macro_rules! impl_algorithm {
+    ($name:ident, $doc:literal, $block:literal, $digest:literal, $mode:literal, $reg:ident) => {
+        #[doc = $doc]
+        pub struct $name;
+
+        impl sealed::Sealed for $name {}
+
+        impl ShaAlgorithm for $name {
+            type Digest = [u8; $digest];
+
+            const BLOCK_LENGTH: usize = $block;
+            const LENGTH_FIELD_LENGTH: usize = $block / 8;
+            const EMPTY_DIGEST: Self::Digest = [0; $digest];
+
+            #[cfg(not(esp32))]
+            const MODE: u8 = $mode;
+
+            #[cfg(esp32)]
+            fn start(sha: &SHA) {
+                paste::paste! {
+                    sha.[<$reg _start>].write(|w| unsafe { w.bits(1) });
+                }
+            }
+
+            #[cfg(esp32)]
+            fn continue_(sha: &SHA) {
+                paste::paste! {
+                    sha.[<$reg _continue>].write(|w| unsafe { w.bits(1) });
+                }
+            }
+
+            #[cfg(esp32)]
+            fn load(sha: &SHA) {
+                paste::paste! {
+                    sha.[<$reg _load>].write(|w| unsafe { w.bits(1) });
+                }
+            }
+
+            #[cfg(esp32)]
+            fn is_busy(sha: &SHA) -> bool {
+                paste::paste! {
+                    sha.[<$reg _busy>].read().bits() != 0
+                }
+            }
+        }
+    };
+}
+
+impl_algorithm!(Sha1, "SHA-1", 64, 20, 0, sha1);
+#[cfg(not(esp32))]
+impl_algorithm!(Sha224, "SHA-224", 64, 28, 1, sha224);
+impl_algorithm!(Sha256, "SHA-256", 64, 32, 2, sha256);
+#[cfg(any(esp32, esp32s2, esp32s3))]
+impl_algorithm!(Sha384, "SHA-384", 128, 48, 3, sha384);
+#[cfg(any(esp32, esp32s2, esp32s3))]
+impl_algorithm!(Sha512, "SHA-512", 128, 64, 4, sha512);
+
+/// SHA accelerator driver computing digests with the algorithm `A`
+pub struct Sha<A: ShaAlgorithm> {
+    sha: SHA,
+    buffer: [u8; MAX_BLOCK_LENGTH],
+    buffered: usize,
+    length: u64,
+    first_block: bool,
+    _algorithm: PhantomData<A>,
+}
+
+impl<A: ShaAlgorithm> Sha<A> {
+    /// Create a new instance and enable the peripheral
+    pub fn new(sha: SHA, peripheral_clock_control: &mut PeripheralClockControl) -> Self {
+        peripheral_clock_control.enable(Peripheral::Sha);
+
+        Self {
+            sha,
+            buffer: [0; MAX_BLOCK_LENGTH],
+            buffered: 0,
+            length: 0,
+            first_block: true,
+            _algorithm: PhantomData,
+        }
+    }
+
+    /// Feed `data` into the digest computation
+    ///
+    /// Complete blocks are processed immediately, the remainder is kept
+    /// until more data arrives or [Sha::finish] is called.
+    pub fn update(&mut self, mut data: &[u8]) {
+        self.length = self.length.wrapping_add(data.len() as u64);
+
+        while !data.is_empty() {
+            let count = usize::min(A::BLOCK_LENGTH - self.buffered, data.len());
+            self.buffer[self.buffered..][..count].copy_from_slice(&data[..count]);
+            self.buffered += count;
+            data = &data[count..];
+
+            if self.buffered == A::BLOCK_LENGTH {
+                self.process_block();
+            }
+        }
+    }
+
+    /// Pad the message, process the final block(s) and return the digest
+    ///
+    /// The driver is reset afterwards and can be used to hash the next
+    /// message.
+    pub fn finish(&mut self) -> A::Digest {
+        let bit_length = self.length.wrapping_mul(8);
+
+        self.buffer[self.buffered] = 0x80;
+        self.buffered += 1;
+
+        // The length doesn't fit into this block anymore, pad it with zeros
+        // and append the length to an additional one
+        if self.buffered > A::BLOCK_LENGTH - A::LENGTH_FIELD_LENGTH {
+            self.buffer[self.buffered..A::BLOCK_LENGTH].fill(0);
+            self.process_block();
+        }
+
+        // The upper bytes of the 128 bit length field of SHA-384/512 are
+        // always zero
+        self.buffer[self.buffered..A::BLOCK_LENGTH - 8].fill(0);
+        self.buffer[A::BLOCK_LENGTH - 8..A::BLOCK_LENGTH]
+            .copy_from_slice(&bit_length.to_be_bytes());
+        self.process_block();
+
+        let mut digest = A::EMPTY_DIGEST;
+        self.read_digest(digest.as_mut());
+
+        self.reset();
+
+        digest
+    }
+
+    /// Discard all data passed to [Sha::update] since the last call to
+    /// [Sha::finish]
+    pub fn reset(&mut self) {
+        self.buffered = 0;
+        self.length = 0;
+        self.first_block = true;
+    }
+
+    /// Return the raw interface to the underlying SHA instance
+    pub fn free(self) -> SHA {
+        self.sha
+    }
+
+    fn process_block(&mut self) {
+        self.wait_idle();
+
+        for (index, word) in self.buffer[..A::BLOCK_LENGTH].chunks_exact(4).enumerate() {
+            let word = word.try_into().unwrap();
+
+            // The ESP32 expects big-endian words in the text registers
+            #[cfg(esp32)]
+            self.sha.text[index].write(|w| unsafe { w.bits(u32::from_be_bytes(word)) });
+            #[cfg(not(esp32))]
+            self.sha.m_mem[index].write(|w| unsafe { w.bits(u32::from_ne_bytes(word)) });
+        }
+
+        #[cfg(esp32)]
+        if self.first_block {
+            A::start(&self.sha);
+        } else {
+            A::continue_(&self.sha);
+        }
+
+        #[cfg(not(esp32))]
+        {
+            self.sha.mode.write(|w| unsafe { w.mode().bits(A::MODE) });
+
+            if self.first_block {
+                self.sha.start.write(|w| unsafe { w.bits(1) });
+            } else {
+                self.sha.continue_.write(|w| unsafe { w.bits(1) });
+            }
+        }
+
+        self.first_block = false;
+        self.buffered = 0;
+    }
+
+    fn read_digest(&mut self, digest: &mut [u8]) {
+        self.wait_idle();
+
+        // The ESP32 has to copy the internal state into the text registers
+        // first, the other chips keep it readable in `H_MEM`
+        #[cfg(esp32)]
+        {
+            A::load(&self.sha);
+            self.wait_idle();
+        }
+
+        for (index, chunk) in digest.chunks_mut(4).enumerate() {
+            #[cfg(esp32)]
+            let bytes = self.sha.text[index].read().bits().to_be_bytes();
+            #[cfg(not(esp32))]
+            let bytes = self.sha.h_mem[index].read().bits().to_ne_bytes();
+
+            chunk.copy_from_slice(&bytes[..chunk.len()]);
+        }
+    }
+
+    fn wait_idle(&self) {
+        #[cfg(esp32)]
+        while A::is_busy(&self.sha) {}
+        #[cfg(not(esp32))]
+        while self.sha.busy.read().bits() != 0 {}
+    }
+}
+
+#[cfg(feature = "digest")]
+mod digest_impl {
+    use digest::{
+        consts::{U20, U32},
+        FixedOutput,
+        FixedOutputReset,
+        HashMarker,
+        Output,
+        OutputSizeUser,
+        Reset,
+        Update,
+    };
+
+    use super::*;
+
+    macro_rules! impl_digest {
+        ($algorithm:ident, $size:ty) => {
+            impl HashMarker for Sha<$algorithm> {}
+
+            impl OutputSizeUser for Sha<$algorithm> {
+                type OutputSize = $size;
+            }
+
+            impl Update for Sha<$algorithm> {
+                fn update(&mut self, data: &[u8]) {
+                    Sha::update(self, data);
+                }
+            }
+
+            impl FixedOutput for Sha<$algorithm> {
+                fn finalize_into(mut self, out: &mut Output<Self>) {
+                    out.copy_from_slice(&self.finish());
+                }
+            }
+
+            impl Reset for Sha<$algorithm> {
+                fn reset(&mut self) {
+                    Sha::reset(self);
+                }
+            }
+
+            impl FixedOutputReset for Sha<$algorithm> {
+                fn finalize_into_reset(&mut self, out: &mut Output<Self>) {
+                    out.copy_from_slice(&self.finish());
+                }
+            }
+        };
+    }
+
+    impl_digest!(Sha1, U20);
+    #[cfg(not(esp32))]
+    impl_digest!(Sha224, digest::consts::U28);
+    impl_digest!(Sha256, U32);
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    impl_digest!(Sha384, digest::consts::U48);
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    impl_digest!(Sha512, digest::consts::U64);
+}
@@ -75,10 +75,41 @@ const EMPTY_WRITE_PAD: u8 = 0x00u8;
 #[allow(unused)]
 const MAX_DMA_SIZE: usize = 32736;
 
+/// Swap the bytes of both 16 bit halves of a FIFO word
+fn swap_halfwords(word: u32) -> u32 {
+    (word & 0x00ff_00ff) << 8 | (word & 0xff00_ff00) >> 8
+}
+
+/// Check that `len` bytes can be transferred with byte swapping enabled
+fn check_word_length(byte_swap: bool, len: usize) -> Result<(), Error> {
+    if byte_swap && len % 2 != 0 {
+        return Err(Error::InvalidWordLength);
+    }
+
+    Ok(())
+}
+
 #[derive(Debug, Clone, Copy)]
 pub enum Error {
     DmaError(DmaError),
     MaxDmaTransferSizeExceeded,
+    /// The requested frequency can't be derived from the selected clock
+    /// source
+    FrequencyOutOfRange,
+    /// The data doesn't fit into the FIFO, so it can't be transferred in a
+    /// single transaction
+    FifoSizeExceeded,
+    /// The transfer queue is full
+    QueueFull,
+    /// Byte swapping is enabled but the data isn't a multiple of 16 bit words
+    InvalidWordLength,
+    /// Byte swapping is enabled, DMA transfers can't swap the bytes
+    ByteSwapUnsupported,
+    /// The selected clock source isn't available on this chip
+    UnsupportedClockSource,
+    /// The clock configuration was locked via
+    /// [ClockControl::freeze_locked](crate::clock::ClockControl::freeze_locked)
+    ClocksLocked,
     Unknown,
 }
 
@@ -103,8 +134,37 @@ pub enum SpiMode {
     Mode3,
 }
 
+/// Chip select behaviour during transfers
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpiCsMode {
+    /// CS is held active for the whole transfer (default), also across the
+    /// FIFO sized chunks of long transfers, by the `CS_KEEP_ACTIVE` bit
+    KeepActive,
+    /// CS is released between every word. Every word is sent as an individual
+    /// SPI transaction, the hardware deasserts CS after each of them. This is
+    /// a lot slower and only useful for devices which expect CS to toggle
+    /// after each byte.
+    PerWord,
+}
+
+/// Clock source of the SPI master clock
+///
+/// The ESP32 and ESP32-S2 always clock SPI from APB.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum SpiClockSource {
+    /// APB clock (derived from the PLL, unless the CPU runs from XTAL)
+    Apb,
+    /// XTAL clock, keeps running when the PLL is disabled
+    ///
+    /// Not available on the ESP32 and ESP32-S2, selecting it returns
+    /// [Error::UnsupportedClockSource] there.
+    Xtal,
+}
+
 pub struct Spi<T> {
     spi: T,
+    cs_mode: SpiCsMode,
+    byte_swap: bool,
 }
 
 impl<T> Spi<T>
@@ -208,7 +268,11 @@ where
     ) -> Self {
         spi.enable_peripheral(peripheral_clock_control);
 
-        let mut spi = Self { spi };
+        let mut spi = Self {
+            spi,
+            cs_mode: SpiCsMode::KeepActive,
+            byte_swap: false,
+        };
         spi.spi.setup(frequency, clocks);
         spi.spi.init();
         spi.spi.set_data_mode(mode);
@@ -216,14 +280,165 @@ where
         spi
     }
 
+    /// Change the bus frequency, this also selects APB as clock source
     pub fn change_bus_frequency(&mut self, frequency: HertzU32, clocks: &Clocks) {
         self.spi.ch_bus_freq(frequency, clocks);
     }
 
+    /// Change the clock source and compute the dividers for `frequency` from
+    /// it
+    ///
+    /// Returns [Error::UnsupportedClockSource] if the chip can't clock SPI
+    /// from `source` and [Error::FrequencyOutOfRange] if `frequency` can't be
+    /// derived from it, the configuration is left unchanged in both cases.
+    /// While the clock configuration is locked, [Error::ClocksLocked] is
+    /// returned.
+    pub fn change_clock_source(
+        &mut self,
+        source: SpiClockSource,
+        frequency: HertzU32,
+        clocks: &Clocks,
+    ) -> Result<(), Error> {
+        if crate::clock::is_locked() {
+            return Err(Error::ClocksLocked);
+        }
+
+        let source_freq = match source {
+            SpiClockSource::Apb => clocks.apb_clock,
+            #[cfg(not(any(esp32, esp32s2)))]
+            SpiClockSource::Xtal => clocks.xtal_clock,
+            #[cfg(any(esp32, esp32s2))]
+            SpiClockSource::Xtal => return Err(Error::UnsupportedClockSource),
+        };
+
+        // pre is clamped to 16 and n to 64 when calculating the dividers
+        if frequency > source_freq || frequency < source_freq / (16 * 64) {
+            return Err(Error::FrequencyOutOfRange);
+        }
+
+        self.spi.ch_clock_source(source, frequency, source_freq);
+
+        Ok(())
+    }
+
+    /// Select whether CS is held active for the whole transfer or toggled
+    /// between every word.
+    ///
+    /// This only affects the blocking transfer functions, single words
+    /// written via `FullDuplex` are always sent as individual transactions.
+    /// The blocking functions return once the last transaction is done, so
+    /// that CS can be released.
+    pub fn set_cs_mode(&mut self, mode: SpiCsMode) {
+        self.cs_mode = mode;
+    }
+
+    /// Swap the bytes of every 16 bit word of written and read data
+    ///
+    /// For devices which expect big endian words, e.g. RGB565 pixels for a
+    /// display. This is a software swap done by the CPU while it copies the
+    /// data to and from the FIFO, so it only applies to the non-DMA transfer
+    /// functions. While enabled, the length of all transfers must be a multiple
+    /// of 2 bytes, otherwise [Error::InvalidWordLength] is returned. Single
+    /// byte writes via `FullDuplex` and [SpiCsMode::PerWord] can't be used.
+    ///
+    /// DMA transfers read and write the buffers directly, an
+    /// [SpiDma](dma::SpiDma) created from this instance returns
+    /// [Error::ByteSwapUnsupported] while byte swapping is enabled. Swap the
+    /// bytes of DMA buffers in place before the transfer instead, e.g. when
+    /// rendering the framebuffer.
+    pub fn set_byte_swap(&mut self, byte_swap: bool) {
+        self.byte_swap = byte_swap;
+    }
+
     /// Return the raw interface to the underlying peripheral instance
     pub fn free(self) -> T {
         self.spi
     }
+
+    /// Write `tx` and then read `rx.len()` bytes in a single SPI transaction
+    ///
+    /// CS stays asserted between writing and reading, as required for
+    /// register reads of many sensors. Since this is done within one
+    /// transaction, `tx.len() + rx.len()` must not exceed the FIFO size,
+    /// otherwise [Error::FifoSizeExceeded] is returned.
+    pub fn write_read(&mut self, tx: &[u8], rx: &mut [u8]) -> Result<(), Error> {
+        let total = tx.len() + rx.len();
+        if total > FIFO_SIZE {
+            return Err(Error::FifoSizeExceeded);
+        }
+        check_word_length(self.byte_swap, tx.len())?;
+
+        let mut buffer = [EMPTY_WRITE_PAD; FIFO_SIZE];
+        buffer[..tx.len()].copy_from_slice(tx);
+
+        self.spi.write_bytes(&buffer[..total], self.byte_swap)?;
+        self.spi.flush()?;
+        self.spi
+            .read_bytes_from_fifo(&mut buffer[..total], self.byte_swap)?;
+
+        rx.copy_from_slice(&buffer[tx.len()..total]);
+
+        Ok(())
+    }
+
+    /// Number of bytes sent within a single SPI transaction
+    fn chunk_size(&self) -> usize {
+        match self.cs_mode {
+            SpiCsMode::KeepActive => FIFO_SIZE,
+            SpiCsMode::PerWord => 1,
+        }
+    }
+
+    /// Run the transactions of `f`, keeping CS asserted between them in
+    /// [SpiCsMode::KeepActive]
+    ///
+    /// Returns once the last transaction is done.
+    fn with_cs<R>(&mut self, f: impl FnOnce(&mut Self) -> Result<R, Error>) -> Result<R, Error> {
+        let keep_active = self.cs_mode == SpiCsMode::KeepActive;
+        if keep_active {
+            self.spi.set_cs_keep_active(true);
+        }
+
+        let result = f(self);
+        let flushed = self.spi.flush();
+
+        if keep_active {
+            self.spi.set_cs_keep_active(false);
+        }
+
+        let value = result?;
+        flushed?;
+        Ok(value)
+    }
+
+    fn write_words(&mut self, words: &[u8]) -> Result<(), Error> {
+        self.with_cs(|this| {
+            for chunk in words.chunks(this.chunk_size()) {
+                this.spi.flush()?;
+                this.spi.write_bytes(chunk, this.byte_swap)?;
+            }
+            Ok(())
+        })
+    }
+
+    fn read_words(&mut self, words: &mut [u8]) -> Result<(), Error> {
+        words.fill(EMPTY_WRITE_PAD);
+        self.transfer_words(words)?;
+        Ok(())
+    }
+
+    fn transfer_words<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
+        self.with_cs(|this| {
+            for chunk in words.chunks_mut(this.chunk_size()) {
+                this.spi.write_bytes(chunk, this.byte_swap)?;
+                this.spi.flush()?;
+                this.spi.read_bytes_from_fifo(chunk, this.byte_swap)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(words)
+    }
 }
 
 impl<T> embedded_hal::spi::FullDuplex<u8> for Spi<T>
@@ -237,6 +452,7 @@ where
     }
 
     fn send(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        check_word_length(self.byte_swap, 1)?;
         self.spi.write_byte(word)
     }
 }
@@ -248,7 +464,7 @@ where
     type Error = Error;
 
     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
-        self.spi.transfer(words)
+        self.transfer_words(words)
     }
 }
 
@@ -259,7 +475,7 @@ where
     type Error = Error;
 
     fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-        self.spi.write_bytes(words)?;
+        self.write_words(words)?;
         self.spi.flush()?;
         Ok(())
     }
@@ -278,10 +494,14 @@ pub mod dma {
     use crate::dma::{
         private::{Rx, Spi2Peripheral, SpiPeripheral, Tx},
         Channel,
+        DmaError,
         DmaTransfer,
         DmaTransferRxTx,
     };
 
+    /// Called with the buffer of a queued transfer once it completed
+    pub type QueuedTransferCallback = fn(&'static [u8]);
+
     pub trait WithDmaSpi2<T, RX, TX, P>
     where
         T: Instance + Spi2Instance,
@@ -316,6 +536,7 @@ pub mod dma {
             SpiDma {
                 spi: self.spi,
                 channel,
+                byte_swap: self.byte_swap,
             }
         }
     }
@@ -334,6 +555,7 @@ pub mod dma {
             SpiDma {
                 spi: self.spi,
                 channel,
+                byte_swap: self.byte_swap,
             }
         }
     }
@@ -350,6 +572,22 @@ pub mod dma {
         tbuffer: TBUFFER,
     }
 
+    impl<T, TX, RX, P, RXBUF, TXBUF> SpiDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Check if both DMA directions are done, see [Channel::is_done]
+        ///
+        /// The SPI may still shift out the last bytes afterwards, `wait`
+        /// takes care of that.
+        pub fn is_done(&mut self) -> bool {
+            self.spi_dma.channel.is_done()
+        }
+    }
+
     impl<T, TX, RX, P, RXBUF, TXBUF> DmaTransferRxTx<RXBUF, TXBUF, SpiDma<T, TX, RX, P>>
         for SpiDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>
     where
@@ -404,6 +642,24 @@ pub mod dma {
         buffer: BUFFER,
     }
 
+    impl<T, TX, RX, P, BUFFER> SpiDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Check if the DMA transfer is done, see [Channel::is_done]
+        ///
+        /// The direction which isn't used by this transfer counts as done.
+        ///
+        /// The SPI may still shift out the last bytes afterwards, `wait`
+        /// takes care of that.
+        pub fn is_done(&mut self) -> bool {
+            self.spi_dma.channel.is_done()
+        }
+    }
+
     impl<T, TX, RX, P, BUFFER> DmaTransfer<BUFFER, SpiDma<T, TX, RX, P>>
         for SpiDmaTransfer<T, TX, RX, P, BUFFER>
     where
@@ -454,6 +710,7 @@ pub mod dma {
     {
         pub(crate) spi: T,
         pub(crate) channel: Channel<TX, RX, P>,
+        byte_swap: bool,
     }
 
     impl<T, TX, RX, P> SpiDma<T, TX, RX, P>
@@ -468,6 +725,34 @@ pub mod dma {
             self.spi
         }
 
+        /// Return the raw interface to the underlying peripheral instance and
+        /// the DMA channel, e.g. to use the channel for another peripheral
+        pub fn free_with_channel(self) -> (T, Channel<TX, RX, P>) {
+            (self.spi, self.channel)
+        }
+
+        /// Number of bytes received by the last DMA read or transfer.
+        ///
+        /// Returns [DmaError::DescriptorEmpty] if the incoming data exceeded
+        /// the receive buffer. In that case the remainder was dropped and the
+        /// transfer should be retried with a bigger buffer.
+        pub fn received_length(&mut self) -> Result<usize, super::Error> {
+            if self.channel.rx.has_dscr_empty_error() {
+                return Err(super::Error::DmaError(DmaError::DescriptorEmpty));
+            }
+
+            Ok(self.channel.rx.received_length())
+        }
+
+        /// DMA transfers can't swap the bytes, see [Spi::set_byte_swap]
+        fn check_byte_swap(&self) -> Result<(), super::Error> {
+            if self.byte_swap {
+                return Err(super::Error::ByteSwapUnsupported);
+            }
+
+            Ok(())
+        }
+
         /// Perform a DMA write.
         ///
         /// This will return a [SpiDmaTransfer] owning the buffer(s) and the SPI
@@ -480,6 +765,7 @@ pub mod dma {
         where
             TXBUF: ReadBuffer<Word = u8>,
         {
+            self.check_byte_swap()?;
             let (ptr, len) = unsafe { words.read_buffer() };
 
             if len > MAX_DMA_SIZE {
@@ -506,6 +792,7 @@ pub mod dma {
         where
             RXBUF: WriteBuffer<Word = u8>,
         {
+            self.check_byte_swap()?;
             let (ptr, len) = unsafe { words.write_buffer() };
 
             if len > MAX_DMA_SIZE {
@@ -534,6 +821,7 @@ pub mod dma {
             TXBUF: ReadBuffer<Word = u8>,
             RXBUF: WriteBuffer<Word = u8>,
         {
+            self.check_byte_swap()?;
             let (write_ptr, write_len) = unsafe { words.read_buffer() };
             let (read_ptr, read_len) = unsafe { read_buffer.write_buffer() };
 
@@ -557,6 +845,187 @@ pub mod dma {
         }
     }
 
+    impl<T, TX, RX, P> SpiDma<T, TX, RX, P>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Turn this instance into a [SpiDmaQueue] holding up to `N` pending
+        /// write transfers
+        ///
+        /// `callback` is invoked from [SpiDmaQueue::process] for every
+        /// completed transfer.
+        pub fn into_queue<const N: usize>(
+            self,
+            callback: Option<QueuedTransferCallback>,
+        ) -> SpiDmaQueue<T, TX, RX, P, N> {
+            SpiDmaQueue {
+                spi_dma: self,
+                buffers: [None; N],
+                head: 0,
+                len: 0,
+                in_flight: false,
+                callback,
+            }
+        }
+    }
+
+    /// A queue of DMA write transfers
+    ///
+    /// Transfers are started back to back: as soon as [SpiDmaQueue::process]
+    /// notices that a transfer completed, the next queued one is started. Call
+    /// it from the interrupt handler signalling the end of a transfer (see
+    /// [SpiDmaQueue::listen]) or poll it from the main loop.
+    ///
+    /// Up to `N` transfers can be pending, including the one in flight. Each
+    /// buffer can hold up to 32736 bytes. The queue itself costs 8 bytes of
+    /// RAM per slot, the buffers are not copied and need to be `'static`.
+    pub struct SpiDmaQueue<T, TX, RX, P, const N: usize>
+    where
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        spi_dma: SpiDma<T, TX, RX, P>,
+        buffers: [Option<&'static [u8]>; N],
+        head: usize,
+        len: usize,
+        in_flight: bool,
+        callback: Option<QueuedTransferCallback>,
+    }
+
+    impl<T, TX, RX, P, const N: usize> SpiDmaQueue<T, TX, RX, P, N>
+    where
+        T: InstanceDma<TX, RX>,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Queue a write transfer, it's started right away if the bus is idle
+        ///
+        /// Returns [super::Error::QueueFull] if `N` transfers are already
+        /// pending.
+        pub fn queue_transfer(&mut self, buffer: &'static [u8]) -> Result<(), super::Error> {
+            self.spi_dma.check_byte_swap()?;
+            if buffer.len() > MAX_DMA_SIZE {
+                return Err(super::Error::MaxDmaTransferSizeExceeded);
+            }
+
+            if self.len == N {
+                return Err(super::Error::QueueFull);
+            }
+
+            self.buffers[(self.head + self.len) % N] = Some(buffer);
+            self.len += 1;
+
+            if !self.in_flight {
+                self.start_next()?;
+            }
+
+            Ok(())
+        }
+
+        /// Complete a finished transfer and start the next one
+        ///
+        /// Does nothing while a transfer is still in progress, so it's safe to
+        /// call it from any interrupt handler or in a loop.
+        pub fn process(&mut self) -> Result<(), super::Error> {
+            if !self.in_flight || !self.is_bus_idle() {
+                return Ok(());
+            }
+
+            self.spi_dma.spi.clear_dma_interrupts();
+            self.in_flight = false;
+            let done = self.buffers[self.head].take();
+            self.head = (self.head + 1) % N;
+            self.len -= 1;
+
+            let result = if self.len > 0 {
+                self.start_next()
+            } else {
+                Ok(())
+            };
+
+            if let (Some(callback), Some(buffer)) = (self.callback, done) {
+                callback(buffer);
+            }
+
+            result
+        }
+
+        /// Enable the transfer done interrupt of the SPI peripheral
+        ///
+        /// The interrupt is cleared by [SpiDmaQueue::process].
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        pub fn listen(&mut self) {
+            self.spi_dma
+                .spi
+                .register_block()
+                .dma_int_ena
+                .modify(|_, w| w.trans_done_int_ena().set_bit());
+        }
+
+        /// Disable the transfer done interrupt of the SPI peripheral
+        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        pub fn unlisten(&mut self) {
+            self.spi_dma
+                .spi
+                .register_block()
+                .dma_int_ena
+                .modify(|_, w| w.trans_done_int_ena().clear_bit());
+        }
+
+        /// Number of pending transfers, including the one in flight
+        pub fn len(&self) -> usize {
+            self.len
+        }
+
+        /// `true` if no transfer is pending
+        pub fn is_empty(&self) -> bool {
+            self.len == 0
+        }
+
+        /// Maximum number of pending transfers
+        pub const fn capacity(&self) -> usize {
+            N
+        }
+
+        /// Wait for all pending transfers to complete and return the
+        /// [SpiDma] instance
+        pub fn free(mut self) -> Result<SpiDma<T, TX, RX, P>, super::Error> {
+            while !self.is_empty() {
+                self.process()?;
+            }
+
+            Ok(self.spi_dma)
+        }
+
+        fn is_bus_idle(&self) -> bool {
+            self.spi_dma
+                .spi
+                .register_block()
+                .cmd
+                .read()
+                .usr()
+                .bit_is_clear()
+        }
+
+        fn start_next(&mut self) -> Result<(), super::Error> {
+            if let Some(buffer) = self.buffers[self.head] {
+                self.spi_dma.spi.start_write_bytes_dma(
+                    buffer.as_ptr(),
+                    buffer.len(),
+                    &mut self.spi_dma.channel.tx,
+                )?;
+                self.in_flight = true;
+            }
+
+            Ok(())
+        }
+    }
+
     impl<T, TX, RX, P> embedded_hal::blocking::spi::Transfer<u8> for SpiDma<T, TX, RX, P>
     where
         T: InstanceDma<TX, RX>,
@@ -567,6 +1036,7 @@ pub mod dma {
         type Error = super::Error;
 
         fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Self::Error> {
+            self.check_byte_swap()?;
             self.spi
                 .transfer_in_place_dma(words, &mut self.channel.tx, &mut self.channel.rx)
         }
@@ -582,6 +1052,7 @@ pub mod dma {
         type Error = super::Error;
 
         fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+            self.check_byte_swap()?;
             self.spi.write_bytes_dma(words, &mut self.channel.tx)?;
             self.spi.flush()?;
             Ok(())
@@ -614,6 +1085,7 @@ pub mod dma {
         {
             /// See also: [`write_bytes`].
             fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
+                self.check_byte_swap()?;
                 self.spi.write_bytes_dma(words, &mut self.channel.tx)?;
                 self.flush()
             }
@@ -627,6 +1099,7 @@ pub mod dma {
             P: SpiPeripheral,
         {
             fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+                self.check_byte_swap()?;
                 self.spi
                     .transfer_dma(&[], words, &mut self.channel.tx, &mut self.channel.rx)?;
                 self.flush()
@@ -649,6 +1122,7 @@ pub mod dma {
             /// been read. Reading and writing happens
             /// simultaneously.
             fn transfer(&mut self, read: &mut [u8], write: &[u8]) -> Result<(), Self::Error> {
+                self.check_byte_swap()?;
                 self.spi
                     .transfer_dma(write, read, &mut self.channel.tx, &mut self.channel.rx)?;
                 self.flush()
@@ -661,6 +1135,7 @@ pub mod dma {
             /// [`write`](SpiBusWrite::write), [`flush`](SpiBusFlush::flush) and
             /// [`read`](SpiBusRead::read).
             fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
+                self.check_byte_swap()?;
                 self.spi.transfer_in_place_dma(
                     words,
                     &mut self.channel.tx,
@@ -718,6 +1193,7 @@ mod ehal1 {
         }
 
         fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+            check_word_length(self.byte_swap, 1)?;
             self.spi.write_byte(word)
         }
     }
@@ -728,7 +1204,7 @@ mod ehal1 {
     {
         /// See also: [`write_bytes`].
         fn write(&mut self, words: &[u8]) -> Result<(), Self::Error> {
-            self.spi.write_bytes(words)
+            self.write_words(words)
         }
     }
 
@@ -738,7 +1214,7 @@ mod ehal1 {
     {
         /// See also: [`read_bytes`].
         fn read(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-            self.spi.read_bytes(words)
+            self.read_words(words)
         }
     }
 
@@ -761,41 +1237,42 @@ mod ehal1 {
                 SpiBusRead::read(self, read)?;
             }
 
+            let chunk_size = self.chunk_size();
             let mut write_from = 0;
             let mut read_from = 0;
 
-            loop {
+            self.with_cs(|this| loop {
                 // How many bytes we write in this chunk
-                let write_inc = core::cmp::min(FIFO_SIZE, write.len() - write_from);
+                let write_inc = core::cmp::min(chunk_size, write.len() - write_from);
                 let write_to = write_from + write_inc;
                 // How many bytes we read in this chunk
-                let read_inc = core::cmp::min(FIFO_SIZE, read.len() - read_from);
+                let read_inc = core::cmp::min(chunk_size, read.len() - read_from);
                 let read_to = read_from + read_inc;
 
                 if (write_inc == 0) && (read_inc == 0) {
-                    break;
+                    break Ok(());
                 }
 
                 if write_to < read_to {
                     // Read more than we write, must pad writing part with zeros
                     let mut empty = [EMPTY_WRITE_PAD; FIFO_SIZE];
                     empty[0..write_inc].copy_from_slice(&write[write_from..write_to]);
-                    SpiBusWrite::write(self, &empty)?;
+                    this.spi.write_bytes(&empty[..read_inc], this.byte_swap)?;
                 } else {
-                    SpiBusWrite::write(self, &write[write_from..write_to])?;
+                    this.spi
+                        .write_bytes(&write[write_from..write_to], this.byte_swap)?;
                 }
 
-                SpiBusFlush::flush(self)?;
+                this.spi.flush()?;
 
                 if read_inc > 0 {
-                    self.spi
-                        .read_bytes_from_fifo(&mut read[read_from..read_to])?;
+                    this.spi
+                        .read_bytes_from_fifo(&mut read[read_from..read_to], this.byte_swap)?;
                 }
 
                 write_from = write_to;
                 read_from = read_to;
-            }
-            Ok(())
+            })
         }
 
         /// Transfer data in place.
@@ -805,12 +1282,7 @@ mod ehal1 {
         /// [`write`](SpiBusWrite::write), [`flush`](SpiBusFlush::flush) and
         /// [`read`](SpiBusRead::read).
         fn transfer_in_place(&mut self, words: &mut [u8]) -> Result<(), Self::Error> {
-            // Since we have the traits so neatly implemented above, use them!
-            for chunk in words.chunks_mut(FIFO_SIZE) {
-                SpiBusWrite::write(self, chunk)?;
-                SpiBusFlush::flush(self)?;
-                self.spi.read_bytes_from_fifo(chunk)?;
-            }
+            self.transfer_words(words)?;
             Ok(())
         }
     }
@@ -1157,6 +1629,20 @@ pub trait Instance {
 
     fn spi_num(&self) -> u8;
 
+    /// Keep CS asserted after the current transaction, until cleared again
+    fn set_cs_keep_active(&mut self, keep_active: bool) {
+        let reg_block = self.register_block();
+
+        #[cfg(esp32)]
+        reg_block
+            .pin
+            .modify(|_, w| w.cs_keep_active().bit(keep_active));
+        #[cfg(not(esp32))]
+        reg_block
+            .misc
+            .modify(|_, w| w.cs_keep_active().bit(keep_active));
+    }
+
     fn init(&mut self) {
         let reg_block = self.register_block();
         reg_block.user.modify(|_, w| {
@@ -1203,6 +1689,10 @@ pub trait Instance {
         // FIXME: this might not be always true
         let apb_clk_freq: HertzU32 = HertzU32::Hz(clocks.apb_clock.to_Hz());
 
+        self.setup_dividers(frequency, apb_clk_freq);
+    }
+
+    fn setup_dividers(&mut self, frequency: HertzU32, apb_clk_freq: HertzU32) {
         let reg_val: u32;
         let duty_cycle = 128;
 
@@ -1358,6 +1848,42 @@ pub trait Instance {
         });
     }
 
+    fn ch_clock_source(
+        &mut self,
+        source: SpiClockSource,
+        frequency: HertzU32,
+        source_freq: HertzU32,
+    ) {
+        // Disable clock source
+        #[cfg(not(any(esp32, esp32s2)))]
+        self.register_block().clk_gate.modify(|_, w| {
+            w.clk_en()
+                .clear_bit()
+                .mst_clk_active()
+                .clear_bit()
+                .mst_clk_sel()
+                .clear_bit()
+        });
+
+        // Change clock frequency
+        self.setup_dividers(frequency, source_freq);
+
+        // Enable the selected clock source
+        #[cfg(not(any(esp32, esp32s2)))]
+        self.register_block().clk_gate.modify(|_, w| {
+            w.clk_en()
+                .set_bit()
+                .mst_clk_active()
+                .set_bit()
+                .mst_clk_sel()
+                .bit(source == SpiClockSource::Apb)
+        });
+
+        // `change_clock_source` rejects everything else
+        #[cfg(any(esp32, esp32s2))]
+        debug_assert!(source == SpiClockSource::Apb);
+    }
+
     fn read_byte(&mut self) -> nb::Result<u8, Error> {
         let reg_block = self.register_block();
 
@@ -1394,27 +1920,49 @@ pub trait Instance {
     /// all bytes of the last chunk to transmit have been sent to the wire. If
     /// you must ensure that the whole messages was written correctly, use
     /// [`flush`].
+    ///
+    /// With `byte_swap` the bytes of every 16 bit word are swapped on the
+    /// way.
     // FIXME: See below.
-    fn write_bytes(&mut self, words: &[u8]) -> Result<(), Error> {
+    fn write_bytes(&mut self, words: &[u8], byte_swap: bool) -> Result<(), Error> {
         let reg_block = self.register_block();
         let num_chunks = words.len() / FIFO_SIZE;
 
+        check_word_length(byte_swap, words.len())?;
+
         // The fifo has a limited fixed size, so the data must be chunked and then
         // transmitted
         for (i, chunk) in words.chunks(FIFO_SIZE).enumerate() {
             self.configure_datalen(chunk.len() as u32 * 8);
 
             let fifo_ptr = reg_block.w0.as_ptr();
-            unsafe {
-                // It seems that `copy_nonoverlapping` is significantly faster than regular
-                // `copy`, by about 20%... ?
-                core::ptr::copy_nonoverlapping::<u32>(
-                    chunk.as_ptr() as *const u32,
-                    fifo_ptr as *mut u32,
-                    // FIXME: Using any other transfer length **does not work**. I don't understand
-                    // why.
-                    FIFO_SIZE / 4,
-                );
+            if byte_swap {
+                let mut swapped = [0u32; FIFO_SIZE / 4];
+                for (word, bytes) in swapped.iter_mut().zip(chunk.chunks(4)) {
+                    let mut buffer = [EMPTY_WRITE_PAD; 4];
+                    buffer[..bytes.len()].copy_from_slice(bytes);
+                    *word = swap_halfwords(u32::from_le_bytes(buffer));
+                }
+
+                unsafe {
+                    core::ptr::copy_nonoverlapping(
+                        swapped.as_ptr(),
+                        fifo_ptr as *mut u32,
+                        FIFO_SIZE / 4,
+                    );
+                }
+            } else {
+                unsafe {
+                    // It seems that `copy_nonoverlapping` is significantly faster than regular
+                    // `copy`, by about 20%... ?
+                    core::ptr::copy_nonoverlapping::<u32>(
+                        chunk.as_ptr() as *const u32,
+                        fifo_ptr as *mut u32,
+                        // FIXME: Using any other transfer length **does not work**. I don't
+                        // understand why.
+                        FIFO_SIZE / 4,
+                    );
+                }
             }
 
             self.update();
@@ -1445,9 +1993,9 @@ pub trait Instance {
         let empty_array = [EMPTY_WRITE_PAD; FIFO_SIZE];
 
         for chunk in words.chunks_mut(FIFO_SIZE) {
-            self.write_bytes(&empty_array[0..chunk.len()])?;
+            self.write_bytes(&empty_array[0..chunk.len()], false)?;
             self.flush()?;
-            self.read_bytes_from_fifo(chunk)?;
+            self.read_bytes_from_fifo(chunk, false)?;
         }
         Ok(())
     }
@@ -1457,19 +2005,25 @@ pub trait Instance {
     /// Copies the contents of the SPI receive FIFO into `words`. This function
     /// doesn't perform flushing. If you want to read the response to
     /// something you have written before, consider using [`transfer`]
-    /// instead.
+    /// instead. With `byte_swap` the bytes of every 16 bit word are swapped
+    /// on the way.
     // FIXME: Using something like `core::slice::from_raw_parts` and
     // `copy_from_slice` on the receive registers works only for the esp32 and
     // esp32c3 varaints. The reason for this is unknown.
-    fn read_bytes_from_fifo(&mut self, words: &mut [u8]) -> Result<(), Error> {
+    fn read_bytes_from_fifo(&mut self, words: &mut [u8], byte_swap: bool) -> Result<(), Error> {
         let reg_block = self.register_block();
 
+        check_word_length(byte_swap, words.len())?;
+
         for chunk in words.chunks_mut(FIFO_SIZE) {
             self.configure_datalen(chunk.len() as u32 * 8);
 
             let mut fifo_ptr = reg_block.w0.as_ptr();
             for index in (0..chunk.len()).step_by(4) {
-                let reg_val = unsafe { *fifo_ptr };
+                let mut reg_val = unsafe { *fifo_ptr };
+                if byte_swap {
+                    reg_val = swap_halfwords(reg_val);
+                }
                 let bytes = reg_val.to_le_bytes();
 
                 let len = usize::min(chunk.len(), index + 4) - index;
@@ -1496,9 +2050,9 @@ pub trait Instance {
 
     fn transfer<'w>(&mut self, words: &'w mut [u8]) -> Result<&'w [u8], Error> {
         for chunk in words.chunks_mut(FIFO_SIZE) {
-            self.write_bytes(chunk)?;
+            self.write_bytes(chunk, false)?;
             self.flush()?;
-            self.read_bytes_from_fifo(chunk)?;
+            self.read_bytes_from_fifo(chunk, false)?;
         }
 
         Ok(words)
@@ -0,0 +1,689 @@
+//! # Serial Peripheral Interface - Slave Mode
+//!
+//! In slave mode the SPI peripheral responds to an external master. All data
+//! is moved via DMA: the buffer receiving the data clocked in by the master
+//! and the response clocked out to it are queued before the master starts the
+//! transaction.
+//!
+//! ```rust,ignore
+//! let mut spi = SpiSlave::new(
+//!     peripherals.SPI2,
+//!     sclk,
+//!     mosi,
+//!     miso,
+//!     cs,
+//!     SpiMode::Mode0,
+//!     &mut peripheral_clock_control,
+//! )
+//! .with_dma(dma_channel.configure(
+//!     false,
+//!     &mut descriptors,
+//!     &mut rx_descriptors,
+//!     DmaPriority::Priority0,
+//! ));
+//!
+//! let transfer = spi.dma_transfer(response, receive_buffer).unwrap();
+//! // `transfer.is_done()` turns true once the master deasserted CS
+//! let (receive_buffer, response, spi) = transfer.wait();
+//! let len = spi.received_length().unwrap();
+//! ```
+//!
+//! ## Transaction length
+//!
+//! A transaction ends when the master deasserts CS, the length is up to the
+//! master. If it clocks fewer bytes than the buffers hold, the transfer
+//! completes nonetheless and [dma::SpiSlaveDma::received_length] tells how
+//! many bytes arrived. This also covers CS being deasserted in the middle of a
+//! frame: the transfer completes with the data received so far, an incomplete
+//! last byte isn't counted (see [dma::SpiSlaveDma::received_bits]).
+//!
+//! If the master clocks more bytes than the receive buffer holds the excess is
+//! dropped and [dma::SpiSlaveDma::received_length] returns an error. Once the
+//! response is exhausted the slave keeps clocking out undefined data.
+//!
+//! Only available on the chips with GDMA (ESP32-C2, ESP32-C3 and ESP32-S3),
+//! using SPI2 or, on the ESP32-S3, SPI3.
+
+use crate::{
+    dma::DmaPeripheral,
+    spi::SpiMode,
+    system::PeripheralClockControl,
+    types::{InputSignal, OutputSignal},
+    InputPin,
+    OutputPin,
+};
+
+/// SPI peripheral in slave mode
+pub struct SpiSlave<T> {
+    spi: T,
+}
+
+impl<T> SpiSlave<T>
+where
+    T: Instance,
+{
+    /// Constructs an SPI slave instance in 8bit dataframe mode
+    pub fn new<SCK: InputPin, MOSI: InputPin, MISO: OutputPin, CS: InputPin>(
+        spi: T,
+        mut sck: SCK,
+        mut mosi: MOSI,
+        mut miso: MISO,
+        mut cs: CS,
+        mode: SpiMode,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Self {
+        sck.set_to_input()
+            .connect_input_to_peripheral(spi.sclk_in_signal());
+
+        mosi.set_to_input()
+            .connect_input_to_peripheral(spi.mosi_in_signal());
+
+        miso.set_to_push_pull_output()
+            .connect_peripheral_to_output(spi.miso_out_signal());
+
+        cs.set_to_input()
+            .connect_input_to_peripheral(spi.cs_in_signal());
+
+        spi.enable_peripheral(peripheral_clock_control);
+
+        let mut spi = Self { spi };
+        spi.spi.init_slave();
+        spi.spi.set_slave_data_mode(mode);
+
+        spi
+    }
+
+    /// Return the raw interface to the underlying peripheral instance
+    pub fn free(self) -> T {
+        self.spi
+    }
+}
+
+pub mod dma {
+    use core::mem;
+
+    use embedded_dma::{ReadBuffer, WriteBuffer};
+
+    use super::{Instance, SpiSlave};
+    #[cfg(esp32s3)]
+    use crate::{dma::private::Spi3Peripheral, spi::Spi3Instance};
+    use crate::{
+        dma::{
+            private::{Rx, Spi2Peripheral, SpiPeripheral, Tx},
+            Channel,
+            DmaError,
+            DmaTransfer,
+            DmaTransferRxTx,
+        },
+        spi::{Error, Spi2Instance},
+    };
+
+    /// The maximum amount of data received or sent in a single transaction
+    const MAX_DMA_SIZE: usize = 32736;
+
+    pub trait WithDmaSpi2<T, RX, TX, P>
+    where
+        T: Instance + Spi2Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        fn with_dma(self, channel: Channel<TX, RX, P>) -> SpiSlaveDma<T, TX, RX, P>;
+    }
+
+    #[cfg(esp32s3)]
+    pub trait WithDmaSpi3<T, RX, TX, P>
+    where
+        T: Instance + Spi3Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        fn with_dma(self, channel: Channel<TX, RX, P>) -> SpiSlaveDma<T, TX, RX, P>;
+    }
+
+    impl<T, RX, TX, P> WithDmaSpi2<T, RX, TX, P> for SpiSlave<T>
+    where
+        T: Instance + Spi2Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral + Spi2Peripheral,
+    {
+        fn with_dma(self, mut channel: Channel<TX, RX, P>) -> SpiSlaveDma<T, TX, RX, P> {
+            channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+            SpiSlaveDma {
+                spi: self.spi,
+                channel,
+            }
+        }
+    }
+
+    #[cfg(esp32s3)]
+    impl<T, RX, TX, P> WithDmaSpi3<T, RX, TX, P> for SpiSlave<T>
+    where
+        T: Instance + Spi3Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral + Spi3Peripheral,
+    {
+        fn with_dma(self, mut channel: Channel<TX, RX, P>) -> SpiSlaveDma<T, TX, RX, P> {
+            channel.tx.init_channel(); // no need to call this for both, TX and RX
+
+            SpiSlaveDma {
+                spi: self.spi,
+                channel,
+            }
+        }
+    }
+
+    /// A queued DMA transfer receiving and sending data
+    pub struct SpiSlaveDmaTransferRxTx<T, TX, RX, P, RBUFFER, TBUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        spi_dma: SpiSlaveDma<T, TX, RX, P>,
+        rbuffer: RBUFFER,
+        tbuffer: TBUFFER,
+    }
+
+    impl<T, TX, RX, P, RXBUF, TXBUF> SpiSlaveDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// `true` once the master ended the transaction by deasserting CS
+        pub fn is_done(&self) -> bool {
+            self.spi_dma.spi.is_transaction_done()
+        }
+
+        /// Clear the transaction done interrupt, see [SpiSlaveDma::listen]
+        pub fn clear_interrupt(&mut self) {
+            self.spi_dma.spi.clear_transaction_done();
+        }
+    }
+
+    impl<T, TX, RX, P, RXBUF, TXBUF> DmaTransferRxTx<RXBUF, TXBUF, SpiSlaveDma<T, TX, RX, P>>
+        for SpiSlaveDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Wait for the master to complete the transaction and return the
+        /// buffers and the SPI instance.
+        fn wait(self) -> (RXBUF, TXBUF, SpiSlaveDma<T, TX, RX, P>) {
+            while !self.is_done() {}
+
+            // `DmaTransfer` needs to have a `Drop` implementation, because we accept
+            // managed buffers that can free their memory on drop. Because of that
+            // we can't move out of the `DmaTransfer`'s fields, so we use `ptr::read`
+            // and `mem::forget`.
+            //
+            // NOTE(unsafe) There is no panic branch between getting the resources
+            // and forgetting `self`.
+            unsafe {
+                let rbuffer = core::ptr::read(&self.rbuffer);
+                let tbuffer = core::ptr::read(&self.tbuffer);
+                let payload = core::ptr::read(&self.spi_dma);
+                mem::forget(self);
+                (rbuffer, tbuffer, payload)
+            }
+        }
+    }
+
+    impl<T, TX, RX, P, RXBUF, TXBUF> Drop for SpiSlaveDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        fn drop(&mut self) {
+            while !self.is_done() {}
+        }
+    }
+
+    /// A queued DMA transfer either receiving or sending data
+    pub struct SpiSlaveDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        spi_dma: SpiSlaveDma<T, TX, RX, P>,
+        buffer: BUFFER,
+    }
+
+    impl<T, TX, RX, P, BUFFER> SpiSlaveDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// `true` once the master ended the transaction by deasserting CS
+        pub fn is_done(&self) -> bool {
+            self.spi_dma.spi.is_transaction_done()
+        }
+
+        /// Clear the transaction done interrupt, see [SpiSlaveDma::listen]
+        pub fn clear_interrupt(&mut self) {
+            self.spi_dma.spi.clear_transaction_done();
+        }
+    }
+
+    impl<T, TX, RX, P, BUFFER> DmaTransfer<BUFFER, SpiSlaveDma<T, TX, RX, P>>
+        for SpiSlaveDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Wait for the master to complete the transaction and return the
+        /// buffer and the SPI instance.
+        fn wait(self) -> (BUFFER, SpiSlaveDma<T, TX, RX, P>) {
+            while !self.is_done() {}
+
+            // NOTE(unsafe) There is no panic branch between getting the resources
+            // and forgetting `self`.
+            unsafe {
+                let buffer = core::ptr::read(&self.buffer);
+                let payload = core::ptr::read(&self.spi_dma);
+                mem::forget(self);
+                (buffer, payload)
+            }
+        }
+    }
+
+    impl<T, TX, RX, P, BUFFER> Drop for SpiSlaveDmaTransfer<T, TX, RX, P, BUFFER>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        fn drop(&mut self) {
+            while !self.is_done() {}
+        }
+    }
+
+    /// A DMA capable SPI slave instance
+    pub struct SpiSlaveDma<T, TX, RX, P>
+    where
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        pub(crate) spi: T,
+        pub(crate) channel: Channel<TX, RX, P>,
+    }
+
+    impl<T, TX, RX, P> SpiSlaveDma<T, TX, RX, P>
+    where
+        T: Instance,
+        TX: Tx,
+        RX: Rx,
+        P: SpiPeripheral,
+    {
+        /// Return the raw interface to the underlying peripheral instance
+        pub fn free(self) -> T {
+            self.spi
+        }
+
+        /// Return the raw interface to the underlying peripheral instance and
+        /// the DMA channel
+        pub fn free_with_channel(self) -> (T, Channel<TX, RX, P>) {
+            (self.spi, self.channel)
+        }
+
+        /// Enable the interrupt signalling that the master ended a transaction
+        ///
+        /// The interrupt stays pending until it's cleared via the
+        /// `clear_interrupt` function of the transfer.
+        pub fn listen(&mut self) {
+            self.spi
+                .register_block()
+                .dma_int_ena
+                .modify(|_, w| w.trans_done_int_ena().set_bit());
+        }
+
+        /// Disable the transaction done interrupt
+        pub fn unlisten(&mut self) {
+            self.spi
+                .register_block()
+                .dma_int_ena
+                .modify(|_, w| w.trans_done_int_ena().clear_bit());
+        }
+
+        /// Number of bits the master clocked in the last transaction
+        ///
+        /// Not a multiple of 8 if CS was deasserted in the middle of a byte.
+        pub fn received_bits(&self) -> usize {
+            self.spi.received_bits()
+        }
+
+        /// Number of complete bytes the master clocked in the last transaction
+        ///
+        /// Returns [DmaError::DescriptorEmpty] if the master sent more data
+        /// than the receive buffer holds. In that case the remainder
+        /// was dropped.
+        pub fn received_length(&mut self) -> Result<usize, Error> {
+            if self.channel.rx.has_dscr_empty_error() {
+                return Err(Error::DmaError(DmaError::DescriptorEmpty));
+            }
+
+            Ok(self.spi.received_bits() / 8)
+        }
+
+        /// Queue a transaction sending `words` and receiving into
+        /// `read_buffer`
+        ///
+        /// The transaction starts when the master asserts CS. The maximum
+        /// amount of data to be sent/received is 32736 bytes.
+        pub fn dma_transfer<TXBUF, RXBUF>(
+            mut self,
+            words: TXBUF,
+            mut read_buffer: RXBUF,
+        ) -> Result<SpiSlaveDmaTransferRxTx<T, TX, RX, P, RXBUF, TXBUF>, Error>
+        where
+            TXBUF: ReadBuffer<Word = u8>,
+            RXBUF: WriteBuffer<Word = u8>,
+        {
+            let (write_ptr, write_len) = unsafe { words.read_buffer() };
+            let (read_ptr, read_len) = unsafe { read_buffer.write_buffer() };
+
+            if write_len > MAX_DMA_SIZE || read_len > MAX_DMA_SIZE {
+                return Err(Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.spi.start_slave_transfer_dma(
+                Some((write_ptr, write_len)),
+                Some((read_ptr, read_len)),
+                &mut self.channel.tx,
+                &mut self.channel.rx,
+            )?;
+
+            Ok(SpiSlaveDmaTransferRxTx {
+                spi_dma: self,
+                rbuffer: read_buffer,
+                tbuffer: words,
+            })
+        }
+
+        /// Queue a transaction sending `words`, the data clocked in by the
+        /// master is ignored
+        ///
+        /// The maximum amount of data to be sent is 32736 bytes.
+        pub fn dma_write<TXBUF>(
+            mut self,
+            words: TXBUF,
+        ) -> Result<SpiSlaveDmaTransfer<T, TX, RX, P, TXBUF>, Error>
+        where
+            TXBUF: ReadBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { words.read_buffer() };
+
+            if len > MAX_DMA_SIZE {
+                return Err(Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.spi.start_slave_transfer_dma(
+                Some((ptr, len)),
+                None,
+                &mut self.channel.tx,
+                &mut self.channel.rx,
+            )?;
+
+            Ok(SpiSlaveDmaTransfer {
+                spi_dma: self,
+                buffer: words,
+            })
+        }
+
+        /// Queue a transaction receiving into `words`
+        ///
+        /// The maximum amount of data to be received is 32736 bytes.
+        pub fn dma_read<RXBUF>(
+            mut self,
+            mut words: RXBUF,
+        ) -> Result<SpiSlaveDmaTransfer<T, TX, RX, P, RXBUF>, Error>
+        where
+            RXBUF: WriteBuffer<Word = u8>,
+        {
+            let (ptr, len) = unsafe { words.write_buffer() };
+
+            if len > MAX_DMA_SIZE {
+                return Err(Error::MaxDmaTransferSizeExceeded);
+            }
+
+            self.spi.start_slave_transfer_dma(
+                None,
+                Some((ptr, len)),
+                &mut self.channel.tx,
+                &mut self.channel.rx,
+            )?;
+
+            Ok(SpiSlaveDmaTransfer {
+                spi_dma: self,
+                buffer: words,
+            })
+        }
+    }
+}
+
+pub trait Instance: crate::spi::Instance {
+    fn sclk_in_signal(&self) -> InputSignal;
+
+    fn mosi_in_signal(&self) -> InputSignal;
+
+    fn miso_out_signal(&self) -> OutputSignal;
+
+    fn cs_in_signal(&self) -> InputSignal;
+
+    fn dma_peripheral(&self) -> DmaPeripheral {
+        match self.spi_num() {
+            2 => DmaPeripheral::Spi2,
+            #[cfg(esp32s3)]
+            3 => DmaPeripheral::Spi3,
+            _ => panic!("Illegal SPI instance"),
+        }
+    }
+
+    fn init_slave(&mut self) {
+        let reg_block = self.register_block();
+
+        reg_block.clock.write(|w| unsafe { w.bits(0) });
+        reg_block.ctrl.write(|w| unsafe { w.bits(0) });
+        reg_block.misc.write(|w| unsafe { w.bits(0) });
+
+        // full duplex, use the whole buffer
+        reg_block.user.write(|w| {
+            w.doutdin()
+                .set_bit()
+                .usr_miso()
+                .set_bit()
+                .usr_mosi()
+                .set_bit()
+                .usr_miso_highpart()
+                .clear_bit()
+                .usr_mosi_highpart()
+                .clear_bit()
+        });
+
+        reg_block.clk_gate.modify(|_, w| {
+            w.clk_en()
+                .set_bit()
+                .mst_clk_active()
+                .clear_bit()
+                .mst_clk_sel()
+                .clear_bit()
+        });
+
+        reg_block.slave.write(|w| w.mode().set_bit());
+
+        // the inlink EOF is generated when the master deasserts CS
+        reg_block.dma_conf.modify(|_, w| w.rx_eof_en().clear_bit());
+
+        reg_block.dma_int_ena.write(|w| unsafe { w.bits(0) });
+    }
+
+    fn set_slave_data_mode(&mut self, data_mode: SpiMode) {
+        let reg_block = self.register_block();
+
+        let (idle_high, sample_rising, clk_mode_13) = match data_mode {
+            SpiMode::Mode0 => (false, false, false),
+            SpiMode::Mode1 => (false, true, true),
+            SpiMode::Mode2 => (true, true, false),
+            SpiMode::Mode3 => (true, false, true),
+        };
+
+        reg_block
+            .misc
+            .modify(|_, w| w.ck_idle_edge().bit(idle_high));
+        reg_block.user.modify(|_, w| {
+            w.rsck_i_edge()
+                .bit(sample_rising)
+                .tsck_i_edge()
+                .bit(sample_rising)
+        });
+        reg_block
+            .slave
+            .modify(|_, w| w.clk_mode_13().bit(clk_mode_13).rsck_data_out().clear_bit());
+    }
+
+    fn start_slave_transfer_dma<TX: crate::dma::private::Tx, RX: crate::dma::private::Rx>(
+        &mut self,
+        write: Option<(*const u8, usize)>,
+        read: Option<(*mut u8, usize)>,
+        tx: &mut TX,
+        rx: &mut RX,
+    ) -> Result<(), crate::spi::Error> {
+        let reg_block = self.register_block();
+
+        // forget about the state of the previous transaction
+        reg_block.slave.modify(|_, w| w.soft_reset().set_bit());
+        reg_block.slave.modify(|_, w| w.soft_reset().clear_bit());
+
+        reg_block.dma_conf.modify(|_, w| {
+            w.rx_afifo_rst()
+                .set_bit()
+                .buf_afifo_rst()
+                .set_bit()
+                .dma_afifo_rst()
+                .set_bit()
+        });
+        reg_block.dma_conf.modify(|_, w| {
+            w.rx_afifo_rst()
+                .clear_bit()
+                .buf_afifo_rst()
+                .clear_bit()
+                .dma_afifo_rst()
+                .clear_bit()
+        });
+
+        reg_block.dma_conf.modify(|_, w| {
+            w.dma_tx_ena()
+                .bit(write.is_some())
+                .dma_rx_ena()
+                .bit(read.is_some())
+        });
+
+        tx.is_done();
+        rx.is_done();
+
+        if let Some((ptr, len)) = write {
+            tx.prepare_transfer(self.dma_peripheral(), ptr, len)?;
+        }
+
+        if let Some((ptr, len)) = read {
+            rx.prepare_transfer(self.dma_peripheral(), ptr, len)?;
+        }
+
+        reg_block.dma_int_clr.write(|w| {
+            w.dma_infifo_full_err_int_clr()
+                .set_bit()
+                .dma_outfifo_empty_err_int_clr()
+                .set_bit()
+                .trans_done_int_clr()
+                .set_bit()
+        });
+
+        self.update();
+
+        reg_block.cmd.modify(|_, w| w.usr().set_bit());
+
+        Ok(())
+    }
+
+    fn is_transaction_done(&self) -> bool {
+        self.register_block()
+            .dma_int_raw
+            .read()
+            .trans_done_int_raw()
+            .bit_is_set()
+    }
+
+    fn clear_transaction_done(&self) {
+        self.register_block()
+            .dma_int_clr
+            .write(|w| w.trans_done_int_clr().set_bit());
+    }
+
+    fn received_bits(&self) -> usize {
+        self.register_block().slave1.read().slv_data_bitlen().bits() as usize
+    }
+}
+
+impl Instance for crate::pac::SPI2 {
+    #[inline(always)]
+    fn sclk_in_signal(&self) -> InputSignal {
+        InputSignal::FSPICLK
+    }
+
+    #[inline(always)]
+    fn mosi_in_signal(&self) -> InputSignal {
+        InputSignal::FSPID
+    }
+
+    #[inline(always)]
+    fn miso_out_signal(&self) -> OutputSignal {
+        OutputSignal::FSPIQ
+    }
+
+    #[inline(always)]
+    fn cs_in_signal(&self) -> InputSignal {
+        InputSignal::FSPICS0
+    }
+}
+
+#[cfg(esp32s3)]
+impl Instance for crate::pac::SPI3 {
+    #[inline(always)]
+    fn sclk_in_signal(&self) -> InputSignal {
+        InputSignal::SPI3_CLK
+    }
+
+    #[inline(always)]
+    fn mosi_in_signal(&self) -> InputSignal {
+        InputSignal::SPI3_D
+    }
+
+    #[inline(always)]
+    fn miso_out_signal(&self) -> OutputSignal {
+        OutputSignal::SPI3_Q
+    }
+
+    #[inline(always)]
+    fn cs_in_signal(&self) -> InputSignal {
+        InputSignal::SPI3_CS0
+    }
+}
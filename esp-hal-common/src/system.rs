@@ -8,6 +8,14 @@
 //! let system = peripherals.SYSTEM.split();
 //! let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 //! ```
+//!
+//! The cause of the last reset is available via [reset_reason], the chip or
+//! the current core can be reset via [software_reset] and
+//! [software_reset_cpu].
+
+use crate::interrupt::InterruptController;
+pub use crate::rtc_cntl::{reset_reason, software_reset, software_reset_cpu, ResetReason};
+
 #[cfg(not(esp32))]
 type SystemPeripheral = crate::pac::SYSTEM;
 #[cfg(esp32)]
@@ -26,14 +34,67 @@ pub enum Peripheral {
     Ledc,
     #[cfg(any(esp32c2, esp32c3))]
     ApbSarAdc,
+    #[cfg(any(esp32c2, esp32c3))]
+    Tsens,
+    #[cfg(any(esp32c3, esp32s3))]
+    Twai,
+    #[cfg(any(esp32c3, esp32s3))]
+    Uhci0,
+    #[cfg(any(esp32c3, esp32s3))]
+    I2s0,
+    #[cfg(esp32s3)]
+    LcdCam,
     #[cfg(any(esp32c2, esp32c3, esp32s3))]
     Gdma,
     #[cfg(any(esp32, esp32s2))]
     Dma,
     #[cfg(any(esp32s2, esp32s3))]
     Usb,
+    #[cfg(any(esp32, esp32s2, esp32s3))]
+    Pcnt,
+    #[cfg(any(esp32, esp32s3))]
+    Mcpwm0,
+    #[cfg(any(esp32, esp32s3))]
+    Mcpwm1,
+    Sha,
+    #[cfg(not(esp32c2))]
+    Aes,
+    /// WiFi/BT MAC and PHY clocks, see
+    /// [PeripheralClockControl::radio_clock_enable]
+    #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+    Radio,
 }
 
+/// Mask of the WiFi/BT MAC and PHY clock enable bits shared by WiFi and BT,
+/// `DPORT_WIFI_CLK_WIFI_BT_COMMON_M` in ESP-IDF's `dport_reg.h`
+#[cfg(esp32)]
+const WIFI_CLK_WIFI_BT_COMMON_M: u32 = 0x0000_03c9;
+/// Mask of the WiFi/BT MAC and PHY clock enable bits shared by WiFi and BT,
+/// `DPORT_WIFI_CLK_WIFI_BT_COMMON_M` in ESP-IDF's `syscon_reg.h`
+#[cfg(esp32s2)]
+const WIFI_CLK_WIFI_BT_COMMON_M: u32 = 0x0000_03c9;
+/// Mask of the WiFi/BT MAC and PHY clock enable bits shared by WiFi and BT,
+/// `SYSTEM_WIFI_CLK_WIFI_BT_COMMON_M` in ESP-IDF's `apb_ctrl_reg.h`
+#[cfg(esp32c2)]
+const WIFI_CLK_WIFI_BT_COMMON_M: u32 = 0x0078_078f;
+/// Mask of the WiFi/BT MAC and PHY clock enable bits shared by WiFi and BT,
+/// `SYSTEM_WIFI_CLK_WIFI_BT_COMMON_M` in ESP-IDF's `apb_ctrl_reg.h`
+#[cfg(esp32c3)]
+const WIFI_CLK_WIFI_BT_COMMON_M: u32 = 0x0078_078f;
+/// Mask of the WiFi/BT MAC and PHY clock enable bits shared by WiFi and BT,
+/// `SYSTEM_WIFI_CLK_WIFI_BT_COMMON_M` in ESP-IDF's `apb_ctrl_reg.h`
+#[cfg(esp32s3)]
+const WIFI_CLK_WIFI_BT_COMMON_M: u32 = 0x0078_078f;
+
+/// AES accelerator bit of the ESP32's `PERI_CLK_EN` and `PERI_RST_EN`
+/// registers
+#[cfg(esp32)]
+const DPORT_PERI_EN_AES: u32 = 1 << 0;
+/// SHA accelerator bit of the ESP32's `PERI_CLK_EN` and `PERI_RST_EN`
+/// registers
+#[cfg(esp32)]
+const DPORT_PERI_EN_SHA: u32 = 1 << 1;
+
 /// Controls the enablement of peripheral clocks.
 pub struct PeripheralClockControl {
     _private: (),
@@ -49,7 +110,7 @@ impl PeripheralClockControl {
         #[cfg(esp32)]
         let (perip_clk_en0, perip_rst_en0) = { (&system.perip_clk_en, &system.perip_rst_en) };
 
-        #[cfg(any(esp32c2, esp32c3, esp32s3))]
+        #[cfg(not(esp32))]
         let (perip_clk_en1, perip_rst_en1) = { (&system.perip_clk_en1, &system.perip_rst_en1) };
 
         match peripheral {
@@ -91,6 +152,31 @@ impl PeripheralClockControl {
                 perip_clk_en0.modify(|_, w| w.apb_saradc_clk_en().set_bit());
                 perip_rst_en0.modify(|_, w| w.apb_saradc_rst().clear_bit());
             }
+            #[cfg(any(esp32c2, esp32c3))]
+            Peripheral::Tsens => {
+                perip_clk_en1.modify(|_, w| w.tsens_clk_en().set_bit());
+                perip_rst_en1.modify(|_, w| w.tsens_rst().clear_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::Twai => {
+                perip_clk_en0.modify(|_, w| w.twai_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.twai_rst().clear_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::Uhci0 => {
+                perip_clk_en0.modify(|_, w| w.uhci0_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.uhci0_rst().clear_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::I2s0 => {
+                perip_clk_en0.modify(|_, w| w.i2s0_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.i2s0_rst().clear_bit());
+            }
+            #[cfg(esp32s3)]
+            Peripheral::LcdCam => {
+                perip_clk_en1.modify(|_, w| w.lcd_cam_clk_en().set_bit());
+                perip_rst_en1.modify(|_, w| w.lcd_cam_rst().clear_bit());
+            }
             #[cfg(any(any(esp32c2, esp32c3, esp32s3)))]
             Peripheral::Gdma => {
                 perip_clk_en1.modify(|_, w| w.dma_clk_en().set_bit());
@@ -113,8 +199,280 @@ impl PeripheralClockControl {
                 perip_clk_en0.modify(|_, w| w.usb_clk_en().set_bit());
                 perip_rst_en0.modify(|_, w| w.usb_rst().clear_bit());
             }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            Peripheral::Pcnt => {
+                perip_clk_en0.modify(|_, w| w.pcnt_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.pcnt_rst().clear_bit());
+            }
+            #[cfg(any(esp32, esp32s3))]
+            Peripheral::Mcpwm0 => {
+                perip_clk_en0.modify(|_, w| w.pwm0_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.pwm0_rst().clear_bit());
+            }
+            #[cfg(any(esp32, esp32s3))]
+            Peripheral::Mcpwm1 => {
+                perip_clk_en0.modify(|_, w| w.pwm1_clk_en().set_bit());
+                perip_rst_en0.modify(|_, w| w.pwm1_rst().clear_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Sha => {
+                system
+                    .peri_clk_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() | DPORT_PERI_EN_SHA) });
+                system
+                    .peri_rst_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() & !DPORT_PERI_EN_SHA) });
+            }
+            #[cfg(esp32c2)]
+            Peripheral::Sha => {
+                perip_clk_en1.modify(|_, w| w.crypto_sha_clk_en().set_bit());
+                perip_rst_en1.modify(|_, w| w.crypto_sha_rst().clear_bit());
+            }
+            #[cfg(any(esp32c3, esp32s2, esp32s3))]
+            Peripheral::Sha => {
+                perip_clk_en1.modify(|_, w| w.crypto_sha_clk_en().set_bit());
+                // The SHA accelerator is held in reset as long as the HMAC and
+                // digital signature peripherals depending on it are
+                perip_rst_en1.modify(|_, w| {
+                    w.crypto_sha_rst()
+                        .clear_bit()
+                        .crypto_hmac_rst()
+                        .clear_bit()
+                        .crypto_ds_rst()
+                        .clear_bit()
+                });
+            }
+            #[cfg(esp32)]
+            Peripheral::Aes => {
+                system
+                    .peri_clk_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() | DPORT_PERI_EN_AES) });
+                system
+                    .peri_rst_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() & !DPORT_PERI_EN_AES) });
+            }
+            #[cfg(any(esp32c3, esp32s2, esp32s3))]
+            Peripheral::Aes => {
+                perip_clk_en1.modify(|_, w| w.crypto_aes_clk_en().set_bit());
+                // The AES accelerator is held in reset as long as the digital
+                // signature peripheral depending on it is
+                perip_rst_en1
+                    .modify(|_, w| w.crypto_aes_rst().clear_bit().crypto_ds_rst().clear_bit());
+            }
+            #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+            Peripheral::Radio => {
+                self.radio_clock_enable();
+            }
+        }
+    }
+
+    /// Disables the given peripheral and holds it in reset
+    ///
+    /// The peripheral needs to be enabled again via
+    /// [PeripheralClockControl::enable] before it can be used, its
+    /// configuration is lost. There is no reference counting: disabling a
+    /// peripheral which is still used by a driver breaks that driver. This
+    /// also applies to clocks shared by several drivers, e.g. the
+    /// [Peripheral::Dma] clocks on the ESP32-S2 drive the DMA of both SPI2 and
+    /// SPI3.
+    pub fn disable(&mut self, peripheral: Peripheral) {
+        let system = unsafe { &*SystemPeripheral::PTR };
+
+        #[cfg(not(esp32))]
+        let (perip_clk_en0, perip_rst_en0) = { (&system.perip_clk_en0, &system.perip_rst_en0) };
+        #[cfg(esp32)]
+        let (perip_clk_en0, perip_rst_en0) = { (&system.perip_clk_en, &system.perip_rst_en) };
+
+        #[cfg(not(esp32))]
+        let (perip_clk_en1, perip_rst_en1) = { (&system.perip_clk_en1, &system.perip_rst_en1) };
+
+        match peripheral {
+            Peripheral::Spi2 => {
+                perip_clk_en0.modify(|_, w| w.spi2_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.spi2_rst().set_bit());
+            }
+            #[cfg(not(esp32c2))]
+            Peripheral::Spi3 => {
+                perip_clk_en0.modify(|_, w| w.spi3_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.spi3_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::I2cExt0 => {
+                perip_clk_en0.modify(|_, w| w.i2c0_ext0_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.i2c0_ext0_rst().set_bit());
+            }
+            #[cfg(not(esp32))]
+            Peripheral::I2cExt0 => {
+                perip_clk_en0.modify(|_, w| w.i2c_ext0_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.i2c_ext0_rst().set_bit());
+            }
+            #[cfg(not(any(esp32c2, esp32c3)))]
+            Peripheral::I2cExt1 => {
+                perip_clk_en0.modify(|_, w| w.i2c_ext1_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.i2c_ext1_rst().set_bit());
+            }
+            #[cfg(not(esp32c2))]
+            Peripheral::Rmt => {
+                perip_clk_en0.modify(|_, w| w.rmt_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.rmt_rst().set_bit());
+            }
+            Peripheral::Ledc => {
+                perip_clk_en0.modify(|_, w| w.ledc_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.ledc_rst().set_bit());
+            }
+            #[cfg(any(esp32c2, esp32c3))]
+            Peripheral::ApbSarAdc => {
+                perip_clk_en0.modify(|_, w| w.apb_saradc_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.apb_saradc_rst().set_bit());
+            }
+            #[cfg(any(esp32c2, esp32c3))]
+            Peripheral::Tsens => {
+                perip_clk_en1.modify(|_, w| w.tsens_clk_en().clear_bit());
+                perip_rst_en1.modify(|_, w| w.tsens_rst().set_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::Twai => {
+                perip_clk_en0.modify(|_, w| w.twai_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.twai_rst().set_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::Uhci0 => {
+                perip_clk_en0.modify(|_, w| w.uhci0_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.uhci0_rst().set_bit());
+            }
+            #[cfg(any(esp32c3, esp32s3))]
+            Peripheral::I2s0 => {
+                perip_clk_en0.modify(|_, w| w.i2s0_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.i2s0_rst().set_bit());
+            }
+            #[cfg(esp32s3)]
+            Peripheral::LcdCam => {
+                perip_clk_en1.modify(|_, w| w.lcd_cam_clk_en().clear_bit());
+                perip_rst_en1.modify(|_, w| w.lcd_cam_rst().set_bit());
+            }
+            #[cfg(any(any(esp32c2, esp32c3, esp32s3)))]
+            Peripheral::Gdma => {
+                perip_clk_en1.modify(|_, w| w.dma_clk_en().clear_bit());
+                perip_rst_en1.modify(|_, w| w.dma_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Dma => {
+                perip_clk_en0.modify(|_, w| w.spi_dma_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.spi_dma_rst().set_bit());
+            }
+            #[cfg(esp32s2)]
+            Peripheral::Dma => {
+                perip_clk_en0.modify(|_, w| w.spi2_dma_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.spi2_dma_rst().set_bit());
+                perip_clk_en0.modify(|_, w| w.spi3_dma_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.spi3_dma_rst().set_bit());
+            }
+            #[cfg(any(esp32s2, esp32s3))]
+            Peripheral::Usb => {
+                perip_clk_en0.modify(|_, w| w.usb_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.usb_rst().set_bit());
+            }
+            #[cfg(any(esp32, esp32s2, esp32s3))]
+            Peripheral::Pcnt => {
+                perip_clk_en0.modify(|_, w| w.pcnt_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.pcnt_rst().set_bit());
+            }
+            #[cfg(any(esp32, esp32s3))]
+            Peripheral::Mcpwm0 => {
+                perip_clk_en0.modify(|_, w| w.pwm0_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.pwm0_rst().set_bit());
+            }
+            #[cfg(any(esp32, esp32s3))]
+            Peripheral::Mcpwm1 => {
+                perip_clk_en0.modify(|_, w| w.pwm1_clk_en().clear_bit());
+                perip_rst_en0.modify(|_, w| w.pwm1_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Sha => {
+                system
+                    .peri_clk_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() & !DPORT_PERI_EN_SHA) });
+                system
+                    .peri_rst_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() | DPORT_PERI_EN_SHA) });
+            }
+            #[cfg(not(esp32))]
+            Peripheral::Sha => {
+                perip_clk_en1.modify(|_, w| w.crypto_sha_clk_en().clear_bit());
+                perip_rst_en1.modify(|_, w| w.crypto_sha_rst().set_bit());
+            }
+            #[cfg(esp32)]
+            Peripheral::Aes => {
+                system
+                    .peri_clk_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() & !DPORT_PERI_EN_AES) });
+                system
+                    .peri_rst_en
+                    .modify(|r, w| unsafe { w.bits(r.bits() | DPORT_PERI_EN_AES) });
+            }
+            #[cfg(any(esp32c3, esp32s2, esp32s3))]
+            Peripheral::Aes => {
+                perip_clk_en1.modify(|_, w| w.crypto_aes_clk_en().clear_bit());
+                perip_rst_en1.modify(|_, w| w.crypto_aes_rst().set_bit());
+            }
+            #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+            Peripheral::Radio => {
+                self.radio_clock_disable();
+            }
         }
     }
+
+    /// Enables the clocks of the WiFi/BT MAC and the PHY and powers up the
+    /// PHY power domain.
+    ///
+    /// The HAL doesn't contain a radio driver, this is intended to be used by
+    /// external radio crates.
+    #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+    pub fn radio_clock_enable(&mut self) {
+        let rtc_cntl = unsafe { &*crate::pac::RTC_CNTL::PTR };
+
+        rtc_cntl
+            .dig_pwc
+            .modify(|_, w| w.wifi_force_pd().clear_bit());
+        rtc_cntl
+            .dig_iso
+            .modify(|_, w| w.wifi_force_iso().clear_bit());
+
+        Self::wifi_clk_en().modify(|r, w| unsafe { w.bits(r.bits() | WIFI_CLK_WIFI_BT_COMMON_M) });
+    }
+
+    /// Disables the clocks of the WiFi/BT MAC and the PHY and powers down the
+    /// PHY power domain.
+    #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+    pub fn radio_clock_disable(&mut self) {
+        let rtc_cntl = unsafe { &*crate::pac::RTC_CNTL::PTR };
+
+        Self::wifi_clk_en().modify(|r, w| unsafe { w.bits(r.bits() & !WIFI_CLK_WIFI_BT_COMMON_M) });
+
+        rtc_cntl.dig_iso.modify(|_, w| w.wifi_force_iso().set_bit());
+        rtc_cntl.dig_pwc.modify(|_, w| w.wifi_force_pd().set_bit());
+    }
+
+    /// Returns whether the WiFi/BT MAC and PHY clocks are currently enabled
+    #[cfg(any(esp32, esp32c2, esp32c3, esp32s2, esp32s3))]
+    pub(crate) fn is_radio_clock_enabled() -> bool {
+        Self::wifi_clk_en().read().bits() & WIFI_CLK_WIFI_BT_COMMON_M == WIFI_CLK_WIFI_BT_COMMON_M
+    }
+
+    #[cfg(esp32)]
+    fn wifi_clk_en() -> &'static crate::pac::dport::WIFI_CLK_EN {
+        unsafe { &(*crate::pac::DPORT::PTR).wifi_clk_en }
+    }
+
+    #[cfg(esp32s2)]
+    fn wifi_clk_en() -> &'static crate::pac::syscon::WIFI_CLK_EN {
+        unsafe { &(*crate::pac::SYSCON::PTR).wifi_clk_en }
+    }
+
+    #[cfg(any(esp32c2, esp32c3, esp32s3))]
+    fn wifi_clk_en() -> &'static crate::pac::apb_ctrl::WIFI_CLK_EN {
+        unsafe { &(*crate::pac::APB_CTRL::PTR).wifi_clk_en }
+    }
 }
 
 /// Controls the configuration of the chip's clocks.
@@ -139,6 +497,7 @@ pub struct SystemParts {
     pub peripheral_clock_control: PeripheralClockControl,
     pub clock_control: SystemClockControl,
     pub cpu_control: CpuControl,
+    pub interrupt_controller: InterruptController,
     #[cfg(any(esp32, esp32s2))]
     pub dma: Dma,
 }
@@ -161,6 +520,7 @@ impl SystemExt for SystemPeripheral {
             peripheral_clock_control: PeripheralClockControl { _private: () },
             clock_control: SystemClockControl { _private: () },
             cpu_control: CpuControl { _private: () },
+            interrupt_controller: InterruptController { _private: () },
             #[cfg(any(esp32, esp32s2))]
             dma: Dma { _private: () },
         }
@@ -1,6 +1,7 @@
 use core::{intrinsics::transmute, marker::PhantomData};
 
-use fugit::MillisDurationU32;
+use fugit::{MicrosDurationU64, MillisDurationU32};
+use void::Void;
 
 use crate::pac::{
     generic::Reg,
@@ -59,6 +60,14 @@ impl SystemTimer {
 
         ((value_hi as u64) << 32) | value_lo as u64
     }
+
+    /// Busy-wait for the given number of microseconds
+    fn delay_us(us: u64) {
+        let t0 = Self::now();
+        let ticks = us * (Self::TICKS_PER_SECOND / 1_000_000);
+
+        while Self::now().wrapping_sub(t0) < ticks {}
+    }
 }
 
 #[derive(Debug)]
@@ -73,8 +82,8 @@ pub struct Alarm<MODE, const CHANNEL: u8> {
 }
 
 impl<T, const CHANNEL: u8> Alarm<T, CHANNEL> {
-    // private constructor
-    fn new() -> Self {
+    // crate private constructor
+    pub(crate) fn new() -> Self {
         Self { _pd: PhantomData }
     }
 
@@ -94,6 +103,32 @@ impl<T, const CHANNEL: u8> Alarm<T, CHANNEL> {
         }
     }
 
+    pub fn disable_interrupt(&self) {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        match CHANNEL {
+            0 => systimer
+                .int_ena
+                .modify(|_, w| w.target0_int_ena().clear_bit()),
+            1 => systimer
+                .int_ena
+                .modify(|_, w| w.target1_int_ena().clear_bit()),
+            2 => systimer
+                .int_ena
+                .modify(|_, w| w.target2_int_ena().clear_bit()),
+            _ => unreachable!(),
+        }
+    }
+
+    pub fn is_interrupt_set(&self) -> bool {
+        let systimer = unsafe { &*SYSTIMER::ptr() };
+        match CHANNEL {
+            0 => systimer.int_raw.read().target0_int_raw().bit_is_set(),
+            1 => systimer.int_raw.read().target1_int_raw().bit_is_set(),
+            2 => systimer.int_raw.read().target2_int_raw().bit_is_set(),
+            _ => unreachable!(),
+        }
+    }
+
     pub fn clear_interrupt(&self) {
         let systimer = unsafe { &*SYSTIMER::ptr() };
         match CHANNEL {
@@ -196,6 +231,58 @@ impl<const CHANNEL: u8> Alarm<Target, CHANNEL> {
     }
 }
 
+impl<const CHANNEL: u8> embedded_hal::timer::CountDown for Alarm<Target, CHANNEL> {
+    type Time = MicrosDurationU64;
+
+    fn start<Time>(&mut self, timeout: Time)
+    where
+        Time: Into<Self::Time>,
+    {
+        let ticks = timeout.into().ticks() * (SystemTimer::TICKS_PER_SECOND / 1_000_000);
+
+        self.clear_interrupt();
+        self.set_target(SystemTimer::now() + ticks);
+    }
+
+    fn wait(&mut self) -> nb::Result<(), Void> {
+        if self.is_interrupt_set() {
+            self.clear_interrupt();
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+}
+
+impl<T, const CHANNEL: u8> embedded_hal::blocking::delay::DelayMs<T> for Alarm<Target, CHANNEL>
+where
+    T: Into<u32>,
+{
+    fn delay_ms(&mut self, ms: T) {
+        SystemTimer::delay_us(ms.into() as u64 * 1000);
+    }
+}
+
+impl<T, const CHANNEL: u8> embedded_hal::blocking::delay::DelayUs<T> for Alarm<Target, CHANNEL>
+where
+    T: Into<u32>,
+{
+    fn delay_us(&mut self, us: T) {
+        SystemTimer::delay_us(us.into() as u64);
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl<const CHANNEL: u8> embedded_hal_1::delay::DelayUs for Alarm<Target, CHANNEL> {
+    type Error = core::convert::Infallible;
+
+    fn delay_us(&mut self, us: u32) -> Result<(), Self::Error> {
+        SystemTimer::delay_us(us as u64);
+
+        Ok(())
+    }
+}
+
 impl<const CHANNEL: u8> Alarm<Periodic, CHANNEL> {
     pub fn set_period(&self, period: fugit::HertzU32) {
         let time_period: MillisDurationU32 = period.into_duration();
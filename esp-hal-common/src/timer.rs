@@ -87,9 +87,13 @@ where
 }
 
 /// General-purpose timer
+///
+/// [CountDown::start] starts a periodic timer, [Timer::start_oneshot] a timer
+/// which fires only once.
 pub struct Timer<T> {
     timg: T,
     apb_clk_freq: HertzU32,
+    auto_reload: bool,
 }
 
 /// Timer driver
@@ -101,7 +105,11 @@ where
     pub fn new(timg: T, apb_clk_freq: HertzU32) -> Self {
         // TODO: this currently assumes APB_CLK is being used, as we don't yet have a
         //       way to select the XTAL_CLK.
-        Self { timg, apb_clk_freq }
+        Self {
+            timg,
+            apb_clk_freq,
+            auto_reload: true,
+        }
     }
 
     /// Return the raw interface to the underlying timer instance
@@ -133,6 +141,36 @@ where
     pub fn read_raw(&self) -> u64 {
         self.timg.read_raw()
     }
+
+    /// Start the timer, the alarm fires once after `timeout`
+    ///
+    /// [CountDown::wait] completes once, afterwards the timer needs to be
+    /// started again.
+    pub fn start_oneshot<Time>(&mut self, timeout: Time)
+    where
+        Time: Into<MicrosDurationU64>,
+    {
+        self.start_alarm(timeout.into(), false);
+    }
+
+    fn start_alarm(&mut self, timeout: MicrosDurationU64, auto_reload: bool) {
+        self.timg.set_counter_active(false);
+        self.timg.set_alarm_active(false);
+
+        self.timg.reset_counter();
+
+        // TODO: this currently assumes APB_CLK is being used, as we don't yet have a
+        //       way to select the XTAL_CLK.
+        // TODO: can we cache the divider (only get it on initialization)?
+        let ticks = timeout_to_ticks(timeout, self.apb_clk_freq, self.timg.divider());
+        self.timg.load_alarm_value(ticks);
+
+        self.timg.set_counter_decrementing(false);
+        self.timg.set_auto_reload(auto_reload);
+        self.auto_reload = auto_reload;
+        self.timg.set_counter_active(true);
+        self.timg.set_alarm_active(true);
+    }
 }
 
 /// Timer peripheral instance
@@ -459,25 +497,12 @@ where
 {
     type Time = MicrosDurationU64;
 
+    /// Start a periodic timer, the alarm fires every `timeout`
     fn start<Time>(&mut self, timeout: Time)
     where
         Time: Into<Self::Time>,
     {
-        self.timg.set_counter_active(false);
-        self.timg.set_alarm_active(false);
-
-        self.timg.reset_counter();
-
-        // TODO: this currently assumes APB_CLK is being used, as we don't yet have a
-        //       way to select the XTAL_CLK.
-        // TODO: can we cache the divider (only get it on initialization)?
-        let ticks = timeout_to_ticks(timeout, self.apb_clk_freq, self.timg.divider());
-        self.timg.load_alarm_value(ticks);
-
-        self.timg.set_counter_decrementing(false);
-        self.timg.set_auto_reload(true);
-        self.timg.set_counter_active(true);
-        self.timg.set_alarm_active(true);
+        self.start_alarm(timeout.into(), true);
     }
 
     fn wait(&mut self) -> nb::Result<(), Void> {
@@ -487,7 +512,8 @@ where
 
         if self.timg.is_interrupt_set() {
             self.timg.clear_interrupt();
-            self.timg.set_alarm_active(true);
+            // a one-shot alarm stays disabled
+            self.timg.set_alarm_active(self.auto_reload);
 
             Ok(())
         } else {
@@ -0,0 +1,667 @@
+//! # Two-Wire Automotive Interface (TWAI)
+//!
+//! ### Summary
+//! The TWAI controller is compatible with CAN 2.0B. It sends and receives
+//! data and remote frames with standard (11 bit) and extended (29 bit)
+//! identifiers. An external transceiver is needed to connect to the bus.
+//!
+//! The bit timing is derived from the APB clock. Received frames are stored
+//! in a FIFO of 64 bytes, i.e. 4 frames of 8 data bytes with extended IDs,
+//! until they are read with [Twai::receive]. An acceptance filter
+//! ([Filter]) can drop uninteresting frames in hardware.
+//!
+//! With the `embedded-can` feature the `embedded_can::blocking::Can` and
+//! `embedded_can::nb::Can` traits are implemented.
+//!
+//! This driver is available on the ESP32-C3 and ESP32-S3.
+//!
+//! ### Example
+//! ```no_run
+//! let mut twai = Twai::new(
+//!     peripherals.TWAI,
+//!     io.pins.gpio2,
+//!     io.pins.gpio3,
+//!     500u32.kHz(),
+//!     &clocks,
+//!     &mut system.peripheral_clock_control,
+//! )
+//! .unwrap();
+//! twai.set_filter(Filter::standard(0x100, 0x700));
+//!
+//! let frame = Frame::new(Id::Standard(0x123), &[1, 2, 3]).unwrap();
+//! twai.transmit(&frame).unwrap();
+//!
+//! let frame = twai.receive().unwrap();
+//! ```
+
+#![deny(missing_docs)]
+
+use fugit::HertzU32;
+
+use crate::{
+    clock::Clocks,
+    gpio::{InputPin, InputSignal, OutputPin, OutputSignal},
+    pac::TWAI,
+    system::{Peripheral, PeripheralClockControl},
+};
+
+const MODE_RESET: u32 = 1 << 0;
+const MODE_LISTEN_ONLY: u32 = 1 << 1;
+const MODE_SELF_TEST: u32 = 1 << 2;
+const MODE_SINGLE_FILTER: u32 = 1 << 3;
+
+const CMD_TX_REQ: u32 = 1 << 0;
+const CMD_RELEASE_BUF: u32 = 1 << 2;
+const CMD_CLR_OVERRUN: u32 = 1 << 3;
+const CMD_SELF_RX_REQ: u32 = 1 << 4;
+
+const STATUS_RX_BUF: u32 = 1 << 0;
+const STATUS_OVERRUN: u32 = 1 << 1;
+const STATUS_TX_BUF: u32 = 1 << 2;
+const STATUS_TX_COMPLETE: u32 = 1 << 3;
+const STATUS_BUS_OFF: u32 = 1 << 7;
+
+const FRAME_INFO_EXTENDED: u8 = 1 << 7;
+const FRAME_INFO_RTR: u8 = 1 << 6;
+
+/// Largest prescaler of the TWAI clock
+const MAX_PRESCALER: u32 = 16384;
+
+/// TWAI errors
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Error {
+    /// The baud rate can't be derived from the APB clock
+    UnsupportedBaudRate,
+    /// The controller has left the bus after too many errors, see
+    /// [Twai::start_bus_recovery]
+    BusOff,
+    /// Frames were lost because the receive FIFO was full
+    Overrun,
+}
+
+/// Identifier of a frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Id {
+    /// Standard 11 bit identifier
+    Standard(u16),
+    /// Extended 29 bit identifier
+    Extended(u32),
+}
+
+impl Id {
+    fn is_valid(&self) -> bool {
+        match *self {
+            Id::Standard(id) => id <= 0x7ff,
+            Id::Extended(id) => id <= 0x1fff_ffff,
+        }
+    }
+}
+
+/// A data or remote frame
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Frame {
+    id: Id,
+    remote: bool,
+    dlc: usize,
+    data: [u8; 8],
+}
+
+impl Frame {
+    /// Creates a data frame, `None` if the ID is out of range or there are
+    /// more than 8 data bytes
+    pub fn new(id: Id, data: &[u8]) -> Option<Self> {
+        if !id.is_valid() || data.len() > 8 {
+            return None;
+        }
+
+        let mut frame = Frame {
+            id,
+            remote: false,
+            dlc: data.len(),
+            data: [0; 8],
+        };
+        frame.data[..data.len()].copy_from_slice(data);
+
+        Some(frame)
+    }
+
+    /// Creates a remote frame requesting `dlc` bytes, `None` if the ID is out
+    /// of range or `dlc` is larger than 8
+    pub fn new_remote(id: Id, dlc: usize) -> Option<Self> {
+        if !id.is_valid() || dlc > 8 {
+            return None;
+        }
+
+        Some(Frame {
+            id,
+            remote: true,
+            dlc,
+            data: [0; 8],
+        })
+    }
+
+    /// The identifier of the frame
+    pub fn id(&self) -> Id {
+        self.id
+    }
+
+    /// Whether the frame has an extended identifier
+    pub fn is_extended(&self) -> bool {
+        matches!(self.id, Id::Extended(_))
+    }
+
+    /// Whether this is a remote frame
+    pub fn is_remote_frame(&self) -> bool {
+        self.remote
+    }
+
+    /// The data length code
+    pub fn dlc(&self) -> usize {
+        self.dlc
+    }
+
+    /// The data bytes, empty for remote frames
+    pub fn data(&self) -> &[u8] {
+        if self.remote {
+            &[]
+        } else {
+            &self.data[..self.dlc]
+        }
+    }
+}
+
+/// Acceptance filter for received frames
+///
+/// The filter is applied to the ID bits of frames with the ID type it was
+/// created for. Frames with the other ID type are compared bitwise against the
+/// same filter bits, they are best filtered in software.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Filter {
+    code: u32,
+    /// Bits set here are ignored
+    dont_care: u32,
+}
+
+impl Filter {
+    /// Accepts all frames
+    pub const ACCEPT_ALL: Self = Filter {
+        code: 0,
+        dont_care: u32::MAX,
+    };
+
+    /// Accepts standard frames whose ID matches `id` in the bits set in `mask`
+    pub fn standard(id: u16, mask: u16) -> Self {
+        Filter {
+            code: (id as u32 & 0x7ff) << 21,
+            dont_care: !((mask as u32 & 0x7ff) << 21),
+        }
+    }
+
+    /// Accepts extended frames whose ID matches `id` in the bits set in `mask`
+    pub fn extended(id: u32, mask: u32) -> Self {
+        Filter {
+            code: (id & 0x1fff_ffff) << 3,
+            dont_care: !((mask & 0x1fff_ffff) << 3),
+        }
+    }
+}
+
+/// Operating mode of the controller
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Mode {
+    /// Takes part in the bus traffic
+    Normal,
+    /// Only receives frames, without sending acknowledgements or error
+    /// frames
+    ListenOnly,
+    /// Doesn't need acknowledgements for sent frames and receives its own
+    /// frames, for testing without other nodes on the bus
+    SelfTest,
+}
+
+/// Interrupt events
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Event {
+    /// A frame was received
+    Receive,
+    /// The transmit buffer is free again
+    Transmit,
+    /// The error or bus-off state changed
+    ErrorWarning,
+    /// Frames were lost because the receive FIFO was full
+    Overrun,
+    /// The controller became error passive or error active again
+    ErrorPassive,
+    /// The controller lost the arbitration while sending
+    ArbitrationLost,
+    /// An error was detected on the bus
+    BusError,
+}
+
+impl Event {
+    fn mask(self) -> u32 {
+        match self {
+            Event::Receive => 1 << 0,
+            Event::Transmit => 1 << 1,
+            Event::ErrorWarning => 1 << 2,
+            Event::Overrun => 1 << 3,
+            Event::ErrorPassive => 1 << 5,
+            Event::ArbitrationLost => 1 << 6,
+            Event::BusError => 1 << 7,
+        }
+    }
+}
+
+/// Set of [Event]s which raised the TWAI interrupt, see
+/// [Twai::clear_interrupt]
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Events(u32);
+
+impl Events {
+    /// Whether the given event is part of the set
+    pub fn contains(&self, event: Event) -> bool {
+        self.0 & event.mask() != 0
+    }
+
+    /// Whether no event is part of the set
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    /// The raw value of the interrupt register
+    pub fn bits(&self) -> u32 {
+        self.0
+    }
+}
+
+/// TWAI driver
+pub struct Twai {
+    twai: TWAI,
+    mode: Mode,
+}
+
+impl Twai {
+    /// Configures the controller for the given baud rate and starts it in
+    /// [Mode::Normal], accepting all frames
+    ///
+    /// The baud rate must divide the APB clock into 20 or 25 time quanta per
+    /// bit, which sample at 80% and 68% of the bit respectively. All the usual
+    /// rates from 25 kbit/s to 1 Mbit/s are supported.
+    pub fn new<TX: OutputPin, RX: InputPin>(
+        twai: TWAI,
+        mut tx_pin: TX,
+        mut rx_pin: RX,
+        baud_rate: HertzU32,
+        clocks: &Clocks,
+        peripheral_clock_control: &mut PeripheralClockControl,
+    ) -> Result<Self, Error> {
+        let (prescaler, tseg1, tseg2) = bit_timing(clocks.apb_clock, baud_rate)?;
+
+        peripheral_clock_control.enable(Peripheral::Twai);
+
+        tx_pin
+            .set_to_push_pull_output()
+            .connect_peripheral_to_output(OutputSignal::TWAI_TX);
+        rx_pin
+            .set_to_input()
+            .connect_input_to_peripheral(InputSignal::TWAI_RX);
+
+        let mut this = Twai {
+            twai,
+            mode: Mode::Normal,
+        };
+
+        this.enter_reset_mode();
+        // Synchronization jump width of 3 time quanta
+        this.twai
+            .bus_timing_0
+            .write(|w| unsafe { w.bits((prescaler / 2 - 1) | 2 << 14) });
+        this.twai
+            .bus_timing_1
+            .write(|w| unsafe { w.bits((tseg1 - 1) | (tseg2 - 1) << 4) });
+        this.twai.int_ena.write(|w| unsafe { w.bits(0) });
+        this.write_filter(Filter::ACCEPT_ALL);
+        // Reading the interrupt register clears it
+        this.twai.int_raw.read();
+        this.leave_reset_mode();
+
+        Ok(this)
+    }
+
+    /// Changes the operating mode
+    pub fn set_mode(&mut self, mode: Mode) {
+        self.mode = mode;
+        self.enter_reset_mode();
+        self.leave_reset_mode();
+    }
+
+    /// Changes the acceptance filter
+    ///
+    /// The controller is briefly stopped, frames on the bus in the meantime
+    /// are missed.
+    pub fn set_filter(&mut self, filter: Filter) {
+        self.enter_reset_mode();
+        self.write_filter(filter);
+        self.leave_reset_mode();
+    }
+
+    /// Puts a frame into the transmit buffer, waiting for the buffer to be
+    /// free first
+    pub fn transmit(&mut self, frame: &Frame) -> Result<(), Error> {
+        loop {
+            match self.try_transmit(frame) {
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(error)) => return Err(error),
+                Ok(()) => return Ok(()),
+            }
+        }
+    }
+
+    /// Puts a frame into the transmit buffer if it's free
+    pub fn try_transmit(&mut self, frame: &Frame) -> nb::Result<(), Error> {
+        let status = self.twai.status.read().bits();
+        if status & STATUS_BUS_OFF != 0 {
+            return Err(nb::Error::Other(Error::BusOff));
+        }
+        if status & STATUS_TX_BUF == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let mut info = frame.dlc as u8;
+        if frame.remote {
+            info |= FRAME_INFO_RTR;
+        }
+
+        let data_start = match frame.id {
+            Id::Standard(id) => {
+                self.write_data(0, info);
+                self.write_data(1, (id >> 3) as u8);
+                self.write_data(2, (id << 5) as u8);
+                3
+            }
+            Id::Extended(id) => {
+                self.write_data(0, info | FRAME_INFO_EXTENDED);
+                self.write_data(1, (id >> 21) as u8);
+                self.write_data(2, (id >> 13) as u8);
+                self.write_data(3, (id >> 5) as u8);
+                self.write_data(4, (id << 3) as u8);
+                5
+            }
+        };
+        for (index, byte) in frame.data().iter().enumerate() {
+            self.write_data(data_start + index, *byte);
+        }
+
+        // In self test mode the frame is received by the sender itself
+        let cmd = match self.mode {
+            Mode::SelfTest => CMD_SELF_RX_REQ,
+            _ => CMD_TX_REQ,
+        };
+        self.twai.cmd.write(|w| unsafe { w.bits(cmd) });
+
+        Ok(())
+    }
+
+    /// Whether the last frame put into the transmit buffer was sent
+    pub fn is_transmit_complete(&self) -> bool {
+        self.twai.status.read().bits() & STATUS_TX_COMPLETE != 0
+    }
+
+    /// Waits for a frame and returns it
+    pub fn receive(&mut self) -> Result<Frame, Error> {
+        loop {
+            match self.try_receive() {
+                Err(nb::Error::WouldBlock) => {}
+                Err(nb::Error::Other(error)) => return Err(error),
+                Ok(frame) => return Ok(frame),
+            }
+        }
+    }
+
+    /// Returns a received frame, if any
+    ///
+    /// An [Error::Overrun] is reported once, the frames received before the
+    /// FIFO was full can be read afterwards.
+    pub fn try_receive(&mut self) -> nb::Result<Frame, Error> {
+        let status = self.twai.status.read().bits();
+        if status & STATUS_OVERRUN != 0 {
+            self.twai.cmd.write(|w| unsafe { w.bits(CMD_CLR_OVERRUN) });
+            return Err(nb::Error::Other(Error::Overrun));
+        }
+        if status & STATUS_BUS_OFF != 0 {
+            return Err(nb::Error::Other(Error::BusOff));
+        }
+        if status & STATUS_RX_BUF == 0 {
+            return Err(nb::Error::WouldBlock);
+        }
+
+        let info = self.read_data(0);
+        let (id, data_start) = if info & FRAME_INFO_EXTENDED != 0 {
+            let id = (self.read_data(1) as u32) << 21
+                | (self.read_data(2) as u32) << 13
+                | (self.read_data(3) as u32) << 5
+                | (self.read_data(4) as u32) >> 3;
+            (Id::Extended(id), 5)
+        } else {
+            let id = (self.read_data(1) as u16) << 3 | (self.read_data(2) as u16) >> 5;
+            (Id::Standard(id), 3)
+        };
+
+        let mut frame = Frame {
+            id,
+            remote: info & FRAME_INFO_RTR != 0,
+            dlc: (info as usize & 0xf).min(8),
+            data: [0; 8],
+        };
+        if !frame.remote {
+            for index in 0..frame.dlc {
+                frame.data[index] = self.read_data(data_start + index);
+            }
+        }
+
+        self.twai.cmd.write(|w| unsafe { w.bits(CMD_RELEASE_BUF) });
+
+        Ok(frame)
+    }
+
+    /// Whether the controller has left the bus after too many errors
+    pub fn is_bus_off(&self) -> bool {
+        self.twai.status.read().bits() & STATUS_BUS_OFF != 0
+    }
+
+    /// Starts the recovery from the bus-off state
+    ///
+    /// The controller takes part in the bus traffic again after it has seen
+    /// 128 occurrences of 11 recessive bits.
+    pub fn start_bus_recovery(&mut self) {
+        self.leave_reset_mode();
+    }
+
+    /// The receive and transmit error counters
+    pub fn error_counters(&self) -> (u8, u8) {
+        (
+            self.twai.rx_err_cnt.read().bits() as u8,
+            self.twai.tx_err_cnt.read().bits() as u8,
+        )
+    }
+
+    /// Starts raising the TWAI interrupt on the given event
+    pub fn listen(&mut self, event: Event) {
+        self.twai
+            .int_ena
+            .modify(|r, w| unsafe { w.bits(r.bits() | event.mask()) });
+    }
+
+    /// Stops raising the TWAI interrupt on the given event
+    pub fn unlisten(&mut self, event: Event) {
+        self.twai
+            .int_ena
+            .modify(|r, w| unsafe { w.bits(r.bits() & !event.mask()) });
+    }
+
+    /// Clears the interrupt and returns the events which raised it
+    ///
+    /// Reading the interrupt register clears all pending events at once, so
+    /// all of them are returned. The [Event::Receive] interrupt is raised
+    /// again as long as there are frames in the receive FIFO.
+    pub fn clear_interrupt(&mut self) -> Events {
+        Events(self.twai.int_raw.read().bits())
+    }
+
+    /// Releases the peripheral
+    pub fn free(mut self) -> TWAI {
+        self.enter_reset_mode();
+        self.twai
+    }
+
+    fn enter_reset_mode(&mut self) {
+        self.twai
+            .mode
+            .modify(|r, w| unsafe { w.bits(r.bits() | MODE_RESET) });
+    }
+
+    fn leave_reset_mode(&mut self) {
+        let mode = match self.mode {
+            Mode::Normal => 0,
+            Mode::ListenOnly => MODE_LISTEN_ONLY,
+            Mode::SelfTest => MODE_SELF_TEST,
+        };
+        self.twai
+            .mode
+            .modify(|r, w| unsafe { w.bits(r.bits() & MODE_SINGLE_FILTER | mode) });
+    }
+
+    /// Only valid in reset mode, where the first frame buffer bytes are the
+    /// acceptance code and mask
+    fn write_filter(&mut self, filter: Filter) {
+        self.twai
+            .mode
+            .modify(|r, w| unsafe { w.bits(r.bits() | MODE_SINGLE_FILTER) });
+
+        for (index, byte) in filter.code.to_be_bytes().iter().enumerate() {
+            self.write_data(index, *byte);
+        }
+        for (index, byte) in filter.dont_care.to_be_bytes().iter().enumerate() {
+            self.write_data(4 + index, *byte);
+        }
+    }
+
+    /// The frame buffer consists of 13 registers with one byte each
+    fn data_register(&self, index: usize) -> *mut u32 {
+        unsafe { self.twai.data_0.as_ptr().add(index) }
+    }
+
+    fn write_data(&mut self, index: usize, byte: u8) {
+        unsafe { self.data_register(index).write_volatile(byte as u32) }
+    }
+
+    fn read_data(&self, index: usize) -> u8 {
+        unsafe { self.data_register(index).read_volatile() as u8 }
+    }
+}
+
+/// Prescaler and time segments 1 and 2 for the baud rate
+fn bit_timing(apb_clock: HertzU32, baud_rate: HertzU32) -> Result<(u32, u32, u32), Error> {
+    let apb_clock = apb_clock.raw();
+    let baud_rate = baud_rate.raw();
+
+    // Sample point at 80% of the bit with 20 time quanta, or at 68% with 25
+    // time quanta, the same timings as ESP-IDF
+    for (tseg1, tseg2) in [(15, 4), (16, 8)] {
+        let quanta = baud_rate * (1 + tseg1 + tseg2);
+        if quanta == 0 || apb_clock % quanta != 0 {
+            continue;
+        }
+
+        let prescaler = apb_clock / quanta;
+        if prescaler % 2 == 0 && (2..=MAX_PRESCALER).contains(&prescaler) {
+            return Ok((prescaler, tseg1, tseg2));
+        }
+    }
+
+    Err(Error::UnsupportedBaudRate)
+}
+
+#[cfg(feature = "embedded-can")]
+mod ecan {
+    use embedded_can::{ErrorKind, ExtendedId, StandardId};
+
+    use super::{Error, Frame, Id, Twai};
+
+    impl embedded_can::Error for Error {
+        fn kind(&self) -> ErrorKind {
+            match self {
+                Error::Overrun => ErrorKind::Overrun,
+                _ => ErrorKind::Other,
+            }
+        }
+    }
+
+    impl embedded_can::Frame for Frame {
+        fn new(id: impl Into<embedded_can::Id>, data: &[u8]) -> Option<Self> {
+            Frame::new(to_id(id.into()), data)
+        }
+
+        fn new_remote(id: impl Into<embedded_can::Id>, dlc: usize) -> Option<Self> {
+            Frame::new_remote(to_id(id.into()), dlc)
+        }
+
+        fn is_extended(&self) -> bool {
+            Frame::is_extended(self)
+        }
+
+        fn is_remote_frame(&self) -> bool {
+            Frame::is_remote_frame(self)
+        }
+
+        fn id(&self) -> embedded_can::Id {
+            // The IDs of frames are always in range
+            match self.id {
+                Id::Standard(id) => StandardId::new(id).unwrap().into(),
+                Id::Extended(id) => ExtendedId::new(id).unwrap().into(),
+            }
+        }
+
+        fn dlc(&self) -> usize {
+            Frame::dlc(self)
+        }
+
+        fn data(&self) -> &[u8] {
+            Frame::data(self)
+        }
+    }
+
+    fn to_id(id: embedded_can::Id) -> Id {
+        match id {
+            embedded_can::Id::Standard(id) => Id::Standard(id.as_raw()),
+            embedded_can::Id::Extended(id) => Id::Extended(id.as_raw()),
+        }
+    }
+
+    impl embedded_can::blocking::Can for Twai {
+        type Frame = Frame;
+        type Error = Error;
+
+        fn transmit(&mut self, frame: &Frame) -> Result<(), Error> {
+            Twai::transmit(self, frame)
+        }
+
+        fn receive(&mut self) -> Result<Frame, Error> {
+            Twai::receive(self)
+        }
+    }
+
+    impl embedded_can::nb::Can for Twai {
+        type Frame = Frame;
+        type Error = Error;
+
+        /// Frames waiting in the transmit buffer are never replaced
+        fn transmit(&mut self, frame: &Frame) -> nb::Result<Option<Frame>, Error> {
+            self.try_transmit(frame).map(|()| None)
+        }
+
+        fn receive(&mut self) -> nb::Result<Frame, Error> {
+            self.try_receive()
+        }
+    }
+}
@@ -1,25 +1,594 @@
+//! USB Serial/JTAG
+//!
+//! Allows reading from and writing to the USB Serial/JTAG controller's CDC-ACM
+//! endpoint, e.g. via `writeln!(UsbSerialJtag, ...)`.
+//!
+//! [UsbSerialJtag::write_byte_nb], [UsbSerialJtag::read_byte] and
+//! [UsbSerialJtag::flush_tx_nb] never block and return
+//! `nb::Error::WouldBlock` if the endpoint FIFO is full, empty or still owned
+//! by the host respectively. The blocking functions hand a full FIFO to the
+//! host and wait for it to be drained. Data is only sent to the host once the
+//! FIFO is full or it is flushed, `core::fmt::Write` flushes after every
+//! string.
+//!
+//! The USB Serial/JTAG controller can be used as debug output on boards
+//! without a UART connection:
+//! - When the `log` feature is enabled, [set_usb_serial_jtag_logger] installs a
+//!   `log` backend writing to the controller.
+//! - The `defmt-usb-serial-jtag` feature provides a `defmt` global logger
+//!   writing the encoded frames to the controller.
+//! - The `panic-usb-serial-jtag` feature provides a panic handler writing the
+//!   panic message to the controller and halting. Don't combine it with another
+//!   crate providing a panic handler.
+//!
+//! Writing doesn't block forever if no host is attached: if the host doesn't
+//! pick up the data in time, the data is dropped and [Error::Timeout] is
+//! returned.
+
 use crate::pac::USB_DEVICE;
 
+/// Number of polls to wait for the host to take the data out of the endpoint
+/// buffer before giving up
+const FLUSH_TIMEOUT: u32 = 50_000;
+
+/// `EP1_CONF` bit handing the written data to the host
+const WR_DONE: u32 = 1 << 0;
+/// `EP1_CONF` bit set while the IN endpoint FIFO can take more data
+const SERIAL_IN_EP_DATA_FREE: u32 = 1 << 1;
+/// `EP1_CONF` bit set while the OUT endpoint FIFO holds received data
+const SERIAL_OUT_EP_DATA_AVAIL: u32 = 1 << 2;
+
+/// USB Serial/JTAG errors
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Error {
+    /// The host didn't take the data out of the endpoint buffer in time
+    Timeout,
+}
+
+#[cfg(feature = "eh1")]
+impl embedded_hal_1::serial::Error for Error {
+    fn kind(&self) -> embedded_hal_1::serial::ErrorKind {
+        embedded_hal_1::serial::ErrorKind::Other
+    }
+}
+
+/// USB Serial/JTAG controller's CDC-ACM endpoint
 pub struct UsbSerialJtag;
 
+impl UsbSerialJtag {
+    /// Write a byte into the endpoint FIFO, handing a full FIFO to the host
+    /// and waiting for it to be drained first
+    pub fn write_byte(&mut self, word: u8) -> Result<(), Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        if reg_block.ep1_conf.read().bits() & SERIAL_IN_EP_DATA_FREE == 0 {
+            reg_block.ep1_conf.write(|w| unsafe { w.bits(WR_DONE) });
+            Self::wait_for_host()?;
+        }
+
+        reg_block.ep1.write(|w| unsafe { w.bits(word.into()) });
+
+        Ok(())
+    }
+
+    /// Write bytes and flush them to the host
+    pub fn write_bytes(&mut self, data: &[u8]) -> Result<(), Error> {
+        data.iter().try_for_each(|b| self.write_byte(*b))?;
+        self.flush_tx()
+    }
+
+    /// Write a byte into the endpoint FIFO if it has space left
+    pub fn write_byte_nb(&mut self, word: u8) -> nb::Result<(), Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        if reg_block.ep1_conf.read().bits() & SERIAL_IN_EP_DATA_FREE != 0 {
+            reg_block.ep1.write(|w| unsafe { w.bits(word.into()) });
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Hand the data in the endpoint FIFO to the host and wait until it has
+    /// been taken out
+    pub fn flush_tx(&mut self) -> Result<(), Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        reg_block.ep1_conf.write(|w| unsafe { w.bits(WR_DONE) });
+        Self::wait_for_host()
+    }
+
+    /// Hand the data in the endpoint FIFO to the host, returns
+    /// `nb::Error::WouldBlock` until it has been taken out
+    pub fn flush_tx_nb(&mut self) -> nb::Result<(), Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        reg_block.ep1_conf.write(|w| unsafe { w.bits(WR_DONE) });
+
+        if reg_block.ep1_conf.read().bits() & SERIAL_IN_EP_DATA_FREE != 0 {
+            Ok(())
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Read a byte received from the host
+    pub fn read_byte(&mut self) -> nb::Result<u8, Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        if reg_block.ep1_conf.read().bits() & SERIAL_OUT_EP_DATA_AVAIL != 0 {
+            Ok(reg_block.ep1.read().bits() as u8)
+        } else {
+            Err(nb::Error::WouldBlock)
+        }
+    }
+
+    /// Write as many bytes as fit into the endpoint FIFO without blocking,
+    /// returns the number of bytes written
+    pub fn write_bytes_nb(&mut self, data: &[u8]) -> usize {
+        data.iter()
+            .take_while(|b| self.write_byte_nb(**b).is_ok())
+            .count()
+    }
+
+    /// Read the bytes received from the host into `buffer` without blocking,
+    /// returns the number of bytes read
+    pub fn read_bytes(&mut self, buffer: &mut [u8]) -> usize {
+        let mut count = 0;
+        for b in buffer.iter_mut() {
+            match self.read_byte() {
+                Ok(byte) => *b = byte,
+                Err(_) => break,
+            }
+            count += 1;
+        }
+
+        count
+    }
+
+    fn wait_for_host() -> Result<(), Error> {
+        let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+        let mut timeout = FLUSH_TIMEOUT;
+        while reg_block.ep1_conf.read().bits() & SERIAL_IN_EP_DATA_FREE == 0 {
+            // no host attached or it doesn't read the data
+            timeout -= 1;
+            if timeout == 0 {
+                return Err(Error::Timeout);
+            }
+        }
+
+        Ok(())
+    }
+}
+
 impl core::fmt::Write for UsbSerialJtag {
     fn write_str(&mut self, s: &str) -> core::fmt::Result {
+        self.write_bytes(s.as_bytes()).map_err(|_| core::fmt::Error)
+    }
+}
+
+#[cfg(feature = "ufmt")]
+impl ufmt_write::uWrite for UsbSerialJtag {
+    type Error = Error;
+
+    #[inline]
+    fn write_str(&mut self, s: &str) -> Result<(), Self::Error> {
+        self.write_bytes(s.as_bytes())
+    }
+
+    #[inline]
+    fn write_char(&mut self, ch: char) -> Result<(), Self::Error> {
+        let mut buffer = [0u8; 4];
+        self.write_bytes(ch.encode_utf8(&mut buffer).as_bytes())
+    }
+}
+
+impl embedded_hal::serial::Write<u8> for UsbSerialJtag {
+    type Error = Error;
+
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.write_byte_nb(word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.flush_tx_nb()
+    }
+}
+
+impl embedded_hal::serial::Read<u8> for UsbSerialJtag {
+    type Error = Error;
+
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_byte()
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl embedded_hal_1::serial::ErrorType for UsbSerialJtag {
+    type Error = Error;
+}
+
+#[cfg(feature = "eh1")]
+impl embedded_hal_nb::serial::Read for UsbSerialJtag {
+    fn read(&mut self) -> nb::Result<u8, Self::Error> {
+        self.read_byte()
+    }
+}
+
+#[cfg(feature = "eh1")]
+impl embedded_hal_nb::serial::Write for UsbSerialJtag {
+    fn write(&mut self, word: u8) -> nb::Result<(), Self::Error> {
+        self.write_byte_nb(word)
+    }
+
+    fn flush(&mut self) -> nb::Result<(), Self::Error> {
+        self.flush_tx_nb()
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Error for Error {
+    fn kind(&self) -> embedded_io::ErrorKind {
+        embedded_io::ErrorKind::Other
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::Io for UsbSerialJtag {
+    type Error = Error;
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::blocking::Read for UsbSerialJtag {
+    /// Blocks until at least one byte was received, then returns the bytes
+    /// which are available
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        buf[0] = nb::block!(self.read_byte())?;
+
+        Ok(1 + self.read_bytes(&mut buf[1..]))
+    }
+}
+
+#[cfg(feature = "embedded-io")]
+impl embedded_io::blocking::Write for UsbSerialJtag {
+    /// Writes at least one byte, handing a full endpoint FIFO to the host
+    /// first, then writes as many bytes as possible
+    ///
+    /// The data is only sent once the FIFO is full or flushed.
+    fn write(&mut self, buf: &[u8]) -> Result<usize, Self::Error> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        self.write_byte(buf[0])?;
+
+        Ok(1 + self.write_bytes_nb(&buf[1..]))
+    }
+
+    fn flush(&mut self) -> Result<(), Self::Error> {
+        self.flush_tx()
+    }
+}
+
+/// Async `embedded-io` implementation
+///
+/// The futures enable the USB Serial/JTAG interrupts they wait for while they
+/// are pending. The interrupt handler needs to call [handle_interrupt] which
+/// wakes the waiting tasks:
+///
+/// ```rust,ignore
+/// #[interrupt]
+/// fn USB_SERIAL_JTAG() {
+///     esp_hal_common::usb_serial_jtag::asynch::handle_interrupt();
+/// }
+/// ```
+///
+/// Unlike the blocking functions the futures don't time out when no host is
+/// attached.
+#[cfg(all(feature = "embedded-io", feature = "async"))]
+pub mod asynch {
+    use core::{
+        cell::RefCell,
+        future::Future,
+        pin::Pin,
+        task::{Context, Poll, Waker},
+    };
+
+    use critical_section::Mutex;
+
+    use super::{Error, UsbSerialJtag};
+    use crate::pac::USB_DEVICE;
+
+    static RX_WAKER: Mutex<RefCell<Option<Waker>>> = Mutex::new(RefCell::new(None));
+    static TX_WAKER: Mutex<RefCell<Option<Waker>>> = Mutex::new(RefCell::new(None));
+
+    fn register(waker: &Mutex<RefCell<Option<Waker>>>, new: &Waker) {
+        critical_section::with(|cs| {
+            let mut waker = waker.borrow_ref_mut(cs);
+            match waker.as_ref() {
+                Some(old) if old.will_wake(new) => {}
+                _ => *waker = Some(new.clone()),
+            }
+        });
+    }
+
+    fn wake(waker: &Mutex<RefCell<Option<Waker>>>) {
+        if let Some(waker) = critical_section::with(|cs| waker.borrow_ref_mut(cs).take()) {
+            waker.wake();
+        }
+    }
+
+    /// Future completing once at least one byte was received
+    pub struct ReadFuture<'a> {
+        usb_serial: &'a mut UsbSerialJtag,
+        buf: &'a mut [u8],
+    }
+
+    impl<'a> Future for ReadFuture<'a> {
+        type Output = Result<usize, Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+            // register and clear before checking, otherwise a packet arriving
+            // in between is missed
+            register(&RX_WAKER, cx.waker());
+            reg_block
+                .int_clr
+                .write(|w| w.serial_out_recv_pkt_int_clr().set_bit());
+
+            let count = this.usb_serial.read_bytes(this.buf);
+            let ready = count > 0 || this.buf.is_empty();
+
+            critical_section::with(|_| {
+                reg_block
+                    .int_ena
+                    .modify(|_, w| w.serial_out_recv_pkt_int_ena().bit(!ready))
+            });
+
+            if ready {
+                Poll::Ready(Ok(count))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Future completing once at least one byte was written to the endpoint
+    /// FIFO
+    pub struct WriteFuture<'a> {
+        usb_serial: &'a mut UsbSerialJtag,
+        buf: &'a [u8],
+        flushed: bool,
+    }
+
+    impl<'a> Future for WriteFuture<'a> {
+        type Output = Result<usize, Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+            register(&TX_WAKER, cx.waker());
+            reg_block
+                .int_clr
+                .write(|w| w.serial_in_empty_int_clr().set_bit());
+
+            let count = this.usb_serial.write_bytes_nb(this.buf);
+            let ready = count > 0 || this.buf.is_empty();
+
+            // hand the full FIFO to the host once
+            if !ready && !this.flushed {
+                this.usb_serial.flush_tx_nb().ok();
+                this.flushed = true;
+            }
+
+            // raised once the host took the data out of the FIFO
+            critical_section::with(|_| {
+                reg_block
+                    .int_ena
+                    .modify(|_, w| w.serial_in_empty_int_ena().bit(!ready))
+            });
+
+            if ready {
+                Poll::Ready(Ok(count))
+            } else {
+                Poll::Pending
+            }
+        }
+    }
+
+    /// Future completing once the host took the data out of the endpoint FIFO
+    pub struct FlushFuture<'a> {
+        usb_serial: &'a mut UsbSerialJtag,
+    }
+
+    impl<'a> Future for FlushFuture<'a> {
+        type Output = Result<(), Error>;
+
+        fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            let this = self.get_mut();
+            let reg_block = unsafe { &*USB_DEVICE::PTR };
+
+            register(&TX_WAKER, cx.waker());
+            reg_block
+                .int_clr
+                .write(|w| w.serial_in_empty_int_clr().set_bit());
+
+            let result = this.usb_serial.flush_tx_nb();
+            let ready = !matches!(result, Err(nb::Error::WouldBlock));
+
+            critical_section::with(|_| {
+                reg_block
+                    .int_ena
+                    .modify(|_, w| w.serial_in_empty_int_ena().bit(!ready))
+            });
+
+            match result {
+                Err(nb::Error::WouldBlock) => Poll::Pending,
+                Err(nb::Error::Other(e)) => Poll::Ready(Err(e)),
+                Ok(()) => Poll::Ready(Ok(())),
+            }
+        }
+    }
+
+    impl embedded_io::asynch::Read for UsbSerialJtag {
+        type ReadFuture<'a>
+            = ReadFuture<'a>
+        where
+            Self: 'a;
+
+        fn read<'a>(&'a mut self, buf: &'a mut [u8]) -> Self::ReadFuture<'a> {
+            ReadFuture {
+                usb_serial: self,
+                buf,
+            }
+        }
+    }
+
+    impl embedded_io::asynch::Write for UsbSerialJtag {
+        type WriteFuture<'a>
+            = WriteFuture<'a>
+        where
+            Self: 'a;
+
+        fn write<'a>(&'a mut self, buf: &'a [u8]) -> Self::WriteFuture<'a> {
+            WriteFuture {
+                usb_serial: self,
+                buf,
+                flushed: false,
+            }
+        }
+
+        type FlushFuture<'a>
+            = FlushFuture<'a>
+        where
+            Self: 'a;
+
+        fn flush<'a>(&'a mut self) -> Self::FlushFuture<'a> {
+            FlushFuture { usb_serial: self }
+        }
+    }
+
+    /// Wake the tasks waiting for the USB Serial/JTAG controller
+    ///
+    /// Needs to be called from the USB Serial/JTAG interrupt handler, see the
+    /// [module level documentation](self). The interrupts which fired are
+    /// disabled, their status bits are left for the futures to clear.
+    pub fn handle_interrupt() {
         let reg_block = unsafe { &*USB_DEVICE::PTR };
 
-        // TODO: 64 byte chunks max
-        for chunk in s.as_bytes().chunks(32) {
-            unsafe {
-                for &b in chunk {
-                    reg_block.ep1.write(|w| w.bits(b.into()))
-                }
-                reg_block.ep1_conf.write(|w| w.bits(0b001));
+        let (rx, tx) = critical_section::with(|_| {
+            let raw = reg_block.int_raw.read();
+            let ena = reg_block.int_ena.read();
+
+            let rx = raw.serial_out_recv_pkt_int_raw().bit_is_set()
+                && ena.serial_out_recv_pkt_int_ena().bit_is_set();
+            let tx = raw.serial_in_empty_int_raw().bit_is_set()
+                && ena.serial_in_empty_int_ena().bit_is_set();
 
-                while reg_block.ep1_conf.read().bits() & 0b011 == 0b000 {
-                    // wait
+            reg_block.int_ena.modify(|_, w| {
+                if rx {
+                    w.serial_out_recv_pkt_int_ena().clear_bit();
                 }
+                if tx {
+                    w.serial_in_empty_int_ena().clear_bit();
+                }
+                w
+            });
+
+            (rx, tx)
+        });
+
+        if rx {
+            wake(&RX_WAKER);
+        }
+
+        if tx {
+            wake(&TX_WAKER);
+        }
+    }
+}
+
+/// Route `log` output to the USB Serial/JTAG controller
+///
+/// Messages above `level` are discarded. This is a shortcut for
+/// [logger::init](crate::logger::init) with the USB Serial/JTAG controller as
+/// writer.
+#[cfg(feature = "log")]
+pub fn set_usb_serial_jtag_logger(level: log::LevelFilter) {
+    static mut WRITER: UsbSerialJtag = UsbSerialJtag;
+
+    // `UsbSerialJtag` is a zero-sized handle, handing out several references
+    // to it is fine
+    crate::logger::init(level, unsafe { &mut WRITER });
+}
+
+#[cfg(feature = "panic-usb-serial-jtag")]
+#[panic_handler]
+fn panic(info: &core::panic::PanicInfo) -> ! {
+    use core::fmt::Write;
+
+    writeln!(UsbSerialJtag, "\n{}", info).ok();
+
+    loop {}
+}
+
+#[cfg(feature = "defmt-usb-serial-jtag")]
+mod defmt_logger {
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    use critical_section::RestoreState;
+
+    use super::UsbSerialJtag;
+
+    #[defmt::global_logger]
+    struct Logger;
+
+    static TAKEN: AtomicBool = AtomicBool::new(false);
+    static mut CS_RESTORE: RestoreState = RestoreState::invalid();
+    static mut ENCODER: defmt::Encoder = defmt::Encoder::new();
+
+    unsafe impl defmt::Logger for Logger {
+        fn acquire() {
+            let restore = unsafe { critical_section::acquire() };
+
+            if TAKEN.load(Ordering::Relaxed) {
+                panic!("defmt logger taken reentrantly");
+            }
+            TAKEN.store(true, Ordering::Relaxed);
+
+            unsafe {
+                CS_RESTORE = restore;
+                ENCODER.start_frame(write);
             }
         }
 
-        core::fmt::Result::Ok(())
+        unsafe fn flush() {
+            // Gives up if no host is attached, like all writes
+            UsbSerialJtag.flush_tx().ok();
+        }
+
+        unsafe fn release() {
+            ENCODER.end_frame(write);
+            UsbSerialJtag.flush_tx().ok();
+
+            TAKEN.store(false, Ordering::Relaxed);
+            critical_section::release(CS_RESTORE);
+        }
+
+        unsafe fn write(bytes: &[u8]) {
+            ENCODER.write(bytes, write);
+        }
+    }
+
+    fn write(bytes: &[u8]) {
+        UsbSerialJtag.write_bytes(bytes).ok();
     }
 }
@@ -32,7 +32,9 @@ struct RamArgs {
 /// RTC slow or RTC fast ram instead of the normal SRAM.
 ///
 /// The uninitialized option will skip initialization of the memory
-/// (e.g. to persist it across resets or deep sleep mode for the RTC RAM)
+/// (e.g. to persist it across resets or deep sleep mode for the RTC RAM).
+/// `esp_hal_common::rtc_cntl::sleep::RtcRam` wraps such a static and tells
+/// retained contents apart from garbage after power-on.
 ///
 /// Not all targets support RTC slow ram.
 
@@ -29,7 +29,7 @@ static BUTTON: Mutex<RefCell<Option<Gpio0<Input<PullDown>>>>> = Mutex::new(RefCe
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.DPORT.split();
+    let mut system = peripherals.DPORT.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
@@ -49,7 +49,10 @@ fn main() -> ! {
 
     critical_section::with(|cs| BUTTON.borrow_ref_mut(cs).replace(button));
 
-    interrupt::enable(pac::Interrupt::GPIO, interrupt::Priority::Priority2).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::GPIO, interrupt::Priority::Priority2)
+        .unwrap();
 
     led.set_high().unwrap();
 
@@ -0,0 +1,52 @@
+//! This shows how to use UART0 as `log` backend.
+//! You can see the output with `espflash` if you provide the `--monitor` option
+
+#![no_std]
+#![no_main]
+
+use esp32_hal::{
+    clock::ClockControl,
+    logger,
+    pac::{Peripherals, UART0},
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+    Serial,
+};
+use esp_backtrace as _;
+use log::LevelFilter;
+use nb::block;
+use xtensa_lx_rt::entry;
+
+static mut SERIAL: Option<Serial<UART0>> = None;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut timer0 = timer_group0.timer0;
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let serial0 = unsafe { SERIAL.insert(Serial::new(peripherals.UART0, &clocks)) };
+    logger::init(LevelFilter::Info, serial0);
+
+    timer0.start(1u64.secs());
+
+    let mut count = 0u32;
+    loop {
+        log::info!("Hello world! ({})", count);
+        // Below the configured level, so it's discarded
+        log::debug!("Not printed");
+
+        count += 1;
+        block!(timer0.wait()).unwrap();
+    }
+}
@@ -0,0 +1,126 @@
+//! Reads a quadrature (rotary) encoder with 4x decoding using the pulse
+//! counter.
+//!
+//! Connect the encoder's A output to GPIO5 and the B output to GPIO18, the
+//! common pin to GND. Every edge of either signal is counted, the direction
+//! is derived from the level of the other signal.
+//!
+//! The counter wraps around at +/-1000, the PCNT interrupt keeps track of the
+//! wrap-arounds to extend the 16 bit counter.
+
+#![no_std]
+#![no_main]
+
+use core::cell::{Cell, RefCell};
+
+use critical_section::Mutex;
+use esp32_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    interrupt,
+    pac::{self, Peripherals},
+    pcnt::{Channel, ChannelConfig, CtrlMode, EdgeMode, PulseCounter, Unit0},
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use xtensa_lx_rt::entry;
+
+const LIMIT: i16 = 1000;
+
+static UNIT: Mutex<RefCell<Option<Unit0>>> = Mutex::new(RefCell::new(None));
+static WRAPS: Mutex<Cell<i32>> = Mutex::new(Cell::new(0));
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.DPORT.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut pin_a = io.pins.gpio5.into_pull_up_input();
+    let mut pin_b = io.pins.gpio18.into_pull_up_input();
+
+    let pcnt = PulseCounter::new(peripherals.PCNT, &mut system.peripheral_clock_control);
+    let mut unit = pcnt.unit0;
+
+    // Channel 0 counts the edges of A, B selects the direction
+    unit.configure_channel(
+        Channel::Channel0,
+        ChannelConfig {
+            pos_edge: EdgeMode::Decrement,
+            neg_edge: EdgeMode::Increment,
+            high_ctrl: CtrlMode::Keep,
+            low_ctrl: CtrlMode::Reverse,
+        },
+    );
+    unit.set_signal_input(Channel::Channel0, &mut pin_a);
+    unit.set_ctrl_input(Channel::Channel0, &mut pin_b);
+
+    // Channel 1 counts the edges of B, A selects the direction
+    unit.configure_channel(
+        Channel::Channel1,
+        ChannelConfig {
+            pos_edge: EdgeMode::Increment,
+            neg_edge: EdgeMode::Decrement,
+            high_ctrl: CtrlMode::Keep,
+            low_ctrl: CtrlMode::Reverse,
+        },
+    );
+    unit.set_signal_input(Channel::Channel1, &mut pin_b);
+    unit.set_ctrl_input(Channel::Channel1, &mut pin_a);
+
+    // Ignore glitches shorter than 1us (80 APB cycles)
+    unit.set_filter(Some(80));
+    unit.set_limits(-LIMIT, LIMIT);
+    unit.listen();
+    unit.clear();
+    unit.resume();
+
+    critical_section::with(|cs| UNIT.borrow_ref_mut(cs).replace(unit));
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::PCNT, interrupt::Priority::Priority2)
+        .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let position = critical_section::with(|cs| {
+            let value = UNIT.borrow_ref(cs).as_ref().unwrap().get_count();
+            WRAPS.borrow(cs).get() * LIMIT as i32 + value as i32
+        });
+        println!("Position: {}", position);
+        delay.delay_ms(250u32);
+    }
+}
+
+#[interrupt]
+fn PCNT() {
+    critical_section::with(|cs| {
+        let mut unit = UNIT.borrow_ref_mut(cs);
+        let unit = unit.as_mut().unwrap();
+
+        if unit.interrupt_set() {
+            let events = unit.get_events();
+            let wraps = WRAPS.borrow(cs);
+            if events.high_limit {
+                wraps.set(wraps.get() + 1);
+            } else if events.low_limit {
+                wraps.set(wraps.get() - 1);
+            }
+            unit.reset_interrupt();
+        }
+    });
+}
@@ -27,7 +27,7 @@ static SERIAL: Mutex<RefCell<Option<Serial<UART0>>>> = Mutex::new(RefCell::new(N
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.DPORT.split();
+    let mut system = peripherals.DPORT.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     // Disable the TIMG watchdog timer.
@@ -38,7 +38,7 @@ fn main() -> ! {
     let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
     let mut wdt1 = timer_group1.wdt;
 
-    let mut serial0 = Serial::new(peripherals.UART0);
+    let mut serial0 = Serial::new(peripherals.UART0, &clocks);
     let mut rtc = Rtc::new(peripherals.RTC_CNTL);
 
     // Disable MWDT and RWDT (Watchdog) flash boot protection
@@ -51,7 +51,10 @@ fn main() -> ! {
     serial0.listen_at_cmd();
     serial0.listen_rx_fifo_full();
 
-    interrupt::enable(pac::Interrupt::UART0, interrupt::Priority::Priority2).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::UART0, interrupt::Priority::Priority2)
+        .unwrap();
 
     timer0.start(1u64.secs());
 
@@ -10,7 +10,6 @@ use core::cell::RefCell;
 use critical_section::Mutex;
 use esp32_hal::{
     clock::ClockControl,
-    interrupt,
     interrupt::Priority,
     pac::{self, Peripherals, TIMG0, TIMG1},
     prelude::*,
@@ -28,7 +27,7 @@ static TIMER11: Mutex<RefCell<Option<Timer<Timer1<TIMG1>>>>> = Mutex::new(RefCel
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.DPORT.split();
+    let mut system = peripherals.DPORT.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     // Disable the TIMG watchdog timer.
@@ -49,10 +48,22 @@ fn main() -> ! {
     wdt1.disable();
     rtc.rwdt.disable();
 
-    interrupt::enable(pac::Interrupt::TG0_T0_LEVEL, Priority::Priority2).unwrap();
-    interrupt::enable(pac::Interrupt::TG0_T1_LEVEL, Priority::Priority2).unwrap();
-    interrupt::enable(pac::Interrupt::TG1_T0_LEVEL, Priority::Priority3).unwrap();
-    interrupt::enable(pac::Interrupt::TG1_T1_LEVEL, Priority::Priority3).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG0_T0_LEVEL, Priority::Priority2)
+        .unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG0_T1_LEVEL, Priority::Priority2)
+        .unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG1_T0_LEVEL, Priority::Priority3)
+        .unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG1_T1_LEVEL, Priority::Priority3)
+        .unwrap();
     timer00.start(500u64.millis());
     timer00.listen();
     timer01.start(2500u64.millis());
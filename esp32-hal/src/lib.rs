@@ -1,21 +1,31 @@
 #![no_std]
 
 pub use embedded_hal as ehal;
+#[cfg(feature = "log")]
+pub use esp_hal_common::logger;
 pub use esp_hal_common::{
+    aes,
     clock,
-    cpu_control::CpuControl,
+    cpu,
+    cpu_control::{self, CpuControl},
+    crc,
     dma,
     dma::pdma,
     efuse,
+    flash,
     gpio as gpio_types,
     i2c,
     interrupt,
     ledc,
     macros,
+    mcpwm,
     pac,
+    pcnt,
     prelude,
     pulse_control,
+    rtc_cntl,
     serial,
+    sha,
     spi,
     timer,
     utils,
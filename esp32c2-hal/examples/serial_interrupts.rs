@@ -28,11 +28,11 @@ static SERIAL: Mutex<RefCell<Option<Serial<UART0>>>> = Mutex::new(RefCell::new(N
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     let mut rtc = Rtc::new(peripherals.RTC_CNTL);
-    let mut serial0 = Serial::new(peripherals.UART0);
+    let mut serial0 = Serial::new(peripherals.UART0, &clocks);
     let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
     let mut timer0 = timer_group0.timer0;
     let mut wdt0 = timer_group0.wdt;
@@ -51,8 +51,11 @@ fn main() -> ! {
 
     critical_section::with(|cs| SERIAL.borrow_ref_mut(cs).replace(serial0));
 
-    interrupt::enable(pac::Interrupt::UART0, interrupt::Priority::Priority1).unwrap();
-    interrupt::set_kind(
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::UART0, interrupt::Priority::Priority1)
+        .unwrap();
+    system.interrupt_controller.set_kind(
         Cpu::ProCpu,
         interrupt::CpuInterrupt::Interrupt1, // Interrupt 1 handles priority one interrupts
         interrupt::InterruptKind::Edge,
@@ -23,7 +23,7 @@ static TIMER0: Mutex<RefCell<Option<Timer<Timer0<TIMG0>>>>> = Mutex::new(RefCell
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     // Disable the watchdog timers. For the ESP32-C2, this includes the Super WDT,
@@ -37,7 +37,10 @@ fn main() -> ! {
     rtc.rwdt.disable();
     wdt0.disable();
 
-    interrupt::enable(pac::Interrupt::TG0_T0_LEVEL, interrupt::Priority::Priority1).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG0_T0_LEVEL, interrupt::Priority::Priority1)
+        .unwrap();
     timer0.start(500u64.millis());
     timer0.listen();
 
@@ -3,18 +3,28 @@
 use core::arch::global_asm;
 
 pub use embedded_hal as ehal;
+#[cfg(feature = "embassy")]
+pub use esp_hal_common::embassy;
+#[cfg(feature = "log")]
+pub use esp_hal_common::logger;
 pub use esp_hal_common::{
     clock,
+    cpu,
+    crc,
     dma::{self, gdma},
     efuse,
+    flash,
     gpio as gpio_types,
     i2c,
     interrupt,
     macros,
     pac,
     prelude,
+    rtc_cntl,
     serial,
+    sha,
     spi,
+    spi_slave,
     system,
     systimer,
     timer,
@@ -33,7 +43,7 @@ pub mod gpio;
 
 /// Common module for analog functions
 pub mod analog {
-    pub use esp_hal_common::analog::{AvailableAnalog, SarAdcExt};
+    pub use esp_hal_common::analog::{temperature_sensor, AvailableAnalog, SarAdcExt};
 }
 
 extern "C" {
@@ -61,6 +61,8 @@ fn main() -> ! {
     loop {
         let pin_value: u16 = nb::block!(adc1.read(&mut pin)).unwrap();
         println!("PIN2 ADC reading = {}", pin_value);
+        let pin_mv = nb::block!(adc1.read_calibrated_mv(&mut pin)).unwrap();
+        println!("PIN2 voltage = {} mV", pin_mv);
         delay.delay_ms(1500u32);
     }
 }
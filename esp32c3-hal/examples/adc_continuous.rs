@@ -0,0 +1,103 @@
+//! Continuously samples GPIO2 and GPIO3 at 20kHz, the samples are streamed to
+//! a ring buffer by DMA and printed in batches.
+//!
+//! Connect potentiometers to GPIO2 and GPIO3 and see the values change when
+//! rotating the shafts.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    adc::{AdcConfig, Attenuation, Sample, ADC, ADC1},
+    analog::SarAdcExt,
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    gpio::IO,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let analog = peripherals.APB_SARADC.split();
+
+    let mut adc1_config = AdcConfig::new();
+    adc1_config.enable_pin(io.pins.gpio2.into_analog(), Attenuation::Attenuation11dB);
+    adc1_config.enable_pin(io.pins.gpio3.into_analog(), Attenuation::Attenuation11dB);
+
+    let adc1 = ADC::<ADC1>::adc(
+        &mut system.peripheral_clock_control,
+        analog.adc1,
+        adc1_config,
+    )
+    .unwrap();
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let adc1 = adc1
+        .continuous(
+            dma_channel.configure(
+                false,
+                &mut descriptors,
+                &mut rx_descriptors,
+                DmaPriority::Priority0,
+            ),
+            20u32.kHz(),
+            &clocks,
+        )
+        .unwrap();
+
+    // DMA buffer require a static life-time, each half holds 256 samples
+    let mut transfer = adc1.start(buffer(), 1024).unwrap();
+
+    let mut samples = [Sample {
+        channel: 0,
+        value: 0,
+    }; 256];
+
+    loop {
+        if transfer.available() >= samples.len() {
+            let count = transfer.pop(&mut samples);
+
+            if transfer.take_error().is_some() {
+                println!("Samples were lost");
+            }
+
+            println!("{:?}", &samples[..count][..4]);
+        }
+    }
+}
+
+fn buffer() -> &'static mut [u8; 2048] {
+    static mut BUFFER: [u8; 2048] = [0u8; 2048];
+    unsafe { &mut BUFFER }
+}
@@ -0,0 +1,60 @@
+//! Encrypts and decrypts a block with the AES accelerator and prints the
+//! results.
+//!
+//! The key and plaintext are taken from the AES-128 example of FIPS-197
+//! (appendix C.1), so the ciphertext can be checked against the reference.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    aes::{Aes, Key},
+    clock::ClockControl,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut aes = Aes::new(peripherals.AES, &mut system.peripheral_clock_control);
+    aes.set_key(Key::Key128([
+        0x00, 0x01, 0x02, 0x03, 0x04, 0x05, 0x06, 0x07, 0x08, 0x09, 0x0a, 0x0b, 0x0c, 0x0d, 0x0e,
+        0x0f,
+    ]));
+
+    let mut block = [
+        0x00, 0x11, 0x22, 0x33, 0x44, 0x55, 0x66, 0x77, 0x88, 0x99, 0xaa, 0xbb, 0xcc, 0xdd, 0xee,
+        0xff,
+    ];
+
+    // Expected: 69c4e0d86a7b0430d8cdb78070b4c55a
+    aes.encrypt_block(&mut block);
+    println!("Ciphertext: {:02x?}", block);
+
+    aes.decrypt_block(&mut block);
+    println!("Plaintext:  {:02x?}", block);
+
+    loop {}
+}
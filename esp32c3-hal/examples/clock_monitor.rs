@@ -23,7 +23,7 @@ static RTC: Mutex<RefCell<Option<Rtc>>> = Mutex::new(RefCell::new(None));
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     let mut rtc = Rtc::new(peripherals.RTC_CNTL);
@@ -41,7 +41,10 @@ fn main() -> ! {
         clocks.xtal_clock.to_MHz()
     );
 
-    interrupt::enable(pac::Interrupt::RTC_CORE, interrupt::Priority::Priority1).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::RTC_CORE, interrupt::Priority::Priority1)
+        .unwrap();
 
     critical_section::with(|cs| {
         RTC.borrow_ref_mut(cs).replace(rtc);
@@ -0,0 +1,57 @@
+//! Counts the wakeups from deep sleep in RTC fast memory.
+//!
+//! The chip enters deep sleep for 5 seconds after printing the count. After
+//! waking up it boots again, the count survives in RTC memory and is
+//! incremented. Power cycling the chip starts counting from 1 again.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    macros::ram,
+    pac::Peripherals,
+    prelude::*,
+    rtc_cntl::{
+        reset_reason,
+        sleep::{deep_sleep, wakeup_cause, RtcRam, WakeSource},
+    },
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[ram(rtc_fast, uninitialized)]
+static BOOT_COUNT: RtcRam<u32> = RtcRam::new();
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let count = BOOT_COUNT.get().unwrap_or(0) + 1;
+    BOOT_COUNT.set(count);
+
+    println!("Reset reason: {:?}", reset_reason());
+    println!("Wakeup cause: {:?}", wakeup_cause());
+    println!("Boot count: {}", count);
+
+    let error = deep_sleep(&mut rtc, &[WakeSource::Timer(5u64.secs())]).unwrap_err();
+    panic!("Deep sleep failed: {:?}", error);
+}
@@ -0,0 +1,122 @@
+//! Shows the buffer alignment checks of DMA transfers in burst mode
+//!
+//! With burst mode enabled, the DMA accesses memory in words. RX buffers need
+//! to start at a word aligned address and their length needs to be a multiple
+//! of 4, TX buffers only need an aligned start address. Transfers with
+//! buffers which don't meet this are rejected with
+//! `DmaError::BufferNotAligned` before anything is started.
+//!
+//! A few memory-to-memory copies with deliberately misaligned slices are
+//! tried, followed by one which meets the requirements.
+//!
+//! No external connections are needed.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::{DmaError, DmaPriority},
+    gdma::Gdma,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+const BUFFER_SIZE: usize = 64;
+
+#[repr(C, align(4))]
+struct Aligned([u8; BUFFER_SIZE]);
+
+static mut SRC: Aligned = Aligned([0u8; BUFFER_SIZE]);
+static mut DST: Aligned = Aligned([0u8; BUFFER_SIZE]);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+
+    let mut tx_descriptors = [0u32; 3];
+    let mut rx_descriptors = [0u32; 3];
+
+    let mut channel = dma.channel0.configure(
+        true,
+        &mut tx_descriptors,
+        &mut rx_descriptors,
+        DmaPriority::Priority0,
+    );
+
+    // DMA buffer require a static life-time
+    for (i, v) in unsafe { SRC.0.iter_mut() }.enumerate() {
+        *v = i as u8;
+    }
+
+    // the TX buffer doesn't start at a word boundary
+    let result = channel.mem2mem(unsafe { &SRC.0[1..33] }, unsafe { &mut DST.0[..32] });
+    println!(
+        "misaligned source: {}",
+        if matches!(result, Err(DmaError::BufferNotAligned)) {
+            "rejected"
+        } else {
+            "NOT REJECTED"
+        }
+    );
+    drop(result);
+
+    // the RX buffer doesn't start at a word boundary
+    let result = channel.mem2mem(unsafe { &SRC.0[..32] }, unsafe { &mut DST.0[1..33] });
+    println!(
+        "misaligned destination: {}",
+        if matches!(result, Err(DmaError::BufferNotAligned)) {
+            "rejected"
+        } else {
+            "NOT REJECTED"
+        }
+    );
+    drop(result);
+
+    // the RX buffer starts at a word boundary but isn't a multiple of 4 long
+    let result = channel.mem2mem(unsafe { &SRC.0[..30] }, unsafe { &mut DST.0[..30] });
+    println!(
+        "odd destination length: {}",
+        if matches!(result, Err(DmaError::BufferNotAligned)) {
+            "rejected"
+        } else {
+            "NOT REJECTED"
+        }
+    );
+    drop(result);
+
+    // both buffers are aligned
+    unsafe { DST.0.fill(0) };
+    let transfer = channel
+        .mem2mem(unsafe { &SRC.0[..32] }, unsafe { &mut DST.0[..32] })
+        .unwrap();
+    let (src, dst) = transfer.wait();
+    println!(
+        "aligned copy is {}",
+        if src == dst { "correct" } else { "WRONG" }
+    );
+
+    loop {}
+}
@@ -0,0 +1,139 @@
+//! Shows how the GDMA priorities arbitrate between channels
+//!
+//! Two memory-to-memory copies of the same size are started at the same time,
+//! one on channel 0 and one on channel 1. The time each copy needs is measured
+//! with the SYSTIMER.
+//!
+//! In the first round channel 1 gets the highest priority and channel 0 the
+//! lowest one: channel 1 preempts channel 0 and finishes well before it, even
+//! though channel 0 was started first. In the second round both channels have
+//! the same priority and share the bus, so both copies finish at about the
+//! same time.
+//!
+//! No external connections are needed.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::{descriptor_words, DmaPriority},
+    gdma::Gdma,
+    pac::Peripherals,
+    prelude::*,
+    systimer::SystemTimer,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+const BUFFER_SIZE: usize = 32000;
+const DESCRIPTOR_WORDS: usize = descriptor_words(BUFFER_SIZE);
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let mut creator0 = dma.channel0;
+    let mut creator1 = dma.channel1;
+
+    let mut tx_descriptors0 = [0u32; DESCRIPTOR_WORDS];
+    let mut rx_descriptors0 = [0u32; DESCRIPTOR_WORDS];
+    let mut tx_descriptors1 = [0u32; DESCRIPTOR_WORDS];
+    let mut rx_descriptors1 = [0u32; DESCRIPTOR_WORDS];
+
+    // DMA buffer require a static life-time
+    let (mut src0, mut dst0, mut src1, mut dst1) = buffers();
+    for (i, v) in src0.iter_mut().chain(src1.iter_mut()).enumerate() {
+        *v = (i % 255) as u8;
+    }
+
+    for (priority0, priority1) in [
+        (DmaPriority::Priority0, DmaPriority::Priority9),
+        (DmaPriority::Priority5, DmaPriority::Priority5),
+    ] {
+        let mut channel0 =
+            creator0.configure(false, &mut tx_descriptors0, &mut rx_descriptors0, priority0);
+        let mut channel1 =
+            creator1.configure(false, &mut tx_descriptors1, &mut rx_descriptors1, priority1);
+
+        println!(
+            "channel 0: TX {} RX {}, channel 1: TX {} RX {}",
+            channel0.tx_priority() as u8,
+            channel0.rx_priority() as u8,
+            channel1.tx_priority() as u8,
+            channel1.rx_priority() as u8,
+        );
+
+        dst0.fill(0);
+        dst1.fill(0);
+
+        let start = SystemTimer::now();
+        let mut transfer0 = channel0.mem2mem(src0, dst0).unwrap();
+        let mut transfer1 = channel1.mem2mem(src1, dst1).unwrap();
+
+        let mut done0 = None;
+        let mut done1 = None;
+        while done0.is_none() || done1.is_none() {
+            if done0.is_none() && transfer0.is_done() {
+                done0 = Some(SystemTimer::now() - start);
+            }
+            if done1.is_none() && transfer1.is_done() {
+                done1 = Some(SystemTimer::now() - start);
+            }
+        }
+
+        (src0, dst0) = transfer0.wait();
+        (src1, dst1) = transfer1.wait();
+
+        println!(
+            "channel 0 done after {} ticks, channel 1 done after {} ticks",
+            done0.unwrap(),
+            done1.unwrap()
+        );
+        println!(
+            "copies are {}",
+            if src0 == dst0 && src1 == dst1 {
+                "correct"
+            } else {
+                "WRONG"
+            }
+        );
+
+        creator0 = channel0.free();
+        creator1 = channel1.free();
+    }
+
+    loop {}
+}
+
+fn buffers() -> (
+    &'static mut [u8; BUFFER_SIZE],
+    &'static mut [u8; BUFFER_SIZE],
+    &'static mut [u8; BUFFER_SIZE],
+    &'static mut [u8; BUFFER_SIZE],
+) {
+    static mut SRC0: [u8; BUFFER_SIZE] = [0u8; BUFFER_SIZE];
+    static mut DST0: [u8; BUFFER_SIZE] = [0u8; BUFFER_SIZE];
+    static mut SRC1: [u8; BUFFER_SIZE] = [0u8; BUFFER_SIZE];
+    static mut DST1: [u8; BUFFER_SIZE] = [0u8; BUFFER_SIZE];
+    unsafe { (&mut SRC0, &mut DST0, &mut SRC1, &mut DST1) }
+}
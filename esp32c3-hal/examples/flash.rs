@@ -0,0 +1,56 @@
+//! Erases the last sector of the flash, writes a pattern to it and reads it
+//! back.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    flash,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let capacity = flash::capacity();
+    let offset = capacity - flash::SECTOR_SIZE;
+    println!("Flash size: {} bytes, using offset {:#x}", capacity, offset);
+
+    let mut buffer = [0u8; 32];
+    flash::erase_sector(offset).unwrap();
+    flash::read(offset, &mut buffer).unwrap();
+    println!("After erase: {:02x?}", buffer);
+
+    let mut data = [0u8; 32];
+    for (index, byte) in data.iter_mut().enumerate() {
+        *byte = index as u8;
+    }
+    flash::write(offset, &data).unwrap();
+    flash::read(offset, &mut buffer).unwrap();
+    println!("After write: {:02x?}", buffer);
+
+    loop {}
+}
@@ -0,0 +1,78 @@
+//! I2C timeout example
+//!
+//! This example repeatedly reads a register of a target and recovers from a
+//! target stretching the clock forever, e.g. because it hangs. The I2C driver
+//! aborts the transfer after the configured timeout and resets the controller,
+//! so the next attempt can be made right away.
+//!
+//! To try the timeout path, pull SCL low while the example is running.
+//!
+//! The following wiring is assumed:
+//! - SDA => GPIO1
+//! - SCL => GPIO2
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    i2c::{Error, I2C},
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+const TARGET_ADDRESS: u8 = 0x77;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    // Give up if SCL is held low for more than 1000 bus clock cycles (10ms at
+    // 100kHz)
+    let mut i2c = I2C::new_with_timeout(
+        peripherals.I2C0,
+        io.pins.gpio1,
+        io.pins.gpio2,
+        100u32.kHz(),
+        Some(1000),
+        &mut system.peripheral_clock_control,
+        &clocks,
+    )
+    .unwrap();
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        let mut data = [0u8; 2];
+        match i2c.write_read(TARGET_ADDRESS, &[0xd0], &mut data) {
+            Ok(()) => println!("Read {:02x?}", data),
+            Err(Error::TimeOut) => println!("Bus stuck, timed out"),
+            Err(err) => println!("Error {:?}", err),
+        }
+
+        delay.delay_ms(500u32);
+    }
+}
@@ -0,0 +1,119 @@
+//! Plays a 440 Hz square wave via I2S, e.g. on a MAX98357A or PCM5102A DAC
+//! board.
+//!
+//! The samples are streamed from a ring buffer of two chunks: while the DMA
+//! sends one chunk, the other one is refilled.
+//!
+//! Connect BCLK to GPIO2, WS (LRCK) to GPIO3 and DIN to GPIO4. MCLK is output
+//! on GPIO5 for DACs which need it.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    gpio::IO,
+    i2s::{DataFormat, I2s, Standard},
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+const SAMPLE_RATE: u32 = 44100;
+const FREQUENCY: u32 = 440;
+const AMPLITUDE: i16 = 4000;
+
+/// Size of one chunk, 1000 stereo frames of 16 bit samples
+const CHUNK_SIZE: usize = 4000;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut tx_descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let i2s = I2s::new(
+        peripherals.I2S,
+        Standard::Philips,
+        DataFormat::Data16Channel16,
+        SAMPLE_RATE.Hz(),
+        dma_channel.configure(
+            false,
+            &mut tx_descriptors,
+            &mut rx_descriptors,
+            DmaPriority::Priority0,
+        ),
+        &mut system.peripheral_clock_control,
+    )
+    .unwrap()
+    .with_mclk(io.pins.gpio5);
+
+    let i2s_tx = i2s
+        .i2s_tx
+        .with_pins(io.pins.gpio2, io.pins.gpio3, io.pins.gpio4);
+
+    // DMA buffer require a static life-time
+    let buffer = buffer();
+    let mut phase = 0;
+    for chunk in buffer.chunks_mut(CHUNK_SIZE) {
+        fill(chunk, &mut phase);
+    }
+
+    let mut transfer = i2s_tx.write_dma_circular(buffer, CHUNK_SIZE).unwrap();
+
+    loop {
+        if let Some(index) = transfer.take_completed_chunk() {
+            fill(transfer.chunk_mut(index), &mut phase);
+        }
+
+        if transfer.take_error().is_some() {
+            println!("Underflow, a chunk wasn't refilled in time");
+        }
+    }
+}
+
+/// Fill `chunk` with stereo frames of the square wave, `phase` counts the
+/// frames sent so far
+fn fill(chunk: &mut [u8], phase: &mut u32) {
+    for frame in chunk.chunks_exact_mut(4) {
+        let high = (*phase * FREQUENCY * 2 / SAMPLE_RATE) % 2 == 0;
+        let sample = if high { AMPLITUDE } else { -AMPLITUDE };
+
+        frame[..2].copy_from_slice(&sample.to_le_bytes());
+        frame[2..].copy_from_slice(&sample.to_le_bytes());
+
+        *phase = (*phase + 1) % SAMPLE_RATE;
+    }
+}
+
+fn buffer() -> &'static mut [u8; 2 * CHUNK_SIZE] {
+    static mut BUFFER: [u8; 2 * CHUNK_SIZE] = [0u8; 2 * CHUNK_SIZE];
+    unsafe { &mut BUFFER }
+}
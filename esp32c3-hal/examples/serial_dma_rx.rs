@@ -0,0 +1,94 @@
+//! Receives frames of unknown length on UART1 via DMA.
+//!
+//! A transfer completes when the buffer is full or the RX line was idle for
+//! the length of 10 characters. Connect GPIO2 to a serial adapter (115200
+//! baud) and send some lines of text.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::DmaPriority,
+    gdma::Gdma,
+    pac::Peripherals,
+    prelude::*,
+    serial::{config::Config, TxRxPins},
+    timer::TimerGroup,
+    Rtc,
+    Serial,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    // Disable watchdog timers
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let pins = TxRxPins::new_tx_rx(
+        io.pins.gpio1.into_push_pull_output(),
+        io.pins.gpio2.into_floating_input(),
+    );
+
+    let serial1 = Serial::new_with_config(
+        peripherals.UART1,
+        Some(Config::default()),
+        Some(pins),
+        &clocks,
+    );
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let mut serial1 = serial1.with_rx_dma(
+        peripherals.UHCI0,
+        dma_channel.configure(
+            false,
+            &mut descriptors,
+            &mut rx_descriptors,
+            DmaPriority::Priority0,
+        ),
+        10,
+        &mut system.peripheral_clock_control,
+    );
+
+    let mut buffer = buffer();
+
+    loop {
+        let transfer = serial1.read(buffer).unwrap();
+        let (result, received, serial) = transfer.wait();
+
+        match result {
+            Ok(len) => println!("Received {:02x?}", &received[..len]),
+            Err(err) => println!("Error {:?}", err),
+        }
+
+        buffer = received;
+        serial1 = serial;
+    }
+}
+
+fn buffer() -> &'static mut [u8; 256] {
+    static mut BUFFER: [u8; 256] = [0u8; 256];
+    unsafe { &mut BUFFER }
+}
@@ -13,7 +13,7 @@ use esp32c3_hal::{
     interrupt,
     pac::{self, Peripherals, UART0},
     prelude::*,
-    serial::config::AtCmdConfig,
+    serial::{config::AtCmdConfig, UartEvent},
     timer::TimerGroup,
     Cpu,
     Rtc,
@@ -28,11 +28,11 @@ static SERIAL: Mutex<RefCell<Option<Serial<UART0>>>> = Mutex::new(RefCell::new(N
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     let mut rtc = Rtc::new(peripherals.RTC_CNTL);
-    let mut serial0 = Serial::new(peripherals.UART0);
+    let mut serial0 = Serial::new(peripherals.UART0, &clocks);
     let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
     let mut timer0 = timer_group0.timer0;
     let mut wdt0 = timer_group0.wdt;
@@ -47,15 +47,20 @@ fn main() -> ! {
 
     serial0.set_at_cmd(AtCmdConfig::new(None, None, None, b'#', None));
     serial0.set_rx_fifo_full_threshold(30);
-    serial0.listen_at_cmd();
-    serial0.listen_rx_fifo_full();
+    serial0.set_rx_timeout(Some(10));
+    serial0.listen(UartEvent::AtCmd);
+    serial0.listen(UartEvent::RxFifoFull);
+    serial0.listen(UartEvent::RxTimeout);
 
     timer0.start(1u64.secs());
 
     critical_section::with(|cs| SERIAL.borrow_ref_mut(cs).replace(serial0));
 
-    interrupt::enable(pac::Interrupt::UART0, interrupt::Priority::Priority1).unwrap();
-    interrupt::set_kind(
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::UART0, interrupt::Priority::Priority1)
+        .unwrap();
+    system.interrupt_controller.set_kind(
         Cpu::ProCpu,
         interrupt::CpuInterrupt::Interrupt1, // Interrupt 1 handles priority one interrupts
         interrupt::InterruptKind::Edge,
@@ -67,7 +72,7 @@ fn main() -> ! {
 
     loop {
         critical_section::with(|cs| {
-            writeln!(SERIAL.borrow_ref_mut(cs).as_mut().unwrap(), "Hello World! Send a single `#` character or send at least 30 characters and see the interrupts trigger.").ok();
+            writeln!(SERIAL.borrow_ref_mut(cs).as_mut().unwrap(), "Hello World! Send a single `#` character or send some characters and see the interrupts trigger.").ok();
         });
 
         block!(timer0.wait()).unwrap();
@@ -80,21 +85,21 @@ fn UART0() {
         let mut serial = SERIAL.borrow_ref_mut(cs);
         let serial = serial.as_mut().unwrap();
 
-        let mut cnt = 0;
-        while let nb::Result::Ok(_c) = serial.read() {
-            cnt += 1;
-        }
+        let mut buffer = [0u8; 128];
+        let cnt = serial.read_available(&mut buffer);
         writeln!(serial, "Read {} bytes", cnt,).ok();
 
         writeln!(
             serial,
-            "Interrupt AT-CMD: {} RX-FIFO-FULL: {}",
-            serial.at_cmd_interrupt_set(),
-            serial.rx_fifo_full_interrupt_set(),
+            "Interrupt AT-CMD: {} RX-FIFO-FULL: {} RX-TIMEOUT: {}",
+            serial.is_interrupt_set(UartEvent::AtCmd),
+            serial.is_interrupt_set(UartEvent::RxFifoFull),
+            serial.is_interrupt_set(UartEvent::RxTimeout),
         )
         .ok();
 
-        serial.reset_at_cmd_interrupt();
-        serial.reset_rx_fifo_full_interrupt();
+        serial.clear_interrupt(UartEvent::AtCmd);
+        serial.clear_interrupt(UartEvent::RxFifoFull);
+        serial.clear_interrupt(UartEvent::RxTimeout);
     });
 }
@@ -0,0 +1,51 @@
+//! Computes the SHA-256 digest of a message using the SHA accelerator and
+//! prints it.
+//!
+//! The message is fed in two parts to show the streaming interface, the
+//! printed digest is the same as the one of the whole message.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    pac::Peripherals,
+    prelude::*,
+    sha::{Sha, Sha256},
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let mut sha = Sha::<Sha256>::new(peripherals.SHA, &mut system.peripheral_clock_control);
+
+    sha.update(b"The quick brown fox ");
+    sha.update(b"jumps over the lazy dog");
+    let digest = sha.finish();
+
+    // Expected: d7a8fbb307d7809469ca9abcb0082e4f8d5651e46d3cdb762d02d0bf37c9e592
+    println!("{:02x?}", digest);
+
+    loop {}
+}
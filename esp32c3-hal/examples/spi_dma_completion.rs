@@ -0,0 +1,138 @@
+//! Polls SPI DMA transfers for completion
+//!
+//! Folowing pins are used:
+//! SCLK    GPIO6
+//! MISO    GPIO2
+//! MOSI    GPIO7
+//! CS      GPIO10
+//!
+//! Depending on your target and the board you are using you have to change the
+//! pins.
+//!
+//! Connect MISO and MOSI pins to see the outgoing data is read as incoming
+//! data.
+//!
+//! The first transfer only writes, the RX direction of the DMA channel is never
+//! started and counts as done, so `is_done` only waits for TX. The second
+//! transfer is full-duplex, `is_done` becomes true only after both directions
+//! completed, i.e. once the whole buffer was received.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    dma::{DmaPriority, DmaTransfer, DmaTransferRxTx},
+    gdma::Gdma,
+    gpio::IO,
+    pac::Peripherals,
+    prelude::*,
+    spi::{dma::WithDmaSpi2, Spi, SpiMode},
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let sclk = io.pins.gpio6;
+    let miso = io.pins.gpio2;
+    let mosi = io.pins.gpio7;
+    let cs = io.pins.gpio10;
+
+    let dma = Gdma::new(peripherals.DMA, &mut system.peripheral_clock_control);
+    let dma_channel = dma.channel0;
+
+    let mut descriptors = [0u32; 8 * 3];
+    let mut rx_descriptors = [0u32; 8 * 3];
+
+    let mut spi = Spi::new(
+        peripherals.SPI2,
+        sclk,
+        mosi,
+        miso,
+        cs,
+        100u32.kHz(),
+        SpiMode::Mode0,
+        &mut system.peripheral_clock_control,
+        &clocks,
+    )
+    .with_dma(dma_channel.configure(
+        false,
+        &mut descriptors,
+        &mut rx_descriptors,
+        DmaPriority::Priority0,
+    ));
+
+    let mut delay = Delay::new(&clocks);
+
+    // DMA buffer require a static life-time
+    let mut send = buffer1();
+    let mut receive = buffer2();
+
+    for (i, v) in send.iter_mut().enumerate() {
+        *v = (i % 255) as u8;
+    }
+
+    loop {
+        // Write only, RX is never started
+        let mut transfer = spi.dma_write(send).unwrap();
+        let mut polls = 0u32;
+        while !transfer.is_done() {
+            polls += 1;
+        }
+        (send, spi) = transfer.wait();
+        println!("write done after {} polls", polls);
+
+        // Full-duplex, done once both directions completed
+        receive.fill(0);
+        let mut transfer = spi.dma_transfer(send, receive).unwrap();
+        let mut polls = 0u32;
+        while !transfer.is_done() {
+            polls += 1;
+        }
+        (receive, send, spi) = transfer.wait();
+        println!(
+            "transfer done after {} polls, received data is {}",
+            polls,
+            if receive == send {
+                "complete"
+            } else {
+                "INCOMPLETE"
+            }
+        );
+
+        delay.delay_ms(1000u32);
+    }
+}
+
+fn buffer1() -> &'static mut [u8; 4000] {
+    static mut BUFFER: [u8; 4000] = [0u8; 4000];
+    unsafe { &mut BUFFER }
+}
+
+fn buffer2() -> &'static mut [u8; 4000] {
+    static mut BUFFER: [u8; 4000] = [0u8; 4000];
+    unsafe { &mut BUFFER }
+}
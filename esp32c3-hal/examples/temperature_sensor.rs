@@ -0,0 +1,54 @@
+//! Reads the internal temperature sensor of the chip once per second.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    analog::{
+        temperature_sensor::{TemperatureRange, TemperatureSensor},
+        SarAdcExt,
+    },
+    clock::ClockControl,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Delay,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let analog = peripherals.APB_SARADC.split();
+    let mut sensor = TemperatureSensor::new(
+        analog.temperature_sensor,
+        TemperatureRange::Minus10To80,
+        &mut system.peripheral_clock_control,
+    );
+
+    let mut delay = Delay::new(&clocks);
+
+    loop {
+        println!("Chip temperature: {:.1} °C", sensor.read_celsius());
+        delay.delay_ms(1000u32);
+    }
+}
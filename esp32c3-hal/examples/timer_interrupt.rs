@@ -25,7 +25,7 @@ static TIMER1: Mutex<RefCell<Option<Timer<Timer0<TIMG1>>>>> = Mutex::new(RefCell
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
@@ -43,11 +43,17 @@ fn main() -> ! {
     wdt0.disable();
     wdt1.disable();
 
-    interrupt::enable(pac::Interrupt::TG0_T0_LEVEL, interrupt::Priority::Priority1).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG0_T0_LEVEL, interrupt::Priority::Priority1)
+        .unwrap();
     timer0.start(500u64.millis());
     timer0.listen();
 
-    interrupt::enable(pac::Interrupt::TG1_T0_LEVEL, interrupt::Priority::Priority1).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::TG1_T0_LEVEL, interrupt::Priority::Priority1)
+        .unwrap();
     timer1.start(1u64.secs());
     timer1.listen();
 
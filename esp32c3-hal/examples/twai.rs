@@ -0,0 +1,72 @@
+//! Sends a frame once per second in self test mode and prints the frames
+//! received.
+//!
+//! In self test mode the controller receives its own frames and doesn't need
+//! an acknowledgement from another node. Connect GPIO2 (TX) and GPIO3 (RX) to
+//! a CAN transceiver, or directly to each other to run it without a bus.
+
+#![no_std]
+#![no_main]
+
+use esp32c3_hal::{
+    clock::ClockControl,
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    twai::{Filter, Frame, Id, Mode, Twai},
+    Delay,
+    Rtc,
+    IO,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use riscv_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-C3, this includes the Super WDT,
+    // the RTC WDT, and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.swd.disable();
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+
+    let mut twai = Twai::new(
+        peripherals.TWAI,
+        io.pins.gpio2,
+        io.pins.gpio3,
+        500u32.kHz(),
+        &clocks,
+        &mut system.peripheral_clock_control,
+    )
+    .unwrap();
+    twai.set_mode(Mode::SelfTest);
+    // Only accept standard frames with IDs 0x100 to 0x1ff
+    twai.set_filter(Filter::standard(0x100, 0x700));
+
+    let mut delay = Delay::new(&clocks);
+    let mut counter = 0u8;
+
+    loop {
+        let frame = Frame::new(Id::Standard(0x123), &[counter, 1, 2, 3]).unwrap();
+        twai.transmit(&frame).unwrap();
+
+        let frame = twai.receive().unwrap();
+        println!("Received {:?}: {:?}", frame.id(), frame.data());
+
+        counter = counter.wrapping_add(1);
+        delay.delay_ms(1000u32);
+    }
+}
@@ -43,5 +43,5 @@ analog! {
     Gpio2 => 2
     Gpio3 => 3
     Gpio4 => 4
-    Gpio5 => 4
+    Gpio5 => 5
 }
@@ -6,24 +6,38 @@ use core::arch::{asm, global_asm};
 use core::mem::size_of;
 
 pub use embedded_hal as ehal;
+#[cfg(feature = "embassy")]
+pub use esp_hal_common::embassy;
+#[cfg(feature = "log")]
+pub use esp_hal_common::logger;
 pub use esp_hal_common::{
+    aes,
     clock,
+    cpu,
+    crc,
     dma,
     dma::gdma,
     efuse,
+    flash,
     gpio as gpio_types,
     i2c,
+    i2s,
     interrupt,
     ledc,
     macros,
     pac,
     prelude,
     pulse_control,
+    rtc_cntl,
     serial,
+    sha,
     spi,
+    spi_slave,
     system,
     systimer,
     timer,
+    twai,
+    usb_serial_jtag,
     utils,
     Cpu,
     Delay,
@@ -45,7 +59,7 @@ pub mod gpio;
 
 /// Common module for analog functions
 pub mod analog {
-    pub use esp_hal_common::analog::{AvailableAnalog, SarAdcExt};
+    pub use esp_hal_common::analog::{temperature_sensor, AvailableAnalog, SarAdcExt};
 }
 
 extern "C" {
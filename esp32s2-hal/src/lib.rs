@@ -1,11 +1,17 @@
 #![no_std]
 
 pub use embedded_hal as ehal;
+#[cfg(feature = "log")]
+pub use esp_hal_common::logger;
 pub use esp_hal_common::{
+    aes,
     clock,
+    cpu,
+    crc,
     dma,
     dma::pdma,
     efuse,
+    flash,
     gpio as gpio_types,
     i2c::{self, I2C},
     interrupt,
@@ -13,9 +19,12 @@ pub use esp_hal_common::{
     macros,
     otg_fs,
     pac,
+    pcnt,
     prelude,
     pulse_control,
+    rtc_cntl,
     serial,
+    sha,
     spi,
     systimer,
     timer,
@@ -37,7 +46,7 @@ pub mod gpio;
 
 /// Common module for analog functions
 pub mod analog {
-    pub use esp_hal_common::analog::{AvailableAnalog, SensExt};
+    pub use esp_hal_common::analog::{temperature_sensor, AvailableAnalog, SensExt};
 }
 
 #[no_mangle]
@@ -0,0 +1,91 @@
+//! Drives a half bridge with a 20 kHz complementary PWM at 25% duty and 500 ns
+//! dead-time.
+//!
+//! The high side is driven by GPIO4, the low side by GPIO5. Pulling GPIO6 low
+//! switches both outputs off until the reset button is pressed.
+
+#![no_std]
+#![no_main]
+
+use esp32s3_hal::{
+    clock::ClockControl,
+    gpio::IO,
+    mcpwm::{
+        operator::{
+            DeadTimeCfg,
+            DeadTimeMode,
+            FaultConfig,
+            FaultMode,
+            GeneratorAction,
+            PwmPinConfig,
+        },
+        timer::PwmWorkingMode,
+        FaultPolarity,
+        Mcpwm,
+        PeripheralClockConfig,
+    },
+    pac::Peripherals,
+    prelude::*,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use xtensa_lx_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let mut system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    // Disable the watchdog timers. For the ESP32-S3, this includes the RTC WDT,
+    // and the TIMG WDTs.
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt0 = timer_group0.wdt;
+    let timer_group1 = TimerGroup::new(peripherals.TIMG1, &clocks);
+    let mut wdt1 = timer_group1.wdt;
+
+    rtc.rwdt.disable();
+    wdt0.disable();
+    wdt1.disable();
+
+    let io = IO::new(peripherals.GPIO, peripherals.IO_MUX);
+    let mut fault_pin = io.pins.gpio6.into_pull_up_input();
+
+    // 40 MHz peripheral clock, the dead-time is counted in its cycles
+    let clock_cfg = PeripheralClockConfig::with_frequency(40u32.MHz()).unwrap();
+    let mut mcpwm = Mcpwm::new(
+        peripherals.MCPWM0,
+        clock_cfg,
+        &mut system.peripheral_clock_control,
+    );
+
+    let timer_cfg = clock_cfg
+        .timer_clock_with_frequency(1999, PwmWorkingMode::Increase, 20u32.kHz())
+        .unwrap();
+    mcpwm.timer0.start(timer_cfg);
+
+    mcpwm
+        .fault0
+        .enable(&mut fault_pin, FaultPolarity::ActiveLow);
+
+    mcpwm.operator0.set_timer(&mcpwm.timer0);
+    let mut pins = mcpwm.operator0.with_linked_pins(
+        io.pins.gpio4,
+        PwmPinConfig::UP_ACTIVE_HIGH,
+        io.pins.gpio5,
+        DeadTimeCfg::new(DeadTimeMode::ActiveHighComplementary, 20, 20),
+    );
+    pins.set_fault_handling(Some(FaultConfig {
+        mode: FaultMode::OneShot,
+        fault0: true,
+        fault1: false,
+        fault2: false,
+        action_a: GeneratorAction::SetLow,
+        action_b: GeneratorAction::SetLow,
+    }));
+    pins.set_timestamp_a(timer_cfg.period() / 4);
+
+    loop {}
+}
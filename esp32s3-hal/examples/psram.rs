@@ -0,0 +1,53 @@
+//! This shows how to bring up the quad SPI PSRAM of e.g. an ESP32-S3-WROOM-1
+//! N8R2 module, fill it with a pattern and read it back.
+
+#![no_std]
+#![no_main]
+
+use esp32s3_hal::{
+    clock::ClockControl,
+    pac::Peripherals,
+    prelude::*,
+    psram,
+    timer::TimerGroup,
+    Rtc,
+};
+use esp_backtrace as _;
+use esp_println::println;
+use xtensa_lx_rt::entry;
+
+#[entry]
+fn main() -> ! {
+    let peripherals = Peripherals::take().unwrap();
+    let system = peripherals.SYSTEM.split();
+    let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
+
+    let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
+    let mut wdt = timer_group0.wdt;
+    let mut rtc = Rtc::new(peripherals.RTC_CNTL);
+
+    // Disable MWDT and RWDT (Watchdog) flash boot protection
+    wdt.disable();
+    rtc.rwdt.disable();
+
+    let region = psram::init_psram().unwrap();
+    println!(
+        "PSRAM mapped at {:p}, {} bytes",
+        region.start(),
+        region.size()
+    );
+
+    let memory = unsafe { region.as_mut_slice() };
+    for (index, byte) in memory.iter_mut().enumerate() {
+        *byte = index as u8;
+    }
+
+    let errors = memory
+        .iter()
+        .enumerate()
+        .filter(|(index, byte)| **byte != *index as u8)
+        .count();
+    println!("{} mismatches", errors);
+
+    loop {}
+}
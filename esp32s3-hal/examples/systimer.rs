@@ -9,7 +9,6 @@ use core::cell::RefCell;
 use critical_section::Mutex;
 use esp32s3_hal::{
     clock::ClockControl,
-    interrupt,
     interrupt::Priority,
     pac::{self, Peripherals},
     prelude::*,
@@ -29,7 +28,7 @@ static ALARM2: Mutex<RefCell<Option<Alarm<Target, 2>>>> = Mutex::new(RefCell::ne
 #[entry]
 fn main() -> ! {
     let peripherals = Peripherals::take().unwrap();
-    let system = peripherals.SYSTEM.split();
+    let mut system = peripherals.SYSTEM.split();
     let clocks = ClockControl::boot_defaults(system.clock_control).freeze();
 
     let timer_group0 = TimerGroup::new(peripherals.TIMG0, &clocks);
@@ -62,9 +61,18 @@ fn main() -> ! {
         ALARM2.borrow_ref_mut(cs).replace(alarm2);
     });
 
-    interrupt::enable(pac::Interrupt::SYSTIMER_TARGET0, Priority::Priority1).unwrap();
-    interrupt::enable(pac::Interrupt::SYSTIMER_TARGET1, Priority::Priority2).unwrap();
-    interrupt::enable(pac::Interrupt::SYSTIMER_TARGET2, Priority::Priority3).unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET0, Priority::Priority1)
+        .unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET1, Priority::Priority2)
+        .unwrap();
+    system
+        .interrupt_controller
+        .enable(pac::Interrupt::SYSTIMER_TARGET2, Priority::Priority3)
+        .unwrap();
 
     // Initialize the Delay peripheral, and use it to toggle the LED state in a
     // loop.
@@ -1,4 +1,5 @@
-//! This shows how to output text via USB Serial/JTAG.
+//! This shows how to output text via USB Serial/JTAG and echo back received
+//! data.
 //! You need to connect via the Serial/JTAG interface to see any output.
 //! Most dev-kits use a USB-UART-bridge - in that case you won't see any output.
 
@@ -36,6 +37,14 @@ fn main() -> ! {
 
     loop {
         writeln!(UsbSerialJtag, "Hello world!").ok();
+
+        // echo back what the host sent
+        let mut buffer = [0u8; 64];
+        let len = UsbSerialJtag.read_bytes(&mut buffer);
+        if len > 0 {
+            UsbSerialJtag.write_bytes(&buffer[..len]).ok();
+        }
+
         delay.delay_ms(500u32);
     }
 }
@@ -3,24 +3,41 @@
 #![cfg_attr(feature = "direct-boot", feature(asm_experimental_arch))]
 
 pub use embedded_hal as ehal;
+#[cfg(feature = "embassy")]
+pub use esp_hal_common::embassy;
+#[cfg(feature = "log")]
+pub use esp_hal_common::logger;
 pub use esp_hal_common::{
+    aes,
     clock,
-    cpu_control::CpuControl,
+    cpu,
+    cpu_control::{self, CpuControl},
+    crc,
     dma::{self, gdma},
     efuse,
+    flash,
     gpio as gpio_types,
     i2c,
+    i2s,
     interrupt,
+    lcd_cam,
     ledc,
     macros,
+    mcpwm,
     otg_fs,
     pac,
+    pcnt,
     prelude,
+    psram,
     pulse_control,
+    rtc_cntl,
     serial,
+    sha,
     spi,
+    spi_slave,
     systimer,
     timer,
+    twai,
     usb_serial_jtag,
     utils,
     Cpu,
@@ -40,7 +57,7 @@ pub mod gpio;
 
 /// Common module for analog functions
 pub mod analog {
-    pub use esp_hal_common::analog::{AvailableAnalog, SensExt};
+    pub use esp_hal_common::analog::{temperature_sensor, AvailableAnalog, SensExt};
 }
 
 #[no_mangle]